@@ -3,10 +3,124 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::arithmetic_side_effects)]
 
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+// ========================================
+// CROSS-CONTRACT INTERFACE
+// ========================================
+
+/// Errors of the PSP22 standard, encoded the way every PSP22 token encodes them
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Token-specific failure
+    Custom(String),
+    /// Sender holds less than the amount
+    InsufficientBalance,
+    /// Spender is allowed less than the amount
+    InsufficientAllowance,
+    /// Recipient is the zero address
+    ZeroRecipientAddress,
+    /// Sender is the zero address
+    ZeroSenderAddress,
+    /// Recipient contract rejected the transfer
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 messages token-denominated escrows call
+///
+/// Named after the standard so the selectors match any PSP22 token.
+#[ink::trait_definition]
+#[allow(clippy::upper_case_acronyms)]
+pub trait PSP22 {
+    /// Move `value` tokens from the caller to `to`
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Move `value` tokens from `from` to `to` out of the caller's allowance
+    #[ink(message)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+}
+
 #[ink::contract]
 mod escrow {
+    use super::PSP22;
+    use ink::codegen::TraitCallBuilder;
     use ink::storage::Mapping;
-    use ink::prelude::{string::String, vec::Vec};
+    use ink::prelude::{format, string::String, vec::Vec};
+
+    /// Contract interface version, bumped on every ABI-breaking change
+    /// (message signatures, event fields or topics)
+    pub const CONTRACT_VERSION: u32 = 46;
+
+    /// Denominator of basis-point fractions
+    pub const BPS_DENOMINATOR: u16 = 10_000;
+
+    /// Client rejections after which a milestone escalates to a dispute, unless set at creation
+    pub const DEFAULT_ESCALATION_THRESHOLD: u8 = 2;
+
+    /// Highest protocol fee the owner can set, in basis points
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    /// Maximum number of escrow IDs returned by one page of an account listing
+    pub const MAX_PAGE_SIZE: u32 = 50;
+
+    /// Maximum number of entries of each account index scanned by `has_pending_actions`
+    pub const PENDING_SCAN_LIMIT: usize = 50;
+
+    /// Maximum length in bytes of an escrow's display token symbol
+    pub const MAX_TOKEN_SYMBOL_LEN: usize = 12;
+
+    /// Maximum number of display decimals of an escrow's token
+    pub const MAX_TOKEN_DECIMALS: u8 = 36;
+
+    /// Maximum number of milestones per escrow
+    pub const MAX_MILESTONES: usize = 50;
+
+    /// Maximum number of milestones a milestone can depend on
+    pub const MAX_MILESTONE_DEPENDENCIES: usize = 5;
+
+    /// Maximum number of milestones released by one `release_milestones` call
+    pub const MAX_BATCH_RELEASE: usize = 20;
+
+    /// Time in milliseconds after a milestone's deadline during which an
+    /// extension request holds off `reclaim_expired_milestone`
+    pub const DEADLINE_EXTENSION_GRACE_MS: u64 = 48 * 60 * 60 * 1000;
+
+    /// Maximum number of progress notes stored per milestone
+    pub const MAX_PROGRESS_NOTES: usize = 20;
+
+    /// Maximum length in bytes of a progress note or evidence URI
+    pub const MAX_NOTE_URI_LEN: usize = 256;
+
+    /// Maximum number of evidence entries each party can submit per dispute
+    pub const MAX_EVIDENCE_PER_PARTY: usize = 10;
+
+    /// Maximum length in bytes of a `describe_escrow` description
+    pub const MAX_DESCRIPTION_LEN: usize = 256;
+
+    /// Time in milliseconds an amendment proposal stays acceptable, unless the owner changes it
+    pub const DEFAULT_AMENDMENT_WINDOW_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+    /// Maximum number of timesheets per drawdown escrow; approved ones become
+    /// released milestones, so this matches `MAX_MILESTONES`
+    pub const MAX_TIMESHEETS: u32 = MAX_MILESTONES as u32;
+
+    /// Lowest score of a counterparty rating
+    pub const MIN_RATING: u8 = 1;
+
+    /// Highest score of a counterparty rating
+    pub const MAX_RATING: u8 = 5;
+
+    /// Maximum length in bytes of a rating comment URI
+    pub const MAX_RATING_URI_LEN: usize = 256;
+
+    /// Maximum length in bytes of a rejection or dispute reason
+    pub const MAX_REASON_LEN: usize = 256;
+
+    /// Format version of `EscrowSnapshot`, its first encoded byte
+    pub const SNAPSHOT_VERSION: u8 = 1;
 
     // ========================================
     // TYPES MODULE
@@ -22,10 +136,75 @@ mod escrow {
         Funded,
         /// All milestones completed
         Completed,
-        /// Cancelled by mutual agreement or arbiter
+        /// Cancelled by mutual agreement
         Cancelled,
         /// In dispute, waiting for arbitration
         Disputed,
+        /// Settled by the arbiter or after client abandonment
+        Resolved,
+        /// Not fully funded by its funding deadline
+        Expired,
+        /// Created, waiting for the freelancer to accept
+        PendingAcceptance,
+    }
+
+    impl EscrowStatus {
+        /// Whether the escrow is closed and no funds move anymore
+        pub fn is_terminal(&self) -> bool {
+            matches!(self, Self::Completed | Self::Cancelled | Self::Resolved | Self::Expired)
+        }
+    }
+
+    /// Why an escrow ended before all milestones were released
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum TerminationReason {
+        /// Both parties agreed to cancel
+        MutualCancel,
+        /// The arbiter split the funds
+        ArbiterDecision,
+        /// The client stopped acting past the abandonment timeout
+        Timeout,
+        /// The escrow lapsed before it was funded or completed
+        Expired,
+        /// The deadlock policy settled an unanswered cancellation request
+        Deadlock,
+        /// The freelancer declined the escrow or every milestone before funding
+        Declined,
+    }
+
+    /// How `resolve_deadlock` settles the funds still held
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum DeadlockPolicy {
+        /// Refund everything to the client, whoever requested
+        #[default]
+        RefundClient,
+        /// Pay everything to the freelancer, whoever requested
+        PayFreelancer,
+    }
+
+    /// Destructive action guarded by a confirmation hash on strict escrows
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ConfirmableAction {
+        /// `request_cancel`
+        RequestCancel,
+        /// `approve_cancel`
+        ApproveCancel,
+        /// `raise_dispute`
+        RaiseDispute,
+    }
+
+    /// Whether the engagement may be publicized as a registry claim
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum EscrowPrivacy {
+        /// Outcomes may be attested publicly in the registry
+        #[default]
+        AttestPublicly,
+        /// No claim is issued for this escrow
+        NoAttestation,
     }
 
     /// Represents a milestone in an escrow
@@ -40,6 +219,41 @@ mod escrow {
         pub released: bool,
         /// Description or URI pointing to off-chain milestone details
         pub description: String,
+        /// IDs of earlier milestones that must be released first
+        pub depends_on: Vec<u32>,
+        /// Timestamp the freelancer is due to deliver the milestone by; once it
+        /// passes, the client may reclaim the milestone if unreleased
+        pub deadline: Option<u64>,
+        /// Whether the client reclaimed the milestone after its deadline
+        pub forfeited: bool,
+        /// Hash of the deliverable the freelancer submitted for review (None = not submitted)
+        pub proof_hash: Option<Hash>,
+        /// Number of submissions the client rejected
+        pub rejections: u32,
+        /// Whether the milestone escalated to a dispute awaiting the arbiter's ruling
+        pub disputed: bool,
+        /// Whether the arbiter ruled on the milestone, which then never escalates again
+        pub arbitrated: bool,
+        /// Part of `amount` not paid out yet; set to `amount` whenever the
+        /// milestone is added to an escrow, whatever the caller passed
+        pub remaining: Balance,
+    }
+
+    impl Milestone {
+        /// Whether the milestone was either released or reclaimed
+        pub fn is_settled(&self) -> bool {
+            self.released || self.forfeited
+        }
+    }
+
+    /// Later deadline the freelancer asked for on a milestone
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct DeadlineExtension {
+        /// Deadline the milestone gets if the client approves
+        pub new_deadline: u64,
+        /// Timestamp when the extension was requested
+        pub requested_at: u64,
     }
 
     /// Represents an escrow agreement
@@ -60,20 +274,471 @@ mod escrow {
         pub deposited: Balance,
         /// List of milestones for this escrow
         pub milestones: Vec<Milestone>,
+        /// Number of released milestones, kept in step with their `released` flags
+        pub released_count: u32,
+        /// Number of reclaimed milestones, kept in step with their `forfeited` flags
+        pub forfeited_count: u32,
+        /// Current status of the escrow
+        pub status: EscrowStatus,
+        /// Account that requested cancellation (if any)
+        pub cancel_requested_by: Option<AccountId>,
+        /// Timestamp when escrow was created
+        pub created_at: u64,
+        /// Timestamp when escrow was funded (if funded)
+        pub funded_at: Option<u64>,
+        /// Whether outcomes may be attested in the registry
+        pub privacy: EscrowPrivacy,
+        /// Pending privacy change and the party that proposed it
+        pub privacy_proposal: Option<(AccountId, EscrowPrivacy)>,
+        /// Timestamp of the client's last funding or release
+        pub last_client_activity_at: Option<u64>,
+        /// Client inactivity in milliseconds after which the escrow counts as abandoned (0 = never)
+        pub abandonment_timeout_ms: u64,
+        /// Share of the unreleased balance paid to the freelancer on abandonment, in basis points
+        pub abandonment_freelancer_bps: u16,
+        /// Time in milliseconds after an unanswered cancellation request until it can be settled (0 = never)
+        pub deadlock_timeout_ms: u64,
+        /// How an unanswered cancellation request is settled
+        pub deadlock_policy: DeadlockPolicy,
+        /// Timestamp of the pending cancellation request (if any)
+        pub cancel_requested_at: Option<u64>,
+        /// Party that raised the dispute (if any)
+        pub disputed_by: Option<AccountId>,
+        /// Why the dispute was raised (if it was)
+        pub dispute_reason: Option<String>,
+        /// Why the escrow was cancelled or resolved (if it was)
+        pub termination_reason: Option<TerminationReason>,
+        /// Client rejections after which a milestone escalates to a dispute
+        pub escalation_threshold: u8,
+        /// Milestones up to this amount may be released by the freelancer (0 = disabled)
+        pub auto_release_threshold: Balance,
+        /// Whether destructive actions require a confirmation hash
+        pub strict_confirmation: bool,
+        /// Account refunds are paid to instead of the client (set at funding)
+        pub refund_to: Option<AccountId>,
+        /// Display-only symbol of the token amounts are denominated in
+        pub token_symbol: Option<String>,
+        /// Display-only number of decimals of the token amounts are denominated in
+        pub token_decimals: Option<u8>,
+        /// Timestamp after which an escrow not fully funded can be expired (None = never)
+        pub funding_deadline: Option<u64>,
+        /// Whether milestones can only be released once the freelancer submitted them
+        pub require_submission_before_release: bool,
+        /// PSP22 token the escrow is funded and paid out in (None = native currency)
+        pub token: Option<AccountId>,
+        /// Protocol fee taken from releases in basis points, fixed at creation
+        pub fee_bps: u16,
+        /// Fee deposited on top of the milestones, paid to the arbiter if it
+        /// resolves a dispute and refunded to the client otherwise
+        pub arbiter_fee: Balance,
+        /// Pending arbiter replacement and the party that proposed it
+        pub arbiter_proposal: Option<(AccountId, AccountId)>,
+        /// Account the freelancer's payouts are sent to instead of the freelancer
+        pub payout_address: Option<AccountId>,
+    }
+
+    impl Escrow {
+        /// Whether every milestone was either released or reclaimed, from the counters
+        pub fn all_settled(&self) -> bool {
+            self.released_count.saturating_add(self.forfeited_count) as usize == self.milestones.len()
+        }
+    }
+
+    /// Terms of a drawdown (time-and-materials) escrow
+    ///
+    /// The escrow's `total_amount` is the cap. Every approved timesheet is
+    /// appended to its milestones as a released milestone, so settlement,
+    /// disputes and reconciliation work as for milestone escrows.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Drawdown {
+        /// Amount paid per approved hour
+        pub rate_per_hour: Balance,
+        /// Number of timesheets submitted, the next timesheet ID
+        pub timesheet_count: u32,
+        /// Party that asked to close the drawdown (if any)
+        pub close_requested_by: Option<AccountId>,
+    }
+
+    /// Hours the freelancer bills against a drawdown escrow
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Timesheet {
+        /// Sequential ID within the escrow
+        pub id: u32,
+        /// Hours billed
+        pub hours: u32,
+        /// Hash of the off-chain timesheet details
+        pub proof_hash: Hash,
+        /// Timestamp when the timesheet was submitted
+        pub submitted_at: u64,
+        /// Amount released when approved, capped by the remaining cap (None while pending)
+        pub approved_amount: Option<Balance>,
+    }
+
+    /// Progress update posted by the freelancer against a milestone
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ProgressNote {
+        /// URI pointing to off-chain progress details (at most `MAX_NOTE_URI_LEN` bytes)
+        pub note_uri: String,
+        /// Timestamp when the note was posted
+        pub posted_at: u64,
+    }
+
+    /// Progress notes of one milestone, in posting order
+    type ProgressNotes = Vec<ProgressNote>;
+
+    /// Evidence a party of a disputed escrow points the arbiter to
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Evidence {
+        /// Party that submitted the evidence
+        pub submitter: AccountId,
+        /// Hash of the evidence, so it cannot be swapped after submission
+        pub hash: Hash,
+        /// URI pointing to the evidence (at most `MAX_NOTE_URI_LEN` bytes)
+        pub uri: String,
+        /// Timestamp when the evidence was submitted
+        pub submitted_at: u64,
+    }
+
+    /// Rating one party of a closed escrow gave the other
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Rating {
+        /// Party that gave the rating
+        pub rater: AccountId,
+        /// Party that was rated
+        pub ratee: AccountId,
+        /// Score from `MIN_RATING` to `MAX_RATING`
+        pub score: u8,
+        /// URI pointing to an off-chain review (at most `MAX_RATING_URI_LEN` bytes)
+        pub comment_uri: Option<String>,
+        /// Timestamp when the rating was given
+        pub rated_at: u64,
+    }
+
+    /// Optional creation-time settings for an escrow
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EscrowOptions {
+        /// Display-only token symbol (at most `MAX_TOKEN_SYMBOL_LEN` bytes)
+        pub token_symbol: Option<String>,
+        /// Display-only token decimals (at most `MAX_TOKEN_DECIMALS`)
+        pub token_decimals: Option<u8>,
+        /// Whether outcomes may be attested in the registry
+        pub privacy: EscrowPrivacy,
+        /// Client inactivity in milliseconds after which the escrow counts as abandoned (0 = never)
+        pub abandonment_timeout_ms: u64,
+        /// Share of the unreleased balance paid to the freelancer on abandonment (at most `BPS_DENOMINATOR`)
+        pub abandonment_freelancer_bps: u16,
+        /// Client rejections after which a milestone escalates (None = `DEFAULT_ESCALATION_THRESHOLD`)
+        pub escalation_threshold: Option<u8>,
+        /// Require a confirmation hash for destructive actions
+        pub strict_confirmation: bool,
+        /// Time in milliseconds after an unanswered cancellation request until the requester can
+        /// settle it with `resolve_deadlock`; only used without an arbiter (0 = never)
+        pub deadlock_timeout_ms: u64,
+        /// How `resolve_deadlock` settles the funds still held
+        pub deadlock_policy: DeadlockPolicy,
+        /// Timestamp after which the escrow can no longer be funded and anyone
+        /// may expire it with `expire_escrow` (None = never)
+        pub funding_deadline: Option<u64>,
+        /// Only release milestones the freelancer submitted with `submit_milestone`
+        pub require_submission_before_release: bool,
+        /// PSP22 token to fund and pay out in instead of the native currency
+        /// (None = native currency)
+        pub token: Option<AccountId>,
+        /// Fee the client deposits on top of the milestones for the arbiter,
+        /// paid only if the arbiter resolves a dispute (0 = none; needs an arbiter)
+        pub arbiter_fee: Balance,
+    }
+
+    /// Changes to a funded escrow, applied once both parties agree
+    ///
+    /// Released milestones can't be touched. Timeouts can only be extended.
+    #[derive(Debug, Clone, Default, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Amendment {
+        /// New amounts of unreleased milestones, as (milestone ID, amount)
+        pub amount_changes: Vec<(u32, Balance)>,
+        /// Milestones appended after the existing ones, with IDs continuing the sequence
+        pub new_milestones: Vec<Milestone>,
+        /// Longer client abandonment timeout in milliseconds (0 = never)
+        pub abandonment_timeout_ms: Option<u64>,
+        /// Longer deadlock timeout in milliseconds (0 = never)
+        pub deadlock_timeout_ms: Option<u64>,
+    }
+
+    /// Amendment waiting for the other party of an escrow
+    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct AmendmentProposal {
+        /// Identifier of the proposal within the escrow
+        pub id: u32,
+        /// Party that proposed it
+        pub proposed_by: AccountId,
+        /// Proposed changes
+        pub changes: Amendment,
+        /// Escrow total once the changes are applied
+        pub total_amount: Balance,
+        /// Timestamp after which it can no longer be accepted (None = never)
+        pub expires_at: Option<u64>,
+    }
+
+    /// Compact view of an escrow without milestone details
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EscrowSummary {
+        /// Unique identifier for the escrow
+        pub id: u64,
+        /// Account of the client (payer)
+        pub client: AccountId,
+        /// Account of the freelancer (payee)
+        pub freelancer: AccountId,
+        /// Optional arbiter account for dispute resolution
+        pub arbiter: Option<AccountId>,
         /// Current status of the escrow
         pub status: EscrowStatus,
+        /// Total amount of the escrow (sum of all milestones)
+        pub total_amount: Balance,
+        /// Amount currently deposited in the escrow
+        pub deposited: Balance,
+        /// Sum of released milestone amounts
+        pub released_amount: Balance,
+        /// Number of milestones
+        pub milestone_count: u32,
+        /// Number of released milestones
+        pub released_count: u32,
         /// Account that requested cancellation (if any)
         pub cancel_requested_by: Option<AccountId>,
         /// Timestamp when escrow was created
         pub created_at: u64,
+        /// Earliest timestamp at which the client may open a dispute (None until funded)
+        pub earliest_dispute_at: Option<u64>,
+        /// Whether outcomes may be attested in the registry
+        pub privacy: EscrowPrivacy,
+        /// Why the escrow was cancelled or resolved (if it was)
+        pub termination_reason: Option<TerminationReason>,
+        /// Milestones up to this amount may be released by the freelancer (0 = disabled)
+        pub auto_release_threshold: Balance,
+        /// Whether destructive actions require a confirmation hash
+        pub strict_confirmation: bool,
+        /// Account refunds are paid to instead of the client (set at funding)
+        pub refund_to: Option<AccountId>,
+        /// Display-only token symbol
+        pub token_symbol: Option<String>,
+        /// Display-only token decimals
+        pub token_decimals: Option<u8>,
+    }
+
+    /// Final state of a completed, cancelled or resolved escrow, for archival
+    ///
+    /// `finalize_snapshot` is the Blake2x256 hash of the SCALE encoding of
+    /// this struct, fields in the order below. The layout is frozen for
+    /// `SNAPSHOT_VERSION` 1 and built from its own fields rather than the
+    /// `Escrow` struct, so the hash of a closed escrow stays the same when
+    /// later upgrades add escrow fields. Changing it means a new version.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EscrowSnapshot {
+        /// `SNAPSHOT_VERSION` the snapshot was encoded with
+        pub version: u8,
+        pub escrow_id: u64,
+        pub client: AccountId,
+        pub freelancer: AccountId,
+        pub arbiter: Option<AccountId>,
+        /// Account the client's refunds were paid to
+        pub refund_address: AccountId,
+        pub total_amount: Balance,
+        pub deposited: Balance,
+        /// `(id, amount, released, description)` of every milestone, in escrow order
+        pub milestones: Vec<(u32, Balance, bool, String)>,
+        pub status: EscrowStatus,
+        pub termination_reason: Option<TerminationReason>,
+        pub created_at: u64,
+        pub funded_at: Option<u64>,
+        /// Timestamp of the terminal transition (0 for escrows closed before snapshots existed)
+        pub closed_at: u64,
+        /// Net amount delivered to the freelancer over the escrow's life, without shortfalls
+        pub paid_to_freelancer: Balance,
+        /// Net amount delivered to the client's refund address, without shortfalls
+        pub refunded_to_client: Balance,
+    }
+
+    /// Running value counters for reconciling against the contract balance
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ReconciliationReport {
+        /// Deposited funds not yet released or refunded
+        pub sum_deposits_outstanding: Balance,
+        /// Reserved for funds credited to accounts but not yet withdrawn;
+        /// always 0, since every payout is transferred immediately
+        pub sum_pending_withdrawals: Balance,
+        /// Fees collected and not yet swept
+        pub collected_fees: Balance,
+        /// Insurance pool available for compensations
+        pub insurance_pool: Balance,
+        /// Balance the contract should hold for its obligations
+        pub contract_balance_expected: Balance,
+        /// Sum of every client's `get_totals` spending (deposits net of refunds)
+        pub sum_total_spent: Balance,
+        /// Sum of every freelancer's `get_totals` earnings
+        pub sum_total_earned: Balance,
+        /// Protocol fees taken from releases so far, including withdrawn ones
+        pub sum_fees_charged: Balance,
+    }
+
+    /// Owner-settable contract parameters
+    ///
+    /// Every setter updates this one struct, so `get_config` and
+    /// `ConfigUpdated` cover new knobs as soon as they are added here.
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct EscrowSettings {
+        /// Time in milliseconds after funding during which the client cannot open a dispute
+        pub dispute_grace_ms: u64,
+        /// Maximum total amount of a single escrow (0 = unlimited)
+        pub max_escrow_amount: Balance,
+        /// Time in milliseconds an amendment proposal stays acceptable (0 = no expiry)
+        pub amendment_window_ms: u64,
+        /// Protocol fee taken from milestone releases, in basis points (at most `MAX_FEE_BPS`)
+        pub fee_bps: u16,
+        /// Share of native-currency protocol fees paid into the insurance pool, in basis points
+        pub insurance_bps: u16,
+    }
+
+    /// Contract settings and compile-time limits, for integrators
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EscrowConfig {
+        /// Account allowed to change the settings
+        pub owner: AccountId,
+        /// Value of `CONTRACT_VERSION`
+        pub version: u32,
+        /// Current owner-settable parameters
+        pub settings: EscrowSettings,
+        /// Value of `BPS_DENOMINATOR`
+        pub bps_denominator: u16,
+        /// Value of `MAX_FEE_BPS`
+        pub max_fee_bps: u16,
+        /// Value of `DEFAULT_ESCALATION_THRESHOLD`
+        pub default_escalation_threshold: u8,
+        /// Value of `MAX_MILESTONES`
+        pub max_milestones: u32,
+        /// Value of `MAX_MILESTONE_DEPENDENCIES`
+        pub max_milestone_dependencies: u32,
+        /// Value of `MAX_BATCH_RELEASE`
+        pub max_batch_release: u32,
+        /// Value of `MAX_PROGRESS_NOTES`
+        pub max_progress_notes: u32,
+        /// Value of `MAX_NOTE_URI_LEN`
+        pub max_note_uri_len: u32,
+        /// Value of `MAX_EVIDENCE_PER_PARTY`
+        pub max_evidence_per_party: u32,
+        /// Value of `MAX_TOKEN_SYMBOL_LEN`
+        pub max_token_symbol_len: u32,
+        /// Value of `MAX_TOKEN_DECIMALS`
+        pub max_token_decimals: u8,
+        /// Value of `MAX_DESCRIPTION_LEN`
+        pub max_description_len: u32,
+        /// Value of `PENDING_SCAN_LIMIT`
+        pub pending_scan_limit: u32,
+        /// Value of `MAX_PAGE_SIZE`
+        pub max_page_size: u32,
+    }
+
+    /// Answer of the bounded pending-actions scan
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PendingActions {
+        /// At least one escrow is waiting on the account
+        Yes,
+        /// No escrow is waiting on the account
+        No,
+        /// Nothing found within the scan limit, but some indexes were truncated
+        Unknown,
+    }
+
+    /// Part an account plays in a single escrow
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// Payer of the escrow
+        Client,
+        /// Payee of the escrow
+        Freelancer,
+        /// Dispute resolver of the escrow
+        Arbiter,
+    }
+
+    /// Validation step of a message, as reported by the `simulate_*` views
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ValidationCheck {
+        /// The escrow exists
+        EscrowExists,
+        /// The caller may perform the action
+        Caller,
+        /// The escrow is in one of the accepted statuses
+        Status,
+        /// The milestone exists
+        MilestoneExists,
+        /// The milestone is neither released nor reclaimed yet
+        MilestoneUnreleased,
+        /// The milestones the milestone depends on are released
+        Dependencies,
+        /// The transferred value is positive
+        Amount,
+        /// The confirmation hash matches on strict escrows
+        Confirmation,
+        /// The client's dispute grace period has passed
+        DisputeGrace,
+        /// The escrow's funding deadline has not passed
+        FundingDeadline,
+        /// The freelancer submitted the milestone, on escrows requiring it
+        Submission,
+        /// The milestone is not awaiting the arbiter's ruling
+        MilestoneUndisputed,
+    }
+
+    /// Predicted outcome of a message, with the values behind a failed check
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SimulationResult {
+        /// Whether the message would pass validation
+        pub ok: bool,
+        /// Error the message would return (if any)
+        pub error: Option<EscrowError>,
+        /// Check that failed (if any)
+        pub failed_check: Option<ValidationCheck>,
+        /// Statuses the failed status check accepts (empty for other checks)
+        pub expected_status: Vec<EscrowStatus>,
+        /// Current status of the escrow (None if it doesn't exist)
+        pub actual_status: Option<EscrowStatus>,
+        /// Amount the failed check required (if amount-related)
+        pub required_amount: Option<Balance>,
+        /// Amount that was provided (if amount-related)
+        pub provided_amount: Option<Balance>,
     }
 
     // ========================================
     // ERRORS MODULE
     // ========================================
 
+    /// Problem found in a milestone plan by `validate_milestones`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ValidationIssue {
+        /// Error `create_escrow` returns for the problem
+        pub error: EscrowError,
+        /// Index of the offending milestone (None for problems with the whole plan)
+        pub index: Option<u32>,
+    }
+
     /// Error types for the Escrow contract
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum EscrowError {
         /// Escrow not found with the specified ID
@@ -96,6 +761,89 @@ mod escrow {
         EmptyMilestones,
         /// Cannot create escrow with zero amount
         ZeroAmount,
+        /// Token symbol is empty or too long, or token decimals are too large
+        InvalidTokenMetadata,
+        /// Client tried to open a dispute within the grace period after funding
+        DisputeTooEarly,
+        /// Only the contract owner can perform this operation
+        NotOwner,
+        /// Basis-point value exceeds `BPS_DENOMINATOR`
+        InvalidBps,
+        /// Escalation threshold must be at least one rejection
+        InvalidEscalationThreshold,
+        /// Progress note or evidence URI is empty or too long
+        InvalidNoteUri,
+        /// Milestone already holds `MAX_PROGRESS_NOTES` notes
+        TooManyProgressNotes,
+        /// The timeout or deadline the action waits for has not passed yet
+        TimeoutNotReached,
+        /// Confirmation hash is missing or does not match the action
+        ConfirmationMismatch,
+        /// Escrow total would exceed the maximum escrow amount
+        ExceedsMaxEscrow,
+        /// Client already created an escrow with this salt
+        SaltAlreadyUsed,
+        /// Payout exceeds the funds the ledger holds for the escrow
+        LedgerUnderflow,
+        /// Milestone dependencies are not unique earlier milestones, or too many
+        InvalidDependencies,
+        /// A milestone the released one depends on is not released yet
+        DependenciesNotMet,
+        /// Escrow total or milestone count differs from what the funder expected
+        TermsChanged,
+        /// Escrow was already funded; any value sent with the call is refunded
+        AlreadyFunded,
+        /// Amendment is empty, shortens a timeout, repeats a milestone, or raises
+        /// the total without being proposed by the freelancer
+        InvalidAmendment,
+        /// No pending amendment with this ID
+        AmendmentNotFound,
+        /// The amendment's acceptance window has passed
+        AmendmentExpired,
+        /// No milestone was declined, one was listed twice, or a kept milestone
+        /// depends on a declined one
+        InvalidDecline,
+        /// Escrow would have more than `MAX_MILESTONES` milestones
+        TooManyMilestones,
+        /// Milestone deadlines are not strictly increasing
+        InvalidMilestoneDeadlines,
+        /// The milestone's deadline has already passed
+        DeadlinePassed,
+        /// The milestone already has a pending deadline extension request
+        ExtensionAlreadyPending,
+        /// The compensation exceeds the escrow's recorded shortfall
+        ExceedsShortfall,
+        /// Escrow is not a drawdown escrow
+        NotDrawdown,
+        /// No timesheet with this ID
+        TimesheetNotFound,
+        /// Timesheet was already approved
+        TimesheetAlreadyApproved,
+        /// Escrow already has `MAX_TIMESHEETS` timesheets
+        TooManyTimesheets,
+        /// Rating score is outside `MIN_RATING..=MAX_RATING`, or its comment
+        /// URI is empty or longer than `MAX_RATING_URI_LEN`
+        InvalidRating,
+        /// Caller already rated the counterparty of this escrow
+        AlreadyRated,
+        /// Milestone was reclaimed by the client after its deadline
+        MilestoneForfeited,
+        /// Milestone has no pending submission, or the escrow requires one before release
+        MilestoneNotSubmitted,
+        /// Reason is empty or longer than `MAX_REASON_LEN`
+        InvalidReason,
+        /// A PSP22 transfer of a token-denominated escrow failed
+        TokenTransferFailed,
+        /// Protocol fee exceeds `MAX_FEE_BPS`
+        FeeTooHigh,
+        /// Caller already submitted `MAX_EVIDENCE_PER_PARTY` evidence entries
+        TooManyEvidenceEntries,
+        /// Batch lists more than `MAX_BATCH_RELEASE` milestones
+        BatchTooLarge,
+        /// Freelancer payouts cannot go to the client
+        InvalidPayoutAddress,
+        /// Milestone escalated to a dispute and awaits the arbiter's ruling
+        MilestoneDisputed,
     }
 
     /// Result type for contract operations
@@ -116,11 +864,43 @@ mod escrow {
         pub freelancer: AccountId,
         pub arbiter: Option<AccountId>,
         pub total_amount: Balance,
+        pub token_symbol: Option<String>,
+        pub token_decimals: Option<u8>,
+        pub privacy: EscrowPrivacy,
+        pub token: Option<AccountId>,
+        pub arbiter_fee: Balance,
     }
 
     /// Emitted when funds are deposited into an escrow
     #[ink(event)]
     pub struct EscrowFunded {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        /// Amount added by this deposit
+        pub amount: Balance,
+        /// Amount deposited so far; the escrow is funded once it reaches the total
+        pub deposited: Balance,
+        pub refund_to: Option<AccountId>,
+    }
+
+    /// Emitted when an escrow not fully funded by its deadline is expired
+    #[ink(event)]
+    pub struct EscrowExpired {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        /// Partial deposits returned to the client's refund address
+        pub refund_to_client: Balance,
+        /// `finalize_snapshot` of the expired escrow
+        pub snapshot_hash: Hash,
+    }
+
+    /// Emitted when value sent to `fund_escrow` beyond what was missing is sent back
+    #[ink(event)]
+    pub struct OverpaymentRefunded {
         #[ink(topic)]
         pub escrow_id: u64,
         pub amount: Balance,
@@ -131,917 +911,12567 @@ mod escrow {
     pub struct MilestoneReleased {
         #[ink(topic)]
         pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
         pub milestone_id: u32,
+        /// Gross amount released from the escrow, what remained of the milestone
         pub amount: Balance,
+        /// Protocol fee taken from `amount`
+        pub protocol_fee: Balance,
+        /// What the freelancer received: `amount` minus the fee
+        pub net_to_freelancer: Balance,
     }
 
-    /// Emitted when cancellation is requested
+    /// Emitted when the client pays part of a milestone
     #[ink(event)]
-    pub struct CancelRequested {
+    pub struct MilestonePartiallyReleased {
         #[ink(topic)]
         pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Gross amount released from the escrow
+        pub amount: Balance,
+        /// Protocol fee taken from `amount`
+        pub protocol_fee: Balance,
+        /// What the freelancer received: `amount` minus the fee
+        pub net_to_freelancer: Balance,
+        /// Part of the milestone still to be paid (0 = released)
+        pub remaining: Balance,
+    }
+
+    /// Emitted when the client reclaims an unreleased milestone after its deadline
+    #[ink(event)]
+    pub struct MilestoneReclaimed {
         #[ink(topic)]
-        pub requested_by: AccountId,
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Amount refunded to the client's refund address
+        pub amount: Balance,
     }
 
-    /// Emitted when an escrow is cancelled
+    /// Emitted when the client replaces the milestones of an unfunded escrow
     #[ink(event)]
-    pub struct EscrowCancelled {
+    pub struct MilestonesUpdated {
         #[ink(topic)]
         pub escrow_id: u64,
-        pub refund_to_client: Balance,
-        pub refund_to_freelancer: Balance,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub new_total: Balance,
     }
 
-    /// Emitted when a dispute is resolved by an arbiter
+    /// Emitted when the freelancer accepts an escrow, which can then be funded
     #[ink(event)]
-    pub struct DisputeResolved {
+    pub struct EscrowAccepted {
         #[ink(topic)]
         pub escrow_id: u64,
-        pub freelancer_share: Balance,
-        pub client_refund: Balance,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
     }
 
-    // ========================================
-    // STORAGE MODULE
-    // ========================================
+    /// Emitted when the freelancer declines an escrow instead of accepting it
+    #[ink(event)]
+    pub struct EscrowDeclined {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        /// `finalize_snapshot` of the declined escrow
+        pub snapshot_hash: Hash,
+    }
 
-    /// Main storage structure for the Escrow contract
-    #[ink(storage)]
-    pub struct EscrowMultiRelease {
-        /// Mapping from escrow ID to Escrow
-        escrows: Mapping<u64, Escrow>,
-        /// Counter for the next escrow ID
-        next_escrow_id: u64,
-        /// Mapping from client AccountId to their escrow IDs
-        client_escrows: Mapping<AccountId, Vec<u64>>,
-        /// Mapping from freelancer AccountId to their escrow IDs
-        freelancer_escrows: Mapping<AccountId, Vec<u64>>,
+    /// Emitted for each milestone the freelancer declines before funding
+    #[ink(event)]
+    pub struct MilestoneDeclined {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        /// ID the milestone had before the remaining ones were re-sequenced
+        pub milestone_id: u32,
+        pub amount: Balance,
     }
 
-    // ========================================
-    // CONTRACT IMPLEMENTATION
-    // ========================================
+    /// Emitted when the freelancer asks for a later milestone deadline
+    #[ink(event)]
+    pub struct DeadlineExtensionRequested {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        pub new_deadline: u64,
+    }
 
-    impl Default for EscrowMultiRelease {
-        fn default() -> Self {
-            Self::new()
-        }
+    /// Emitted when the client approves a deadline extension
+    #[ink(event)]
+    pub struct DeadlineExtended {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        pub old: u64,
+        pub new: u64,
     }
 
-    impl EscrowMultiRelease {
-        /// Constructor that initializes the contract
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {
-                escrows: Mapping::default(),
-                next_escrow_id: 0,
-                client_escrows: Mapping::default(),
-                freelancer_escrows: Mapping::default(),
-            }
-        }
+    /// Emitted when the client turns down a deadline extension
+    #[ink(event)]
+    pub struct DeadlineExtensionRejected {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+    }
 
-        /// Create a new escrow with milestones
+    /// Emitted when the last milestone of an escrow is released
+    #[ink(event)]
+    pub struct EscrowCompleted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub total_amount: Balance,
+        /// Arbiter fee sent back to the client since no dispute was resolved
+        pub arbiter_fee_refunded: Balance,
+        /// `finalize_snapshot` of the completed escrow
+        pub snapshot_hash: Hash,
+    }
+
+    /// Emitted when cancellation is requested
+    #[ink(event)]
+    pub struct CancelRequested {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub requested_by: AccountId,
+        pub client: AccountId,
+        pub freelancer: AccountId,
+    }
+
+    /// Emitted when the requester takes back a pending cancellation request
+    #[ink(event)]
+    pub struct CancelRequestWithdrawn {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub withdrawn_by: AccountId,
+    }
+
+    /// Emitted when a party raises a dispute for the arbiter
+    #[ink(event)]
+    pub struct DisputeRaised {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub raised_by: AccountId,
+        pub reason: String,
+    }
+
+    /// Emitted when an escrow is cancelled
+    #[ink(event)]
+    pub struct EscrowCancelled {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub refund_to_client: Balance,
+        pub refund_to_freelancer: Balance,
+        /// Part of the unreleased balance paid to the freelancer on abandonment
+        pub freelancer_compensation: Balance,
+        /// `finalize_snapshot` of the cancelled or resolved escrow
+        pub snapshot_hash: Hash,
+    }
+
+    /// Emitted when an unanswered cancellation request is settled by the deadlock policy
+    #[ink(event)]
+    pub struct DeadlockResolved {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub requested_by: AccountId,
+        pub policy: DeadlockPolicy,
+        /// Funds still held and delivered according to `policy`
+        pub amount: Balance,
+        /// `finalize_snapshot` of the resolved escrow
+        pub snapshot_hash: Hash,
+    }
+
+    /// Emitted when a dispute is resolved by an arbiter
+    #[ink(event)]
+    pub struct DisputeResolved {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub freelancer_share: Balance,
+        /// Arbiter fee paid to the arbiter from its own deposit, on top of both shares
+        pub arbiter_fee: Balance,
+        pub client_refund: Balance,
+        /// `finalize_snapshot` of the resolved escrow
+        pub snapshot_hash: Hash,
+    }
+
+    /// Emitted when the freelancer submits a milestone for review
+    #[ink(event)]
+    pub struct MilestoneSubmitted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Hash of the submitted deliverable
+        pub proof_hash: Hash,
+    }
+
+    /// Emitted when the client rejects a submitted milestone
+    #[ink(event)]
+    pub struct MilestoneRejected {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Why the submission was rejected
+        pub reason: String,
+    }
+
+    /// Emitted when a milestone reaches the escrow's escalation threshold of rejections
+    #[ink(event)]
+    pub struct MilestoneEscalated {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Rejections the milestone had when it escalated
+        pub rejections: u32,
+    }
+
+    /// Emitted when the arbiter rules on an escalated milestone
+    #[ink(event)]
+    pub struct MilestoneDisputeResolved {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        /// Whether the milestone was released to the freelancer (false = sent back for rework)
+        pub released: bool,
+    }
+
+    /// Emitted when the freelancer posts a progress note on a milestone
+    #[ink(event)]
+    pub struct ProgressPosted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestone_id: u32,
+        pub note_uri: String,
+    }
+
+    /// Emitted when a party submits evidence for a disputed escrow
+    #[ink(event)]
+    pub struct EvidenceSubmitted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub submitter: AccountId,
+        pub hash: Hash,
+        pub uri: String,
+    }
+
+    /// Emitted when a party of a closed escrow rates the other
+    #[ink(event)]
+    pub struct CounterpartyRated {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub rater: AccountId,
+        #[ink(topic)]
+        pub ratee: AccountId,
+        pub score: u8,
+        pub comment_uri: Option<String>,
+    }
+
+    /// Emitted when a party proposes a privacy change for a funded escrow
+    #[ink(event)]
+    pub struct PrivacyChangeProposed {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub proposed_by: AccountId,
+        pub privacy: EscrowPrivacy,
+    }
+
+    /// Emitted when the privacy setting of an escrow changes
+    #[ink(event)]
+    pub struct PrivacyChanged {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub privacy: EscrowPrivacy,
+    }
+
+    /// Emitted when the client changes the auto-release threshold of an escrow
+    #[ink(event)]
+    pub struct AutoReleaseThresholdSet {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub threshold: Balance,
+    }
+
+    /// Emitted when a party proposes an amendment to a funded escrow
+    #[ink(event)]
+    pub struct AmendmentProposed {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub proposed_by: AccountId,
+        pub amendment_id: u32,
+        pub total_amount: Balance,
+        pub expires_at: Option<u64>,
+    }
+
+    /// Milestone IDs and amounts of an escrow, emitted after `EscrowCreated`
+    /// and whenever the milestones change
+    ///
+    /// Holds at most `MAX_MILESTONES` entries; descriptions are left out to
+    /// bound its size.
+    #[ink(event)]
+    pub struct MilestonesSnapshot {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub milestones: Vec<(u32, Balance)>,
+    }
+
+    /// Emitted when the client appends a paid milestone to a funded escrow
+    #[ink(event)]
+    pub struct MilestoneAdded {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub milestone_id: u32,
+        pub amount: Balance,
+    }
+
+    /// Emitted when an amendment is applied
+    #[ink(event)]
+    pub struct AmendmentAccepted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub amendment_id: u32,
+        pub previous_total: Balance,
+        pub total_amount: Balance,
+    }
+
+    /// Emitted when the other party rejects an amendment or its proposer withdraws it
+    #[ink(event)]
+    pub struct AmendmentRejected {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub rejected_by: AccountId,
+        pub amendment_id: u32,
+    }
+
+    /// Emitted when a drawdown escrow is created, right after `EscrowCreated`
+    #[ink(event)]
+    pub struct DrawdownCreated {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub cap: Balance,
+        pub rate_per_hour: Balance,
+    }
+
+    /// Emitted when the freelancer submits a timesheet
+    #[ink(event)]
+    pub struct TimesheetSubmitted {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        pub timesheet_id: u32,
+        pub hours: u32,
+        pub proof_hash: Hash,
+    }
+
+    /// Emitted when the client approves a timesheet, after `MilestoneReleased`
+    #[ink(event)]
+    pub struct TimesheetApproved {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub timesheet_id: u32,
+        /// Released milestone recording the payment
+        pub milestone_id: u32,
+        /// Gross amount released, at most the remaining cap
+        pub amount: Balance,
+    }
+
+    /// Emitted when a party asks to close a drawdown escrow
+    #[ink(event)]
+    pub struct DrawdownCloseRequested {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub requested_by: AccountId,
+    }
+
+    /// Emitted when both parties closed a drawdown escrow, after `EscrowCompleted`
+    #[ink(event)]
+    pub struct DrawdownClosed {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub client: AccountId,
+        #[ink(topic)]
+        pub freelancer: AccountId,
+        /// Total released through approved timesheets
+        pub drawn: Balance,
+        /// Unused cap refunded to the client
+        pub refund_to_client: Balance,
+    }
+
+    /// Emitted when the client changes where refunds of an escrow are paid
+    #[ink(event)]
+    pub struct RefundAddressSet {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub refund_to: Option<AccountId>,
+    }
+
+    /// Emitted when the freelancer changes where its payouts are sent
+    #[ink(event)]
+    pub struct PayoutAddressChanged {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub new_address: AccountId,
+    }
+
+    /// Emitted when a party proposes a new arbiter for an escrow
+    #[ink(event)]
+    pub struct ArbiterChangeProposed {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub proposed_by: AccountId,
+        pub new_arbiter: AccountId,
+    }
+
+    /// Emitted when both parties agreed on a new arbiter
+    #[ink(event)]
+    pub struct ArbiterChanged {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        pub old: AccountId,
+        pub new: AccountId,
+    }
+
+    /// Emitted when the owner changes the maximum escrow amount
+    #[ink(event)]
+    pub struct MaxEscrowUpdated {
+        pub max_escrow_amount: Balance,
+    }
+
+    /// Emitted when the owner withdraws collected protocol fees
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        pub to: AccountId,
+        /// Token the fees were collected in (None = native currency)
+        pub token: Option<AccountId>,
+        pub amount: Balance,
+    }
+
+    /// Emitted when the owner changes any contract setting
+    #[ink(event)]
+    pub struct ConfigUpdated {
+        pub settings: EscrowSettings,
+    }
+
+    /// Emitted when the owner exempts a client from the maximum escrow amount
+    #[ink(event)]
+    pub struct ExemptClientAdded {
+        #[ink(topic)]
+        pub client: AccountId,
+    }
+
+    /// Emitted when the owner removes a client's exemption
+    #[ink(event)]
+    pub struct ExemptClientRemoved {
+        #[ink(topic)]
+        pub client: AccountId,
+    }
+
+    /// Emitted when a payout could not be delivered and is owed from the insurance pool
+    #[ink(event)]
+    pub struct ShortfallRecorded {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Emitted when someone tops up the insurance pool
+    #[ink(event)]
+    pub struct InsurancePoolFunded {
+        #[ink(topic)]
+        pub from: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Emitted when the owner pays a compensation from the insurance pool
+    #[ink(event)]
+    pub struct CompensationPaid {
+        #[ink(topic)]
+        pub escrow_id: u64,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount: Balance,
+    }
+
+    // ========================================
+    // STORAGE MODULE
+    // ========================================
+
+    /// Key a caller passes to `create_escrow` so a retry reuses its escrow
+    type IdempotencyKey = [u8; 32];
+
+    /// Main storage structure for the Escrow contract
+    #[ink(storage)]
+    pub struct EscrowMultiRelease {
+        /// Mapping from escrow ID to Escrow
+        escrows: Mapping<u64, Escrow>,
+        /// Counter for the next escrow ID
+        next_escrow_id: u64,
+        /// Mapping from client AccountId to their escrow IDs
+        client_escrows: Mapping<AccountId, Vec<u64>>,
+        /// Mapping from freelancer AccountId to their escrow IDs
+        freelancer_escrows: Mapping<AccountId, Vec<u64>>,
+        /// Mapping from arbiter AccountId to the escrow IDs they arbitrate
+        arbiter_escrows: Mapping<AccountId, Vec<u64>>,
+        /// Deposited funds not yet released or refunded, across all escrows
+        deposits_outstanding: Balance,
+        /// Fees collected and not yet swept
+        collected_fees: Balance,
+        /// Native funds set aside to compensate recorded shortfalls
+        insurance_pool: Balance,
+        /// Undelivered payouts per escrow not compensated yet
+        shortfalls: Mapping<u64, Balance>,
+        /// Fees collected from token-denominated escrows and not yet swept, per token
+        collected_token_fees: Mapping<AccountId, Balance>,
+        /// Native-currency fees charged so far, including swept ones
+        sum_fees_charged: Balance,
+        /// Account allowed to change contract settings
+        owner: AccountId,
+        /// Owner-settable parameters
+        settings: EscrowSettings,
+        /// Progress notes per (escrow ID, milestone ID)
+        progress_notes: Mapping<(u64, u32), ProgressNotes>,
+        /// Clients allowed to exceed `settings.max_escrow_amount`
+        exempt_clients: Mapping<AccountId, ()>,
+        /// Salted escrow keys (`hash(client ++ salt)`) to escrow ID
+        salted_escrows: Mapping<Hash, u64>,
+        /// Escrow created for each (client, idempotency key), never cleared
+        idempotency_keys: Mapping<(AccountId, IdempotencyKey), u64>,
+        /// Funds held per escrow; every payout is debited here first
+        ledger: Mapping<u64, Balance>,
+        /// Funds paid out to each freelancer
+        total_earned: Mapping<AccountId, Balance>,
+        /// Funds deposited by each client, net of refunds
+        total_spent: Mapping<AccountId, Balance>,
+        /// Sum of `total_earned` over all accounts
+        sum_total_earned: Balance,
+        /// Sum of `total_spent` over all accounts
+        sum_total_spent: Balance,
+        /// Pending amendment per escrow
+        amendments: Mapping<u64, AmendmentProposal>,
+        /// Number of amendments proposed per escrow, the next amendment ID
+        amendment_counts: Mapping<u64, u32>,
+        /// Pending deadline extension per (escrow ID, milestone ID)
+        deadline_extensions: Mapping<(u64, u32), DeadlineExtension>,
+        /// End of the reclaim grace per (escrow ID, milestone ID), kept when
+        /// the client rejects the request so a rejection cannot skip it
+        extension_grace: Mapping<(u64, u32), u64>,
+        /// Terms of drawdown escrows; escrows without an entry are milestone escrows
+        drawdowns: Mapping<u64, Drawdown>,
+        /// Timesheets per (escrow ID, timesheet ID)
+        timesheets: Mapping<(u64, u32), Timesheet>,
+        /// Net (paid to freelancer, refunded to client) per escrow
+        payout_totals: Mapping<u64, (Balance, Balance)>,
+        /// Timestamp each escrow reached a terminal status
+        closed_at: Mapping<u64, u64>,
+        /// Ratings per (escrow ID, rater)
+        ratings: Mapping<(u64, AccountId), Rating>,
+        /// (sum of scores, number of ratings) received per account
+        rating_summaries: Mapping<AccountId, (u64, u32)>,
+        /// Dispute evidence per escrow, in submission order
+        evidence: Mapping<u64, Vec<Evidence>>,
+    }
+
+    // ========================================
+    // SETTLEMENT MATH
+    // ========================================
+
+    /// Split of an escrow's funds at a point in time
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Settlement {
+        /// Sum of released milestone amounts
+        released: Balance,
+        /// Sum of milestone amounts neither released nor reclaimed yet
+        unreleased: Balance,
+        /// Sum of milestone amounts the client reclaimed after their deadline
+        forfeited: Balance,
+        /// Deposited funds still held by the contract for this escrow
+        refundable: Balance,
+    }
+
+    /// Side of an escrow a payout goes to
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Payee {
+        /// The freelancer, as payment
+        Freelancer,
+        /// The client's refund address, as a refund
+        Client,
+        /// The arbiter, as its fee for resolving a dispute
+        Arbiter,
+    }
+
+    /// How a gross release splits into the protocol fee and the amount the freelancer receives
+    ///
+    /// `gross == protocol_fee + net` always holds.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct PayoutBreakdown {
+        /// Amount leaving the escrow
+        gross: Balance,
+        /// Part kept by the protocol
+        protocol_fee: Balance,
+        /// Part transferred to the freelancer
+        net: Balance,
+    }
+
+    /// Split a gross release into fee and net
+    ///
+    /// Every release pays `net` and reports this breakdown, so the emitted
+    /// figures are never recomputed. Refunds and dispute shares are never
+    /// charged and do not go through it.
+    fn payout_breakdown(gross: Balance, protocol_fee_bps: u16) -> PayoutBreakdown {
+        let protocol_fee = bps_share(gross, protocol_fee_bps);
+        PayoutBreakdown {
+            gross,
+            protocol_fee,
+            net: gross.saturating_sub(protocol_fee),
+        }
+    }
+
+    /// Amount the client deposits to fund an escrow: its milestones plus the arbiter fee
+    fn funding_target(escrow: &Escrow) -> Balance {
+        escrow.total_amount.saturating_add(escrow.arbiter_fee)
+    }
+
+    /// Part of the funds held for an escrow that is its arbiter fee
+    ///
+    /// Deposits cover the milestones first, so a partial deposit holds no fee.
+    fn arbiter_fee_held(escrow: &Escrow) -> Balance {
+        escrow.deposited.saturating_sub(escrow.total_amount).min(escrow.arbiter_fee)
+    }
+
+    /// `bps` basis points of `amount`, rounded down and overflow-free
+    fn bps_share(amount: Balance, bps: u16) -> Balance {
+        let bps = Balance::from(bps.min(BPS_DENOMINATOR));
+        let denominator = Balance::from(BPS_DENOMINATOR);
+        (amount / denominator) * bps + (amount % denominator) * bps / denominator
+    }
+
+    /// Whether every milestone `milestone` depends on is released
+    fn dependencies_released(escrow: &Escrow, milestone: &Milestone) -> bool {
+        // Milestone IDs are their indices
+        milestone
+            .depends_on
+            .iter()
+            .all(|&dep| escrow.milestones.get(dep as usize).is_some_and(|m| m.released))
+    }
+
+    /// Start every milestone with its whole amount still to be paid
+    fn reset_remaining(milestones: &mut [Milestone]) {
+        for milestone in milestones {
+            milestone.remaining = milestone.amount;
+        }
+    }
+
+    /// Compute the settlement of an escrow, saturating instead of overflowing
+    ///
+    /// Partial releases count as released; the rest of a milestone is
+    /// unreleased, or forfeited once reclaimed.
+    fn settlement(escrow: &Escrow) -> Settlement {
+        let mut released: Balance = 0;
+        let mut unreleased: Balance = 0;
+        let mut forfeited: Balance = 0;
+        for milestone in &escrow.milestones {
+            released = released.saturating_add(milestone.amount.saturating_sub(milestone.remaining));
+            if milestone.forfeited {
+                forfeited = forfeited.saturating_add(milestone.remaining);
+            } else {
+                unreleased = unreleased.saturating_add(milestone.remaining);
+            }
+        }
+
+        Settlement {
+            released,
+            unreleased,
+            forfeited,
+            refundable: escrow.deposited.saturating_sub(released).saturating_sub(forfeited),
+        }
+    }
+
+    /// Fail with `InvalidArbiter` if the escrow's arbiter is also one of its parties
+    ///
+    /// Checked again right before an arbiter moves funds, so a party can never
+    /// settle its own dispute however the roles came to overlap.
+    fn validate_roles_distinct(escrow: &Escrow) -> Result<()> {
+        if escrow.arbiter.is_some_and(|arbiter| arbiter == escrow.client || arbiter == escrow.freelancer) {
+            return Err(EscrowError::InvalidArbiter);
+        }
+        Ok(())
+    }
+
+    /// Whether `milestone`'s deadline, if any, is later than every deadline in `earlier`
+    fn deadline_follows<'a>(earlier: impl IntoIterator<Item = &'a Milestone>, milestone: &Milestone) -> bool {
+        let Some(deadline) = milestone.deadline else {
+            return true;
+        };
+        earlier.into_iter().filter_map(|m| m.deadline).all(|earlier| earlier < deadline)
+    }
+
+    // ========================================
+    // VALIDATION
+    // ========================================
+
+    /// Failed validation step, shared by messages and their simulators
+    #[derive(Debug, Clone, Copy)]
+    struct CheckFailure {
+        /// Error the message returns
+        error: EscrowError,
+        /// Step that failed
+        check: ValidationCheck,
+        /// Statuses accepted by a failed status check
+        expected_status: &'static [EscrowStatus],
+        /// Amounts compared by a failed amount check
+        amounts: Option<(Balance, Balance)>,
+    }
+
+    impl CheckFailure {
+        fn new(error: EscrowError, check: ValidationCheck) -> Self {
+            Self { error, check, expected_status: &[], amounts: None }
+        }
+
+        fn status(expected_status: &'static [EscrowStatus]) -> Self {
+            Self { expected_status, ..Self::new(EscrowError::InvalidStatus, ValidationCheck::Status) }
+        }
+    }
+
+    /// Result of a shared validation function
+    type Checked<T> = core::result::Result<T, CheckFailure>;
+
+    // ========================================
+    // CONTRACT IMPLEMENTATION
+    // ========================================
+
+    impl Default for EscrowMultiRelease {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl EscrowMultiRelease {
+        /// Constructor that initializes the contract, with the caller as owner
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                escrows: Mapping::default(),
+                next_escrow_id: 0,
+                client_escrows: Mapping::default(),
+                freelancer_escrows: Mapping::default(),
+                arbiter_escrows: Mapping::default(),
+                deposits_outstanding: 0,
+                collected_fees: 0,
+                insurance_pool: 0,
+                shortfalls: Mapping::default(),
+                collected_token_fees: Mapping::default(),
+                sum_fees_charged: 0,
+                owner: Self::env().caller(),
+                settings: EscrowSettings {
+                    amendment_window_ms: DEFAULT_AMENDMENT_WINDOW_MS,
+                    ..EscrowSettings::default()
+                },
+                progress_notes: Mapping::default(),
+                exempt_clients: Mapping::default(),
+                salted_escrows: Mapping::default(),
+                idempotency_keys: Mapping::default(),
+                ledger: Mapping::default(),
+                total_earned: Mapping::default(),
+                total_spent: Mapping::default(),
+                sum_total_earned: 0,
+                sum_total_spent: 0,
+                amendments: Mapping::default(),
+                amendment_counts: Mapping::default(),
+                deadline_extensions: Mapping::default(),
+                extension_grace: Mapping::default(),
+                drawdowns: Mapping::default(),
+                timesheets: Mapping::default(),
+                payout_totals: Mapping::default(),
+                closed_at: Mapping::default(),
+                ratings: Mapping::default(),
+                rating_summaries: Mapping::default(),
+                evidence: Mapping::default(),
+            }
+        }
+
+        /// Constructor that also sets the protocol fee on milestone releases
+        ///
+        /// # Arguments
+        /// * `fee_bps` - Fee in basis points, at most `MAX_FEE_BPS`
+        ///
+        /// # Errors
+        /// * `FeeTooHigh` - If the fee exceeds `MAX_FEE_BPS`
+        #[ink(constructor)]
+        pub fn new_with_fee(fee_bps: u16) -> Result<Self> {
+            if fee_bps > MAX_FEE_BPS {
+                return Err(EscrowError::FeeTooHigh);
+            }
+            let mut contract = Self::new();
+            contract.settings.fee_bps = fee_bps;
+            Ok(contract)
+        }
+
+        /// Create a new escrow with milestones
+        ///
+        /// A wallet retrying a timed-out submission passes the same
+        /// `idempotency_key` again and gets the escrow created the first time,
+        /// without creating another one. Keys are scoped to the caller.
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account of the freelancer who will receive payments
+        /// * `milestones` - Vector of milestones defining payment structure
+        /// * `arbiter` - Optional arbiter account for dispute resolution
+        /// * `idempotency_key` - Optional key identifying this creation request
+        ///
+        /// # Returns
+        /// * `u64` - The ID of the new escrow, or of the escrow already created
+        ///   by the caller with `idempotency_key`
+        ///
+        /// # Errors
+        /// * `EmptyMilestones` - If milestones vector is empty
+        /// * `TooManyMilestones` - If there are more than `MAX_MILESTONES` milestones
+        /// * `ZeroAmount` - If total amount of milestones is zero
+        /// * `ExceedsMaxEscrow` - If the total is above the maximum escrow amount and the client is not exempt
+        /// * `InvalidDependencies` - If the milestone dependencies are invalid
+        ///
+        /// # Events
+        /// * `EscrowCreated` - Emitted when escrow is successfully created, not on a retry
+        /// * `MilestonesSnapshot` - Emitted right after with the milestone IDs and amounts
+        #[ink(message)]
+        pub fn create_escrow(
+            &mut self,
+            freelancer: AccountId,
+            milestones: Vec<Milestone>,
+            arbiter: Option<AccountId>,
+            idempotency_key: Option<IdempotencyKey>,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            if let Some(escrow_id) = idempotency_key.and_then(|key| self.idempotency_keys.get((caller, key))) {
+                return Ok(escrow_id);
+            }
+
+            let escrow_id =
+                self.create_escrow_with_options(freelancer, milestones, arbiter, EscrowOptions::default())?;
+            if let Some(key) = idempotency_key {
+                self.idempotency_keys.insert((caller, key), &escrow_id);
+            }
+
+            Ok(escrow_id)
+        }
+
+        /// Create a new escrow with milestones and optional settings
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account of the freelancer who will receive payments
+        /// * `milestones` - Vector of milestones defining payment structure
+        /// * `arbiter` - Optional arbiter account for dispute resolution
+        /// * `options` - Optional creation-time settings
+        ///
+        /// # Errors
+        /// * `EmptyMilestones` - If milestones vector is empty
+        /// * `TooManyMilestones` - If there are more than `MAX_MILESTONES` milestones
+        /// * `ZeroAmount` - If total amount of milestones is zero
+        /// * `ExceedsMaxEscrow` - If the total is above the maximum escrow amount and the client is not exempt
+        /// * `InvalidDependencies` - If a milestone depends on itself, a later or repeated milestone,
+        ///   or more than `MAX_MILESTONE_DEPENDENCIES` milestones
+        /// * `InvalidTokenMetadata` - If the display token metadata is out of bounds
+        /// * `InvalidBps` - If the abandonment split exceeds `BPS_DENOMINATOR`
+        /// * `InvalidEscalationThreshold` - If the escalation threshold is zero
+        ///
+        /// # Events
+        /// * `EscrowCreated` - Emitted when escrow is successfully created
+        /// * `MilestonesSnapshot` - Emitted right after with the milestone IDs and amounts
+        #[ink(message)]
+        pub fn create_escrow_with_options(
+            &mut self,
+            freelancer: AccountId,
+            milestones: Vec<Milestone>,
+            arbiter: Option<AccountId>,
+            options: EscrowOptions,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+
+            // Validate milestones
+            if let Some(issue) = self.milestone_issues(caller, &milestones).first() {
+                return Err(issue.error);
+            }
+
+            let mut milestones = milestones;
+            reset_remaining(&mut milestones);
+            let total_amount: Balance = milestones
+                .iter()
+                .map(|m| m.amount)
+                .sum();
+
+            // Validate display token metadata
+            if let Some(symbol) = &options.token_symbol {
+                if symbol.is_empty() || symbol.len() > MAX_TOKEN_SYMBOL_LEN {
+                    return Err(EscrowError::InvalidTokenMetadata);
+                }
+            }
+            if options.token_decimals.is_some_and(|decimals| decimals > MAX_TOKEN_DECIMALS) {
+                return Err(EscrowError::InvalidTokenMetadata);
+            }
+
+            // Only an arbiter can earn an arbiter fee
+            if options.arbiter_fee > 0 && arbiter.is_none() {
+                return Err(EscrowError::InvalidArbiter);
+            }
+
+            // Validate abandonment split
+            if options.abandonment_freelancer_bps > BPS_DENOMINATOR {
+                return Err(EscrowError::InvalidBps);
+            }
+
+            // Validate escalation threshold
+            let escalation_threshold = options.escalation_threshold.unwrap_or(DEFAULT_ESCALATION_THRESHOLD);
+            if escalation_threshold == 0 {
+                return Err(EscrowError::InvalidEscalationThreshold);
+            }
+
+            let escrow = self.insert_escrow(freelancer, milestones, total_amount, arbiter, options, escalation_threshold);
+            self.emit_milestones_snapshot(&escrow);
+
+            Ok(escrow.id)
+        }
+
+        /// Store a validated escrow for the caller, index it and emit `EscrowCreated`
+        fn insert_escrow(
+            &mut self,
+            freelancer: AccountId,
+            milestones: Vec<Milestone>,
+            total_amount: Balance,
+            arbiter: Option<AccountId>,
+            options: EscrowOptions,
+            escalation_threshold: u8,
+        ) -> Escrow {
+            let caller = self.env().caller();
+            let escrow_id = self.next_escrow_id;
+            let created_at = self.env().block_timestamp();
+
+            let escrow = Escrow {
+                id: escrow_id,
+                client: caller,
+                freelancer,
+                arbiter,
+                total_amount,
+                deposited: 0,
+                milestones,
+                released_count: 0,
+                forfeited_count: 0,
+                status: EscrowStatus::PendingAcceptance,
+                cancel_requested_by: None,
+                created_at,
+                funded_at: None,
+                privacy: options.privacy,
+                privacy_proposal: None,
+                last_client_activity_at: None,
+                abandonment_timeout_ms: options.abandonment_timeout_ms,
+                abandonment_freelancer_bps: options.abandonment_freelancer_bps,
+                deadlock_timeout_ms: options.deadlock_timeout_ms,
+                deadlock_policy: options.deadlock_policy,
+                cancel_requested_at: None,
+                disputed_by: None,
+                dispute_reason: None,
+                termination_reason: None,
+                escalation_threshold,
+                auto_release_threshold: 0,
+                strict_confirmation: options.strict_confirmation,
+                refund_to: None,
+                token_symbol: options.token_symbol,
+                token_decimals: options.token_decimals,
+                funding_deadline: options.funding_deadline,
+                require_submission_before_release: options.require_submission_before_release,
+                token: options.token,
+                fee_bps: self.settings.fee_bps,
+                arbiter_fee: options.arbiter_fee,
+                arbiter_proposal: None,
+                payout_address: None,
+            };
+
+            // Store escrow
+            self.escrows.insert(escrow_id, &escrow);
+
+            // Add to client's escrows list
+            let mut client_list = self.client_escrows.get(caller).unwrap_or_default();
+            client_list.push(escrow_id);
+            self.client_escrows.insert(caller, &client_list);
+
+            // Add to freelancer's escrows list
+            let mut freelancer_list = self.freelancer_escrows.get(escrow.freelancer).unwrap_or_default();
+            freelancer_list.push(escrow_id);
+            self.freelancer_escrows.insert(escrow.freelancer, &freelancer_list);
+
+            // Add to arbiter's escrows list
+            if let Some(arbiter) = escrow.arbiter {
+                let mut arbiter_list = self.arbiter_escrows.get(arbiter).unwrap_or_default();
+                arbiter_list.push(escrow_id);
+                self.arbiter_escrows.insert(arbiter, &arbiter_list);
+            }
+
+            // Increment escrow counter
+            self.next_escrow_id += 1;
+
+            // Emit event
+            self.env().emit_event(EscrowCreated {
+                escrow_id,
+                client: caller,
+                freelancer: escrow.freelancer,
+                arbiter: escrow.arbiter,
+                total_amount,
+                token_symbol: escrow.token_symbol.clone(),
+                token_decimals: escrow.token_decimals,
+                privacy: escrow.privacy,
+                token: escrow.token,
+                arbiter_fee: escrow.arbiter_fee,
+            });
+
+            escrow
+        }
+
+        /// Create a new escrow whose key the client can compute before the call
+        ///
+        /// The key is `compute_escrow_id(client, salt)` and resolves to the
+        /// sequential escrow ID through `get_escrow_by_salt`.
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account of the freelancer who will receive payments
+        /// * `milestones` - Vector of milestones defining payment structure
+        /// * `arbiter` - Optional arbiter account for dispute resolution
+        /// * `salt` - Client-chosen salt, usable once per client
+        ///
+        /// # Errors
+        /// * `SaltAlreadyUsed` - If the client already created an escrow with this salt
+        /// * Any error of `create_escrow`
+        ///
+        /// # Events
+        /// * `EscrowCreated` - Emitted when escrow is successfully created
+        /// * `MilestonesSnapshot` - Emitted right after with the milestone IDs and amounts
+        #[ink(message)]
+        pub fn create_escrow_with_salt(
+            &mut self,
+            freelancer: AccountId,
+            milestones: Vec<Milestone>,
+            arbiter: Option<AccountId>,
+            salt: [u8; 32],
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            let key = self.compute_escrow_id(caller, salt);
+            if self.salted_escrows.contains(key) {
+                return Err(EscrowError::SaltAlreadyUsed);
+            }
+
+            let escrow_id = self.create_escrow(freelancer, milestones, arbiter, None)?;
+            self.salted_escrows.insert(key, &escrow_id);
+
+            Ok(escrow_id)
+        }
+
+        /// Deposit funds into an escrow, in one call or over several
+        ///
+        /// Every deposit adds the transferred value to `deposited`. The escrow
+        /// stays `Created` until the deposits reach `total_amount`, then becomes
+        /// `Funded`. Value beyond what is still missing is sent back to the caller.
+        ///
+        /// Token-denominated escrows take no value: the missing amount is pulled
+        /// from the caller with `PSP22::transfer_from` in one deposit, so the
+        /// client must approve the contract for it first.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to fund
+        /// * `refund_to` - Account refunds are paid to (None = the client, or
+        ///   the account given with an earlier deposit)
+        /// * `expected_total` - Total the funder signed for (None = don't check)
+        /// * `expected_milestone_count` - Milestone count the funder signed for (None = don't check)
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `AlreadyFunded` - If escrow was fully funded before; the transferred
+        ///   value is sent back to the caller
+        /// * `InvalidStatus` - If escrow is not in Created status, a cancellation is pending or its
+        ///   funding deadline has passed
+        /// * `ZeroAmount` - If no value is transferred
+        /// * `InvalidAmount` - If value is transferred to a token-denominated escrow
+        /// * `InsufficientFunds` - If refunding a repeated funding or an overpayment fails
+        /// * `TermsChanged` - If the escrow no longer matches a given expectation
+        /// * `TokenTransferFailed` - If pulling the tokens failed, e.g. for a missing allowance
+        ///
+        /// # Events
+        /// * `OverpaymentRefunded` - Emitted first if more than the missing amount was sent
+        /// * `EscrowFunded` - Emitted for every deposit
+        #[ink(message, payable)]
+        pub fn fund_escrow(
+            &mut self,
+            escrow_id: u64,
+            refund_to: Option<AccountId>,
+            expected_total: Option<Balance>,
+            expected_milestone_count: Option<u32>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            let transferred = self.env().transferred_value();
+
+            let mut escrow = match self.check_fund(escrow_id, caller, transferred) {
+                Ok(escrow) => escrow,
+                // A retried funding must not leave its value with the contract
+                Err(failure) if failure.error == EscrowError::AlreadyFunded => {
+                    if transferred > 0 && self.env().transfer(caller, transferred).is_err() {
+                        return Err(EscrowError::InsufficientFunds);
+                    }
+                    return Err(EscrowError::AlreadyFunded);
+                }
+                Err(failure) => return Err(failure.error),
+            };
+
+            // Don't lock funds against terms the funder never saw
+            if expected_total.is_some_and(|total| total != escrow.total_amount)
+                || expected_milestone_count.is_some_and(|count| count as usize != escrow.milestones.len())
+            {
+                return Err(EscrowError::TermsChanged);
+            }
+
+            // Credit the deposit up to what is still missing and send back the rest
+            let missing = funding_target(&escrow).saturating_sub(escrow.deposited);
+            let amount = if escrow.token.is_some() { missing } else { transferred.min(missing) };
+            let overpayment = transferred.saturating_sub(amount);
+            if overpayment > 0 {
+                if self.env().transfer(caller, overpayment).is_err() {
+                    return Err(EscrowError::InsufficientFunds);
+                }
+                self.env().emit_event(OverpaymentRefunded {
+                    escrow_id,
+                    amount: overpayment,
+                });
+            }
+
+            let now = self.env().block_timestamp();
+            escrow.deposited = escrow.deposited.saturating_add(amount);
+            escrow.last_client_activity_at = Some(now);
+            if refund_to.is_some() {
+                escrow.refund_to = refund_to;
+            }
+            if escrow.deposited >= funding_target(&escrow) {
+                escrow.status = EscrowStatus::Funded;
+                escrow.funded_at = Some(now);
+            }
+            self.escrows.insert(escrow_id, &escrow);
+            self.record_deposit(&escrow, amount);
+            self.collect_tokens(&escrow, caller, amount)?;
+
+            // Emit event
+            self.env().emit_event(EscrowFunded {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                amount,
+                deposited: escrow.deposited,
+                refund_to: escrow.refund_to,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Expire an escrow that was not fully funded by its funding deadline
+        ///
+        /// Anyone can call this once the deadline has passed, so lapsed escrows
+        /// stop showing up as pending for the parties. Partial deposits are
+        /// refunded to the client's refund address.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `InvalidStatus` - If escrow is not in PendingAcceptance or Created status or has no
+        ///   funding deadline
+        /// * `TimeoutNotReached` - If the funding deadline has not passed
+        ///
+        /// # Events
+        /// * `EscrowExpired` - Emitted when the escrow is expired
+        #[ink(message)]
+        pub fn expire_escrow(&mut self, escrow_id: u64) -> Result<()> {
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            if !matches!(escrow.status, EscrowStatus::PendingAcceptance | EscrowStatus::Created) {
+                return Err(EscrowError::InvalidStatus);
+            }
+            let Some(deadline) = escrow.funding_deadline else {
+                return Err(EscrowError::InvalidStatus);
+            };
+            if self.env().block_timestamp() <= deadline {
+                return Err(EscrowError::TimeoutNotReached);
+            }
+
+            // Return whatever was deposited so far
+            let settlement = settlement(&escrow);
+            let refunded = if settlement.refundable > 0 {
+                self.pay_out_insured(&escrow, Payee::Client, settlement.refundable)?
+            } else {
+                0
+            };
+
+            escrow.status = EscrowStatus::Expired;
+            escrow.termination_reason = Some(TerminationReason::Expired);
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(EscrowExpired {
+                escrow_id,
+                refund_to_client: refunded,
+                snapshot_hash,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Release payment for a specific milestone
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone to release
+        ///
+        /// The freelancer may also release milestones whose amount does not
+        /// exceed the escrow's auto-release threshold.
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client, or the freelancer
+        ///   releases a milestone above the auto-release threshold
+        /// * `InvalidStatus` - If escrow is not in Funded or Disputed status
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If milestone was already released
+        /// * `MilestoneForfeited` - If the client reclaimed the milestone
+        /// * `MilestoneDisputed` - If the milestone awaits the arbiter's ruling
+        /// * `MilestoneNotSubmitted` - If the escrow requires a submission and the freelancer has not submitted
+        /// * `DependenciesNotMet` - If a milestone it depends on is not released yet
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the milestone amount
+        /// * `InsufficientFunds` - If the transfer to the freelancer fails
+        ///
+        /// # Events
+        /// * `MilestoneReleased` - Emitted when milestone is successfully released
+        /// * `EscrowCompleted` - Emitted if it was the last open milestone
+        #[ink(message)]
+        pub fn release_milestone(&mut self, escrow_id: u64, milestone_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+
+            let (mut escrow, milestone_index) =
+                self.check_release(escrow_id, milestone_id, caller).map_err(|f| f.error)?;
+            if escrow.client == caller {
+                escrow.last_client_activity_at = Some(self.env().block_timestamp());
+            }
+            self.pay_milestone(escrow, milestone_index)?;
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Pay part of a milestone to the freelancer (only by the client)
+        ///
+        /// Lowers the milestone's `remaining` amount. The milestone counts as
+        /// released once nothing remains, and the escrow completes if it was
+        /// the last open one. A later `release_milestone` pays what remains.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `amount` - Part of the milestone to pay, before fees
+        ///
+        /// # Errors
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidAmount` - If `amount` is zero or exceeds the milestone's remaining amount
+        /// * Any other error of `release_milestone`
+        ///
+        /// # Events
+        /// * `MilestonePartiallyReleased` - Emitted for the payment
+        /// * `EscrowCompleted` - Emitted if it settled the last open milestone
+        #[ink(message)]
+        pub fn release_partial(&mut self, escrow_id: u64, milestone_id: u32, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+
+            let (mut escrow, milestone_index) =
+                self.check_release(escrow_id, milestone_id, caller).map_err(|f| f.error)?;
+
+            // The auto-release allowance covers whole milestones only
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            let milestone = &mut escrow.milestones[milestone_index];
+            if amount == 0 || amount > milestone.remaining {
+                return Err(EscrowError::InvalidAmount);
+            }
+            milestone.remaining = milestone.remaining.saturating_sub(amount);
+            let remaining = milestone.remaining;
+            if remaining == 0 {
+                milestone.released = true;
+                escrow.released_count = escrow.released_count.saturating_add(1);
+                self.clear_deadline_extension(escrow_id, milestone_id);
+            }
+            escrow.last_client_activity_at = Some(self.env().block_timestamp());
+
+            let breakdown = payout_breakdown(amount, escrow.fee_bps);
+            self.pay_out(&escrow, Payee::Freelancer, breakdown.net)?;
+            self.collect_fee(&escrow, breakdown.protocol_fee)?;
+
+            if escrow.all_settled() {
+                escrow.status = EscrowStatus::Completed;
+            }
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, amount);
+
+            self.env().emit_event(MilestonePartiallyReleased {
+                escrow_id,
+                milestone_id,
+                amount: breakdown.gross,
+                protocol_fee: breakdown.protocol_fee,
+                net_to_freelancer: breakdown.net,
+                remaining,
+            });
+            if escrow.status == EscrowStatus::Completed {
+                self.complete_escrow(&mut escrow)?;
+            }
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Release several milestones in one call
+        ///
+        /// Every milestone is checked as in `release_milestone` before anything
+        /// is paid, in the order given, so a milestone may depend on one listed
+        /// earlier in the batch. The net amounts are then paid in a single
+        /// transfer: either all milestones are released or none is.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_ids` - IDs of the milestones to release
+        ///
+        /// # Errors
+        /// * `BatchTooLarge` - If more than `MAX_BATCH_RELEASE` milestones are listed
+        /// * `MilestoneNotFound` - If the batch is empty or a milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If a milestone was already released or
+        ///   is listed twice
+        /// * Any other error of `release_milestone`
+        ///
+        /// # Events
+        /// * `MilestoneReleased` - Emitted for each milestone, in batch order
+        /// * `EscrowCompleted` - Emitted if the batch released the last open milestones
+        #[ink(message)]
+        pub fn release_milestones(&mut self, escrow_id: u64, milestone_ids: Vec<u32>) -> Result<()> {
+            let caller = self.env().caller();
+
+            if milestone_ids.len() > MAX_BATCH_RELEASE {
+                return Err(EscrowError::BatchTooLarge);
+            }
+            if milestone_ids.is_empty() {
+                return Err(EscrowError::MilestoneNotFound);
+            }
+
+            let mut escrow = self.check_release_escrow(escrow_id, caller).map_err(|f| f.error)?;
+
+            // Validate against a working copy, so repeated IDs fail as released
+            let mut released: Vec<(u32, PayoutBreakdown)> = Vec::new();
+            for &milestone_id in &milestone_ids {
+                let milestone_index =
+                    Self::check_release_milestone(&escrow, milestone_id, caller).map_err(|f| f.error)?;
+                let milestone = &mut escrow.milestones[milestone_index];
+                released.push((milestone_id, payout_breakdown(milestone.remaining, escrow.fee_bps)));
+                milestone.released = true;
+                milestone.remaining = 0;
+            }
+            escrow.released_count = escrow.released_count.saturating_add(released.len() as u32);
+            if escrow.client == caller {
+                escrow.last_client_activity_at = Some(self.env().block_timestamp());
+            }
+
+            let amount: Balance = released.iter().map(|(_, breakdown)| breakdown.gross).sum();
+            let net: Balance = released.iter().map(|(_, breakdown)| breakdown.net).sum();
+            let fee: Balance = released.iter().map(|(_, breakdown)| breakdown.protocol_fee).sum();
+
+            self.pay_out(&escrow, Payee::Freelancer, net)?;
+            self.collect_fee(&escrow, fee)?;
+
+            if escrow.all_settled() {
+                escrow.status = EscrowStatus::Completed;
+            }
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, amount);
+
+            for (milestone_id, breakdown) in released {
+                self.clear_deadline_extension(escrow_id, milestone_id);
+                self.env().emit_event(MilestoneReleased {
+                    escrow_id,
+                    client: escrow.client,
+                    freelancer: escrow.freelancer,
+                    milestone_id,
+                    amount: breakdown.gross,
+                    protocol_fee: breakdown.protocol_fee,
+                    net_to_freelancer: breakdown.net,
+                });
+            }
+            if escrow.status == EscrowStatus::Completed {
+                self.complete_escrow(&mut escrow)?;
+            }
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Release every unreleased milestone to the freelancer and complete
+        /// the escrow (only by the client)
+        ///
+        /// The outstanding amount is paid in a single transfer. Dependencies
+        /// are not checked since all milestones are released together.
+        /// Refunding the rest to the client instead needs the freelancer's
+        /// consent, through `request_cancel` and `approve_cancel`.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `MilestoneDisputed` - If a milestone awaits the arbiter's ruling
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the outstanding amount
+        /// * `InsufficientFunds` - If the transfer to the freelancer fails
+        ///
+        /// # Events
+        /// * `MilestoneReleased` - Emitted for each milestone released by this call
+        /// * `EscrowCompleted` - Emitted once all milestones are released
+        #[ink(message)]
+        pub fn settle_remaining(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Disputes go through the arbiter or the cancellation flow
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // The unused cap of a drawdown is not owed to the freelancer
+            if self.drawdowns.contains(escrow_id) {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // The arbiter rules on escalated milestones
+            if escrow.milestones.iter().any(|m| m.disputed) {
+                return Err(EscrowError::MilestoneDisputed);
+            }
+
+            let outstanding: Vec<(u32, PayoutBreakdown)> = escrow
+                .milestones
+                .iter()
+                .filter(|m| !m.is_settled())
+                .map(|m| (m.id, payout_breakdown(m.remaining, escrow.fee_bps)))
+                .collect();
+            let amount: Balance = outstanding.iter().map(|(_, breakdown)| breakdown.gross).sum();
+            let net: Balance = outstanding.iter().map(|(_, breakdown)| breakdown.net).sum();
+            let fee: Balance = outstanding.iter().map(|(_, breakdown)| breakdown.protocol_fee).sum();
+
+            self.pay_out(&escrow, Payee::Freelancer, net)?;
+            self.collect_fee(&escrow, fee)?;
+
+            for milestone in escrow.milestones.iter_mut().filter(|m| !m.forfeited) {
+                milestone.released = true;
+                milestone.remaining = 0;
+            }
+            escrow.released_count = (escrow.milestones.len() as u32).saturating_sub(escrow.forfeited_count);
+            escrow.status = EscrowStatus::Completed;
+            escrow.last_client_activity_at = Some(self.env().block_timestamp());
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, amount);
+
+            for (milestone_id, breakdown) in outstanding {
+                self.clear_deadline_extension(escrow_id, milestone_id);
+                self.env().emit_event(MilestoneReleased {
+                    escrow_id,
+                    client: escrow.client,
+                    freelancer: escrow.freelancer,
+                    milestone_id,
+                    amount: breakdown.gross,
+                    protocol_fee: breakdown.protocol_fee,
+                    net_to_freelancer: breakdown.net,
+                });
+            }
+            self.complete_escrow(&mut escrow)?;
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Ask the client for a later milestone deadline (only by the freelancer)
+        ///
+        /// Must be requested before the current deadline. A milestone has at
+        /// most one pending request; the client answers it with
+        /// `approve_deadline_extension` or `reject_deadline_extension`. Unless
+        /// the client approves, `reclaim_expired_milestone` then waits
+        /// `DEADLINE_EXTENSION_GRACE_MS` past the deadline, so the client
+        /// cannot race the expiry, even by rejecting the request first.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `new_deadline` - Requested deadline
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status or the milestone has no deadline
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` / `MilestoneForfeited` - If milestone was released or reclaimed
+        /// * `DeadlinePassed` - If the current deadline has passed
+        /// * `InvalidMilestoneDeadlines` - If `new_deadline` is not later than the current
+        ///   deadline, or not earlier than the deadlines of later milestones
+        /// * `ExtensionAlreadyPending` - If the milestone already has a pending request
+        ///
+        /// # Events
+        /// * `DeadlineExtensionRequested` - Emitted when the request is stored
+        #[ink(message)]
+        pub fn request_deadline_extension(&mut self, escrow_id: u64, milestone_id: u32, new_deadline: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone = escrow
+                .milestones
+                .iter()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+            let deadline = milestone.deadline.ok_or(EscrowError::InvalidStatus)?;
+            if self.env().block_timestamp() > deadline {
+                return Err(EscrowError::DeadlinePassed);
+            }
+
+            // Deadlines stay strictly increasing across milestones
+            let overtakes_later = escrow
+                .milestones
+                .iter()
+                .filter(|m| m.id > milestone_id)
+                .filter_map(|m| m.deadline)
+                .any(|later| later <= new_deadline);
+            if new_deadline <= deadline || overtakes_later {
+                return Err(EscrowError::InvalidMilestoneDeadlines);
+            }
+
+            if self.deadline_extensions.contains((escrow_id, milestone_id)) {
+                return Err(EscrowError::ExtensionAlreadyPending);
+            }
+
+            let requested_at = self.env().block_timestamp();
+            self.deadline_extensions
+                .insert((escrow_id, milestone_id), &DeadlineExtension { new_deadline, requested_at });
+            self.extension_grace.insert(
+                (escrow_id, milestone_id),
+                &deadline.saturating_add(DEADLINE_EXTENSION_GRACE_MS),
+            );
+
+            self.env().emit_event(DeadlineExtensionRequested {
+                escrow_id,
+                milestone_id,
+                new_deadline,
+            });
+
+            Ok(())
+        }
+
+        /// Approve the pending deadline extension of a milestone (only by the client)
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status or no extension is pending
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` / `MilestoneForfeited` - If milestone was released or reclaimed
+        ///
+        /// # Events
+        /// * `DeadlineExtended` - Emitted with the old and new deadline
+        #[ink(message)]
+        pub fn approve_deadline_extension(&mut self, escrow_id: u64, milestone_id: u32) -> Result<()> {
+            let mut escrow = self.check_extension_answer(escrow_id, milestone_id)?;
+            let extension =
+                self.deadline_extensions.take((escrow_id, milestone_id)).ok_or(EscrowError::InvalidStatus)?;
+            // The new deadline replaces the grace on the old one
+            self.extension_grace.remove((escrow_id, milestone_id));
+
+            let milestone = escrow
+                .milestones
+                .iter_mut()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+            let old = milestone.deadline.unwrap_or_default();
+            milestone.deadline = Some(extension.new_deadline);
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(DeadlineExtended {
+                escrow_id,
+                milestone_id,
+                old,
+                new: extension.new_deadline,
+            });
+
+            Ok(())
+        }
+
+        /// Turn down the pending deadline extension of a milestone (only by the client)
+        ///
+        /// The freelancer may ask again while the deadline has not passed. The
+        /// reclaim grace of the request still applies, so rejecting it does
+        /// not let the client reclaim the milestone any earlier.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        ///
+        /// # Errors
+        /// * Same as `approve_deadline_extension`
+        ///
+        /// # Events
+        /// * `DeadlineExtensionRejected` - Emitted when the request is cleared
+        #[ink(message)]
+        pub fn reject_deadline_extension(&mut self, escrow_id: u64, milestone_id: u32) -> Result<()> {
+            self.check_extension_answer(escrow_id, milestone_id)?;
+            self.deadline_extensions
+                .take((escrow_id, milestone_id))
+                .ok_or(EscrowError::InvalidStatus)?;
+
+            self.env().emit_event(DeadlineExtensionRejected { escrow_id, milestone_id });
+
+            Ok(())
+        }
+
+        /// Get the pending deadline extension of a milestone
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        ///
+        /// # Returns
+        /// * `Option<DeadlineExtension>` - The pending request, if any
+        #[ink(message)]
+        pub fn get_deadline_extension(&self, escrow_id: u64, milestone_id: u32) -> Option<DeadlineExtension> {
+            self.deadline_extensions.get((escrow_id, milestone_id))
+        }
+
+        /// Reclaim an unreleased milestone whose deadline has passed (client only)
+        ///
+        /// Keeps the client from being stuck when the freelancer disappears
+        /// mid-project. The milestone amount is refunded to the client's refund
+        /// address and the milestone is marked forfeited, so it can no longer be
+        /// released. Once no milestone is left open, the escrow is cancelled.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone to reclaim
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status or the milestone has no deadline
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If milestone was already released
+        /// * `MilestoneForfeited` - If milestone was already reclaimed
+        /// * `MilestoneDisputed` - If the milestone awaits the arbiter's ruling
+        /// * `TimeoutNotReached` - If the milestone deadline has not passed, or the
+        ///   milestone had an extension request and its reclaim grace has not ended
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the milestone amount
+        ///
+        /// # Events
+        /// * `MilestoneReclaimed` - Emitted when the milestone is reclaimed
+        /// * `EscrowCancelled` - Emitted if it was the last open milestone
+        #[ink(message)]
+        pub fn reclaim_expired_milestone(&mut self, escrow_id: u64, milestone_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Disputed escrows are settled by the arbiter
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone_index = escrow
+                .milestones
+                .iter()
+                .position(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+            let milestone = &escrow.milestones[milestone_index];
+
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+            if milestone.disputed {
+                return Err(EscrowError::MilestoneDisputed);
+            }
+            let deadline = milestone.deadline.ok_or(EscrowError::InvalidStatus)?;
+            if now <= deadline {
+                return Err(EscrowError::TimeoutNotReached);
+            }
+
+            // A pending or rejected extension request holds off the reclaim until its grace ends
+            if self
+                .extension_grace
+                .get((escrow_id, milestone_id))
+                .is_some_and(|grace_end| now <= grace_end)
+            {
+                return Err(EscrowError::TimeoutNotReached);
+            }
+            self.clear_deadline_extension(escrow_id, milestone_id);
+
+            // Partial releases already paid stay with the freelancer
+            let refund = milestone.remaining;
+            let refunded = self.pay_out_insured(&escrow, Payee::Client, refund)?;
+
+            escrow.milestones[milestone_index].forfeited = true;
+            escrow.forfeited_count = escrow.forfeited_count.saturating_add(1);
+            escrow.last_client_activity_at = Some(now);
+            let closed = escrow.all_settled();
+            // Closing without a dispute hands the arbiter fee back too
+            let arbiter_fee = if closed { arbiter_fee_held(&escrow) } else { 0 };
+            if closed {
+                escrow.status = EscrowStatus::Cancelled;
+                escrow.termination_reason = Some(TerminationReason::Expired);
+            }
+            escrow.deposited = escrow.deposited.saturating_sub(arbiter_fee);
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, refund);
+            let fee_refunded = if arbiter_fee > 0 {
+                self.settle_deposit(&escrow, arbiter_fee);
+                self.pay_out_insured(&escrow, Payee::Client, arbiter_fee)?
+            } else {
+                0
+            };
+
+            self.env().emit_event(MilestoneReclaimed {
+                escrow_id,
+                milestone_id,
+                amount: refunded,
+            });
+            if closed {
+                let snapshot_hash = self.close_escrow(&escrow);
+                self.env().emit_event(EscrowCancelled {
+                    escrow_id,
+                    client: escrow.client,
+                    freelancer: escrow.freelancer,
+                    refund_to_client: refunded.saturating_add(fee_refunded),
+                    refund_to_freelancer: settlement(&escrow).released,
+                    freelancer_compensation: 0,
+                    snapshot_hash,
+                });
+            }
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Request cancellation of an escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to cancel
+        /// * `confirmation` - `get_confirmation_hash(escrow_id, RequestCancel)`, required on strict escrows
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not client or freelancer
+        /// * `InvalidStatus` - If escrow is in invalid status for cancellation
+        /// * `ConfirmationMismatch` - If the escrow is strict and the confirmation is wrong
+        /// * `DisputeTooEarly` - If the client requests cancellation within the dispute grace period
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payout
+        ///
+        /// # Events
+        /// * `CancelRequested` - Emitted when cancellation is requested
+        /// * `EscrowCancelled` - Emitted if both parties requested (mutual cancellation)
+        #[ink(message)]
+        pub fn request_cancel(&mut self, escrow_id: u64, confirmation: Option<Hash>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.check_cancel(escrow_id, caller, confirmation).map_err(|f| f.error)?;
+
+            // Check if other party already requested
+            if let Some(previous_request) = escrow.cancel_requested_by {
+                if previous_request != caller {
+                    // Both parties requested - mutual cancellation
+                    let settlement = settlement(&escrow);
+
+                    // Refund the funds still held to client
+                    let refunded = if settlement.refundable > 0 {
+                        self.pay_out_insured(&escrow, Payee::Client, settlement.refundable)?
+                    } else {
+                        0
+                    };
+
+                    escrow.status = EscrowStatus::Cancelled;
+                    escrow.termination_reason = Some(TerminationReason::MutualCancel);
+                    self.settle_deposit(&escrow, settlement.refundable);
+
+                    // Emit events
+                    let snapshot_hash = self.close_escrow(&escrow);
+                    self.env().emit_event(EscrowCancelled {
+                        escrow_id,
+                        client: escrow.client,
+                        freelancer: escrow.freelancer,
+                        refund_to_client: refunded,
+                        refund_to_freelancer: settlement.released,
+                        freelancer_compensation: 0,
+                        snapshot_hash,
+                    });
+                }
+            } else {
+                // First cancellation request, the escrow carries on until the other party answers
+                escrow.cancel_requested_by = Some(caller);
+                escrow.cancel_requested_at = Some(self.env().block_timestamp());
+
+                self.env().emit_event(CancelRequested {
+                    escrow_id,
+                    requested_by: caller,
+                    client: escrow.client,
+                    freelancer: escrow.freelancer,
+                });
+            }
+
+            // Update escrow
+            self.escrows.insert(escrow_id, &escrow);
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Withdraw a pending cancellation request (only by the requester)
+        ///
+        /// For when the parties work things out off-chain. The escrow carries
+        /// on as if the request had never been made; a dispute raised in the
+        /// meantime stays with the arbiter.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `InvalidStatus` - If no cancellation request is pending
+        /// * `Unauthorized` - If caller did not request the cancellation
+        ///
+        /// # Events
+        /// * `CancelRequestWithdrawn` - Emitted when the request is cleared
+        #[ink(message)]
+        pub fn withdraw_cancel_request(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // A closed escrow has nothing pending anymore
+            let requested_by = escrow
+                .cancel_requested_by
+                .filter(|_| !escrow.status.is_terminal())
+                .ok_or(EscrowError::InvalidStatus)?;
+            if requested_by != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            escrow.cancel_requested_by = None;
+            escrow.cancel_requested_at = None;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(CancelRequestWithdrawn {
+                escrow_id,
+                withdrawn_by: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Approve cancellation (mutual agreement)
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to approve cancellation
+        /// * `confirmation` - `get_confirmation_hash(escrow_id, ApproveCancel)`, required on strict escrows
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the other party
+        /// * `InvalidStatus` - If no cancellation was requested
+        /// * `ConfirmationMismatch` - If the escrow is strict and the confirmation is wrong
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payout
+        ///
+        /// # Events
+        /// * `EscrowCancelled` - Emitted when cancellation is approved
+        #[ink(message)]
+        pub fn approve_cancel(&mut self, escrow_id: u64, confirmation: Option<Hash>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // A closed escrow cannot be cancelled again
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // Verify cancellation was requested
+            let requested_by = escrow.cancel_requested_by.ok_or(EscrowError::InvalidStatus)?;
+
+            // Verify caller is the other party
+            if requested_by == escrow.client {
+                if caller != escrow.freelancer {
+                    return Err(EscrowError::Unauthorized);
+                }
+            } else if requested_by == escrow.freelancer {
+                if caller != escrow.client {
+                    return Err(EscrowError::Unauthorized);
+                }
+            } else {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            self.ensure_confirmed(&escrow, ConfirmableAction::ApproveCancel, confirmation)?;
+
+            let settlement = settlement(&escrow);
+
+            // Refund the funds still held to client
+            let refunded = if settlement.refundable > 0 {
+                self.pay_out_insured(&escrow, Payee::Client, settlement.refundable)?
+            } else {
+                0
+            };
+
+            escrow.status = EscrowStatus::Cancelled;
+            escrow.termination_reason = Some(TerminationReason::MutualCancel);
+            self.escrows.insert(escrow_id, &escrow);
+            self.clear_deadline_extensions(&escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            // Emit event
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(EscrowCancelled {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                refund_to_client: refunded,
+                refund_to_freelancer: settlement.released,
+                freelancer_compensation: 0,
+                snapshot_hash,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Raise a dispute for the arbiter to settle (client or freelancer)
+        ///
+        /// Freezes the escrow in `Disputed` until the arbiter resolves it.
+        /// Milestones can still be released meanwhile. A pending cancellation
+        /// request is contested the same way.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `reason` - Why the party disagrees
+        /// * `confirmation` - `get_confirmation_hash(escrow_id, RaiseDispute)`, required on strict escrows
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is neither the client nor the freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `InvalidArbiter` - If the escrow has no arbiter to settle the dispute
+        /// * `ConfirmationMismatch` - If the escrow is strict and the confirmation is wrong
+        /// * `DisputeTooEarly` - If the client raises it within the dispute grace period
+        /// * `InvalidReason` - If the reason is empty or longer than `MAX_REASON_LEN`
+        ///
+        /// # Events
+        /// * `DisputeRaised` - Emitted when the escrow becomes Disputed
+        #[ink(message)]
+        pub fn raise_dispute(&mut self, escrow_id: u64, reason: String, confirmation: Option<Hash>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // Without an arbiter nobody could settle it
+            if escrow.arbiter.is_none() {
+                return Err(EscrowError::InvalidArbiter);
+            }
+
+            self.ensure_confirmed(&escrow, ConfirmableAction::RaiseDispute, confirmation)?;
+
+            // The client cannot freeze a freshly funded escrow
+            if caller == escrow.client {
+                if let Some(earliest) = self.earliest_dispute_at(&escrow) {
+                    if self.env().block_timestamp() < earliest {
+                        return Err(EscrowError::DisputeTooEarly);
+                    }
+                }
+            }
+
+            if reason.is_empty() || reason.len() > MAX_REASON_LEN {
+                return Err(EscrowError::InvalidReason);
+            }
+
+            escrow.status = EscrowStatus::Disputed;
+            escrow.disputed_by = Some(caller);
+            escrow.dispute_reason = Some(reason.clone());
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(DisputeRaised {
+                escrow_id,
+                raised_by: caller,
+                reason,
+            });
+
+            Ok(())
+        }
+
+        /// Resolve a dispute by the designated arbiter
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow in dispute
+        /// * `freelancer_share` - Amount to give to freelancer
+        /// * `client_refund` - Amount to refund to client
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the arbiter
+        /// * `InvalidStatus` - If escrow is not in Disputed status
+        /// * `InvalidArbiter` - If the escrow has no arbiter, or the arbiter is also
+        ///   the client or the freelancer
+        /// * `InvalidAmount` - If freelancer_share + client_refund + arbiter fee != funds still held
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payout
+        ///
+        /// # Events
+        /// * `DisputeResolved` - Emitted when dispute is resolved, with the arbiter fee paid
+        #[ink(message)]
+        pub fn resolve_dispute_by_arbiter(
+            &mut self,
+            escrow_id: u64,
+            freelancer_share: Balance,
+            client_refund: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the arbiter
+            let arbiter = escrow.arbiter.ok_or(EscrowError::InvalidArbiter)?;
+            if arbiter != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Verify escrow is in Disputed status
+            if escrow.status != EscrowStatus::Disputed {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // A party must never settle its own dispute
+            validate_roles_distinct(&escrow)?;
+
+            // Verify amounts sum to the funds still held (released milestones are already paid)
+            let settlement = settlement(&escrow);
+            let arbiter_fee = arbiter_fee_held(&escrow);
+            if freelancer_share.saturating_add(client_refund).saturating_add(arbiter_fee) != settlement.refundable {
+                return Err(EscrowError::InvalidAmount);
+            }
+
+            // Transfer funds; undelivered parts become the escrow's shortfall
+            let arbiter_paid = if arbiter_fee > 0 {
+                self.pay_out_insured(&escrow, Payee::Arbiter, arbiter_fee)?
+            } else {
+                0
+            };
+
+            let freelancer_paid = if freelancer_share > 0 {
+                self.pay_out_insured(&escrow, Payee::Freelancer, freelancer_share)?
+            } else {
+                0
+            };
+
+            let client_refunded = if client_refund > 0 {
+                self.pay_out_insured(&escrow, Payee::Client, client_refund)?
+            } else {
+                0
+            };
+
+            escrow.status = EscrowStatus::Resolved;
+            escrow.termination_reason = Some(TerminationReason::ArbiterDecision);
+            self.escrows.insert(escrow_id, &escrow);
+            self.clear_deadline_extensions(&escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            // Emit event
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(DisputeResolved {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                freelancer_share: freelancer_paid,
+                arbiter_fee: arbiter_paid,
+                client_refund: client_refunded,
+                snapshot_hash,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Rule on a milestone escalated by repeated rejections (only by the arbiter)
+        ///
+        /// Either releases the milestone to the freelancer as `release_milestone`
+        /// would, or sends it back so the freelancer reworks and submits it
+        /// again. Either way, further rejections never escalate it again.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the escalated milestone
+        /// * `release` - Whether to release the milestone (false = send it back for rework)
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the arbiter
+        /// * `InvalidStatus` - If escrow is not in Funded or Disputed status, or
+        ///   the milestone is not awaiting a ruling
+        /// * `InvalidArbiter` - If the escrow has no arbiter, or the arbiter is also
+        ///   the client or the freelancer
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `DependenciesNotMet` - If releasing and a milestone it depends on is not released yet
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the milestone amount
+        ///
+        /// # Events
+        /// * `MilestoneDisputeResolved` - Emitted with the ruling
+        /// * `MilestoneReleased` - Emitted if the milestone is released
+        /// * `EscrowCompleted` - Emitted if it released the last open milestone
+        #[ink(message)]
+        pub fn resolve_milestone_dispute(&mut self, escrow_id: u64, milestone_id: u32, release: bool) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the arbiter
+            let arbiter = escrow.arbiter.ok_or(EscrowError::InvalidArbiter)?;
+            if arbiter != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded && escrow.status != EscrowStatus::Disputed {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // A party must never settle its own dispute
+            validate_roles_distinct(&escrow)?;
+
+            let milestone_index = escrow
+                .milestones
+                .iter()
+                .position(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+            let milestone = &escrow.milestones[milestone_index];
+            if !milestone.disputed {
+                return Err(EscrowError::InvalidStatus);
+            }
+            if release && !dependencies_released(&escrow, milestone) {
+                return Err(EscrowError::DependenciesNotMet);
+            }
+
+            let milestone = &mut escrow.milestones[milestone_index];
+            milestone.disputed = false;
+            milestone.arbitrated = true;
+            self.env().emit_event(MilestoneDisputeResolved {
+                escrow_id,
+                milestone_id,
+                released: release,
+            });
+            if release {
+                self.pay_milestone(escrow, milestone_index)?;
+            } else {
+                self.escrows.insert(escrow_id, &escrow);
+            }
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Settle a funded escrow whose client stopped acting
+        ///
+        /// Once the client has neither funded nor released anything for the
+        /// escrow's abandonment timeout, either party can resolve it. The funds
+        /// still held are split: `abandonment_freelancer_bps` of them go to the
+        /// freelancer as compensation for unreleased work, the rest (including
+        /// any rounding remainder) back to the client.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the abandoned escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not client or freelancer
+        /// * `InvalidStatus` - If escrow is not Funded or has no abandonment timeout
+        /// * `TimeoutNotReached` - If the client was active too recently
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payout
+        ///
+        /// # Events
+        /// * `EscrowCancelled` - Emitted with the split, compensation included
+        #[ink(message)]
+        pub fn resolve_abandoned(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is client or freelancer
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Only funded escrows with a timeout can be abandoned
+            if escrow.status != EscrowStatus::Funded || escrow.abandonment_timeout_ms == 0 {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let last_activity = escrow.last_client_activity_at.ok_or(EscrowError::InvalidStatus)?;
+            if self.env().block_timestamp() < last_activity.saturating_add(escrow.abandonment_timeout_ms) {
+                return Err(EscrowError::TimeoutNotReached);
+            }
+
+            // Split the funds still held; the unearned arbiter fee goes back to the client
+            let settlement = settlement(&escrow);
+            let compensation = bps_share(
+                settlement.refundable.saturating_sub(arbiter_fee_held(&escrow)),
+                escrow.abandonment_freelancer_bps,
+            );
+            let client_refund = settlement.refundable.saturating_sub(compensation);
+
+            let compensated = if compensation > 0 {
+                self.pay_out_insured(&escrow, Payee::Freelancer, compensation)?
+            } else {
+                0
+            };
+
+            let refunded = if client_refund > 0 {
+                self.pay_out_insured(&escrow, Payee::Client, client_refund)?
+            } else {
+                0
+            };
+
+            escrow.status = EscrowStatus::Resolved;
+            escrow.termination_reason = Some(TerminationReason::Timeout);
+            self.escrows.insert(escrow_id, &escrow);
+            self.clear_deadline_extensions(&escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            // Emit event
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(EscrowCancelled {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                refund_to_client: refunded,
+                refund_to_freelancer: settlement.released,
+                freelancer_compensation: compensated,
+                snapshot_hash,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Settle an unanswered cancellation request on an escrow without arbiter
+        ///
+        /// Once the counterparty has neither approved nor countered the request
+        /// for the escrow's deadlock timeout, the requester can settle it. The
+        /// funds still held go to the client's refund address or the freelancer
+        /// according to the escrow's deadlock policy, regardless of who asked.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the deadlocked escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller did not request the cancellation
+        /// * `InvalidStatus` - If escrow is closed, has no pending cancellation request, has an
+        ///   arbiter or no deadlock timeout
+        /// * `TimeoutNotReached` - If the request is more recent than the deadlock timeout
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payout
+        ///
+        /// # Events
+        /// * `DeadlockResolved` - Emitted with the policy applied
+        #[ink(message)]
+        pub fn resolve_deadlock(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Escrows with an arbiter settle disputes through the arbiter
+            if escrow.status.is_terminal()
+                || escrow.cancel_requested_by.is_none()
+                || escrow.arbiter.is_some()
+                || escrow.deadlock_timeout_ms == 0
+            {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if escrow.cancel_requested_by != Some(caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            let requested_at = escrow.cancel_requested_at.ok_or(EscrowError::InvalidStatus)?;
+            if self.env().block_timestamp() < requested_at.saturating_add(escrow.deadlock_timeout_ms) {
+                return Err(EscrowError::TimeoutNotReached);
+            }
+
+            let settlement = settlement(&escrow);
+            let payee = match escrow.deadlock_policy {
+                DeadlockPolicy::RefundClient => Payee::Client,
+                DeadlockPolicy::PayFreelancer => Payee::Freelancer,
+            };
+            let paid = if settlement.refundable > 0 {
+                self.pay_out_insured(&escrow, payee, settlement.refundable)?
+            } else {
+                0
+            };
+
+            escrow.status = EscrowStatus::Resolved;
+            escrow.termination_reason = Some(TerminationReason::Deadlock);
+            self.escrows.insert(escrow_id, &escrow);
+            self.clear_deadline_extensions(&escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(DeadlockResolved {
+                escrow_id,
+                requested_by: caller,
+                policy: escrow.deadlock_policy,
+                amount: paid,
+                snapshot_hash,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Set the amount up to which the freelancer may release milestones (only by client)
+        ///
+        /// Takes effect immediately and can be changed until the escrow is
+        /// completed, cancelled or resolved. A threshold of 0 disables it.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `threshold` - Largest milestone amount the freelancer may release
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is Completed, Cancelled or Resolved
+        ///
+        /// # Events
+        /// * `AutoReleaseThresholdSet` - Emitted when the threshold is stored
+        #[ink(message)]
+        pub fn set_auto_release_threshold(&mut self, escrow_id: u64, threshold: Balance) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            escrow.auto_release_threshold = threshold;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(AutoReleaseThresholdSet { escrow_id, threshold });
+
+            Ok(())
+        }
+
+        /// Change where the freelancer's payouts are sent (only by freelancer)
+        ///
+        /// Applies to every later payout to the freelancer: releases, partial
+        /// releases, cancellation and dispute shares. Setting the freelancer's
+        /// own account clears the override.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `payout` - Account the freelancer's payouts are sent to
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is in a terminal status
+        /// * `InvalidPayoutAddress` - If `payout` is the client
+        ///
+        /// # Events
+        /// * `PayoutAddressChanged` - Emitted when the address is stored
+        #[ink(message)]
+        pub fn set_payout_address(&mut self, escrow_id: u64, payout: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if payout == escrow.client {
+                return Err(EscrowError::InvalidPayoutAddress);
+            }
+
+            escrow.payout_address = (payout != escrow.freelancer).then_some(payout);
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(PayoutAddressChanged {
+                escrow_id,
+                new_address: payout,
+            });
+
+            Ok(())
+        }
+
+        /// Change where refunds of a funded escrow are paid (only by client)
+        ///
+        /// Not allowed while a cancellation or dispute is pending, so the
+        /// destination cannot move under a settlement in progress.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `refund_to` - Account refunds are paid to (None = the client)
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not Funded or a cancellation is pending
+        ///
+        /// # Events
+        /// * `RefundAddressSet` - Emitted when the address is stored
+        #[ink(message)]
+        pub fn set_refund_address(&mut self, escrow_id: u64, refund_to: Option<AccountId>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded || escrow.cancel_requested_by.is_some() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            escrow.refund_to = refund_to;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(RefundAddressSet { escrow_id, refund_to });
+
+            Ok(())
+        }
+
+        /// Change the privacy setting of an escrow
+        ///
+        /// Before funding the client changes it directly. Once funded, this only
+        /// records a proposal that the other party must accept with
+        /// `accept_privacy_change`; a new proposal replaces any pending one.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `privacy` - New privacy setting
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not client or freelancer, or the
+        ///   freelancer calls before funding
+        /// * `InvalidStatus` - If escrow is Completed or Cancelled
+        ///
+        /// # Events
+        /// * `PrivacyChanged` - Emitted when the client changes it before funding
+        /// * `PrivacyChangeProposed` - Emitted when a change is proposed after funding
+        #[ink(message)]
+        pub fn propose_privacy_change(&mut self, escrow_id: u64, privacy: EscrowPrivacy) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is client or freelancer
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            match escrow.status {
+                EscrowStatus::PendingAcceptance | EscrowStatus::Created => {
+                    // Nothing is committed yet, the client decides alone
+                    if caller != escrow.client {
+                        return Err(EscrowError::Unauthorized);
+                    }
+
+                    escrow.privacy = privacy;
+                    escrow.privacy_proposal = None;
+                    self.escrows.insert(escrow_id, &escrow);
+
+                    self.env().emit_event(PrivacyChanged { escrow_id, privacy });
+                }
+                EscrowStatus::Funded | EscrowStatus::Disputed => {
+                    escrow.privacy_proposal = Some((caller, privacy));
+                    self.escrows.insert(escrow_id, &escrow);
+
+                    self.env().emit_event(PrivacyChangeProposed {
+                        escrow_id,
+                        proposed_by: caller,
+                        privacy,
+                    });
+                }
+                EscrowStatus::Completed | EscrowStatus::Cancelled | EscrowStatus::Resolved | EscrowStatus::Expired => {
+                    return Err(EscrowError::InvalidStatus);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Accept the privacy change proposed by the other party
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `InvalidStatus` - If no change was proposed, or escrow is Completed or Cancelled
+        /// * `Unauthorized` - If caller is not the other party
+        ///
+        /// # Events
+        /// * `PrivacyChanged` - Emitted when the change is applied
+        #[ink(message)]
+        pub fn accept_privacy_change(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let (proposed_by, privacy) = escrow.privacy_proposal.ok_or(EscrowError::InvalidStatus)?;
+
+            // Verify caller is the other party
+            let other_party = if proposed_by == escrow.client {
+                escrow.freelancer
+            } else {
+                escrow.client
+            };
+            if caller != other_party {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            escrow.privacy = privacy;
+            escrow.privacy_proposal = None;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(PrivacyChanged { escrow_id, privacy });
+
+            Ok(())
+        }
+
+        /// Propose replacing the arbiter of an escrow
+        ///
+        /// Lets the parties move a dispute to someone else when the arbiter
+        /// lost its keys or stopped responding. The other party must approve
+        /// with `approve_arbiter_change`; a new proposal replaces any pending
+        /// one. Any arbiter fee goes to whoever resolves the dispute.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `new_arbiter` - Account to arbitrate instead
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not client or freelancer
+        /// * `InvalidStatus` - If escrow is in a terminal status
+        /// * `InvalidArbiter` - If escrow has no arbiter, or `new_arbiter` is
+        ///   the current arbiter, the client or the freelancer
+        ///
+        /// # Events
+        /// * `ArbiterChangeProposed` - Emitted when the change is proposed
+        #[ink(message)]
+        pub fn propose_arbiter_change(&mut self, escrow_id: u64, new_arbiter: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is client or freelancer
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // Only an existing arbiter can be replaced, and never by a party
+            if escrow.arbiter.is_none_or(|arbiter| arbiter == new_arbiter)
+                || new_arbiter == escrow.client
+                || new_arbiter == escrow.freelancer
+            {
+                return Err(EscrowError::InvalidArbiter);
+            }
+
+            escrow.arbiter_proposal = Some((caller, new_arbiter));
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(ArbiterChangeProposed {
+                escrow_id,
+                proposed_by: caller,
+                new_arbiter,
+            });
+
+            Ok(())
+        }
+
+        /// Approve the arbiter proposed by the other party
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `InvalidStatus` - If no change was proposed, or escrow is in a terminal status
+        /// * `Unauthorized` - If caller is not the other party
+        ///
+        /// # Events
+        /// * `ArbiterChanged` - Emitted when the new arbiter takes over
+        #[ink(message)]
+        pub fn approve_arbiter_change(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            if escrow.status.is_terminal() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let (proposed_by, new_arbiter) = escrow.arbiter_proposal.ok_or(EscrowError::InvalidStatus)?;
+            let old_arbiter = escrow.arbiter.ok_or(EscrowError::InvalidArbiter)?;
+
+            // Verify caller is the other party
+            let other_party = if proposed_by == escrow.client {
+                escrow.freelancer
+            } else {
+                escrow.client
+            };
+            if caller != other_party {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            escrow.arbiter = Some(new_arbiter);
+            escrow.arbiter_proposal = None;
+            validate_roles_distinct(&escrow)?;
+            self.escrows.insert(escrow_id, &escrow);
+
+            // Move the escrow to the new arbiter's list
+            let mut old_list = self.arbiter_escrows.get(old_arbiter).unwrap_or_default();
+            old_list.retain(|&id| id != escrow_id);
+            self.arbiter_escrows.insert(old_arbiter, &old_list);
+            let mut new_list = self.arbiter_escrows.get(new_arbiter).unwrap_or_default();
+            new_list.push(escrow_id);
+            self.arbiter_escrows.insert(new_arbiter, &new_list);
+
+            self.env().emit_event(ArbiterChanged {
+                escrow_id,
+                old: old_arbiter,
+                new: new_arbiter,
+            });
+
+            Ok(())
+        }
+
+        /// Replace the milestones of an unfunded escrow (only by the client)
+        ///
+        /// The new plan goes through the same checks as in `create_escrow`.
+        /// An escrow the freelancer already accepted goes back to
+        /// PendingAcceptance, so the freelancer agrees to the new terms too.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestones` - New milestone list, replacing the current one
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in PendingAcceptance or Created status, holds a
+        ///   deposit or is a drawdown escrow
+        /// * Any milestone error of `create_escrow`
+        ///
+        /// # Events
+        /// * `MilestonesUpdated` - Emitted with the new total
+        /// * `MilestonesSnapshot` - Emitted right after with the new milestones
+        #[ink(message)]
+        pub fn update_milestones(&mut self, escrow_id: u64, milestones: Vec<Milestone>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Deposits were made against the current plan
+            if !matches!(escrow.status, EscrowStatus::PendingAcceptance | EscrowStatus::Created)
+                || escrow.deposited > 0
+            {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // A drawdown has no milestone plan to edit
+            if self.drawdowns.contains(escrow_id) {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if let Some(issue) = self.milestone_issues(caller, &milestones).first() {
+                return Err(issue.error);
+            }
+
+            let mut milestones = milestones;
+            reset_remaining(&mut milestones);
+            escrow.total_amount = milestones.iter().map(|m| m.amount).sum();
+            escrow.milestones = milestones;
+            escrow.status = EscrowStatus::PendingAcceptance;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(MilestonesUpdated {
+                escrow_id,
+                client: caller,
+                freelancer: escrow.freelancer,
+                new_total: escrow.total_amount,
+            });
+            self.emit_milestones_snapshot(&escrow);
+
+            Ok(())
+        }
+
+        /// Accept an escrow so the client can fund it (only by the freelancer)
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in PendingAcceptance status
+        ///
+        /// # Events
+        /// * `EscrowAccepted` - Emitted when the escrow moves to Created
+        #[ink(message)]
+        pub fn accept_escrow(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::PendingAcceptance {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            escrow.status = EscrowStatus::Created;
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(EscrowAccepted {
+                escrow_id,
+                client: escrow.client,
+                freelancer: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Decline an escrow instead of accepting it (only by the freelancer)
+        ///
+        /// The escrow is cancelled and removed from the client, freelancer and
+        /// arbiter indexes, so it no longer shows up in `get_escrows_by_*`.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in PendingAcceptance status
+        ///
+        /// # Events
+        /// * `EscrowDeclined` - Emitted when the escrow is cancelled
+        #[ink(message)]
+        pub fn decline_escrow(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Nothing can be deposited before acceptance, so nothing is refunded
+            if escrow.status != EscrowStatus::PendingAcceptance {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            self.close_declined(escrow);
+
+            Ok(())
+        }
+
+        /// Accept an unfunded escrow except for some of its milestones (only by the freelancer)
+        ///
+        /// The declined milestones are removed and the remaining ones are
+        /// re-sequenced from 0 in their original order, dependencies included.
+        /// The client can then fund the reduced escrow (`expected_total` guards
+        /// against funding terms it did not see) or cancel it. Declining every
+        /// milestone declines the escrow like `decline_escrow`, which also
+        /// removes it from the indexes. Once the client made a first deposit,
+        /// the plan can no longer be declined. On an escrow pending acceptance,
+        /// this accepts the rest of the plan like `accept_escrow`.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `declined_milestones` - IDs of the milestones to remove
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in PendingAcceptance or Created status or holds a deposit
+        /// * `MilestoneNotFound` - If a declined milestone doesn't exist
+        /// * `InvalidDecline` - If the list is empty or repeats a milestone, or a kept
+        ///   milestone depends on a declined one
+        ///
+        /// # Events
+        /// * `MilestoneDeclined` - Emitted for each removed milestone
+        /// * `MilestonesSnapshot` - Emitted with the remaining milestones, if any
+        /// * `EscrowAccepted` - Emitted if the escrow was pending acceptance and milestones remain
+        /// * `EscrowDeclined` - Emitted if no milestone remains
+        #[ink(message)]
+        pub fn accept_escrow_partial(&mut self, escrow_id: u64, declined_milestones: Vec<u32>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Deposits were made against the full plan
+            let pending_acceptance = escrow.status == EscrowStatus::PendingAcceptance;
+            if !(pending_acceptance || escrow.status == EscrowStatus::Created) || escrow.deposited > 0 {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if declined_milestones.is_empty() {
+                return Err(EscrowError::InvalidDecline);
+            }
+            for (i, milestone_id) in declined_milestones.iter().enumerate() {
+                if declined_milestones[..i].contains(milestone_id) {
+                    return Err(EscrowError::InvalidDecline);
+                }
+                if !escrow.milestones.iter().any(|m| m.id == *milestone_id) {
+                    return Err(EscrowError::MilestoneNotFound);
+                }
+            }
+
+            let (declined, mut kept): (Vec<Milestone>, Vec<Milestone>) = escrow
+                .milestones
+                .into_iter()
+                .partition(|m| declined_milestones.contains(&m.id));
+
+            // Kept milestones must not wait on work that will never be done
+            if kept.iter().any(|m| m.depends_on.iter().any(|dep| declined_milestones.contains(dep))) {
+                return Err(EscrowError::InvalidDecline);
+            }
+
+            // Re-sequence, keeping dependencies pointed at the same milestones
+            let old_ids: Vec<u32> = kept.iter().map(|m| m.id).collect();
+            for (idx, milestone) in kept.iter_mut().enumerate() {
+                milestone.id = idx as u32;
+                for dep in milestone.depends_on.iter_mut() {
+                    if let Some(new_id) = old_ids.iter().position(|id| id == dep) {
+                        *dep = new_id as u32;
+                    }
+                }
+            }
+
+            escrow.total_amount = kept.iter().map(|m| m.amount).sum();
+            escrow.milestones = kept;
+
+            for milestone in &declined {
+                self.env().emit_event(MilestoneDeclined {
+                    escrow_id,
+                    freelancer: caller,
+                    milestone_id: milestone.id,
+                    amount: milestone.amount,
+                });
+            }
+
+            // Nothing left to work on, so this is a full decline
+            if escrow.milestones.is_empty() {
+                self.close_declined(escrow);
+                return Ok(());
+            }
+
+            self.emit_milestones_snapshot(&escrow);
+            if pending_acceptance {
+                escrow.status = EscrowStatus::Created;
+                self.env().emit_event(EscrowAccepted {
+                    escrow_id,
+                    client: escrow.client,
+                    freelancer: caller,
+                });
+            }
+            self.escrows.insert(escrow_id, &escrow);
+
+            Ok(())
+        }
+
+        /// Create a drawdown escrow for hourly or time-and-materials work
+        ///
+        /// The client funds the whole cap with `fund_escrow`. The freelancer
+        /// bills hours with `submit_timesheet` and the client pays them with
+        /// `approve_timesheet`. Disputes and mutual cancellation work as for
+        /// milestone escrows and settle the unapproved remainder; both parties
+        /// end the engagement with `close_drawdown`.
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account of the freelancer who will receive payments
+        /// * `cap` - Maximum amount the freelancer can draw
+        /// * `rate_per_hour` - Amount paid per approved hour
+        /// * `arbiter` - Optional arbiter account for dispute resolution
+        ///
+        /// # Returns
+        /// * `u64` - The ID of the new escrow
+        ///
+        /// # Errors
+        /// * `ZeroAmount` - If the cap is zero
+        /// * `InvalidAmount` - If the rate is zero
+        /// * `ExceedsMaxEscrow` - If the cap is above the maximum escrow amount and the client is not exempt
+        ///
+        /// # Events
+        /// * `EscrowCreated` - Emitted with the cap as total amount
+        /// * `DrawdownCreated` - Emitted right after with the rate
+        #[ink(message)]
+        pub fn create_drawdown_escrow(
+            &mut self,
+            freelancer: AccountId,
+            cap: Balance,
+            rate_per_hour: Balance,
+            arbiter: Option<AccountId>,
+        ) -> Result<u64> {
+            if cap == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+            if rate_per_hour == 0 {
+                return Err(EscrowError::InvalidAmount);
+            }
+            let caller = self.env().caller();
+            self.ensure_within_max_escrow(caller, cap)?;
+
+            let escrow = self.insert_escrow(
+                freelancer,
+                Vec::new(),
+                cap,
+                arbiter,
+                EscrowOptions::default(),
+                DEFAULT_ESCALATION_THRESHOLD,
+            );
+            self.drawdowns.insert(
+                escrow.id,
+                &Drawdown {
+                    rate_per_hour,
+                    timesheet_count: 0,
+                    close_requested_by: None,
+                },
+            );
+
+            self.env().emit_event(DrawdownCreated {
+                escrow_id: escrow.id,
+                cap,
+                rate_per_hour,
+            });
+
+            Ok(escrow.id)
+        }
+
+        /// Bill hours against a funded drawdown escrow (only by the freelancer)
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the drawdown escrow
+        /// * `hours` - Hours worked
+        /// * `proof_hash` - Hash of the off-chain timesheet details
+        ///
+        /// # Returns
+        /// * `u32` - The ID of the new timesheet
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `NotDrawdown` - If the escrow is a milestone escrow
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `ZeroAmount` - If no hours are billed
+        /// * `TooManyTimesheets` - If the escrow already has `MAX_TIMESHEETS` timesheets
+        ///
+        /// # Events
+        /// * `TimesheetSubmitted` - Emitted when the timesheet is stored
+        #[ink(message)]
+        pub fn submit_timesheet(&mut self, escrow_id: u64, hours: u32, proof_hash: Hash) -> Result<u32> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let mut drawdown = self.drawdowns.get(escrow_id).ok_or(EscrowError::NotDrawdown)?;
+
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+            if hours == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+            if drawdown.timesheet_count >= MAX_TIMESHEETS {
+                return Err(EscrowError::TooManyTimesheets);
+            }
+
+            let timesheet_id = drawdown.timesheet_count;
+            let timesheet = Timesheet {
+                id: timesheet_id,
+                hours,
+                proof_hash,
+                submitted_at: self.env().block_timestamp(),
+                approved_amount: None,
+            };
+            self.timesheets.insert((escrow_id, timesheet_id), &timesheet);
+            drawdown.timesheet_count = timesheet_id.saturating_add(1);
+            self.drawdowns.insert(escrow_id, &drawdown);
+
+            self.env().emit_event(TimesheetSubmitted {
+                escrow_id,
+                freelancer: caller,
+                timesheet_id,
+                hours,
+                proof_hash,
+            });
+
+            Ok(timesheet_id)
+        }
+
+        /// Pay a timesheet from the cap (only by the client)
+        ///
+        /// Releases `hours * rate_per_hour`, or only what is left of the cap
+        /// if that is less. The payment is recorded as a released milestone;
+        /// the escrow completes once the cap is exhausted.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the drawdown escrow
+        /// * `timesheet_id` - ID of the timesheet to approve
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `NotDrawdown` - If the escrow is a milestone escrow
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `TimesheetNotFound` - If the timesheet doesn't exist
+        /// * `TimesheetAlreadyApproved` - If the timesheet was already approved
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the payment
+        /// * `InsufficientFunds` - If the transfer to the freelancer fails
+        ///
+        /// # Events
+        /// * `MilestoneReleased` - Emitted for the milestone recording the payment
+        /// * `TimesheetApproved` - Emitted right after
+        /// * `EscrowCompleted` - Emitted once the cap is exhausted
+        #[ink(message)]
+        pub fn approve_timesheet(&mut self, escrow_id: u64, timesheet_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let drawdown = self.drawdowns.get(escrow_id).ok_or(EscrowError::NotDrawdown)?;
+
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let mut timesheet = self
+                .timesheets
+                .get((escrow_id, timesheet_id))
+                .ok_or(EscrowError::TimesheetNotFound)?;
+            if timesheet.approved_amount.is_some() {
+                return Err(EscrowError::TimesheetAlreadyApproved);
+            }
+
+            let remaining = escrow.total_amount.saturating_sub(settlement(&escrow).released);
+            let amount = drawdown
+                .rate_per_hour
+                .saturating_mul(Balance::from(timesheet.hours))
+                .min(remaining);
+
+            let breakdown = payout_breakdown(amount, escrow.fee_bps);
+            self.pay_out(&escrow, Payee::Freelancer, breakdown.net)?;
+            self.collect_fee(&escrow, breakdown.protocol_fee)?;
+
+            let milestone_id = escrow.milestones.len() as u32;
+            escrow.milestones.push(Milestone {
+                id: milestone_id,
+                amount,
+                released: true,
+                description: String::new(),
+                depends_on: Vec::new(),
+                deadline: None,
+                forfeited: false,
+                proof_hash: None,
+                rejections: 0,
+                disputed: false,
+                arbitrated: false,
+                remaining: 0,
+            });
+            escrow.released_count = escrow.released_count.saturating_add(1);
+            escrow.last_client_activity_at = Some(self.env().block_timestamp());
+            if amount == remaining {
+                escrow.status = EscrowStatus::Completed;
+            }
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, amount);
+
+            timesheet.approved_amount = Some(amount);
+            self.timesheets.insert((escrow_id, timesheet_id), &timesheet);
+
+            self.env().emit_event(MilestoneReleased {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                milestone_id,
+                amount: breakdown.gross,
+                protocol_fee: breakdown.protocol_fee,
+                net_to_freelancer: breakdown.net,
+            });
+            self.env().emit_event(TimesheetApproved {
+                escrow_id,
+                timesheet_id,
+                milestone_id,
+                amount,
+            });
+            if escrow.status == EscrowStatus::Completed {
+                self.complete_escrow(&mut escrow)?;
+            }
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Close a drawdown escrow and refund the unused cap to the client
+        ///
+        /// Both parties must call this. The first call records the request;
+        /// the other party's call refunds what is left of the cap and completes
+        /// the escrow with the drawn amount as its total. Timesheets still
+        /// pending at that point are not paid. Repeating a request is a no-op.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the drawdown escrow
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `NotDrawdown` - If the escrow is a milestone escrow
+        /// * `Unauthorized` - If caller is not client or freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `LedgerUnderflow` - If the escrow's ledger holds less than the refund
+        ///
+        /// # Events
+        /// * `DrawdownCloseRequested` - Emitted on the first party's request
+        /// * `EscrowCompleted` and `DrawdownClosed` - Emitted once both parties agreed
+        #[ink(message)]
+        pub fn close_drawdown(&mut self, escrow_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let mut drawdown = self.drawdowns.get(escrow_id).ok_or(EscrowError::NotDrawdown)?;
+
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            match drawdown.close_requested_by {
+                Some(requested_by) if requested_by == caller => return Ok(()),
+                Some(_) => {}
+                None => {
+                    drawdown.close_requested_by = Some(caller);
+                    self.drawdowns.insert(escrow_id, &drawdown);
+                    self.env().emit_event(DrawdownCloseRequested {
+                        escrow_id,
+                        requested_by: caller,
+                    });
+                    return Ok(());
+                }
+            }
+
+            // Both parties agreed: refund the unused cap
+            let settlement = settlement(&escrow);
+            let refunded = if settlement.refundable > 0 {
+                self.pay_out_insured(&escrow, Payee::Client, settlement.refundable)?
+            } else {
+                0
+            };
+
+            escrow.total_amount = settlement.released;
+            escrow.deposited = settlement.released;
+            escrow.status = EscrowStatus::Completed;
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, settlement.refundable);
+
+            self.complete_escrow(&mut escrow)?;
+            self.env().emit_event(DrawdownClosed {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                drawn: settlement.released,
+                refund_to_client: refunded,
+            });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Get the terms of a drawdown escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Option<Drawdown>` - Terms if the escrow is a drawdown escrow
+        #[ink(message)]
+        pub fn get_drawdown(&self, escrow_id: u64) -> Option<Drawdown> {
+            self.drawdowns.get(escrow_id)
+        }
+
+        /// Get a timesheet of a drawdown escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `timesheet_id` - ID of the timesheet
+        ///
+        /// # Returns
+        /// * `Option<Timesheet>` - The timesheet if it exists
+        #[ink(message)]
+        pub fn get_timesheet(&self, escrow_id: u64, timesheet_id: u32) -> Option<Timesheet> {
+            self.timesheets.get((escrow_id, timesheet_id))
+        }
+
+        /// Append a milestone to a funded escrow and deposit its amount (only by the client)
+        ///
+        /// For extra deliverables agreed on mid-project. The milestone gets the
+        /// next sequential ID and no dependencies. Value sent beyond `amount` is
+        /// sent back to the caller.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `description` - Description of the new milestone
+        /// * `amount` - Payment of the new milestone
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status, a cancellation is pending
+        ///   or it is a drawdown escrow
+        /// * `ZeroAmount` - If `amount` is zero
+        /// * `TooManyMilestones` - If the escrow already has `MAX_MILESTONES` milestones
+        /// * `InvalidAmount` - If the new total overflows
+        /// * `ExceedsMaxEscrow` - If the new total is above the maximum escrow amount and the client is not exempt
+        /// * `InsufficientFunds` - If less than `amount` is transferred, or sending back the rest failed
+        ///
+        /// # Events
+        /// * `OverpaymentRefunded` - Emitted first if more than `amount` was sent
+        /// * `MilestoneAdded` - Emitted with the new milestone
+        /// * `MilestonesSnapshot` - Emitted right after with all milestones
+        #[ink(message, payable)]
+        pub fn add_milestone(&mut self, escrow_id: u64, description: String, amount: Balance) -> Result<u32> {
+            let caller = self.env().caller();
+            let transferred = self.env().transferred_value();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Nothing more is locked while a cancellation is pending
+            if escrow.status != EscrowStatus::Funded || escrow.cancel_requested_by.is_some() {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // A drawdown has no milestone plan to extend
+            if self.drawdowns.contains(escrow_id) {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+            if escrow.milestones.len() >= MAX_MILESTONES {
+                return Err(EscrowError::TooManyMilestones);
+            }
+            let total_amount = escrow.total_amount.checked_add(amount).ok_or(EscrowError::InvalidAmount)?;
+            self.ensure_within_max_escrow(caller, total_amount)?;
+            // Tokens are pulled below, not sent along
+            let expected = if escrow.token.is_some() { 0 } else { amount };
+            if escrow.token.is_some() && transferred > 0 {
+                return Err(EscrowError::InvalidAmount);
+            }
+            if transferred < expected {
+                return Err(EscrowError::InsufficientFunds);
+            }
+
+            let overpayment = transferred - expected;
+            if overpayment > 0 {
+                if self.env().transfer(caller, overpayment).is_err() {
+                    return Err(EscrowError::InsufficientFunds);
+                }
+                self.env().emit_event(OverpaymentRefunded {
+                    escrow_id,
+                    amount: overpayment,
+                });
+            }
+
+            let milestone_id = escrow.milestones.len() as u32;
+            escrow.milestones.push(Milestone {
+                id: milestone_id,
+                amount,
+                released: false,
+                description,
+                depends_on: Vec::new(),
+                deadline: None,
+                forfeited: false,
+                proof_hash: None,
+                rejections: 0,
+                disputed: false,
+                arbitrated: false,
+                remaining: amount,
+            });
+            escrow.total_amount = total_amount;
+            escrow.deposited = escrow.deposited.saturating_add(amount);
+            escrow.last_client_activity_at = Some(self.env().block_timestamp());
+            self.escrows.insert(escrow_id, &escrow);
+            self.record_deposit(&escrow, amount);
+            self.collect_tokens(&escrow, caller, amount)?;
+
+            self.env().emit_event(MilestoneAdded {
+                escrow_id,
+                client: caller,
+                freelancer: escrow.freelancer,
+                milestone_id,
+                amount,
+            });
+            self.emit_milestones_snapshot(&escrow);
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(milestone_id)
+        }
+
+        /// Propose changes to a funded escrow
+        ///
+        /// The other party applies them with `accept_amendment` before the
+        /// amendment window passes. A new proposal replaces any pending one.
+        /// An amendment that raises the total must come from the freelancer,
+        /// because the client pays the difference when accepting it; one that
+        /// lowers the total refunds the difference on acceptance.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `changes` - Proposed changes
+        ///
+        /// # Returns
+        /// * `u32` - ID of the amendment
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not client or freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `InvalidAmendment` - If the changes are empty, shorten a timeout or repeat a
+        ///   milestone, or the client proposes a higher total
+        /// * `MilestoneNotFound` - If an amount change targets an unknown milestone
+        /// * `MilestoneAlreadyReleased` - If an amount change targets a released milestone
+        /// * `MilestoneDisputed` - If an amount change targets a milestone awaiting the arbiter's ruling
+        /// * `InvalidAmount` - If new milestone IDs don't continue the sequence, or one is released
+        /// * `InvalidDependencies` - If a new milestone depends on itself, a later or repeated
+        ///   milestone, or more than `MAX_MILESTONE_DEPENDENCIES` milestones
+        /// * `ZeroAmount` - If the new total would be zero
+        /// * `TooManyMilestones` - If new milestones would exceed `MAX_MILESTONES`
+        /// * `ExceedsMaxEscrow` - If the new total is above the maximum escrow amount and the client is not exempt
+        ///
+        /// # Events
+        /// * `AmendmentProposed` - Emitted when the proposal is stored
+        #[ink(message)]
+        pub fn propose_amendment(&mut self, escrow_id: u64, changes: Amendment) -> Result<u32> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            let total_amount = self.check_amendment(&escrow, caller, &changes)?;
+
+            let amendment_id = self.amendment_counts.get(escrow_id).unwrap_or(0);
+            let expires_at = match self.settings.amendment_window_ms {
+                0 => None,
+                window_ms => Some(self.env().block_timestamp().saturating_add(window_ms)),
+            };
+            self.amendments.insert(
+                escrow_id,
+                &AmendmentProposal {
+                    id: amendment_id,
+                    proposed_by: caller,
+                    changes,
+                    total_amount,
+                    expires_at,
+                },
+            );
+            self.amendment_counts.insert(escrow_id, &amendment_id.saturating_add(1));
+
+            self.env().emit_event(AmendmentProposed {
+                escrow_id,
+                proposed_by: caller,
+                amendment_id,
+                total_amount,
+                expires_at,
+            });
+
+            Ok(amendment_id)
+        }
+
+        /// Accept and apply the amendment proposed by the other party
+        ///
+        /// The changes are validated again against the current escrow and
+        /// applied together. If they raise the total, the client must transfer
+        /// exactly the difference with this call; if they lower it, the
+        /// difference is refunded to the client's refund address.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `amendment_id` - ID of the pending amendment
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `AmendmentNotFound` - If `amendment_id` is not the pending amendment
+        /// * `Unauthorized` - If caller is not the other party
+        /// * `AmendmentExpired` - If the acceptance window has passed
+        /// * `InsufficientFunds` - If less than the increase is transferred
+        /// * `InvalidAmount` - If more than the increase is transferred
+        /// * Any error of `propose_amendment` the changes no longer pass
+        ///
+        /// # Events
+        /// * `AmendmentAccepted` - Emitted when the changes are applied
+        /// * `MilestonesSnapshot` - Emitted with the amended milestones
+        #[ink(message, payable)]
+        pub fn accept_amendment(&mut self, escrow_id: u64, amendment_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+            let transferred = self.env().transferred_value();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let proposal = self
+                .amendments
+                .get(escrow_id)
+                .filter(|proposal| proposal.id == amendment_id)
+                .ok_or(EscrowError::AmendmentNotFound)?;
+
+            // Verify caller is the other party
+            let other_party = if proposal.proposed_by == escrow.client {
+                escrow.freelancer
+            } else {
+                escrow.client
+            };
+            if caller != other_party {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            let now = self.env().block_timestamp();
+            if proposal.expires_at.is_some_and(|expires_at| now > expires_at) {
+                return Err(EscrowError::AmendmentExpired);
+            }
+
+            // Terms may have moved since the proposal, e.g. a milestone was released
+            let total_amount = self.check_amendment(&escrow, proposal.proposed_by, &proposal.changes)?;
+            let previous_total = escrow.total_amount;
+            let increase = total_amount.saturating_sub(previous_total);
+            // Tokens are pulled below, not sent along
+            let expected = if escrow.token.is_some() { 0 } else { increase };
+            if transferred < expected {
+                return Err(EscrowError::InsufficientFunds);
+            }
+            if transferred > expected {
+                return Err(EscrowError::InvalidAmount);
+            }
+
+            Self::apply_amendment(&mut escrow, proposal.changes);
+            if caller == escrow.client {
+                escrow.last_client_activity_at = Some(now);
+            }
+
+            escrow.total_amount = total_amount;
+            escrow.deposited = total_amount.saturating_add(escrow.arbiter_fee);
+            self.escrows.insert(escrow_id, &escrow);
+            if total_amount > previous_total {
+                self.record_deposit(&escrow, increase);
+                self.collect_tokens(&escrow, caller, increase)?;
+            } else if total_amount < previous_total {
+                let decrease = previous_total - total_amount;
+                self.pay_out_insured(&escrow, Payee::Client, decrease)?;
+                self.settle_deposit(&escrow, decrease);
+            }
+            self.amendments.remove(escrow_id);
+
+            self.env().emit_event(AmendmentAccepted {
+                escrow_id,
+                amendment_id,
+                previous_total,
+                total_amount,
+            });
+            self.emit_milestones_snapshot(&escrow);
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Reject the pending amendment, or withdraw it as its proposer
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `amendment_id` - ID of the pending amendment
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `AmendmentNotFound` - If `amendment_id` is not the pending amendment
+        /// * `Unauthorized` - If caller is not client or freelancer
+        ///
+        /// # Events
+        /// * `AmendmentRejected` - Emitted when the amendment is dropped
+        #[ink(message)]
+        pub fn reject_amendment(&mut self, escrow_id: u64, amendment_id: u32) -> Result<()> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            self.amendments
+                .get(escrow_id)
+                .filter(|proposal| proposal.id == amendment_id)
+                .ok_or(EscrowError::AmendmentNotFound)?;
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            self.amendments.remove(escrow_id);
+            self.env().emit_event(AmendmentRejected {
+                escrow_id,
+                rejected_by: caller,
+                amendment_id,
+            });
+
+            Ok(())
+        }
+
+        /// Get the pending amendment of an escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Option<AmendmentProposal>` - Pending amendment, expired ones included
+        #[ink(message)]
+        pub fn get_amendment(&self, escrow_id: u64) -> Option<AmendmentProposal> {
+            self.amendments.get(escrow_id)
+        }
+
+        /// Set how long amendment proposals stay acceptable (only by the owner)
+        ///
+        /// Only proposals made afterwards use the new window.
+        ///
+        /// # Arguments
+        /// * `window_ms` - Acceptance window in milliseconds, or 0 for no expiry
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        ///
+        /// # Events
+        /// * `ConfigUpdated` - Emitted with the new settings
+        #[ink(message)]
+        pub fn set_amendment_window(&mut self, window_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.update_settings(|settings| settings.amendment_window_ms = window_ms);
+            Ok(())
+        }
+
+        /// Set the grace period after funding during which the client cannot
+        /// open a dispute (only by the owner)
+        ///
+        /// # Arguments
+        /// * `grace_ms` - Grace period in milliseconds, or 0 to disable it
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        ///
+        /// # Events
+        /// * `ConfigUpdated` - Emitted with the new settings
+        #[ink(message)]
+        pub fn set_dispute_grace(&mut self, grace_ms: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.update_settings(|settings| settings.dispute_grace_ms = grace_ms);
+            Ok(())
+        }
+
+        /// Get the dispute grace period
+        ///
+        /// # Returns
+        /// * `u64` - Grace period in milliseconds, 0 if disabled
+        #[ink(message)]
+        pub fn get_dispute_grace(&self) -> u64 {
+            self.settings.dispute_grace_ms
+        }
+
+        /// Set the maximum total amount of a single escrow (only by the owner)
+        ///
+        /// Only creation and growth of escrows are checked, so existing escrows
+        /// above a lowered cap keep working.
+        ///
+        /// # Arguments
+        /// * `max_escrow_amount` - Maximum total amount, or 0 for unlimited
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        ///
+        /// # Events
+        /// * `MaxEscrowUpdated` - Emitted when the cap is stored
+        /// * `ConfigUpdated` - Emitted with the new settings
+        #[ink(message)]
+        pub fn set_max_escrow_amount(&mut self, max_escrow_amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+
+            self.env().emit_event(MaxEscrowUpdated { max_escrow_amount });
+            self.update_settings(|settings| settings.max_escrow_amount = max_escrow_amount);
+
+            Ok(())
+        }
+
+        /// Get the maximum total amount of a single escrow
+        ///
+        /// # Returns
+        /// * `Balance` - Maximum total amount, 0 if unlimited
+        #[ink(message)]
+        pub fn get_max_escrow_amount(&self) -> Balance {
+            self.settings.max_escrow_amount
+        }
+
+        /// Set the protocol fee taken from milestone releases (only by the owner)
+        ///
+        /// Escrows keep the fee in force when they were created, so only
+        /// escrows created afterwards pay the new fee.
+        ///
+        /// # Arguments
+        /// * `fee_bps` - Fee in basis points, at most `MAX_FEE_BPS`
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        /// * `FeeTooHigh` - If the fee exceeds `MAX_FEE_BPS`
+        ///
+        /// # Events
+        /// * `ConfigUpdated` - Emitted with the new settings
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            if fee_bps > MAX_FEE_BPS {
+                return Err(EscrowError::FeeTooHigh);
+            }
+            self.update_settings(|settings| settings.fee_bps = fee_bps);
+            Ok(())
+        }
+
+        /// Get the protocol fee new escrows are created with
+        ///
+        /// # Returns
+        /// * `u16` - Fee in basis points
+        #[ink(message)]
+        pub fn get_fee(&self) -> u16 {
+            self.settings.fee_bps
+        }
+
+        /// Send the collected native-currency fees to `to` (only by the owner)
+        ///
+        /// # Arguments
+        /// * `to` - Account receiving the fees
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        /// * `ZeroAmount` - If no fees were collected since the last withdrawal
+        /// * `InsufficientFunds` - If the transfer fails
+        ///
+        /// # Events
+        /// * `FeesWithdrawn` - Emitted with the amount sent
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, to: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            let amount = self.collected_fees;
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+
+            self.collected_fees = 0;
+            if self.env().transfer(to, amount).is_err() {
+                return Err(EscrowError::InsufficientFunds);
+            }
+
+            self.env().emit_event(FeesWithdrawn { to, token: None, amount });
+            Ok(())
+        }
+
+        /// Send the fees collected from escrows in `token` to `to` (only by the owner)
+        ///
+        /// # Arguments
+        /// * `token` - PSP22 token the fees were collected in
+        /// * `to` - Account receiving the fees
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        /// * `ZeroAmount` - If no fees were collected in `token` since the last withdrawal
+        /// * `TokenTransferFailed` - If the token transfer fails
+        ///
+        /// # Events
+        /// * `FeesWithdrawn` - Emitted with the token and amount sent
+        #[ink(message)]
+        pub fn withdraw_token_fees(&mut self, token: AccountId, to: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            let amount = self.collected_token_fees.get(token).unwrap_or(0);
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+
+            self.collected_token_fees.remove(token);
+            Self::transfer_tokens(token, to, amount)?;
+
+            self.env().emit_event(FeesWithdrawn {
+                to,
+                token: Some(token),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Get the fees collected in a token and not withdrawn yet
+        ///
+        /// Native-currency fees are reported as `collected_fees` by `reconcile`.
+        ///
+        /// # Arguments
+        /// * `token` - PSP22 token to query
+        ///
+        /// # Returns
+        /// * `Balance` - Collected fees, 0 if none
+        #[ink(message)]
+        pub fn get_collected_token_fees(&self, token: AccountId) -> Balance {
+            self.collected_token_fees.get(token).unwrap_or(0)
+        }
+
+        /// Set the share of protocol fees paid into the insurance pool (only by the owner)
+        ///
+        /// Applies to native-currency fees collected afterwards; token fees
+        /// are always kept in full.
+        ///
+        /// # Arguments
+        /// * `insurance_bps` - Share in basis points, at most `BPS_DENOMINATOR`
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        /// * `InvalidBps` - If the share exceeds `BPS_DENOMINATOR`
+        ///
+        /// # Events
+        /// * `ConfigUpdated` - Emitted with the new settings
+        #[ink(message)]
+        pub fn set_insurance_share(&mut self, insurance_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            if insurance_bps > BPS_DENOMINATOR {
+                return Err(EscrowError::InvalidBps);
+            }
+            self.update_settings(|settings| settings.insurance_bps = insurance_bps);
+            Ok(())
+        }
+
+        /// Exempt a client from the maximum escrow amount (only by the owner)
+        ///
+        /// # Arguments
+        /// * `client` - Client account to exempt
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        ///
+        /// # Events
+        /// * `ExemptClientAdded` - Emitted when the client is exempted
+        #[ink(message)]
+        pub fn add_exempt_client(&mut self, client: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+
+            self.exempt_clients.insert(client, &());
+            self.env().emit_event(ExemptClientAdded { client });
+
+            Ok(())
+        }
+
+        /// Remove a client's exemption from the maximum escrow amount (only by the owner)
+        ///
+        /// # Arguments
+        /// * `client` - Client account to remove
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        ///
+        /// # Events
+        /// * `ExemptClientRemoved` - Emitted when the exemption is removed
+        #[ink(message)]
+        pub fn remove_exempt_client(&mut self, client: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+
+            self.exempt_clients.remove(client);
+            self.env().emit_event(ExemptClientRemoved { client });
+
+            Ok(())
+        }
+
+        /// Check whether a client is exempt from the maximum escrow amount
+        ///
+        /// # Arguments
+        /// * `client` - Account to check
+        ///
+        /// # Returns
+        /// * `bool` - True if the client is exempt
+        #[ink(message)]
+        pub fn is_exempt_client(&self, client: AccountId) -> bool {
+            self.exempt_clients.contains(client)
+        }
+
+        /// Top up the insurance pool with the transferred value (anyone)
+        ///
+        /// # Errors
+        /// * `ZeroAmount` - If no value was transferred
+        ///
+        /// # Events
+        /// * `InsurancePoolFunded` - Emitted with the amount added
+        #[ink(message, payable)]
+        pub fn fund_insurance_pool(&mut self) -> Result<()> {
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+
+            self.insurance_pool = self.insurance_pool.saturating_add(amount);
+            self.env().emit_event(InsurancePoolFunded {
+                from: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Pay a party of an escrow from the insurance pool (only by the owner)
+        ///
+        /// Compensations are capped at the escrow's recorded shortfall, so the
+        /// pool only covers payouts the contract failed to deliver. A
+        /// compensation counts as a refund to the client, or as earnings of the
+        /// freelancer or arbiter, in `get_totals`.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow the shortfall was recorded for
+        /// * `to` - Client, freelancer or arbiter of the escrow
+        /// * `amount` - Amount to pay
+        ///
+        /// # Errors
+        /// * `NotOwner` - If caller is not the owner
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If `to` is not the client, the freelancer or the arbiter
+        /// * `ZeroAmount` - If `amount` is 0
+        /// * `ExceedsShortfall` - If `amount` exceeds the recorded shortfall
+        /// * `InsufficientFunds` - If the pool holds less than `amount` or the transfer fails
+        ///
+        /// # Events
+        /// * `CompensationPaid` - Emitted with the escrow, recipient and amount
+        #[ink(message)]
+        pub fn compensate(&mut self, escrow_id: u64, to: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            // Arbiters are owed their fee when it could not be delivered
+            if !Self::is_party(&escrow, to) && escrow.arbiter != Some(to) {
+                return Err(EscrowError::Unauthorized);
+            }
+            if amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+
+            let shortfall = self.shortfalls.get(escrow_id).unwrap_or(0);
+            let remaining = shortfall.checked_sub(amount).ok_or(EscrowError::ExceedsShortfall)?;
+            self.insurance_pool = self
+                .insurance_pool
+                .checked_sub(amount)
+                .ok_or(EscrowError::InsufficientFunds)?;
+            if remaining == 0 {
+                self.shortfalls.remove(escrow_id);
+            } else {
+                self.shortfalls.insert(escrow_id, &remaining);
+            }
+
+            if self.env().transfer(to, amount).is_err() {
+                return Err(EscrowError::InsufficientFunds);
+            }
+            if to == escrow.client {
+                self.record_refunded(&escrow, amount);
+            } else {
+                self.record_earned(to, amount);
+            }
+
+            self.env().emit_event(CompensationPaid { escrow_id, to, amount });
+
+            #[cfg(test)]
+            self.debug_assert_reconciled();
+
+            Ok(())
+        }
+
+        /// Get the funds available in the insurance pool
+        ///
+        /// # Returns
+        /// * `Balance` - Pool balance
+        #[ink(message)]
+        pub fn get_insurance_pool(&self) -> Balance {
+            self.insurance_pool
+        }
+
+        /// Get the undelivered payouts of an escrow not compensated yet
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Balance` - Recorded shortfall, 0 if none
+        #[ink(message)]
+        pub fn get_shortfall(&self, escrow_id: u64) -> Balance {
+            self.shortfalls.get(escrow_id).unwrap_or(0)
+        }
+
+        /// Get the contract owner
+        ///
+        /// # Returns
+        /// * `AccountId` - Account allowed to change contract settings
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Get every contract setting and compile-time limit in one call
+        ///
+        /// # Returns
+        /// * `EscrowConfig` - Owner, version, current settings and limits
+        #[ink(message)]
+        pub fn get_config(&self) -> EscrowConfig {
+            EscrowConfig {
+                owner: self.owner,
+                version: CONTRACT_VERSION,
+                settings: self.settings.clone(),
+                bps_denominator: BPS_DENOMINATOR,
+                max_fee_bps: MAX_FEE_BPS,
+                default_escalation_threshold: DEFAULT_ESCALATION_THRESHOLD,
+                max_milestones: MAX_MILESTONES as u32,
+                max_milestone_dependencies: MAX_MILESTONE_DEPENDENCIES as u32,
+                max_batch_release: MAX_BATCH_RELEASE as u32,
+                max_progress_notes: MAX_PROGRESS_NOTES as u32,
+                max_note_uri_len: MAX_NOTE_URI_LEN as u32,
+                max_evidence_per_party: MAX_EVIDENCE_PER_PARTY as u32,
+                max_token_symbol_len: MAX_TOKEN_SYMBOL_LEN as u32,
+                max_token_decimals: MAX_TOKEN_DECIMALS,
+                max_description_len: MAX_DESCRIPTION_LEN as u32,
+                pending_scan_limit: PENDING_SCAN_LIMIT as u32,
+                max_page_size: MAX_PAGE_SIZE,
+            }
+        }
+
+        /// Get the contract interface version
+        ///
+        /// # Returns
+        /// * `u32` - Value of `CONTRACT_VERSION` this contract was built with
+        #[ink(message)]
+        pub fn get_version(&self) -> u32 {
+            CONTRACT_VERSION
+        }
+
+        /// Get the running value counters for reconciliation
+        ///
+        /// The counters are maintained by every value-moving message, so the
+        /// report costs the same regardless of how many escrows exist. Compare
+        /// `contract_balance_expected` against the contract's free balance: any
+        /// surplus comes from overpaid deposits or the existential deposit.
+        /// Token-denominated escrows are not counted; their deposits are held
+        /// as token balances of the contract.
+        ///
+        /// # Returns
+        /// * `ReconciliationReport` - Outstanding deposits, collected fees, the
+        ///   insurance pool and their sum; pending withdrawals are reserved and always 0
+        #[ink(message)]
+        pub fn reconcile(&self) -> ReconciliationReport {
+            ReconciliationReport {
+                sum_deposits_outstanding: self.deposits_outstanding,
+                sum_pending_withdrawals: 0,
+                collected_fees: self.collected_fees,
+                insurance_pool: self.insurance_pool,
+                contract_balance_expected: self
+                    .deposits_outstanding
+                    .saturating_add(self.collected_fees)
+                    .saturating_add(self.insurance_pool),
+                sum_total_spent: self.sum_total_spent,
+                sum_total_earned: self.sum_total_earned,
+                sum_fees_charged: self.sum_fees_charged,
+            }
+        }
+
+        /// Get the funds the ledger holds for an escrow
+        ///
+        /// Equals the deposit minus everything paid out for the escrow; no
+        /// payout can take it below zero.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to query
+        ///
+        /// # Returns
+        /// * `Balance` - Ledger balance (0 for unknown or unfunded escrows)
+        #[ink(message)]
+        pub fn get_ledger_balance(&self, escrow_id: u64) -> Balance {
+            self.ledger.get(escrow_id).unwrap_or(0)
+        }
+
+        /// Get how much of an escrow's total has been deposited
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Option<(Balance, Balance)>` - (deposited, total amount plus arbiter fee),
+        ///   None if the escrow doesn't exist
+        #[ink(message)]
+        pub fn get_funding_progress(&self, escrow_id: u64) -> Option<(Balance, Balance)> {
+            self.escrows
+                .get(escrow_id)
+                .map(|escrow| (escrow.deposited, funding_target(&escrow)))
+        }
+
+        /// Get what an account earned and spent through the contract, for tax reporting
+        ///
+        /// Earnings are every payment to the account as freelancer (releases,
+        /// arbiter and deadlock shares, abandonment compensation) and every
+        /// arbiter fee it was paid as arbiter. Spending is
+        /// every deposit as client minus the refunds of those deposits, wherever
+        /// the refunds were sent. Token-denominated escrows are not counted.
+        ///
+        /// # Arguments
+        /// * `account` - Account to query
+        ///
+        /// # Returns
+        /// * `(Balance, Balance)` - (total earned, total spent)
+        #[ink(message)]
+        pub fn get_totals(&self, account: AccountId) -> (Balance, Balance) {
+            (
+                self.total_earned.get(account).unwrap_or(0),
+                self.total_spent.get(account).unwrap_or(0),
+            )
+        }
+
+        /// Get escrow details by ID
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to query
+        ///
+        /// # Returns
+        /// * `Option<Escrow>` - Escrow if it exists, None otherwise
+        #[ink(message)]
+        pub fn get_escrow(&self, escrow_id: u64) -> Option<Escrow> {
+            self.escrows.get(escrow_id)
+        }
+
+        /// Get a compact summary of an escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to query
+        ///
+        /// # Returns
+        /// * `Option<EscrowSummary>` - Summary if the escrow exists, None otherwise
+        #[ink(message)]
+        pub fn get_escrow_summary(&self, escrow_id: u64) -> Option<EscrowSummary> {
+            let escrow = self.escrows.get(escrow_id)?;
+            let released_amount = settlement(&escrow).released;
+            let earliest_dispute_at = self.earliest_dispute_at(&escrow);
+
+            Some(EscrowSummary {
+                id: escrow.id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                arbiter: escrow.arbiter,
+                status: escrow.status,
+                total_amount: escrow.total_amount,
+                deposited: escrow.deposited,
+                released_amount,
+                milestone_count: escrow.milestones.len() as u32,
+                released_count: escrow.released_count,
+                cancel_requested_by: escrow.cancel_requested_by,
+                created_at: escrow.created_at,
+                earliest_dispute_at,
+                privacy: escrow.privacy,
+                termination_reason: escrow.termination_reason,
+                auto_release_threshold: escrow.auto_release_threshold,
+                strict_confirmation: escrow.strict_confirmation,
+                refund_to: escrow.refund_to,
+                token_symbol: escrow.token_symbol,
+                token_decimals: escrow.token_decimals,
+            })
+        }
+
+        /// Describe where an escrow stands in one line, for wallet previews
+        ///
+        /// The format is locale-free and stable so it can be parsed:
+        /// `"<Status>: <r>/<n> milestones released, <paid> of <total> paid"`,
+        /// followed by `", awaiting funding"` before funding, `", cancel requested
+        /// by client|freelancer"` while a request is pending, or the termination
+        /// reason once cancelled or resolved. Amounts are plain integers in the
+        /// smallest unit.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to describe
+        ///
+        /// # Returns
+        /// * `Option<String>` - Description of at most `MAX_DESCRIPTION_LEN` bytes,
+        ///   None if the escrow doesn't exist
+        #[ink(message)]
+        pub fn describe_escrow(&self, escrow_id: u64) -> Option<String> {
+            let summary = self.get_escrow_summary(escrow_id)?;
+
+            let status = match summary.status {
+                EscrowStatus::PendingAcceptance => "PendingAcceptance",
+                EscrowStatus::Created => "Created",
+                EscrowStatus::Funded => "Funded",
+                EscrowStatus::Completed => "Completed",
+                EscrowStatus::Cancelled => "Cancelled",
+                EscrowStatus::Disputed => "Disputed",
+                EscrowStatus::Resolved => "Resolved",
+                EscrowStatus::Expired => "Expired",
+            };
+            let mut description = format!(
+                "{}: {}/{} milestones released, {} of {} paid",
+                status, summary.released_count, summary.milestone_count, summary.released_amount, summary.total_amount
+            );
+
+            if summary.status == EscrowStatus::PendingAcceptance {
+                description.push_str(", awaiting freelancer");
+            }
+            if summary.status == EscrowStatus::Created {
+                description.push_str(", awaiting funding");
+            }
+            if let Some(requested_by) = summary.cancel_requested_by.filter(|_| !summary.status.is_terminal()) {
+                let party = if requested_by == summary.client { "client" } else { "freelancer" };
+                description.push_str(", cancel requested by ");
+                description.push_str(party);
+            }
+            if let Some(reason) = summary.termination_reason {
+                description.push_str(match reason {
+                    TerminationReason::MutualCancel => ", mutual cancel",
+                    TerminationReason::ArbiterDecision => ", arbiter decision",
+                    TerminationReason::Timeout => ", client abandoned",
+                    TerminationReason::Expired => ", expired",
+                    TerminationReason::Deadlock => ", deadlock settled",
+                    TerminationReason::Declined => ", declined by freelancer",
+                });
+            }
+
+            // Only ASCII is written, so any byte length is a char boundary
+            description.truncate(MAX_DESCRIPTION_LEN);
+            Some(description)
+        }
+
+        /// Predict whether `fund_escrow` would pass validation
+        ///
+        /// Runs the same checks as the message, without moving funds.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to fund
+        /// * `caller` - Account that would call the message
+        /// * `transferred` - Value that would be transferred
+        ///
+        /// # Returns
+        /// * `SimulationResult` - Outcome, with details of the first failed check
+        #[ink(message)]
+        pub fn simulate_fund(&self, escrow_id: u64, caller: AccountId, transferred: Balance) -> SimulationResult {
+            self.simulation_result(escrow_id, self.check_fund(escrow_id, caller, transferred))
+        }
+
+        /// Predict whether `release_milestone` would pass validation
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone to release
+        /// * `caller` - Account that would call the message
+        ///
+        /// # Returns
+        /// * `SimulationResult` - Outcome, with details of the first failed check
+        #[ink(message)]
+        pub fn simulate_release(&self, escrow_id: u64, milestone_id: u32, caller: AccountId) -> SimulationResult {
+            self.simulation_result(escrow_id, self.check_release(escrow_id, milestone_id, caller))
+        }
+
+        /// Predict whether `request_cancel` would pass validation
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow to cancel
+        /// * `caller` - Account that would call the message
+        /// * `confirmation` - Confirmation hash that would be passed
+        ///
+        /// # Returns
+        /// * `SimulationResult` - Outcome, with details of the first failed check
+        #[ink(message)]
+        pub fn simulate_cancel(&self, escrow_id: u64, caller: AccountId, confirmation: Option<Hash>) -> SimulationResult {
+            self.simulation_result(escrow_id, self.check_cancel(escrow_id, caller, confirmation))
+        }
+
+        /// Check a milestone plan against `create_escrow` without creating anything
+        ///
+        /// Runs the same validation as `create_escrow` for the caller, but
+        /// reports every problem instead of stopping at the first one.
+        ///
+        /// # Arguments
+        /// * `milestones` - Milestones that would be passed to `create_escrow`
+        ///
+        /// # Returns
+        /// * `Vec<ValidationIssue>` - Problems in check order, the first being the error
+        ///   `create_escrow` would return (empty if the plan would be accepted)
+        #[ink(message)]
+        pub fn validate_milestones(&self, milestones: Vec<Milestone>) -> Vec<ValidationIssue> {
+            self.milestone_issues(self.env().caller(), &milestones)
+        }
+
+        /// Get the confirmation hash a strict escrow expects for a destructive action
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `action` - Action to confirm
+        ///
+        /// # Returns
+        /// * `Hash` - Blake2x256 hash of the SCALE-encoded `(escrow_id, action)`
+        #[ink(message)]
+        pub fn get_confirmation_hash(&self, escrow_id: u64, action: ConfirmableAction) -> Hash {
+            Hash::from(self.env().hash_encoded::<ink::env::hash::Blake2x256, _>(&(escrow_id, action)))
+        }
+
+        /// Get the final state of a closed escrow, the preimage of `finalize_snapshot`
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Option<EscrowSnapshot>` - Snapshot if the escrow is completed, cancelled or resolved
+        #[ink(message)]
+        pub fn get_final_snapshot(&self, escrow_id: u64) -> Option<EscrowSnapshot> {
+            self.final_snapshot(&self.escrows.get(escrow_id)?)
+        }
+
+        /// Compute the hash of a closed escrow's final state for notarization
+        ///
+        /// The same hash is emitted in the terminal event (`EscrowCompleted`,
+        /// `EscrowCancelled`, `DisputeResolved` or `DeadlockResolved`). See
+        /// `EscrowSnapshot` for the encoding.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Option<Hash>` - Blake2x256 hash of the SCALE-encoded `EscrowSnapshot`,
+        ///   None if the escrow doesn't exist or is still open
+        #[ink(message)]
+        pub fn finalize_snapshot(&self, escrow_id: u64) -> Option<Hash> {
+            self.get_final_snapshot(escrow_id)
+                .map(|snapshot| self.snapshot_hash(&snapshot))
+        }
+
+        /// Compute the key of an escrow created with `create_escrow_with_salt`
+        ///
+        /// # Arguments
+        /// * `client` - Account that creates the escrow
+        /// * `salt` - Salt passed to `create_escrow_with_salt`
+        ///
+        /// # Returns
+        /// * `Hash` - Blake2x256 hash of `client ++ salt`
+        #[ink(message)]
+        pub fn compute_escrow_id(&self, client: AccountId, salt: [u8; 32]) -> Hash {
+            Hash::from(self.env().hash_encoded::<ink::env::hash::Blake2x256, _>(&(client, salt)))
+        }
+
+        /// Get an escrow created with `create_escrow_with_salt`
+        ///
+        /// # Arguments
+        /// * `client` - Account that created the escrow
+        /// * `salt` - Salt passed to `create_escrow_with_salt`
+        ///
+        /// # Returns
+        /// * `Option<Escrow>` - Escrow if one was created with this salt, None otherwise
+        #[ink(message)]
+        pub fn get_escrow_by_salt(&self, client: AccountId, salt: [u8; 32]) -> Option<Escrow> {
+            let escrow_id = self.salted_escrows.get(self.compute_escrow_id(client, salt))?;
+            self.escrows.get(escrow_id)
+        }
+
+        /// Get all escrow IDs for a client
+        ///
+        /// The list is not capped and can outgrow the return buffer for
+        /// accounts with many escrows; prefer `get_escrows_by_client_paginated`.
+        ///
+        /// # Arguments
+        /// * `client` - Account ID of the client
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - Vector of escrow IDs
+        #[ink(message)]
+        pub fn get_escrows_by_client(&self, client: AccountId) -> Vec<u64> {
+            self.client_escrows.get(client).unwrap_or_default()
+        }
+
+        /// Get a page of the escrow IDs of a client, oldest first
+        ///
+        /// # Arguments
+        /// * `client` - Account ID of the client
+        /// * `offset` - Number of escrow IDs to skip
+        /// * `limit` - Maximum number of escrow IDs to return, capped at `MAX_PAGE_SIZE`
+        ///
+        /// # Returns
+        /// * `(Vec<u64>, u32)` - The page and the client's total number of escrows
+        #[ink(message)]
+        pub fn get_escrows_by_client_paginated(&self, client: AccountId, offset: u32, limit: u32) -> (Vec<u64>, u32) {
+            self.index_page(Role::Client, client, offset, limit)
+        }
+
+        /// Get the escrows of a client in one status
+        ///
+        /// Scans the window of at most `limit` entries of the client's index
+        /// starting at `offset` and keeps those in `status`, so each call loads
+        /// a bounded number of escrows. A page can hold fewer than `limit` IDs;
+        /// the next window starts at `offset + limit`.
+        ///
+        /// # Arguments
+        /// * `client` - Account ID of the client
+        /// * `status` - Status to filter by
+        /// * `offset` - Number of index entries to skip
+        /// * `limit` - Number of index entries to scan, capped at `MAX_PAGE_SIZE`
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - IDs of the scanned escrows in `status`
+        #[ink(message)]
+        pub fn get_escrows_by_client_and_status(
+            &self,
+            client: AccountId,
+            status: EscrowStatus,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<u64> {
+            self.index_page_with_status(Role::Client, client, status, offset, limit)
+        }
+
+        /// Get all escrow IDs for a freelancer
+        ///
+        /// The list is not capped and can outgrow the return buffer for
+        /// accounts with many escrows; prefer `get_escrows_by_freelancer_paginated`.
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account ID of the freelancer
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - Vector of escrow IDs
+        #[ink(message)]
+        pub fn get_escrows_by_freelancer(&self, freelancer: AccountId) -> Vec<u64> {
+            self.freelancer_escrows.get(freelancer).unwrap_or_default()
+        }
+
+        /// Get a page of the escrow IDs of a freelancer, oldest first
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account ID of the freelancer
+        /// * `offset` - Number of escrow IDs to skip
+        /// * `limit` - Maximum number of escrow IDs to return, capped at `MAX_PAGE_SIZE`
+        ///
+        /// # Returns
+        /// * `(Vec<u64>, u32)` - The page and the freelancer's total number of escrows
+        #[ink(message)]
+        pub fn get_escrows_by_freelancer_paginated(
+            &self,
+            freelancer: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> (Vec<u64>, u32) {
+            self.index_page(Role::Freelancer, freelancer, offset, limit)
+        }
+
+        /// Get the escrows of a freelancer in one status
+        ///
+        /// Scans a bounded window of the freelancer's index like
+        /// `get_escrows_by_client_and_status`.
+        ///
+        /// # Arguments
+        /// * `freelancer` - Account ID of the freelancer
+        /// * `status` - Status to filter by
+        /// * `offset` - Number of index entries to skip
+        /// * `limit` - Number of index entries to scan, capped at `MAX_PAGE_SIZE`
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - IDs of the scanned escrows in `status`
+        #[ink(message)]
+        pub fn get_escrows_by_freelancer_and_status(
+            &self,
+            freelancer: AccountId,
+            status: EscrowStatus,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<u64> {
+            self.index_page_with_status(Role::Freelancer, freelancer, status, offset, limit)
+        }
+
+        /// Get all escrow IDs for an arbiter
+        ///
+        /// # Arguments
+        /// * `arbiter` - Account ID of the arbiter
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - Vector of escrow IDs
+        #[ink(message)]
+        pub fn get_escrows_by_arbiter(&self, arbiter: AccountId) -> Vec<u64> {
+            self.arbiter_escrows.get(arbiter).unwrap_or_default()
+        }
+
+        /// Get the escrows of an arbiter that are waiting for a ruling
+        ///
+        /// Scans the arbiter's whole index, the list `get_escrows_by_arbiter` returns.
+        ///
+        /// # Arguments
+        /// * `arbiter` - Account ID of the arbiter
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - IDs of the arbiter's escrows in Disputed status
+        #[ink(message)]
+        pub fn get_disputed_escrows_by_arbiter(&self, arbiter: AccountId) -> Vec<u64> {
+            self.get_escrows_by_arbiter(arbiter)
+                .into_iter()
+                .filter(|&escrow_id| {
+                    self.escrows
+                        .get(escrow_id)
+                        .is_some_and(|escrow| escrow.status == EscrowStatus::Disputed)
+                })
+                .collect()
+        }
+
+        /// Get the roles an account holds in an escrow
+        ///
+        /// # Arguments
+        /// * `account` - Account to check
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Vec<Role>` - Roles in `Client`, `Freelancer`, `Arbiter` order,
+        ///   empty if the escrow doesn't exist or the account is not involved
+        #[ink(message)]
+        pub fn get_roles(&self, account: AccountId, escrow_id: u64) -> Vec<Role> {
+            self.escrows
+                .get(escrow_id)
+                .map(|escrow| Self::role_of(&escrow, account))
+                .unwrap_or_default()
+        }
+
+        /// Get the escrows waiting on an action from an account in one role
+        ///
+        /// Only the account's index for `role` is scanned, so arbiter duties never
+        /// show up as client or freelancer actions and vice versa. At most the
+        /// first `PENDING_SCAN_LIMIT` entries of the index are scanned.
+        ///
+        /// # Arguments
+        /// * `account` - Account to check
+        /// * `role` - Role to list actions for
+        ///
+        /// # Returns
+        /// * `Vec<u64>` - IDs of the escrows waiting on the account
+        #[ink(message)]
+        pub fn get_actionable_escrows(&self, account: AccountId, role: Role) -> Vec<u64> {
+            self.role_escrow_ids(role, account)
+                .into_iter()
+                .take(PENDING_SCAN_LIMIT)
+                .filter(|&escrow_id| {
+                    self.escrows
+                        .get(escrow_id)
+                        .is_some_and(|escrow| Self::awaits_action_from(&escrow, account, role))
+                })
+                .collect()
+        }
+
+        /// Check whether any escrow is waiting on an action from an account
+        ///
+        /// An escrow is waiting on the account when it is the counterparty of a
+        /// pending cancellation request, the freelancer of an escrow pending
+        /// acceptance, the client of a funded escrow with a submitted milestone
+        /// not yet released, or the arbiter of a disputed escrow or milestone.
+        /// Only the first `PENDING_SCAN_LIMIT` entries of each index are scanned,
+        /// and the scan stops at the first match.
+        ///
+        /// # Arguments
+        /// * `account` - Account to check
+        ///
+        /// # Returns
+        /// * `PendingActions` - `Yes`, `No`, or `Unknown` if nothing was found
+        ///   but an index was longer than the scan limit
+        #[ink(message)]
+        pub fn has_pending_actions(&self, account: AccountId) -> PendingActions {
+            let mut truncated = false;
+
+            for role in [Role::Client, Role::Freelancer, Role::Arbiter] {
+                let escrow_ids = self.role_escrow_ids(role, account);
+                if escrow_ids.len() > PENDING_SCAN_LIMIT {
+                    truncated = true;
+                }
+
+                for &escrow_id in escrow_ids.iter().take(PENDING_SCAN_LIMIT) {
+                    if let Some(escrow) = self.escrows.get(escrow_id) {
+                        if Self::awaits_action_from(&escrow, account, role) {
+                            return PendingActions::Yes;
+                        }
+                    }
+                }
+            }
+
+            if truncated {
+                PendingActions::Unknown
+            } else {
+                PendingActions::No
+            }
+        }
+
+        /// Submit a milestone for review (only by the freelancer)
+        ///
+        /// Stores the hash of the deliverable on the milestone so the client
+        /// knows it is ready. Submitting again replaces the proof hash. On
+        /// escrows created with `require_submission_before_release`, a
+        /// milestone can only be released once it was submitted.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `proof_hash` - Hash of the off-chain deliverable
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If milestone was already released
+        /// * `MilestoneForfeited` - If the client reclaimed the milestone
+        /// * `MilestoneDisputed` - If the milestone awaits the arbiter's ruling
+        ///
+        /// # Events
+        /// * `MilestoneSubmitted` - Emitted when the submission is stored
+        #[ink(message)]
+        pub fn submit_milestone(&mut self, escrow_id: u64, milestone_id: u32, proof_hash: Hash) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone = escrow
+                .milestones
+                .iter_mut()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+            if milestone.disputed {
+                return Err(EscrowError::MilestoneDisputed);
+            }
+
+            milestone.proof_hash = Some(proof_hash);
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(MilestoneSubmitted {
+                escrow_id,
+                milestone_id,
+                proof_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Reject a submitted milestone so the freelancer reworks it (only by the client)
+        ///
+        /// Clears the submission and counts the rejection on the milestone.
+        /// Once the milestone reaches the escrow's `escalation_threshold` of
+        /// rejections, it escalates to a dispute for the arbiter to rule on
+        /// with `resolve_milestone_dispute`; the other milestones are not
+        /// affected. Escrows without an arbiter, and milestones the arbiter
+        /// already ruled on, never escalate.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `reason` - Why the submission is rejected
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the client
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If milestone was already released
+        /// * `MilestoneForfeited` - If the client reclaimed the milestone
+        /// * `MilestoneNotSubmitted` - If the milestone has no pending submission
+        /// * `InvalidReason` - If the reason is empty or longer than `MAX_REASON_LEN`
+        ///
+        /// # Events
+        /// * `MilestoneRejected` - Emitted when the submission is rejected
+        /// * `MilestoneEscalated` - Emitted if the rejection escalates the milestone
+        #[ink(message)]
+        pub fn reject_milestone(&mut self, escrow_id: u64, milestone_id: u32, reason: String) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+
+            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone = escrow
+                .milestones
+                .iter_mut()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+            if milestone.proof_hash.is_none() {
+                return Err(EscrowError::MilestoneNotSubmitted);
+            }
+
+            if reason.is_empty() || reason.len() > MAX_REASON_LEN {
+                return Err(EscrowError::InvalidReason);
+            }
+
+            milestone.proof_hash = None;
+            milestone.rejections = milestone.rejections.saturating_add(1);
+            let rejections = milestone.rejections;
+            let escalated = escrow.arbiter.is_some()
+                && !milestone.arbitrated
+                && rejections >= u32::from(escrow.escalation_threshold);
+            if escalated {
+                milestone.disputed = true;
+            }
+            escrow.last_client_activity_at = Some(now);
+            self.escrows.insert(escrow_id, &escrow);
+
+            self.env().emit_event(MilestoneRejected {
+                escrow_id,
+                milestone_id,
+                reason,
+            });
+            if escalated {
+                self.env().emit_event(MilestoneEscalated {
+                    escrow_id,
+                    milestone_id,
+                    rejections,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Post a progress note against a milestone (only by the freelancer)
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `note_uri` - URI pointing to off-chain progress details
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is not the freelancer
+        /// * `InvalidStatus` - If escrow is not in Funded status
+        /// * `MilestoneNotFound` - If milestone doesn't exist
+        /// * `MilestoneAlreadyReleased` - If milestone was already released
+        /// * `InvalidNoteUri` - If the URI is empty or longer than `MAX_NOTE_URI_LEN`
+        /// * `TooManyProgressNotes` - If the milestone already holds `MAX_PROGRESS_NOTES` notes
+        ///
+        /// # Events
+        /// * `ProgressPosted` - Emitted when the note is stored
+        #[ink(message)]
+        pub fn post_progress(&mut self, escrow_id: u64, milestone_id: u32, note_uri: String) -> Result<()> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the freelancer
+            if escrow.freelancer != caller {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            // Disputed and closed escrows take no more notes
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone = escrow
+                .milestones
+                .iter()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+
+            if note_uri.is_empty() || note_uri.len() > MAX_NOTE_URI_LEN {
+                return Err(EscrowError::InvalidNoteUri);
+            }
+
+            let mut notes = self.progress_notes.get((escrow_id, milestone_id)).unwrap_or_default();
+            if notes.len() >= MAX_PROGRESS_NOTES {
+                return Err(EscrowError::TooManyProgressNotes);
+            }
+
+            notes.push(ProgressNote {
+                note_uri: note_uri.clone(),
+                posted_at: self.env().block_timestamp(),
+            });
+            self.progress_notes.insert((escrow_id, milestone_id), &notes);
+
+            self.env().emit_event(ProgressPosted {
+                escrow_id,
+                milestone_id,
+                note_uri,
+            });
+
+            Ok(())
+        }
+
+        /// Get a page of the progress notes of a milestone, oldest first
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        /// * `offset` - Number of notes to skip
+        /// * `limit` - Maximum number of notes to return
+        ///
+        /// # Returns
+        /// * `Vec<ProgressNote>` - Notes in posting order
+        #[ink(message)]
+        pub fn get_progress_notes(&self, escrow_id: u64, milestone_id: u32, offset: u32, limit: u32) -> Vec<ProgressNote> {
+            self.progress_notes
+                .get((escrow_id, milestone_id))
+                .unwrap_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect()
+        }
+
+        /// Point the arbiter of a disputed escrow to evidence (client or freelancer)
+        ///
+        /// Only the hash and a URI are stored; the evidence itself stays off-chain.
+        /// Entries are kept after the dispute is resolved.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `evidence_hash` - Hash of the evidence
+        /// * `uri` - URI pointing to the evidence
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is neither the client nor the freelancer
+        /// * `InvalidStatus` - If escrow is not in Disputed status
+        /// * `InvalidNoteUri` - If the URI is empty or longer than `MAX_NOTE_URI_LEN`
+        /// * `TooManyEvidenceEntries` - If the caller already submitted `MAX_EVIDENCE_PER_PARTY` entries
+        ///
+        /// # Events
+        /// * `EvidenceSubmitted` - Emitted when the evidence is stored
+        #[ink(message)]
+        pub fn submit_evidence(&mut self, escrow_id: u64, evidence_hash: Hash, uri: String) -> Result<()> {
+            let caller = self.env().caller();
+
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is client or freelancer
+            if !Self::is_party(&escrow, caller) {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Disputed {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if uri.is_empty() || uri.len() > MAX_NOTE_URI_LEN {
+                return Err(EscrowError::InvalidNoteUri);
+            }
+
+            let mut entries = self.evidence.get(escrow_id).unwrap_or_default();
+            if entries.iter().filter(|e| e.submitter == caller).count() >= MAX_EVIDENCE_PER_PARTY {
+                return Err(EscrowError::TooManyEvidenceEntries);
+            }
+
+            entries.push(Evidence {
+                submitter: caller,
+                hash: evidence_hash,
+                uri: uri.clone(),
+                submitted_at: self.env().block_timestamp(),
+            });
+            self.evidence.insert(escrow_id, &entries);
+
+            self.env().emit_event(EvidenceSubmitted {
+                escrow_id,
+                submitter: caller,
+                hash: evidence_hash,
+                uri,
+            });
+
+            Ok(())
+        }
+
+        /// Get the evidence submitted for an escrow, oldest first
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Vec<Evidence>` - Entries of both parties in submission order
+        #[ink(message)]
+        pub fn get_evidence(&self, escrow_id: u64) -> Vec<Evidence> {
+            self.evidence.get(escrow_id).unwrap_or_default()
+        }
+
+        /// Rate the other party of a closed escrow (only by the client or freelancer)
+        ///
+        /// Each party rates once per escrow, after it completed, was resolved,
+        /// or was cancelled after funding. Escrows cancelled before any funds
+        /// moved had no engagement to rate.
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `score` - Score from `MIN_RATING` to `MAX_RATING`
+        /// * `comment_uri` - Optional URI pointing to an off-chain review
+        ///
+        /// # Errors
+        /// * `EscrowNotFound` - If escrow doesn't exist
+        /// * `Unauthorized` - If caller is neither the client nor the freelancer
+        /// * `InvalidStatus` - If escrow is still open or was cancelled unfunded
+        /// * `InvalidRating` - If the score is out of range or the URI is empty or too long
+        /// * `AlreadyRated` - If the caller already rated this escrow
+        ///
+        /// # Events
+        /// * `CounterpartyRated` - Emitted when the rating is stored
+        #[ink(message)]
+        pub fn rate_counterparty(&mut self, escrow_id: u64, score: u8, comment_uri: Option<String>) -> Result<()> {
+            let caller = self.env().caller();
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            let ratee = if caller == escrow.client {
+                escrow.freelancer
+            } else if caller == escrow.freelancer {
+                escrow.client
+            } else {
+                return Err(EscrowError::Unauthorized);
+            };
+
+            let rateable = match escrow.status {
+                EscrowStatus::Completed | EscrowStatus::Resolved => true,
+                EscrowStatus::Cancelled => escrow.funded_at.is_some(),
+                _ => false,
+            };
+            if !rateable {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if !(MIN_RATING..=MAX_RATING).contains(&score)
+                || comment_uri
+                    .as_ref()
+                    .is_some_and(|uri| uri.is_empty() || uri.len() > MAX_RATING_URI_LEN)
+            {
+                return Err(EscrowError::InvalidRating);
+            }
+
+            if self.ratings.contains((escrow_id, caller)) {
+                return Err(EscrowError::AlreadyRated);
+            }
+
+            let rating = Rating {
+                rater: caller,
+                ratee,
+                score,
+                comment_uri: comment_uri.clone(),
+                rated_at: self.env().block_timestamp(),
+            };
+            self.ratings.insert((escrow_id, caller), &rating);
+
+            let (total_score, count) = self.rating_summaries.get(ratee).unwrap_or((0, 0));
+            self.rating_summaries.insert(
+                ratee,
+                &(total_score.saturating_add(u64::from(score)), count.saturating_add(1)),
+            );
+
+            self.env().emit_event(CounterpartyRated {
+                escrow_id,
+                rater: caller,
+                ratee,
+                score,
+                comment_uri,
+            });
+
+            Ok(())
+        }
+
+        /// Get the ratings given on an escrow, the client's first
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Vec<Rating>` - Up to two ratings, empty if none were given
+        #[ink(message)]
+        pub fn get_ratings(&self, escrow_id: u64) -> Vec<Rating> {
+            let Some(escrow) = self.escrows.get(escrow_id) else {
+                return Vec::new();
+            };
+            [escrow.client, escrow.freelancer]
+                .into_iter()
+                .filter_map(|rater| self.ratings.get((escrow_id, rater)))
+                .collect()
+        }
+
+        /// Get the ratings an account received across all escrows
+        ///
+        /// # Arguments
+        /// * `account` - Rated account
+        ///
+        /// # Returns
+        /// * `(u64, u32)` - (sum of scores, number of ratings); the average is their quotient
+        #[ink(message)]
+        pub fn get_rating_summary(&self, account: AccountId) -> (u64, u32) {
+            self.rating_summaries.get(account).unwrap_or((0, 0))
+        }
+
+        /// Get a single milestone of an escrow, including its dependencies
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        ///
+        /// # Returns
+        /// * `Option<Milestone>` - Milestone if it exists, None otherwise
+        #[ink(message)]
+        pub fn get_milestone(&self, escrow_id: u64, milestone_id: u32) -> Option<Milestone> {
+            self.escrows
+                .get(escrow_id)?
+                .milestones
+                .into_iter()
+                .find(|m| m.id == milestone_id)
+        }
+
+        /// Get how many submissions of a milestone the client rejected
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        /// * `milestone_id` - ID of the milestone
+        ///
+        /// # Returns
+        /// * `Option<u32>` - Rejection count, None if the milestone doesn't exist
+        #[ink(message)]
+        pub fn get_milestone_rejections(&self, escrow_id: u64, milestone_id: u32) -> Option<u32> {
+            self.get_milestone(escrow_id, milestone_id).map(|m| m.rejections)
+        }
+
+        /// Get all milestones for an escrow
+        ///
+        /// # Arguments
+        /// * `escrow_id` - ID of the escrow
+        ///
+        /// # Returns
+        /// * `Vec<Milestone>` - Vector of milestones
+        #[ink(message)]
+        pub fn get_milestones(&self, escrow_id: u64) -> Vec<Milestone> {
+            self.escrows
+                .get(escrow_id)
+                .map(|escrow| escrow.milestones)
+                .unwrap_or_default()
+        }
+
+        /// Fail with `NotOwner` unless the caller is the owner
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(EscrowError::NotOwner);
+            }
+            Ok(())
+        }
+
+        /// Fail with `ExceedsMaxEscrow` if `total_amount` is above the cap and
+        /// `client` is not exempt; every path that grows an escrow must call this
+        fn ensure_within_max_escrow(&self, client: AccountId, total_amount: Balance) -> Result<()> {
+            if self.settings.max_escrow_amount > 0
+                && total_amount > self.settings.max_escrow_amount
+                && !self.exempt_clients.contains(client)
+            {
+                return Err(EscrowError::ExceedsMaxEscrow);
+            }
+            Ok(())
+        }
+
+        /// Collect every problem of a milestone plan created by `client`, in check order
+        fn milestone_issues(&self, client: AccountId, milestones: &[Milestone]) -> Vec<ValidationIssue> {
+            let mut issues = Vec::new();
+            let mut report = |error, index| issues.push(ValidationIssue { error, index });
+
+            if milestones.is_empty() {
+                report(EscrowError::EmptyMilestones, None);
+            }
+            if milestones.len() > MAX_MILESTONES {
+                report(EscrowError::TooManyMilestones, None);
+            }
+
+            let total_amount: Balance = milestones
+                .iter()
+                .map(|m| m.amount)
+                .sum();
+            if !milestones.is_empty() && total_amount == 0 {
+                report(EscrowError::ZeroAmount, None);
+            }
+            if let Err(error) = self.ensure_within_max_escrow(client, total_amount) {
+                report(error, None);
+            }
+
+            for (idx, milestone) in milestones.iter().enumerate() {
+                let index = Some(idx as u32);
+
+                // IDs must be unique and sequential
+                if milestone.id != idx as u32 {
+                    report(EscrowError::InvalidAmount, index); // Reuse for validation error
+                }
+                let used = milestone.released || milestone.forfeited || milestone.disputed || milestone.arbitrated;
+                if used || milestone.proof_hash.is_some() || milestone.rejections > 0 {
+                    report(EscrowError::InvalidStatus, index); // Milestones should start unreleased
+                }
+                if !deadline_follows(&milestones[..idx], milestone) {
+                    report(EscrowError::InvalidMilestoneDeadlines, index);
+                }
+
+                // Depending only on earlier milestones rules out cycles
+                let deps = &milestone.depends_on;
+                if deps.len() > MAX_MILESTONE_DEPENDENCIES
+                    || deps.iter().enumerate().any(|(i, &dep)| dep >= idx as u32 || deps[..i].contains(&dep))
+                {
+                    report(EscrowError::InvalidDependencies, index);
+                }
+            }
+
+            issues
+        }
+
+        /// Validate an amendment of `escrow` proposed by `proposer` and return the new total
+        fn check_amendment(&self, escrow: &Escrow, proposer: AccountId, changes: &Amendment) -> Result<Balance> {
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            // A drawdown has no milestone plan to amend
+            if self.drawdowns.contains(escrow.id) {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            if changes.amount_changes.is_empty()
+                && changes.new_milestones.is_empty()
+                && changes.abandonment_timeout_ms.is_none()
+                && changes.deadlock_timeout_ms.is_none()
+            {
+                return Err(EscrowError::InvalidAmendment);
+            }
+
+            // Timeouts only grow, 0 (never) being the longest
+            let extends = |current: u64, new: Option<u64>| match new {
+                Some(new) => new == 0 || (current != 0 && new >= current),
+                None => true,
+            };
+            if !extends(escrow.abandonment_timeout_ms, changes.abandonment_timeout_ms)
+                || !extends(escrow.deadlock_timeout_ms, changes.deadlock_timeout_ms)
+            {
+                return Err(EscrowError::InvalidAmendment);
+            }
+
+            let mut total_amount = escrow.total_amount;
+            for (i, &(milestone_id, amount)) in changes.amount_changes.iter().enumerate() {
+                if changes.amount_changes[..i].iter().any(|&(id, _)| id == milestone_id) {
+                    return Err(EscrowError::InvalidAmendment);
+                }
+                let milestone = escrow
+                    .milestones
+                    .iter()
+                    .find(|m| m.id == milestone_id)
+                    .ok_or(EscrowError::MilestoneNotFound)?;
+                // A partly paid milestone keeps its amount
+                if milestone.released || milestone.remaining < milestone.amount {
+                    return Err(EscrowError::MilestoneAlreadyReleased);
+                }
+                if milestone.forfeited {
+                    return Err(EscrowError::MilestoneForfeited);
+                }
+                if milestone.disputed {
+                    return Err(EscrowError::MilestoneDisputed);
+                }
+                total_amount = total_amount.saturating_sub(milestone.amount).saturating_add(amount);
+            }
+
+            if escrow.milestones.len().saturating_add(changes.new_milestones.len()) > MAX_MILESTONES {
+                return Err(EscrowError::TooManyMilestones);
+            }
+
+            // New milestones continue the sequence and may depend on any earlier milestone
+            let first_id = escrow.milestones.len() as u32;
+            for (offset, milestone) in changes.new_milestones.iter().enumerate() {
+                let fresh = !milestone.released
+                    && !milestone.forfeited
+                    && !milestone.disputed
+                    && !milestone.arbitrated
+                    && milestone.proof_hash.is_none()
+                    && milestone.rejections == 0;
+                if milestone.id != first_id.saturating_add(offset as u32) || !fresh {
+                    return Err(EscrowError::InvalidAmount);
+                }
+                let earlier = escrow.milestones.iter().chain(&changes.new_milestones[..offset]);
+                if !deadline_follows(earlier, milestone) {
+                    return Err(EscrowError::InvalidMilestoneDeadlines);
+                }
+                let deps = &milestone.depends_on;
+                if deps.len() > MAX_MILESTONE_DEPENDENCIES
+                    || deps.iter().enumerate().any(|(i, &dep)| dep >= milestone.id || deps[..i].contains(&dep))
+                {
+                    return Err(EscrowError::InvalidDependencies);
+                }
+                total_amount = total_amount.saturating_add(milestone.amount);
+            }
+
+            if total_amount == 0 {
+                return Err(EscrowError::ZeroAmount);
+            }
+            if total_amount > escrow.total_amount {
+                // The client pays the increase when accepting
+                if proposer != escrow.freelancer {
+                    return Err(EscrowError::InvalidAmendment);
+                }
+                self.ensure_within_max_escrow(escrow.client, total_amount)?;
+            }
+
+            Ok(total_amount)
+        }
+
+        /// Apply validated amendment changes to an escrow's milestones and timeouts
+        fn apply_amendment(escrow: &mut Escrow, changes: Amendment) {
+            for (milestone_id, amount) in changes.amount_changes {
+                if let Some(milestone) = escrow.milestones.iter_mut().find(|m| m.id == milestone_id) {
+                    milestone.amount = amount;
+                    milestone.remaining = amount;
+                }
+            }
+            let mut new_milestones = changes.new_milestones;
+            reset_remaining(&mut new_milestones);
+            escrow.milestones.extend(new_milestones);
+            if let Some(timeout_ms) = changes.abandonment_timeout_ms {
+                escrow.abandonment_timeout_ms = timeout_ms;
+            }
+            if let Some(timeout_ms) = changes.deadlock_timeout_ms {
+                escrow.deadlock_timeout_ms = timeout_ms;
+            }
+        }
+
+        /// Release the milestone at `milestone_index` of a checked escrow to the
+        /// freelancer, completing the escrow if it was the last open milestone
+        fn pay_milestone(&mut self, mut escrow: Escrow, milestone_index: usize) -> Result<()> {
+            let escrow_id = escrow.id;
+            let milestone_id = escrow.milestones[milestone_index].id;
+            let milestone_amount = escrow.milestones[milestone_index].remaining;
+
+            // Mark milestone as released
+            escrow.milestones[milestone_index].released = true;
+            escrow.milestones[milestone_index].remaining = 0;
+            escrow.released_count = escrow.released_count.saturating_add(1);
+            self.clear_deadline_extension(escrow_id, milestone_id);
+
+            // Transfer funds to freelancer
+            let breakdown = payout_breakdown(milestone_amount, escrow.fee_bps);
+            self.pay_out(&escrow, Payee::Freelancer, breakdown.net)?;
+            self.collect_fee(&escrow, breakdown.protocol_fee)?;
+
+            // Check if all milestones are released (or reclaimed)
+            if escrow.all_settled() {
+                escrow.status = EscrowStatus::Completed;
+            }
+
+            // Update escrow
+            self.escrows.insert(escrow_id, &escrow);
+            self.settle_deposit(&escrow, milestone_amount);
+
+            // Emit event
+            self.env().emit_event(MilestoneReleased {
+                escrow_id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                milestone_id,
+                amount: breakdown.gross,
+                protocol_fee: breakdown.protocol_fee,
+                net_to_freelancer: breakdown.net,
+            });
+            if escrow.status == EscrowStatus::Completed {
+                self.complete_escrow(&mut escrow)?;
+            }
+
+            Ok(())
+        }
+
+        /// Refund the arbiter fee of an escrow whose milestones are all released,
+        /// then close it and emit `EscrowCompleted`
+        fn complete_escrow(&mut self, escrow: &mut Escrow) -> Result<()> {
+            // Without a resolved dispute the arbiter fee goes back to the client
+            let arbiter_fee = arbiter_fee_held(escrow);
+            let refunded = if arbiter_fee > 0 {
+                escrow.deposited = escrow.deposited.saturating_sub(arbiter_fee);
+                self.escrows.insert(escrow.id, escrow);
+                self.settle_deposit(escrow, arbiter_fee);
+                self.pay_out_insured(escrow, Payee::Client, arbiter_fee)?
+            } else {
+                0
+            };
+
+            let snapshot_hash = self.close_escrow(escrow);
+            self.env().emit_event(EscrowCompleted {
+                escrow_id: escrow.id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                total_amount: escrow.total_amount,
+                arbiter_fee_refunded: refunded,
+                snapshot_hash,
+            });
+            Ok(())
+        }
+
+        /// Remove an escrow from the client, freelancer and arbiter indexes
+        fn unindex_escrow(&mut self, escrow: &Escrow) {
+            let without = |escrow_ids: Option<Vec<u64>>| {
+                let mut escrow_ids = escrow_ids.unwrap_or_default();
+                escrow_ids.retain(|&escrow_id| escrow_id != escrow.id);
+                escrow_ids
+            };
+            self.client_escrows
+                .insert(escrow.client, &without(self.client_escrows.get(escrow.client)));
+            self.freelancer_escrows
+                .insert(escrow.freelancer, &without(self.freelancer_escrows.get(escrow.freelancer)));
+            if let Some(arbiter) = escrow.arbiter {
+                self.arbiter_escrows.insert(arbiter, &without(self.arbiter_escrows.get(arbiter)));
+            }
+        }
+
+        /// Cancel an escrow the freelancer declined, drop it from the indexes
+        /// and emit `EscrowDeclined`
+        fn close_declined(&mut self, mut escrow: Escrow) {
+            escrow.status = EscrowStatus::Cancelled;
+            escrow.termination_reason = Some(TerminationReason::Declined);
+            self.escrows.insert(escrow.id, &escrow);
+            self.unindex_escrow(&escrow);
+
+            let snapshot_hash = self.close_escrow(&escrow);
+            self.env().emit_event(EscrowDeclined {
+                escrow_id: escrow.id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                snapshot_hash,
+            });
+        }
+
+        /// Record when an escrow reached its terminal status and return the
+        /// hash of its final snapshot, for the terminal event
+        fn close_escrow(&mut self, escrow: &Escrow) -> Hash {
+            let now = self.env().block_timestamp();
+            self.closed_at.insert(escrow.id, &now);
+            self.final_snapshot(escrow)
+                .map(|snapshot| self.snapshot_hash(&snapshot))
+                .unwrap_or_default()
+        }
+
+        /// Build the archival snapshot of an escrow, None unless it is terminal
+        fn final_snapshot(&self, escrow: &Escrow) -> Option<EscrowSnapshot> {
+            if !escrow.status.is_terminal() {
+                return None;
+            }
+            let (paid_to_freelancer, refunded_to_client) = self.payout_totals.get(escrow.id).unwrap_or((0, 0));
+            Some(EscrowSnapshot {
+                version: SNAPSHOT_VERSION,
+                escrow_id: escrow.id,
+                client: escrow.client,
+                freelancer: escrow.freelancer,
+                arbiter: escrow.arbiter,
+                refund_address: Self::refund_address(escrow),
+                total_amount: escrow.total_amount,
+                deposited: escrow.deposited,
+                milestones: escrow
+                    .milestones
+                    .iter()
+                    .map(|m| (m.id, m.amount, m.released, m.description.clone()))
+                    .collect(),
+                status: escrow.status.clone(),
+                termination_reason: escrow.termination_reason,
+                created_at: escrow.created_at,
+                funded_at: escrow.funded_at,
+                closed_at: self.closed_at.get(escrow.id).unwrap_or(0),
+                paid_to_freelancer,
+                refunded_to_client,
+            })
+        }
+
+        /// Blake2x256 hash of a snapshot's SCALE encoding
+        fn snapshot_hash(&self, snapshot: &EscrowSnapshot) -> Hash {
+            Hash::from(self.env().hash_encoded::<ink::env::hash::Blake2x256, _>(snapshot))
+        }
+
+        /// Emit `MilestonesSnapshot` with the current milestone IDs and amounts
+        fn emit_milestones_snapshot(&self, escrow: &Escrow) {
+            self.env().emit_event(MilestonesSnapshot {
+                escrow_id: escrow.id,
+                milestones: escrow.milestones.iter().map(|m| (m.id, m.amount)).collect(),
+            });
+        }
+
+        /// Check the confirmation hash of a destructive action on strict escrows
+        fn ensure_confirmed(
+            &self,
+            escrow: &Escrow,
+            action: ConfirmableAction,
+            confirmation: Option<Hash>,
+        ) -> Result<()> {
+            if escrow.strict_confirmation
+                && confirmation != Some(self.get_confirmation_hash(escrow.id, action))
+            {
+                return Err(EscrowError::ConfirmationMismatch);
+            }
+            Ok(())
+        }
+
+        /// Validate `fund_escrow` and return the escrow to fund
+        fn check_fund(&self, escrow_id: u64, caller: AccountId, transferred: Balance) -> Checked<Escrow> {
+            let escrow = self.escrows.get(escrow_id).ok_or(CheckFailure::new(
+                EscrowError::EscrowNotFound,
+                ValidationCheck::EscrowExists,
+            ))?;
+
+            // Verify caller is the client
+            if escrow.client != caller {
+                return Err(CheckFailure::new(EscrowError::Unauthorized, ValidationCheck::Caller));
+            }
+
+            // Verify escrow is in Created status, telling repeated funding apart
+            if escrow.status != EscrowStatus::Created {
+                let failure = CheckFailure::status(&[EscrowStatus::Created]);
+                if escrow.funded_at.is_some() {
+                    return Err(CheckFailure { error: EscrowError::AlreadyFunded, ..failure });
+                }
+                return Err(failure);
+            }
+
+            // Nothing more is locked while a cancellation is pending
+            if escrow.cancel_requested_by.is_some() {
+                return Err(CheckFailure::status(&[EscrowStatus::Created]));
+            }
+
+            // A lapsed escrow only waits to be expired
+            if escrow.funding_deadline.is_some_and(|deadline| self.env().block_timestamp() > deadline) {
+                return Err(CheckFailure::new(EscrowError::InvalidStatus, ValidationCheck::FundingDeadline));
+            }
+
+            // Tokens are pulled, not sent along
+            if escrow.token.is_some() {
+                if transferred > 0 {
+                    return Err(CheckFailure {
+                        amounts: Some((0, transferred)),
+                        ..CheckFailure::new(EscrowError::InvalidAmount, ValidationCheck::Amount)
+                    });
+                }
+                return Ok(escrow);
+            }
+
+            // Any positive deposit counts towards the total
+            if transferred == 0 {
+                return Err(CheckFailure {
+                    amounts: Some((funding_target(&escrow).saturating_sub(escrow.deposited), transferred)),
+                    ..CheckFailure::new(EscrowError::ZeroAmount, ValidationCheck::Amount)
+                });
+            }
+
+            Ok(escrow)
+        }
+
+        /// Validate `release_milestone` and return the escrow and milestone index
+        fn check_release(&self, escrow_id: u64, milestone_id: u32, caller: AccountId) -> Checked<(Escrow, usize)> {
+            let escrow = self.check_release_escrow(escrow_id, caller)?;
+            let milestone_index = Self::check_release_milestone(&escrow, milestone_id, caller)?;
+            Ok((escrow, milestone_index))
+        }
+
+        /// Validate the escrow-level part of a release and return the escrow
+        fn check_release_escrow(&self, escrow_id: u64, caller: AccountId) -> Checked<Escrow> {
+            let escrow = self.escrows.get(escrow_id).ok_or(CheckFailure::new(
+                EscrowError::EscrowNotFound,
+                ValidationCheck::EscrowExists,
+            ))?;
+
+            // Verify caller is the client, or the freelancer with an auto-release allowance
+            let by_client = escrow.client == caller;
+            if !by_client && (escrow.freelancer != caller || escrow.auto_release_threshold == 0) {
+                return Err(CheckFailure::new(EscrowError::Unauthorized, ValidationCheck::Caller));
+            }
+
+            // Verify escrow is in valid status
+            if escrow.status != EscrowStatus::Funded && escrow.status != EscrowStatus::Disputed {
+                return Err(CheckFailure::status(&[EscrowStatus::Funded, EscrowStatus::Disputed]));
+            }
+
+            Ok(escrow)
+        }
+
+        /// Validate releasing one milestone of an escrow that passed
+        /// `check_release_escrow` and return the milestone's index
+        fn check_release_milestone(escrow: &Escrow, milestone_id: u32, caller: AccountId) -> Checked<usize> {
+            let by_client = escrow.client == caller;
+            let unauthorized = CheckFailure::new(EscrowError::Unauthorized, ValidationCheck::Caller);
+
+            // Find milestone
+            let milestone_index = escrow
+                .milestones
+                .iter()
+                .position(|m| m.id == milestone_id)
+                .ok_or(CheckFailure::new(EscrowError::MilestoneNotFound, ValidationCheck::MilestoneExists))?;
+
+            let milestone = &escrow.milestones[milestone_index];
+
+            // Larger milestones still need the client
+            if !by_client && milestone.amount > escrow.auto_release_threshold {
+                return Err(CheckFailure {
+                    amounts: Some((escrow.auto_release_threshold, milestone.amount)),
+                    ..unauthorized
+                });
+            }
+
+            if milestone.released {
+                return Err(CheckFailure::new(
+                    EscrowError::MilestoneAlreadyReleased,
+                    ValidationCheck::MilestoneUnreleased,
+                ));
+            }
+            if milestone.forfeited {
+                return Err(CheckFailure::new(EscrowError::MilestoneForfeited, ValidationCheck::MilestoneUnreleased));
+            }
+            if milestone.disputed {
+                return Err(CheckFailure::new(EscrowError::MilestoneDisputed, ValidationCheck::MilestoneUndisputed));
+            }
+
+            if escrow.require_submission_before_release && milestone.proof_hash.is_none() {
+                return Err(CheckFailure::new(EscrowError::MilestoneNotSubmitted, ValidationCheck::Submission));
+            }
+
+            if !dependencies_released(escrow, milestone) {
+                return Err(CheckFailure::new(EscrowError::DependenciesNotMet, ValidationCheck::Dependencies));
+            }
+
+            Ok(milestone_index)
+        }
+
+        /// Validate `request_cancel` and return the escrow to cancel
+        fn check_cancel(&self, escrow_id: u64, caller: AccountId, confirmation: Option<Hash>) -> Checked<Escrow> {
+            let escrow = self.escrows.get(escrow_id).ok_or(CheckFailure::new(
+                EscrowError::EscrowNotFound,
+                ValidationCheck::EscrowExists,
+            ))?;
+
+            // Verify caller is client or freelancer
+            if !Self::is_party(&escrow, caller) {
+                return Err(CheckFailure::new(EscrowError::Unauthorized, ValidationCheck::Caller));
+            }
+
+            // Verify escrow is in valid status
+            if escrow.status.is_terminal() {
+                return Err(CheckFailure::status(&[
+                    EscrowStatus::PendingAcceptance,
+                    EscrowStatus::Created,
+                    EscrowStatus::Funded,
+                    EscrowStatus::Disputed,
+                ]));
+            }
+
+            if let Err(error) = self.ensure_confirmed(&escrow, ConfirmableAction::RequestCancel, confirmation) {
+                return Err(CheckFailure::new(error, ValidationCheck::Confirmation));
+            }
+
+            // The client cannot back out of a freshly funded escrow right away
+            if escrow.cancel_requested_by.is_none() && caller == escrow.client {
+                if let Some(earliest) = self.earliest_dispute_at(&escrow) {
+                    if self.env().block_timestamp() < earliest {
+                        return Err(CheckFailure::new(EscrowError::DisputeTooEarly, ValidationCheck::DisputeGrace));
+                    }
+                }
+            }
+
+            Ok(escrow)
+        }
+
+        /// Turn the outcome of a shared validation function into a `SimulationResult`
+        fn simulation_result<T>(&self, escrow_id: u64, outcome: Checked<T>) -> SimulationResult {
+            let actual_status = self.escrows.get(escrow_id).map(|escrow| escrow.status);
+
+            match outcome {
+                Ok(_) => SimulationResult {
+                    ok: true,
+                    error: None,
+                    failed_check: None,
+                    expected_status: Vec::new(),
+                    actual_status,
+                    required_amount: None,
+                    provided_amount: None,
+                },
+                Err(failure) => SimulationResult {
+                    ok: false,
+                    error: Some(failure.error),
+                    failed_check: Some(failure.check),
+                    expected_status: failure.expected_status.to_vec(),
+                    actual_status,
+                    required_amount: failure.amounts.map(|(required, _)| required),
+                    provided_amount: failure.amounts.map(|(_, provided)| provided),
+                },
+            }
+        }
+
+        /// Apply an owner change to the settings and emit `ConfigUpdated`
+        fn update_settings(&mut self, update: impl FnOnce(&mut EscrowSettings)) {
+            update(&mut self.settings);
+            self.env().emit_event(ConfigUpdated {
+                settings: self.settings.clone(),
+            });
+        }
+
+        /// Earliest timestamp at which the client may open a dispute, once funded
+        fn earliest_dispute_at(&self, escrow: &Escrow) -> Option<u64> {
+            escrow
+                .funded_at
+                .map(|funded_at| funded_at.saturating_add(self.settings.dispute_grace_ms))
+        }
+
+        /// Recompute outstanding deposits from scratch and compare against the counter
+        #[cfg(test)]
+        fn debug_assert_reconciled(&self) {
+            let outstanding: Balance = (0..self.next_escrow_id)
+                .filter_map(|escrow_id| self.escrows.get(escrow_id))
+                .filter(|escrow| escrow.token.is_none())
+                .map(|escrow| Self::held_funds(&escrow))
+                .sum();
+
+            assert_eq!(outstanding, self.deposits_outstanding, "deposit counter drifted");
+
+            // Every deposit not refunded was either paid to a freelancer,
+            // charged as a fee, is still held, or is owed from the insurance pool
+            let shortfalls: Balance = (0..self.next_escrow_id)
+                .map(|escrow_id| self.get_shortfall(escrow_id))
+                .sum();
+            assert_eq!(
+                self.sum_total_spent,
+                self.sum_total_earned
+                    .saturating_add(self.deposits_outstanding)
+                    .saturating_add(self.sum_fees_charged)
+                    .saturating_add(shortfalls),
+                "spending and earnings drifted"
+            );
+
+            for escrow in (0..self.next_escrow_id).filter_map(|escrow_id| self.escrows.get(escrow_id)) {
+                assert_eq!(self.get_ledger_balance(escrow.id), Self::held_funds(&escrow), "ledger drifted");
+            }
+        }
+
+        /// Funds the contract should still hold for an escrow
+        /// (cancelled, resolved and expired escrows have paid everything out)
+        #[cfg(test)]
+        fn held_funds(escrow: &Escrow) -> Balance {
+            match escrow.status {
+                EscrowStatus::Cancelled | EscrowStatus::Resolved | EscrowStatus::Expired => 0,
+                _ => settlement(escrow).refundable,
+            }
+        }
+
+        /// Drop the deadline extension request and reclaim grace of a milestone
+        fn clear_deadline_extension(&mut self, escrow_id: u64, milestone_id: u32) {
+            self.deadline_extensions.remove((escrow_id, milestone_id));
+            self.extension_grace.remove((escrow_id, milestone_id));
+        }
+
+        /// Drop the deadline extension requests of every milestone of a closed escrow
+        fn clear_deadline_extensions(&mut self, escrow: &Escrow) {
+            for milestone in &escrow.milestones {
+                self.clear_deadline_extension(escrow.id, milestone.id);
+            }
+        }
+
+        /// Validate that the caller can answer a deadline extension request and return the escrow
+        fn check_extension_answer(&self, escrow_id: u64, milestone_id: u32) -> Result<Escrow> {
+            let escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+
+            // Verify caller is the client
+            if escrow.client != self.env().caller() {
+                return Err(EscrowError::Unauthorized);
+            }
+
+            if escrow.status != EscrowStatus::Funded {
+                return Err(EscrowError::InvalidStatus);
+            }
+
+            let milestone = escrow
+                .milestones
+                .iter()
+                .find(|m| m.id == milestone_id)
+                .ok_or(EscrowError::MilestoneNotFound)?;
+            if milestone.released {
+                return Err(EscrowError::MilestoneAlreadyReleased);
+            }
+            if milestone.forfeited {
+                return Err(EscrowError::MilestoneForfeited);
+            }
+
+            Ok(escrow)
+        }
+
+        /// Page of the escrow IDs where `account` holds `role`, with the total count
+        fn index_page(&self, role: Role, account: AccountId, offset: u32, limit: u32) -> (Vec<u64>, u32) {
+            let escrow_ids = self.role_escrow_ids(role, account);
+            let total = escrow_ids.len() as u32;
+            let page = escrow_ids
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect();
+            (page, total)
+        }
+
+        /// Escrows in `status` among a page of the escrow IDs where `account` holds `role`
+        fn index_page_with_status(
+            &self,
+            role: Role,
+            account: AccountId,
+            status: EscrowStatus,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<u64> {
+            let (page, _) = self.index_page(role, account, offset, limit);
+            page.into_iter()
+                .filter(|&escrow_id| self.escrows.get(escrow_id).is_some_and(|escrow| escrow.status == status))
+                .collect()
+        }
+
+        /// IDs of the escrows where `account` holds `role`, from the matching account index
+        fn role_escrow_ids(&self, role: Role, account: AccountId) -> Vec<u64> {
+            match role {
+                Role::Client => self.client_escrows.get(account),
+                Role::Freelancer => self.freelancer_escrows.get(account),
+                Role::Arbiter => self.arbiter_escrows.get(account),
+            }
+            .unwrap_or_default()
+        }
+
+        /// Roles `account` holds in `escrow`, in `Client`, `Freelancer`, `Arbiter` order
+        fn role_of(escrow: &Escrow, account: AccountId) -> Vec<Role> {
+            let mut roles = Vec::new();
+            if escrow.client == account {
+                roles.push(Role::Client);
+            }
+            if escrow.freelancer == account {
+                roles.push(Role::Freelancer);
+            }
+            if escrow.arbiter == Some(account) {
+                roles.push(Role::Arbiter);
+            }
+            roles
+        }
+
+        /// Whether `account` is the client or the freelancer of `escrow`
+        fn is_party(escrow: &Escrow, account: AccountId) -> bool {
+            Self::role_of(escrow, account)
+                .iter()
+                .any(|role| matches!(role, Role::Client | Role::Freelancer))
+        }
+
+        /// Debit `amount` from an escrow's ledger balance, then transfer it to `payee`
         ///
-        /// # Arguments
-        /// * `freelancer` - Account of the freelancer who will receive payments
-        /// * `milestones` - Vector of milestones defining payment structure
-        /// * `arbiter` - Optional arbiter account for dispute resolution
+        /// Keeps a bookkeeping bug in one escrow from paying out another
+        /// escrow's deposit: the debit fails before any transfer happens.
+        /// Delivered payments count towards the freelancer's (or for arbiter
+        /// fees, the arbiter's) earnings, delivered refunds reduce the client's
+        /// spending. Token-denominated escrows pay out with `PSP22::transfer`
+        /// and stay out of the native-currency totals.
         ///
-        /// # Errors
-        /// * `EmptyMilestones` - If milestones vector is empty
-        /// * `ZeroAmount` - If total amount of milestones is zero
+        /// A failed transfer reverts the whole call with `InsufficientFunds`
+        /// (`TokenTransferFailed` for tokens). Used for releases to the
+        /// freelancer, which must not complete without the payment.
+        fn pay_out(&mut self, escrow: &Escrow, payee: Payee, amount: Balance) -> Result<Balance> {
+            self.deliver(escrow, payee, amount, false)
+        }
+
+        /// Like `pay_out`, but a native transfer that fails does not block the settlement
         ///
-        /// # Events
-        /// * `EscrowCreated` - Emitted when escrow is successfully created
-        #[ink(message)]
-        pub fn create_escrow(
-            &mut self,
-            freelancer: AccountId,
-            milestones: Vec<Milestone>,
-            arbiter: Option<AccountId>,
-        ) -> Result<u64> {
-            let caller = self.env().caller();
+        /// Used for refunds and dispute resolutions: the undelivered amount moves
+        /// to the insurance pool and is recorded as the escrow's shortfall, for the
+        /// owner to `compensate`. Only funds the contract holds can be owed from
+        /// the pool, so the call still reverts with `InsufficientFunds` if its
+        /// balance does not cover `amount`. A failed token transfer reverts the
+        /// whole call. Returns the amount delivered, so events never report money
+        /// the payee did not get.
+        fn pay_out_insured(&mut self, escrow: &Escrow, payee: Payee, amount: Balance) -> Result<Balance> {
+            self.deliver(escrow, payee, amount, true)
+        }
+
+        /// Shared body of `pay_out` and `pay_out_insured`
+        fn deliver(&mut self, escrow: &Escrow, payee: Payee, amount: Balance, insured: bool) -> Result<Balance> {
+            let balance = self.ledger.get(escrow.id).unwrap_or(0);
+            let remaining = balance.checked_sub(amount).ok_or(EscrowError::LedgerUnderflow)?;
+            self.ledger.insert(escrow.id, &remaining);
+
+            let to = match payee {
+                Payee::Freelancer => escrow.payout_address.unwrap_or(escrow.freelancer),
+                Payee::Client => Self::refund_address(escrow),
+                Payee::Arbiter => escrow.arbiter.ok_or(EscrowError::InvalidArbiter)?,
+            };
+
+            match escrow.token {
+                None => {
+                    if insured && self.env().balance() < amount {
+                        return Err(EscrowError::InsufficientFunds);
+                    }
+                    if self.env().transfer(to, amount).is_err() {
+                        if !insured {
+                            return Err(EscrowError::InsufficientFunds);
+                        }
+                        self.record_shortfall(escrow.id, to, amount);
+                        return Ok(0);
+                    }
+                }
+                Some(token) => Self::transfer_tokens(token, to, amount)?,
+            }
+
+            let (paid, refunded) = self.payout_totals.get(escrow.id).unwrap_or((0, 0));
+            let totals = match payee {
+                Payee::Freelancer => (paid.saturating_add(amount), refunded),
+                Payee::Client => (paid, refunded.saturating_add(amount)),
+                Payee::Arbiter => (paid, refunded),
+            };
+            self.payout_totals.insert(escrow.id, &totals);
+            if escrow.token.is_none() {
+                match payee {
+                    // Earnings count for the freelancer wherever they are paid
+                    Payee::Freelancer => self.record_earned(escrow.freelancer, amount),
+                    Payee::Arbiter => self.record_earned(to, amount),
+                    Payee::Client => self.record_refunded(escrow, amount),
+                }
+            }
+            Ok(amount)
+        }
+
+        /// Send `amount` of `token` held by the contract to `to` with `PSP22::transfer`
+        fn transfer_tokens(token: AccountId, to: AccountId, amount: Balance) -> Result<()> {
+            let mut psp22: ink::contract_ref!(PSP22) = token.into();
+            let outcome = psp22
+                .call_mut()
+                .transfer(to, amount, Vec::new())
+                .try_invoke();
+            if !matches!(outcome, Ok(Ok(Ok(())))) {
+                return Err(EscrowError::TokenTransferFailed);
+            }
+            Ok(())
+        }
+
+        /// Move a release's protocol fee from the escrow's ledger to the collected fees
+        ///
+        /// `insurance_bps` of native-currency fees go to the insurance pool instead.
+        fn collect_fee(&mut self, escrow: &Escrow, fee: Balance) -> Result<()> {
+            if fee == 0 {
+                return Ok(());
+            }
+            let balance = self.ledger.get(escrow.id).unwrap_or(0);
+            let remaining = balance.checked_sub(fee).ok_or(EscrowError::LedgerUnderflow)?;
+            self.ledger.insert(escrow.id, &remaining);
+
+            match escrow.token {
+                Some(token) => {
+                    let collected = self.collected_token_fees.get(token).unwrap_or(0);
+                    self.collected_token_fees.insert(token, &collected.saturating_add(fee));
+                }
+                None => {
+                    let insured = bps_share(fee, self.settings.insurance_bps);
+                    self.insurance_pool = self.insurance_pool.saturating_add(insured);
+                    self.collected_fees = self.collected_fees.saturating_add(fee.saturating_sub(insured));
+                    self.sum_fees_charged = self.sum_fees_charged.saturating_add(fee);
+                }
+            }
+            Ok(())
+        }
+
+        /// Credit a deposit of `amount` to an escrow's ledger
+        ///
+        /// Native-currency deposits also count towards the outstanding deposits
+        /// and the client's spending.
+        fn record_deposit(&mut self, escrow: &Escrow, amount: Balance) {
+            let ledger = self.ledger.get(escrow.id).unwrap_or(0);
+            self.ledger.insert(escrow.id, &ledger.saturating_add(amount));
+            if escrow.token.is_some() {
+                return;
+            }
+            self.deposits_outstanding = self.deposits_outstanding.saturating_add(amount);
+            let spent = self.total_spent.get(escrow.client).unwrap_or(0);
+            self.total_spent.insert(escrow.client, &spent.saturating_add(amount));
+            self.sum_total_spent = self.sum_total_spent.saturating_add(amount);
+        }
+
+        /// Take `amount` of an escrow's deposit out of the outstanding deposits
+        /// once it was paid out (native-currency escrows only)
+        fn settle_deposit(&mut self, escrow: &Escrow, amount: Balance) {
+            if escrow.token.is_none() {
+                self.deposits_outstanding = self.deposits_outstanding.saturating_sub(amount);
+            }
+        }
+
+        /// Pull `amount` tokens of a token-denominated escrow from `from` with
+        /// `PSP22::transfer_from`; does nothing for native-currency escrows
+        ///
+        /// Called after the deposit is recorded, so a token calling back into
+        /// the contract already sees it. A failed pull reverts the whole call.
+        fn collect_tokens(&mut self, escrow: &Escrow, from: AccountId, amount: Balance) -> Result<()> {
+            let Some(token) = escrow.token else {
+                return Ok(());
+            };
+
+            let mut psp22: ink::contract_ref!(PSP22) = token.into();
+            let outcome = psp22
+                .call_mut()
+                .transfer_from(from, self.env().account_id(), amount, Vec::new())
+                .try_invoke();
+            if !matches!(outcome, Ok(Ok(Ok(())))) {
+                return Err(EscrowError::TokenTransferFailed);
+            }
+            Ok(())
+        }
+
+        /// Count a delivered payment towards `earner`'s earnings
+        fn record_earned(&mut self, earner: AccountId, amount: Balance) {
+            let earned = self.total_earned.get(earner).unwrap_or(0);
+            self.total_earned.insert(earner, &earned.saturating_add(amount));
+            self.sum_total_earned = self.sum_total_earned.saturating_add(amount);
+        }
+
+        /// Deduct a delivered refund from the client's spending
+        fn record_refunded(&mut self, escrow: &Escrow, amount: Balance) {
+            let spent = self.total_spent.get(escrow.client).unwrap_or(0);
+            self.total_spent.insert(escrow.client, &spent.saturating_sub(amount));
+            self.sum_total_spent = self.sum_total_spent.saturating_sub(amount);
+        }
+
+        /// Keep an undelivered payout in the insurance pool and owe it to the escrow
+        fn record_shortfall(&mut self, escrow_id: u64, to: AccountId, amount: Balance) {
+            let shortfall = self.shortfalls.get(escrow_id).unwrap_or(0);
+            self.shortfalls.insert(escrow_id, &shortfall.saturating_add(amount));
+            self.insurance_pool = self.insurance_pool.saturating_add(amount);
+            self.env().emit_event(ShortfallRecorded { escrow_id, to, amount });
+        }
+
+        /// Account the client's refunds of `escrow` are paid to
+        fn refund_address(escrow: &Escrow) -> AccountId {
+            escrow.refund_to.unwrap_or(escrow.client)
+        }
+
+        /// Whether an escrow is blocked until `account` acts on it in `role`
+        fn awaits_action_from(escrow: &Escrow, account: AccountId, role: Role) -> bool {
+            if escrow.status.is_terminal() || !Self::role_of(escrow, account).contains(&role) {
+                return false;
+            }
+
+            match role {
+                // Arbiter of a dispute, or of a milestone escalated to one
+                Role::Arbiter => escrow.status == EscrowStatus::Disputed || escrow.milestones.iter().any(|m| m.disputed),
+                // Freelancer of an escrow waiting to be accepted
+                Role::Freelancer if escrow.status == EscrowStatus::PendingAcceptance => true,
+                // Client of a funded escrow with a submission to review
+                Role::Client
+                    if escrow.status == EscrowStatus::Funded
+                        && escrow.milestones.iter().any(|m| m.proof_hash.is_some() && !m.is_settled()) =>
+                {
+                    true
+                }
+                // Counterparty of a pending cancellation request
+                Role::Client | Role::Freelancer => {
+                    escrow.cancel_requested_by.is_some_and(|requested_by| requested_by != account)
+                }
+            }
+        }
+    }
+
+    // ========================================
+    // UNIT TESTS
+    // ========================================
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Helper function to get default accounts
+        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        /// Helper function to set caller
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        }
+
+        /// Helper function to set balance
+        /// In ink! 5.0, minimum balance is 1,000,000 (except for 0 which reaps the account)
+        fn set_balance(account: AccountId, balance: Balance) {
+            let min_balance: Balance = 1_000_000;
+            // If balance > 0, ensure it's at least the minimum
+            let actual_balance = if balance > 0 && balance < min_balance {
+                min_balance
+            } else {
+                balance
+            };
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(account, actual_balance);
+        }
+
+        /// Helper function to create test milestones
+        fn create_test_milestones() -> Vec<Milestone> {
+            vec![
+                Milestone {
+                    id: 0,
+                    amount: 1000,
+                    released: false,
+                    description: "Milestone 1".to_string(),
+                    depends_on: Vec::new(),
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: 1000,
+                },
+                Milestone {
+                    id: 1,
+                    amount: 2000,
+                    released: false,
+                    description: "Milestone 2".to_string(),
+                    depends_on: Vec::new(),
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: 2000,
+                },
+            ]
+        }
+
+        /// Helper function to decode the recorded event at `index`
+        fn recorded_event<E: scale::Decode>(index: usize) -> E {
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            <E as scale::Decode>::decode(&mut &events[index].data[..])
+                .expect("failed to decode event")
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let contract = EscrowMultiRelease::new();
+            assert_eq!(contract.get_escrows_by_client(default_accounts().alice), Vec::<u64>::new());
+        }
+
+        #[ink::test]
+        fn test_create_escrow_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+
+            let result = contract.create_escrow(accounts.bob, milestones.clone(), None, None);
+
+            assert!(result.is_ok());
+            let escrow_id = result.unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.client, accounts.alice);
+            assert_eq!(escrow.freelancer, accounts.bob);
+            assert_eq!(escrow.total_amount, 3000);
+            assert_eq!(escrow.status, EscrowStatus::PendingAcceptance);
+            assert_eq!(escrow.milestones.len(), 2);
+        }
+
+        #[ink::test]
+        fn test_create_escrow_empty_milestones_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            let result = contract.create_escrow(accounts.bob, vec![], None, None);
+
+            assert_eq!(result, Err(EscrowError::EmptyMilestones));
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Fund escrow
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            let result = contract.fund_escrow(escrow_id, None, None, None);
+
+            assert!(result.is_ok());
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.deposited, 3000);
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_unauthorized_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Try to fund as Bob (should fail)
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            let result = contract.fund_escrow(escrow_id, None, None, None);
+
+            assert_eq!(result, Err(EscrowError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_in_installments() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(contract.get_funding_progress(escrow_id), Some((0, 3000)));
+            assert_eq!(contract.get_funding_progress(escrow_id + 1), None);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::ZeroAmount));
+
+            // A partial deposit leaves the escrow waiting for the rest
+            set_timestamp(10);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(contract.fund_escrow(escrow_id, Some(accounts.django), None, None), Ok(()));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Created);
+            assert_eq!(escrow.funded_at, None);
+            assert_eq!(contract.get_funding_progress(escrow_id), Some((1000, 3000)));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 1000);
+            assert_eq!(contract.release_milestone(escrow_id, 0), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+
+            let funded: EscrowFunded = recorded_event(3);
+            assert_eq!((funded.amount, funded.deposited), (1000, 1000));
+
+            // The freelancer can no longer decline part of the plan being paid for
+            set_caller(accounts.bob);
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![1]), Err(EscrowError::InvalidStatus));
+
+            set_caller(accounts.alice);
+            set_timestamp(20);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Created);
+
+            // The last deposit completes the funding
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert_eq!(escrow.funded_at, Some(20));
+            assert_eq!(escrow.refund_to, Some(accounts.django));
+            assert_eq!(contract.get_funding_progress(escrow_id), Some((3000, 3000)));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3000));
+
+            let funded: EscrowFunded = recorded_event(5);
+            assert_eq!((funded.amount, funded.deposited), (500, 3000));
+            assert_eq!(funded.refund_to, Some(accounts.django));
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::AlreadyFunded));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_refunds_overpayment() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 2_000_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut contract = EscrowMultiRelease::new();
+
+            // (transferred, expected refund)
+            for (transferred, refund) in [(3000, 0), (3001, 1), (1_000_000_000, 1_000_000_000 - 3000)] {
+                set_caller(accounts.alice);
+                let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+                accept_as_freelancer(&mut contract, escrow_id);
+                let before = ink::env::test::recorded_events().count();
+                let balance = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(transferred);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+                assert_eq!(
+                    ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap(),
+                    balance + refund
+                );
+                assert_eq!(contract.get_escrow(escrow_id).unwrap().deposited, 3000);
+                assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+
+                let events = ink::env::test::recorded_events().count();
+                if refund == 0 {
+                    assert_eq!(events, before + 1);
+                } else {
+                    let refunded: OverpaymentRefunded = recorded_event(before);
+                    assert_eq!((refunded.escrow_id, refunded.amount), (escrow_id, refund));
+                    assert_eq!(events, before + 2);
+                }
+                let funded: EscrowFunded = recorded_event(events - 1);
+                assert_eq!(funded.amount, 3000);
+                check_invariants(&contract, escrow_id);
+            }
+        }
+
+        #[ink::test]
+        fn test_cancel_partially_funded_escrow_refunds_deposits() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1200);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let cancelled: EscrowCancelled = recorded_event(events - 1);
+            assert_eq!(cancelled.refund_to_client, 1200);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 0));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_expected_terms() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+
+            // Terms the funder saw before they changed: nothing is locked
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, Some(2000), None),
+                Err(EscrowError::TermsChanged)
+            );
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, Some(3000), Some(1)),
+                Err(EscrowError::TermsChanged)
+            );
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Created);
+            assert_eq!(escrow.deposited, 0);
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 0);
+
+            // Matching expectations fund as usual
+            contract.fund_escrow(escrow_id, None, Some(3000), Some(2)).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert_eq!(escrow.deposited, 3000);
+
+            // Other failures still take precedence over the terms check
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, Some(1), None),
+                Err(EscrowError::AlreadyFunded)
+            );
+        }
+
+        #[ink::test]
+        fn test_fund_escrow_twice_refunds_value() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // A retry of the same call gets its value back and a distinct error
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, None, None),
+                Err(EscrowError::AlreadyFunded)
+            );
+            assert_eq!(ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap(), before + 3000);
+
+            // Nothing more is attributed to the escrow
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.deposited, 3000);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 3000);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3000));
+
+            // Still reported after the escrow closes, and predicted by the simulator
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+            let prediction = contract.simulate_fund(escrow_id, accounts.alice, 3000);
+            assert_eq!(prediction.error, Some(EscrowError::AlreadyFunded));
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, None, None),
+                Err(EscrowError::AlreadyFunded)
+            );
+
+            // Without value there is nothing to refund
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, None, None),
+                Err(EscrowError::AlreadyFunded)
+            );
+            assert_eq!(ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap(), before);
+        }
+
+        #[ink::test]
+        fn test_release_milestone_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Fund escrow
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Release first milestone
+            let result = contract.release_milestone(escrow_id, 0);
+
+            assert!(result.is_ok());
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert!(escrow.milestones[0].released);
+            assert!(!escrow.milestones[1].released);
+            assert_eq!(escrow.status, EscrowStatus::Funded); // Not all released yet
+        }
+
+        #[ink::test]
+        fn test_release_all_milestones_completes_escrow() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Fund escrow
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Release all milestones
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert!(escrow.milestones.iter().all(|m| m.released));
+        }
+
+        #[ink::test]
+        fn test_release_milestone_unauthorized_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Try to release as Bob (should fail)
+            set_caller(accounts.bob);
+            let result = contract.release_milestone(escrow_id, 0);
+
+            assert_eq!(result, Err(EscrowError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn test_request_cancel_mutual_agreement() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Alice requests cancel
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            // Bob requests cancel (mutual agreement)
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+        }
+
+        #[ink::test]
+        fn test_approve_cancel_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Alice requests cancel
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            // Bob approves cancel
+            set_caller(accounts.bob);
+            let result = contract.approve_cancel(escrow_id, None);
+
+            assert!(result.is_ok());
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+        }
+
+        #[ink::test]
+        fn test_resolve_dispute_by_arbiter() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // A cancellation request alone is no dispute
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+            set_caller(accounts.charlie);
+            assert_eq!(contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000), Err(EscrowError::InvalidStatus));
+
+            // The freelancer contests it
+            set_caller(accounts.bob);
+            contract.raise_dispute(escrow_id, "Work was delivered".to_string(), None).unwrap();
+
+            // Arbiter resolves dispute
+            set_caller(accounts.charlie);
+            let result = contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000);
+
+            assert!(result.is_ok());
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Resolved);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::ArbiterDecision));
+        }
+
+        #[ink::test]
+        fn test_resolve_dispute_unauthorized_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+
+            // Try to resolve as non-arbiter (should fail)
+            set_caller(accounts.bob);
+            let result = contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000);
+
+            assert_eq!(result, Err(EscrowError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn test_resolve_dispute_blocked_when_arbiter_is_a_party() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let as_freelancer = contract.create_escrow(accounts.bob, milestones.clone(), Some(accounts.bob), None).unwrap();
+            let as_client = contract.create_escrow(accounts.bob, milestones, Some(accounts.alice), None).unwrap();
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            for escrow_id in [as_freelancer, as_client] {
+                accept_as_freelancer(&mut contract, escrow_id);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+                contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            }
+
+            // The freelancer acting as arbiter can't award itself the funds
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.resolve_dispute_by_arbiter(as_freelancer, 3000, 0),
+                Err(EscrowError::InvalidArbiter)
+            );
+
+            // Nor can the client refund itself
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.resolve_dispute_by_arbiter(as_client, 0, 3000),
+                Err(EscrowError::InvalidArbiter)
+            );
+
+            // Nothing moved: both escrows stay disputed and fully held
+            for escrow_id in [as_freelancer, as_client] {
+                assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Disputed);
+                assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+            }
+            assert_eq!(contract.get_totals(accounts.bob), (0, 0));
+        }
+
+        #[ink::test]
+        fn test_get_escrows_by_client() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+
+            let escrow_id1 = contract.create_escrow(accounts.bob, milestones.clone(), None, None).unwrap();
+            let escrow_id2 = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+
+            let escrows = contract.get_escrows_by_client(accounts.alice);
+            assert_eq!(escrows.len(), 2);
+            assert!(escrows.contains(&escrow_id1));
+            assert!(escrows.contains(&escrow_id2));
+        }
+
+        #[ink::test]
+        fn test_get_escrows_by_freelancer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+
+            let escrows = contract.get_escrows_by_freelancer(accounts.bob);
+            assert_eq!(escrows.len(), 1);
+            assert_eq!(escrows[0], escrow_id);
+        }
+
+        #[ink::test]
+        fn test_get_milestones() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones.clone(), None, None).unwrap();
+
+            let retrieved_milestones = contract.get_milestones(escrow_id);
+            assert_eq!(retrieved_milestones.len(), 2);
+            assert_eq!(retrieved_milestones[0].id, 0);
+            assert_eq!(retrieved_milestones[1].id, 1);
+        }
+
+        #[ink::test]
+        fn test_create_escrow_with_token_metadata() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let options = EscrowOptions {
+                token_symbol: Some("DOT".to_string()),
+                token_decimals: Some(10),
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+
+            let summary = contract.get_escrow_summary(escrow_id).unwrap();
+            assert_eq!(summary.token_symbol, Some("DOT".to_string()));
+            assert_eq!(summary.token_decimals, Some(10));
+            assert_eq!(summary.total_amount, 3000);
+
+            let created: EscrowCreated = recorded_event(0);
+            assert_eq!(created.token_symbol, Some("DOT".to_string()));
+            assert_eq!(created.token_decimals, Some(10));
+        }
+
+        #[ink::test]
+        fn test_create_escrow_without_token_metadata() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+
+            let summary = contract.get_escrow_summary(escrow_id).unwrap();
+            assert_eq!(summary.token_symbol, None);
+            assert_eq!(summary.token_decimals, None);
+        }
+
+        #[ink::test]
+        fn test_create_escrow_invalid_token_metadata_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // Symbol longer than MAX_TOKEN_SYMBOL_LEN
+            let options = EscrowOptions {
+                token_symbol: Some("VERYLONGSYMBOL".to_string()),
+                token_decimals: Some(12),
+                ..Default::default()
+            };
+            let result = contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options);
+            assert_eq!(result, Err(EscrowError::InvalidTokenMetadata));
+
+            // Empty symbol
+            let options = EscrowOptions {
+                token_symbol: Some(String::new()),
+                token_decimals: None,
+                ..Default::default()
+            };
+            let result = contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options);
+            assert_eq!(result, Err(EscrowError::InvalidTokenMetadata));
+
+            // Decimals above MAX_TOKEN_DECIMALS
+            let options = EscrowOptions {
+                token_symbol: None,
+                token_decimals: Some(MAX_TOKEN_DECIMALS + 1),
+                ..Default::default()
+            };
+            let result = contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options);
+            assert_eq!(result, Err(EscrowError::InvalidTokenMetadata));
+        }
+
+        #[ink::test]
+        fn test_get_escrow_summary() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            let summary = contract.get_escrow_summary(escrow_id).unwrap();
+            assert_eq!(summary.id, escrow_id);
+            assert_eq!(summary.client, accounts.alice);
+            assert_eq!(summary.freelancer, accounts.bob);
+            assert_eq!(summary.arbiter, Some(accounts.charlie));
+            assert_eq!(summary.status, EscrowStatus::Funded);
+            assert_eq!(summary.deposited, 3000);
+            assert_eq!(summary.released_amount, 1000);
+            assert_eq!(summary.milestone_count, 2);
+
+            assert!(contract.get_escrow_summary(999).is_none());
+        }
+
+        #[ink::test]
+        fn test_has_pending_actions_none() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            assert_eq!(contract.has_pending_actions(accounts.alice), PendingActions::No);
+            assert_eq!(contract.has_pending_actions(accounts.bob), PendingActions::No);
+            assert_eq!(contract.has_pending_actions(accounts.charlie), PendingActions::No);
+            assert_eq!(contract.has_pending_actions(accounts.django), PendingActions::No);
+        }
+
+        #[ink::test]
+        fn test_has_pending_actions_cancel_counterparty() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Alice requests cancellation, Bob has to respond
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            assert_eq!(contract.has_pending_actions(accounts.bob), PendingActions::Yes);
+            assert_eq!(contract.has_pending_actions(accounts.alice), PendingActions::No);
+        }
+
+        #[ink::test]
+        fn test_has_pending_actions_arbiter_on_dispute() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.has_pending_actions(accounts.charlie), PendingActions::No);
+
+            // A cancellation request is for the freelancer to answer
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.has_pending_actions(accounts.charlie), PendingActions::No);
+
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            assert_eq!(contract.has_pending_actions(accounts.charlie), PendingActions::Yes);
+        }
+
+        #[ink::test]
+        fn test_has_pending_actions_client_on_submission() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            assert_eq!(contract.has_pending_actions(accounts.alice), PendingActions::No);
+
+            // A submitted milestone waits on the client's review
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 0, Hash::from([0x01; 32])).unwrap();
+            assert_eq!(contract.has_pending_actions(accounts.alice), PendingActions::Yes);
+            assert_eq!(contract.has_pending_actions(accounts.bob), PendingActions::No);
+
+            // Releasing it leaves nothing to review
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.has_pending_actions(accounts.alice), PendingActions::No);
+        }
+
+        #[ink::test]
+        fn test_has_pending_actions_truncated_scan() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // Fill Bob's freelancer index past the scan limit with idle escrows
+            for _ in 0..PENDING_SCAN_LIMIT {
+                let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+                accept_as_freelancer(&mut contract, escrow_id);
+            }
+            assert_eq!(contract.has_pending_actions(accounts.bob), PendingActions::No);
+
+            // The pending escrow sits beyond the scan limit
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            assert_eq!(contract.has_pending_actions(accounts.bob), PendingActions::Unknown);
+        }
+
+        #[ink::test]
+        fn test_get_version() {
+            let contract = EscrowMultiRelease::new();
+            assert_eq!(contract.get_version(), CONTRACT_VERSION);
+        }
+
+        #[ink::test]
+        fn test_funding_and_release_events_carry_parties() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded, [4] MilestoneReleased
+            let funded: EscrowFunded = recorded_event(3);
+            assert_eq!(funded.escrow_id, escrow_id);
+            assert_eq!(funded.client, accounts.alice);
+            assert_eq!(funded.freelancer, accounts.bob);
+            assert_eq!(funded.amount, 3000);
+
+            let released: MilestoneReleased = recorded_event(4);
+            assert_eq!(released.escrow_id, escrow_id);
+            assert_eq!(released.client, accounts.alice);
+            assert_eq!(released.freelancer, accounts.bob);
+            assert_eq!(released.milestone_id, 0);
+            assert_eq!(released.amount, 1000);
+            assert_eq!(released.amount, released.protocol_fee + released.net_to_freelancer);
+            assert_eq!(released.net_to_freelancer, 1000);
+        }
+
+        #[ink::test]
+        fn test_cancel_events_carry_parties() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded, [4] CancelRequested, [5] EscrowCancelled
+            let requested: CancelRequested = recorded_event(4);
+            assert_eq!(requested.requested_by, accounts.alice);
+            assert_eq!(requested.client, accounts.alice);
+            assert_eq!(requested.freelancer, accounts.bob);
+
+            let cancelled: EscrowCancelled = recorded_event(5);
+            assert_eq!(cancelled.escrow_id, escrow_id);
+            assert_eq!(cancelled.client, accounts.alice);
+            assert_eq!(cancelled.freelancer, accounts.bob);
+            assert_eq!(cancelled.refund_to_client, 3000);
+        }
+
+        #[ink::test]
+        fn test_dispute_resolved_event_carries_parties() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let milestones = create_test_milestones();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000).unwrap();
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded, [4] DisputeRaised, [5] DisputeResolved
+            let resolved: DisputeResolved = recorded_event(5);
+            assert_eq!(resolved.escrow_id, escrow_id);
+            assert_eq!(resolved.client, accounts.alice);
+            assert_eq!(resolved.freelancer, accounts.bob);
+            assert_eq!(resolved.freelancer_share, 1000);
+            assert_eq!(resolved.client_refund, 2000);
+        }
+
+        /// Helper function to set the block timestamp
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+        }
+
+        /// Helper function to create and fund an escrow from alice to bob at `funded_at`
+        fn create_funded_escrow(contract: &mut EscrowMultiRelease, funded_at: u64) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            set_timestamp(funded_at);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            escrow_id
+        }
+
+        #[ink::test]
+        fn test_client_dispute_blocked_within_grace() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            contract.set_dispute_grace(1_000).unwrap();
+            let escrow_id = create_funded_escrow(&mut contract, 5_000);
+
+            let summary = contract.get_escrow_summary(escrow_id).unwrap();
+            assert_eq!(summary.earliest_dispute_at, Some(6_000));
+
+            set_timestamp(5_999);
+            assert_eq!(contract.request_cancel(escrow_id, None), Err(EscrowError::DisputeTooEarly));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            set_timestamp(6_000);
+            assert!(contract.request_cancel(escrow_id, None).is_ok());
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+        }
+
+        #[ink::test]
+        fn test_freelancer_dispute_ignores_grace() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            contract.set_dispute_grace(1_000).unwrap();
+            let escrow_id = create_funded_escrow(&mut contract, 5_000);
+
+            set_caller(accounts.bob);
+            assert!(contract.request_cancel(escrow_id, None).is_ok());
+
+            // The client can still agree to the freelancer's cancellation right away
+            set_caller(accounts.alice);
+            assert!(contract.request_cancel(escrow_id, None).is_ok());
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
+        }
+
+        #[ink::test]
+        fn test_dispute_grace_settings() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            assert_eq!(contract.get_owner(), accounts.alice);
+            assert_eq!(contract.get_dispute_grace(), 0);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_dispute_grace(1_000), Err(EscrowError::NotOwner));
+
+            // Unfunded escrows have no dispute timestamp yet
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().earliest_dispute_at, None);
+        }
+
+        #[ink::test]
+        fn test_privacy_defaults_to_attest_publicly() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().privacy, EscrowPrivacy::AttestPublicly);
+            let created: EscrowCreated = recorded_event(0);
+            assert_eq!(created.privacy, EscrowPrivacy::AttestPublicly);
+        }
+
+        #[ink::test]
+        fn test_privacy_chosen_at_creation() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let options = EscrowOptions {
+                privacy: EscrowPrivacy::NoAttestation,
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().privacy, EscrowPrivacy::NoAttestation);
+            let created: EscrowCreated = recorded_event(0);
+            assert_eq!(created.privacy, EscrowPrivacy::NoAttestation);
+
+            // Before funding the client changes it alone, the freelancer cannot
+            contract.propose_privacy_change(escrow_id, EscrowPrivacy::AttestPublicly).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().privacy, EscrowPrivacy::AttestPublicly);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.propose_privacy_change(escrow_id, EscrowPrivacy::NoAttestation),
+                Err(EscrowError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn test_privacy_change_after_funding_needs_consent() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // Client proposes, nothing changes yet
+            contract.propose_privacy_change(escrow_id, EscrowPrivacy::NoAttestation).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.privacy, EscrowPrivacy::AttestPublicly);
+            assert_eq!(escrow.privacy_proposal, Some((accounts.alice, EscrowPrivacy::NoAttestation)));
+
+            // The proposer and outsiders cannot accept
+            assert_eq!(contract.accept_privacy_change(escrow_id), Err(EscrowError::Unauthorized));
+            set_caller(accounts.charlie);
+            assert_eq!(contract.accept_privacy_change(escrow_id), Err(EscrowError::Unauthorized));
+
+            // Freelancer accepts
+            set_caller(accounts.bob);
+            contract.accept_privacy_change(escrow_id).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.privacy, EscrowPrivacy::NoAttestation);
+            assert_eq!(escrow.privacy_proposal, None);
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded, [4] PrivacyChangeProposed, [5] PrivacyChanged
+            let proposed: PrivacyChangeProposed = recorded_event(4);
+            assert_eq!(proposed.proposed_by, accounts.alice);
+            let changed: PrivacyChanged = recorded_event(5);
+            assert_eq!(changed.privacy, EscrowPrivacy::NoAttestation);
+
+            // Nothing left to accept
+            set_caller(accounts.alice);
+            assert_eq!(contract.accept_privacy_change(escrow_id), Err(EscrowError::InvalidStatus));
+        }
+
+        /// Helper function to build an escrow from (amount, released) milestones
+        fn settlement_escrow(deposited: Balance, milestones: &[(Balance, bool)]) -> Escrow {
+            let accounts = default_accounts();
+            Escrow {
+                id: 0,
+                client: accounts.alice,
+                freelancer: accounts.bob,
+                arbiter: None,
+                total_amount: milestones.iter().fold(0, |sum: Balance, (amount, _)| sum.saturating_add(*amount)),
+                deposited,
+                milestones: milestones
+                    .iter()
+                    .enumerate()
+                    .map(|(id, &(amount, released))| Milestone {
+                        id: id as u32,
+                        amount,
+                        released,
+                        description: String::new(),
+                        depends_on: Vec::new(),
+                        deadline: None,
+                        forfeited: false,
+                        proof_hash: None,
+                        rejections: 0,
+                        disputed: false,
+                        arbitrated: false,
+                        remaining: if released { 0 } else { amount },
+                    })
+                    .collect(),
+                released_count: milestones.iter().filter(|(_, released)| *released).count() as u32,
+                forfeited_count: 0,
+                status: EscrowStatus::Funded,
+                cancel_requested_by: None,
+                created_at: 0,
+                funded_at: None,
+                privacy: EscrowPrivacy::AttestPublicly,
+                privacy_proposal: None,
+                last_client_activity_at: None,
+                abandonment_timeout_ms: 0,
+                abandonment_freelancer_bps: 0,
+                deadlock_timeout_ms: 0,
+                deadlock_policy: DeadlockPolicy::RefundClient,
+                cancel_requested_at: None,
+                disputed_by: None,
+                dispute_reason: None,
+                termination_reason: None,
+                escalation_threshold: DEFAULT_ESCALATION_THRESHOLD,
+                auto_release_threshold: 0,
+                strict_confirmation: false,
+                refund_to: None,
+                token_symbol: None,
+                token_decimals: None,
+                funding_deadline: None,
+                require_submission_before_release: false,
+                token: None,
+                fee_bps: 0,
+                arbiter_fee: 0,
+                arbiter_proposal: None,
+                payout_address: None,
+            }
+        }
+
+        #[test]
+        fn test_settlement_nothing_released() {
+            let escrow = settlement_escrow(3000, &[(1000, false), (2000, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 0, unreleased: 3000, forfeited: 0, refundable: 3000 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_all_released() {
+            let escrow = settlement_escrow(3000, &[(1000, true), (2000, true)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 3000, unreleased: 0, forfeited: 0, refundable: 0 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_partial_release() {
+            let escrow = settlement_escrow(6000, &[(1000, true), (2000, false), (3000, true)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 4000, unreleased: 2000, forfeited: 0, refundable: 2000 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_unfunded() {
+            let escrow = settlement_escrow(0, &[(1000, false), (2000, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 0, unreleased: 3000, forfeited: 0, refundable: 0 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_partially_funded() {
+            // Only the first milestone is covered by the deposit
+            let escrow = settlement_escrow(1000, &[(1000, false), (2000, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 0, unreleased: 3000, forfeited: 0, refundable: 1000 }
+            );
+
+            let escrow = settlement_escrow(1500, &[(1000, true), (2000, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 1000, unreleased: 2000, forfeited: 0, refundable: 500 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_reclaimed_milestone() {
+            let mut escrow = settlement_escrow(6000, &[(1000, true), (2000, false), (3000, false)]);
+            escrow.milestones[1].forfeited = true;
+            escrow.forfeited_count = 1;
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 1000, unreleased: 3000, forfeited: 2000, refundable: 3000 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_released_exceeds_deposit() {
+            // Never negative, even if the books are inconsistent
+            let escrow = settlement_escrow(500, &[(1000, true), (2000, false)]);
+            assert_eq!(settlement(&escrow).refundable, 0);
+        }
+
+        #[test]
+        fn test_settlement_no_milestones() {
+            let escrow = settlement_escrow(700, &[]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 0, unreleased: 0, forfeited: 0, refundable: 700 }
+            );
+        }
+
+        #[test]
+        fn test_settlement_saturates_near_max() {
+            let escrow = settlement_escrow(Balance::MAX, &[(Balance::MAX - 1, true), (1, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: Balance::MAX - 1, unreleased: 1, forfeited: 0, refundable: 1 }
+            );
+
+            let escrow = settlement_escrow(Balance::MAX, &[(Balance::MAX, true), (Balance::MAX, true)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: Balance::MAX, unreleased: 0, forfeited: 0, refundable: 0 }
+            );
+
+            let escrow = settlement_escrow(Balance::MAX, &[(Balance::MAX, false), (Balance::MAX, false)]);
+            assert_eq!(
+                settlement(&escrow),
+                Settlement { released: 0, unreleased: Balance::MAX, forfeited: 0, refundable: Balance::MAX }
+            );
+        }
+
+        #[ink::test]
+        fn test_termination_status_and_reason_per_path() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // Mutual cancel through request_cancel from both sides
+            let both_requested = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(both_requested, None).unwrap();
+            set_caller(accounts.bob);
+            contract.request_cancel(both_requested, None).unwrap();
+
+            // Mutual cancel through approve_cancel
+            let approved = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(approved, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(approved, None).unwrap();
+
+            // Arbiter decision
+            set_caller(accounts.alice);
+            let arbitrated = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, arbitrated);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(arbitrated, None, None, None).unwrap();
+            contract.raise_dispute(arbitrated, "Work not delivered".to_string(), None).unwrap();
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(arbitrated, 1500, 1500).unwrap();
+
+            // Completed escrows have no termination reason
+            let completed = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(completed, 0).unwrap();
+            contract.release_milestone(completed, 1).unwrap();
+
+            let outcome = |escrow_id: u64| {
+                let summary = contract.get_escrow_summary(escrow_id).unwrap();
+                (summary.status, summary.termination_reason)
+            };
+            assert_eq!(outcome(both_requested), (EscrowStatus::Cancelled, Some(TerminationReason::MutualCancel)));
+            assert_eq!(outcome(approved), (EscrowStatus::Cancelled, Some(TerminationReason::MutualCancel)));
+            assert_eq!(outcome(arbitrated), (EscrowStatus::Resolved, Some(TerminationReason::ArbiterDecision)));
+            assert_eq!(outcome(completed), (EscrowStatus::Completed, None));
+        }
+
+        #[ink::test]
+        fn test_resolved_escrow_cannot_be_cancelled() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000).unwrap();
+
+            // The pending cancel request must not refund the client a second time
+            set_caller(accounts.bob);
+            assert_eq!(contract.approve_cancel(escrow_id, None), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.request_cancel(escrow_id, None), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_escalation_threshold_option() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            let default_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            assert_eq!(contract.get_escrow(default_id).unwrap().escalation_threshold, DEFAULT_ESCALATION_THRESHOLD);
+
+            let options = EscrowOptions {
+                escalation_threshold: Some(3),
+                ..Default::default()
+            };
+            let custom_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            assert_eq!(contract.get_escrow(custom_id).unwrap().escalation_threshold, 3);
+
+            let options = EscrowOptions {
+                escalation_threshold: Some(0),
+                ..Default::default()
+            };
+            assert_eq!(
+                contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options),
+                Err(EscrowError::InvalidEscalationThreshold)
+            );
+        }
+
+        #[ink::test]
+        fn test_post_progress_cap_and_paging() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            set_caller(accounts.bob);
+            for i in 0..MAX_PROGRESS_NOTES {
+                set_timestamp(i as u64);
+                contract.post_progress(escrow_id, 0, format!("ipfs://note{}", i)).unwrap();
+            }
+            assert_eq!(
+                contract.post_progress(escrow_id, 0, "ipfs://one-too-many".to_string()),
+                Err(EscrowError::TooManyProgressNotes)
+            );
+
+            // The cap is per milestone
+            contract.post_progress(escrow_id, 1, "ipfs://other".to_string()).unwrap();
+
+            let page = contract.get_progress_notes(escrow_id, 0, 18, 5);
+            assert_eq!(page.len(), 2);
+            assert_eq!(page[0], ProgressNote { note_uri: "ipfs://note18".to_string(), posted_at: 18 });
+            assert_eq!(contract.get_progress_notes(escrow_id, 0, 0, 3).len(), 3);
+            assert!(contract.get_progress_notes(escrow_id, 0, 20, 3).is_empty());
+
+            let posted: ProgressPosted = recorded_event(4);
+            assert_eq!(posted.escrow_id, escrow_id);
+            assert_eq!(posted.milestone_id, 0);
+            assert_eq!(posted.note_uri, "ipfs://note0");
+        }
+
+        #[ink::test]
+        fn test_post_progress_restrictions() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // Not funded yet
+            set_caller(accounts.alice);
+            let unfunded = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(contract.post_progress(unfunded, 0, "ipfs://n".to_string()), Err(EscrowError::InvalidStatus));
+
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // Only the freelancer, only valid URIs and milestones
+            assert_eq!(contract.post_progress(escrow_id, 0, "ipfs://n".to_string()), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(contract.post_progress(escrow_id, 0, String::new()), Err(EscrowError::InvalidNoteUri));
+            assert_eq!(
+                contract.post_progress(escrow_id, 0, "a".repeat(MAX_NOTE_URI_LEN + 1)),
+                Err(EscrowError::InvalidNoteUri)
+            );
+            assert_eq!(contract.post_progress(escrow_id, 7, "ipfs://n".to_string()), Err(EscrowError::MilestoneNotFound));
+
+            // Notes survive the release but no new ones are accepted
+            contract.post_progress(escrow_id, 0, "ipfs://before-release".to_string()).unwrap();
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.post_progress(escrow_id, 0, "ipfs://n".to_string()),
+                Err(EscrowError::MilestoneAlreadyReleased)
+            );
+            assert_eq!(contract.get_progress_notes(escrow_id, 0, 0, 10)[0].note_uri, "ipfs://before-release");
+
+            // A pending cancellation doesn't stop the work, the cancellation does
+            contract.request_cancel(escrow_id, None).unwrap();
+            contract.post_progress(escrow_id, 1, "ipfs://before-cancel".to_string()).unwrap();
+            set_caller(accounts.alice);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(contract.post_progress(escrow_id, 1, "ipfs://n".to_string()), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.get_progress_notes(escrow_id, 0, 0, 10).len(), 1);
+        }
+
+        #[ink::test]
+        fn test_auto_release_threshold() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // Only the client sets it
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_auto_release_threshold(escrow_id, 1500), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            contract.set_auto_release_threshold(escrow_id, 1500).unwrap();
+            let event_count = ink::env::test::recorded_events().count();
+            let event = recorded_event::<AutoReleaseThresholdSet>(event_count - 1);
+            assert_eq!(event.threshold, 1500);
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().auto_release_threshold, 1500);
+
+            // Freelancer releases the small milestone but not the large one
+            set_caller(accounts.bob);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert!(contract.get_escrow(escrow_id).unwrap().milestones[0].released);
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::Unauthorized));
+
+            // Freelancer releases don't count as client activity
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().last_client_activity_at, Some(0));
+
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            assert_eq!(contract.set_auto_release_threshold(escrow_id, 0), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_lowering_auto_release_threshold_takes_effect() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            set_caller(accounts.alice);
+            contract.set_auto_release_threshold(escrow_id, 2000).unwrap();
+
+            // Lowered before the freelancer gets to the second milestone
+            set_caller(accounts.bob);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            set_caller(accounts.alice);
+            contract.set_auto_release_threshold(escrow_id, 1000).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::Unauthorized));
+
+            // Disabled again
+            set_caller(accounts.alice);
+            contract.set_auto_release_threshold(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().auto_release_threshold, 0);
+            set_caller(accounts.bob);
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn test_create_escrow_with_salt() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            let salt = [0x42; 32];
+
+            // Key is known before creation and differs per client
+            let key = contract.compute_escrow_id(accounts.alice, salt);
+            assert_ne!(key, contract.compute_escrow_id(accounts.charlie, salt));
+            assert_ne!(key, contract.compute_escrow_id(accounts.alice, [0x43; 32]));
+            assert!(contract.get_escrow_by_salt(accounts.alice, salt).is_none());
+
+            // Salted escrows still get sequential IDs
+            set_caller(accounts.alice);
+            contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let escrow_id = contract
+                .create_escrow_with_salt(accounts.bob, create_test_milestones(), None, salt)
+                .unwrap();
+            assert_eq!(escrow_id, 1);
+            assert_eq!(contract.compute_escrow_id(accounts.alice, salt), key);
+            assert_eq!(contract.get_escrow_by_salt(accounts.alice, salt).unwrap().id, escrow_id);
+            assert!(contract.get_escrow_by_salt(accounts.charlie, salt).is_none());
+
+            // Another client can use the same salt
+            set_caller(accounts.charlie);
+            let other_id = contract
+                .create_escrow_with_salt(accounts.bob, create_test_milestones(), None, salt)
+                .unwrap();
+            assert_eq!(contract.get_escrow_by_salt(accounts.charlie, salt).unwrap().id, other_id);
+        }
+
+        #[ink::test]
+        fn test_create_escrow_with_salt_reuse_fails() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            let salt = [0x42; 32];
+
+            // A failed creation doesn't consume the salt
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.create_escrow_with_salt(accounts.bob, Vec::new(), None, salt),
+                Err(EscrowError::EmptyMilestones)
+            );
+            assert!(contract.get_escrow_by_salt(accounts.alice, salt).is_none());
+
+            let escrow_id = contract
+                .create_escrow_with_salt(accounts.bob, create_test_milestones(), None, salt)
+                .unwrap();
+            assert_eq!(
+                contract.create_escrow_with_salt(accounts.bob, create_test_milestones(), None, salt),
+                Err(EscrowError::SaltAlreadyUsed)
+            );
+            assert_eq!(contract.get_escrow_by_salt(accounts.alice, salt).unwrap().id, escrow_id);
+            assert_eq!(contract.get_escrows_by_client(accounts.alice), vec![escrow_id]);
+        }
+
+        #[ink::test]
+        fn test_refund_to_treasury_on_mutual_cancel() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.django, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, Some(accounts.django), None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().refund_to, Some(accounts.django));
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let funded = <EscrowFunded as scale::Decode>::decode(&mut &events[3].data[..]).unwrap();
+            assert_eq!(funded.refund_to, Some(accounts.django));
+
+            // Authorization still keys off the client
+            set_caller(accounts.django);
+            assert_eq!(contract.request_cancel(escrow_id, None), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+
+            let client_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+
+            let treasury = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.django).unwrap();
+            let client_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(treasury, 1_000_000 + 2000);
+            assert_eq!(client_after, client_before);
+        }
+
+        #[ink::test]
+        fn test_set_refund_address() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.django, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Only the client, and only once funded
+            assert_eq!(
+                contract.set_refund_address(escrow_id, Some(accounts.django)),
+                Err(EscrowError::InvalidStatus)
+            );
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.set_refund_address(escrow_id, Some(accounts.bob)),
+                Err(EscrowError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            contract.set_refund_address(escrow_id, Some(accounts.django)).unwrap();
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().refund_to, Some(accounts.django));
+
+            // Locked while a cancellation is pending
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_refund_address(escrow_id, None), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().refund_to, Some(accounts.django));
+        }
+
+        #[ink::test]
+        fn test_refund_defaults_to_client() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().refund_to, None);
+
+            contract.request_cancel(escrow_id, None).unwrap();
+            let client_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            let client_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(client_after, client_before + 3000);
+        }
+
+        #[ink::test]
+        fn test_ledger_tracks_payouts() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2000);
+
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+        }
+
+        #[ink::test]
+        fn test_ledger_blocks_double_release() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            let other_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+
+            // Simulate a bookkeeping bug that forgets a milestone was paid
+            let mut escrow = contract.get_escrow(escrow_id).unwrap();
+            escrow.milestones[1].released = false;
+            escrow.milestones[1].remaining = escrow.milestones[1].amount;
+            escrow.released_count = 1;
+            escrow.status = EscrowStatus::Funded;
+            contract.escrows.insert(escrow_id, &escrow);
+
+            // The contract still holds the other escrow's deposit, but can't pay it out here
+            let freelancer_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::LedgerUnderflow));
+            let freelancer_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(freelancer_after, freelancer_before);
+            assert_eq!(contract.get_ledger_balance(other_id), 3000);
+        }
+
+        /// Create an escrow from alice to bob without arbiter, funded at timestamp 0, that
+        /// deadlocks after 1000 ms
+        fn create_deadlock_escrow(contract: &mut EscrowMultiRelease, policy: DeadlockPolicy) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(0);
+            let options = EscrowOptions {
+                deadlock_timeout_ms: 1_000,
+                deadlock_policy: policy,
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            escrow_id
+        }
+
+        #[ink::test]
+        fn test_resolve_deadlock_client_requested() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_deadlock_escrow(&mut contract, DeadlockPolicy::RefundClient);
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            // Nothing to settle before a request
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::InvalidStatus));
+
+            set_timestamp(500);
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().cancel_requested_at, Some(500));
+
+            // Too early, and only the requester may settle
+            set_timestamp(1_499);
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::TimeoutNotReached));
+            set_timestamp(1_500);
+            set_caller(accounts.bob);
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::Unauthorized));
+
+            let client_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            set_caller(accounts.alice);
+            contract.resolve_deadlock(escrow_id).unwrap();
+            let client_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(client_after, client_before + 2000);
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Resolved);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Deadlock));
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::InvalidStatus));
+
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let resolved = <DeadlockResolved as scale::Decode>::decode(&mut &events.last().unwrap().data[..]).unwrap();
+            assert_eq!(resolved.requested_by, accounts.alice);
+            assert_eq!(resolved.policy, DeadlockPolicy::RefundClient);
+            assert_eq!(resolved.amount, 2000);
+        }
+
+        #[ink::test]
+        fn test_resolve_deadlock_freelancer_requested() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            // The policy applies whoever requested
+            let mut contract = EscrowMultiRelease::new();
+            let refund_id = create_deadlock_escrow(&mut contract, DeadlockPolicy::RefundClient);
+            let pay_id = create_deadlock_escrow(&mut contract, DeadlockPolicy::PayFreelancer);
+            set_caller(accounts.bob);
+            contract.request_cancel(refund_id, None).unwrap();
+            contract.request_cancel(pay_id, None).unwrap();
+
+            set_timestamp(1_000);
+            let client_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            let freelancer_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            contract.resolve_deadlock(refund_id).unwrap();
+            contract.resolve_deadlock(pay_id).unwrap();
+            let client_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            let freelancer_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(client_after, client_before + 3000);
+            assert_eq!(freelancer_after, freelancer_before + 3000);
+        }
+
+        #[ink::test]
+        fn test_resolve_deadlock_needs_timeout_and_no_arbiter() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            // No deadlock timeout
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_timestamp(u64::MAX);
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::InvalidStatus));
+
+            // With an arbiter
+            set_timestamp(0);
+            let options = EscrowOptions {
+                deadlock_timeout_ms: 1_000,
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), Some(accounts.charlie), options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_timestamp(1_000);
+            assert_eq!(contract.resolve_deadlock(escrow_id), Err(EscrowError::InvalidStatus));
+        }
+
+        /// Milestones of 1000 each with the given dependencies
+        fn dependent_milestones(deps: &[&[u32]]) -> Vec<Milestone> {
+            deps.iter()
+                .enumerate()
+                .map(|(id, depends_on)| Milestone {
+                    id: id as u32,
+                    amount: 1000,
+                    released: false,
+                    description: String::new(),
+                    depends_on: depends_on.to_vec(),
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: 1000,
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_milestone_dependencies_diamond() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            // 0 -> {1, 2} -> 3, and 4 independent
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let milestones = dependent_milestones(&[&[], &[0], &[0], &[1, 2], &[]]);
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(contract.get_milestone(escrow_id, 3).unwrap().depends_on, vec![1, 2]);
+            assert!(contract.get_milestone(escrow_id, 5).is_none());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(5000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::DependenciesNotMet));
+            contract.release_milestone(escrow_id, 4).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 2).unwrap();
+
+            // One branch of the diamond is still open
+            assert_eq!(contract.release_milestone(escrow_id, 3), Err(EscrowError::DependenciesNotMet));
+            let prediction = contract.simulate_release(escrow_id, 3, accounts.alice);
+            assert_eq!(prediction.failed_check, Some(ValidationCheck::Dependencies));
+
+            contract.release_milestone(escrow_id, 1).unwrap();
+            contract.release_milestone(escrow_id, 3).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+        }
+
+        #[ink::test]
+        fn test_invalid_milestone_dependencies() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+
+            let cases: [&[&[u32]]; 4] = [
+                &[&[], &[1]],    // itself
+                &[&[1], &[]],    // a later milestone
+                &[&[], &[0, 0]], // repeated
+                &[&[], &[7]],    // unknown
+            ];
+            for deps in cases {
+                assert_eq!(
+                    contract.create_escrow(accounts.bob, dependent_milestones(deps), None, None),
+                    Err(EscrowError::InvalidDependencies)
+                );
+            }
+
+            // At most MAX_MILESTONE_DEPENDENCIES
+            let mut deps: Vec<&[u32]> = vec![&[]; 6];
+            deps.push(&[0, 1, 2, 3, 4, 5]);
+            assert_eq!(
+                contract.create_escrow(accounts.bob, dependent_milestones(&deps), None, None),
+                Err(EscrowError::InvalidDependencies)
+            );
+            deps[6] = &[0, 1, 2, 3, 4];
+            assert!(contract.create_escrow(accounts.bob, dependent_milestones(&deps), None, None).is_ok());
+        }
+
+        /// Assert the invariants every stored escrow must satisfy, whatever
+        /// sequence of messages produced it
+        fn check_invariants(contract: &EscrowMultiRelease, escrow_id: u64) {
+            let escrow = contract.escrows.get(escrow_id).expect("escrow missing");
+            let settlement = settlement(&escrow);
+
+            // Funds only ever leave for released milestones or settlements
+            assert!(
+                settlement.released.saturating_add(settlement.forfeited) <= escrow.deposited,
+                "released or reclaimed more than deposited"
+            );
+            assert!(!escrow.milestones.iter().any(|m| m.released && m.forfeited), "milestone released and reclaimed");
+            assert!(
+                escrow.milestones.iter().all(|m| !m.disputed || (!m.is_settled() && !m.arbitrated)),
+                "settled or arbitrated milestone still disputed"
+            );
+            assert!(
+                escrow.milestones.iter().all(|m| m.remaining <= m.amount && (!m.released || m.remaining == 0)),
+                "remaining amount out of sync"
+            );
+            assert!(escrow.deposited <= funding_target(&escrow), "deposited more than the total");
+            if escrow.funded_at.is_some() {
+                // Completion and the closing reclaim hand the arbiter fee back
+                let fee_returned = escrow.status == EscrowStatus::Completed
+                    || (escrow.status == EscrowStatus::Cancelled
+                        && escrow.termination_reason == Some(TerminationReason::Expired));
+                let expected = if fee_returned {
+                    escrow.total_amount
+                } else {
+                    funding_target(&escrow)
+                };
+                assert_eq!(escrow.deposited, expected, "funded with a partial deposit");
+            } else {
+                assert!(escrow.deposited < funding_target(&escrow) || escrow.deposited == 0, "funded_at out of sync");
+            }
+
+            // The released and reclaimed counters match the milestone flags
+            let released_flags = escrow.milestones.iter().filter(|m| m.released).count() as u32;
+            assert_eq!(escrow.released_count, released_flags, "released counter drifted");
+            let forfeited_flags = escrow.milestones.iter().filter(|m| m.forfeited).count() as u32;
+            assert_eq!(escrow.forfeited_count, forfeited_flags, "reclaimed counter drifted");
+
+            // The status agrees with the rest of the escrow
+            let all_released = released_flags == escrow.milestones.len() as u32;
+            match escrow.status {
+                EscrowStatus::PendingAcceptance => {
+                    assert_eq!(escrow.deposited, 0, "escrow funded before acceptance");
+                }
+                EscrowStatus::Created => {
+                    assert!(escrow.deposited < escrow.total_amount, "created escrow fully funded");
+                    assert_eq!(settlement.released, 0, "created escrow released milestones");
+                }
+                EscrowStatus::Completed => {
+                    assert!(escrow.milestones.iter().all(Milestone::is_settled), "completed with open milestones")
+                }
+                // A fully declined escrow has no milestones left, a drawdown only released ones
+                _ => assert!(
+                    !all_released
+                        || escrow.termination_reason == Some(TerminationReason::Declined)
+                        || contract.drawdowns.contains(escrow_id),
+                    "all milestones released but not completed"
+                ),
+            }
+            assert_eq!(
+                escrow.termination_reason.is_some(),
+                matches!(escrow.status, EscrowStatus::Cancelled | EscrowStatus::Resolved | EscrowStatus::Expired),
+                "termination reason out of sync"
+            );
+
+            // Only a party can request cancellation or raise a dispute, and only a dispute freezes the escrow
+            if let Some(requested_by) = escrow.cancel_requested_by {
+                assert!(EscrowMultiRelease::is_party(&escrow, requested_by), "cancel requested by a third party");
+            }
+            match escrow.disputed_by {
+                Some(disputed_by) => {
+                    assert!(EscrowMultiRelease::is_party(&escrow, disputed_by), "dispute raised by a third party");
+                    assert!(escrow.arbiter.is_some(), "dispute without an arbiter");
+                    assert!(
+                        escrow.status == EscrowStatus::Disputed || escrow.status.is_terminal(),
+                        "dispute raised in {:?}",
+                        escrow.status
+                    );
+                }
+                None => assert_ne!(escrow.status, EscrowStatus::Disputed, "disputed without a dispute"),
+            }
+            assert_eq!(escrow.disputed_by.is_some(), escrow.dispute_reason.is_some(), "dispute_reason out of sync");
+            assert_eq!(
+                escrow.cancel_requested_by.is_some(),
+                escrow.cancel_requested_at.is_some(),
+                "cancel_requested_at out of sync"
+            );
+
+            // Per-escrow ledger and the global deposit counter match the escrows
+            assert_eq!(
+                contract.get_ledger_balance(escrow_id),
+                EscrowMultiRelease::held_funds(&escrow),
+                "ledger drifted"
+            );
+            contract.debug_assert_reconciled();
+        }
+
+        /// Whether a single message may move an escrow from `from` to `to`
+        fn is_valid_transition(from: &EscrowStatus, to: &EscrowStatus) -> bool {
+            use EscrowStatus::*;
+            from == to
+                || matches!(
+                    (from, to),
+                    (PendingAcceptance, Created)
+                        | (PendingAcceptance, Cancelled)
+                        | (Created, PendingAcceptance)
+                        | (PendingAcceptance, Resolved)
+                        | (Created, Funded)
+                        | (Created, Cancelled)
+                        | (Created, Resolved)
+                        | (Funded, Completed)
+                        | (Funded, Cancelled)
+                        | (Funded, Disputed)
+                        | (Funded, Resolved)
+                        | (Disputed, Completed)
+                        | (Disputed, Cancelled)
+                        | (Disputed, Resolved)
+                )
+        }
+
+        /// Caller of a property test operation, relative to the targeted escrow
+        #[derive(Debug, Clone, Copy)]
+        enum Actor {
+            Client,
+            Freelancer,
+            Arbiter,
+            /// Index into the party list, whatever role it holds
+            Party(usize),
+        }
+
+        /// Operation of the random sequence property test; accounts are indices into its party list
+        #[derive(Debug, Clone, Copy)]
+        enum Op {
+            Create {
+                client: usize,
+                freelancer: usize,
+                arbiter: Option<usize>,
+                milestones: u32,
+                abandonment_timeout_ms: u64,
+                deadlock_timeout_ms: u64,
+            },
+            Fund { caller: Actor, escrow_id: u64, short: bool },
+            Release { caller: Actor, escrow_id: u64, milestone_id: u32 },
+            RequestCancel { caller: Actor, escrow_id: u64 },
+            ApproveCancel { caller: Actor, escrow_id: u64 },
+            ResolveDispute { caller: Actor, escrow_id: u64, freelancer_pct: u128 },
+            ResolveAbandoned { caller: Actor, escrow_id: u64 },
+            ResolveDeadlock { caller: Actor, escrow_id: u64 },
+            RaiseDispute { caller: Actor, escrow_id: u64 },
+            Accept { caller: Actor, escrow_id: u64 },
+            Decline { caller: Actor, escrow_id: u64 },
+            Wait { ms: u64 },
+        }
+
+        #[ink::test]
+        fn test_invariants_hold_over_random_sequences() {
+            let accounts = default_accounts();
+            let parties = [accounts.alice, accounts.bob, accounts.charlie, accounts.django, accounts.eve];
+            for party in parties {
+                set_balance(party, 1_000_000_000);
+            }
+
+            // Deterministic pseudo-random generator, so failures reproduce
+            let mut seed: u64 = 0xE5C0;
+            let mut next = move |bound: u64| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+                (seed >> 33) % bound
+            };
+
+            for sequence in 0..300u32 {
+                // A fresh contract account per sequence, so no storage carries over
+                let mut callee = [0xC0; 32];
+                callee[..4].copy_from_slice(&sequence.to_le_bytes());
+                let callee = AccountId::from(callee);
+                set_balance(callee, 1_000_000_000_000);
+                ink::env::test::set_callee::<ink::env::DefaultEnvironment>(callee);
+                set_timestamp(0);
+                let mut contract = EscrowMultiRelease::new();
+                let mut ops: Vec<Op> = Vec::new();
+                let mut now = 0;
+
+                for _ in 0..25 {
+                    let escrows = contract.next_escrow_id;
+                    let party = |n: u64| n as usize;
+                    let escrow_id = next(escrows + 1);
+                    let caller = match next(8) {
+                        0..=2 => Actor::Client,
+                        3 | 4 => Actor::Freelancer,
+                        5 => Actor::Arbiter,
+                        _ => Actor::Party(party(next(parties.len() as u64))),
+                    };
+                    let op = match next(16) {
+                        0 | 1 => {
+                            let client = party(next(parties.len() as u64));
+                            let freelancer = (client + 1 + party(next(parties.len() as u64 - 1))) % parties.len();
+                            Op::Create {
+                                client,
+                                freelancer,
+                                arbiter: (next(2) == 0).then(|| party(next(parties.len() as u64))),
+                                milestones: 1 + next(3) as u32,
+                                abandonment_timeout_ms: next(3) * 1_000,
+                                deadlock_timeout_ms: next(3) * 1_000,
+                            }
+                        }
+                        2 | 3 => Op::Fund { caller, escrow_id, short: next(5) == 0 },
+                        4 | 5 => Op::Release { caller, escrow_id, milestone_id: next(4) as u32 },
+                        6 => Op::RequestCancel { caller, escrow_id },
+                        7 => Op::ApproveCancel { caller, escrow_id },
+                        8 => Op::ResolveDispute { caller, escrow_id, freelancer_pct: next(120) as u128 },
+                        9 => Op::ResolveAbandoned { caller, escrow_id },
+                        10 => Op::ResolveDeadlock { caller, escrow_id },
+                        11 => Op::RaiseDispute { caller, escrow_id },
+                        12 | 13 => Op::Accept { caller, escrow_id },
+                        14 => Op::Decline { caller, escrow_id },
+                        _ => Op::Wait { ms: next(3_000) },
+                    };
+                    ops.push(op);
+
+                    let before: Vec<EscrowStatus> = (0..escrows)
+                        .map(|id| contract.escrows.get(id).unwrap().status)
+                        .collect();
+
+                    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        run_op(&mut contract, &parties, &mut now, op);
+
+                        for id in 0..contract.next_escrow_id {
+                            let after = contract.escrows.get(id).unwrap().status;
+                            if let Some(from) = before.get(id as usize) {
+                                assert!(is_valid_transition(from, &after), "escrow {id}: {from:?} -> {after:?}");
+                            }
+                            check_invariants(&contract, id);
+                        }
+                    }));
+
+                    if let Err(panic) = outcome {
+                        eprintln!("invariant violated in sequence {sequence}, operations:");
+                        for (step, op) in ops.iter().enumerate() {
+                            eprintln!("  {step}: {op:?}");
+                        }
+                        std::panic::resume_unwind(panic);
+                    }
+                }
+            }
+        }
+
+        /// Account acting as `actor` on an escrow (the first party if the escrow or role does not exist)
+        fn actor(contract: &EscrowMultiRelease, parties: &[AccountId], actor: Actor, escrow_id: u64) -> AccountId {
+            let escrow = contract.escrows.get(escrow_id);
+            match actor {
+                Actor::Client => escrow.map(|e| e.client),
+                Actor::Freelancer => escrow.map(|e| e.freelancer),
+                Actor::Arbiter => escrow.and_then(|e| e.arbiter),
+                Actor::Party(index) => Some(parties[index]),
+            }
+            .unwrap_or(parties[0])
+        }
+
+        /// Execute one property test operation, ignoring the message result
+        fn run_op(contract: &mut EscrowMultiRelease, parties: &[AccountId], now: &mut u64, op: Op) {
+            match op {
+                Op::Create { client, freelancer, arbiter, milestones, abandonment_timeout_ms, deadlock_timeout_ms } => {
+                    set_caller(parties[client]);
+                    let milestones = (0..milestones)
+                        .map(|id| Milestone {
+                            id,
+                            amount: 1_000 * (Balance::from(id) + 1),
+                            released: false,
+                            description: String::new(),
+                            depends_on: Vec::new(),
+                            deadline: None,
+                            forfeited: false,
+                            proof_hash: None,
+                            rejections: 0,
+                            disputed: false,
+                            arbitrated: false,
+                            remaining: 1_000 * (Balance::from(id) + 1),
+                        })
+                        .collect();
+                    let options = EscrowOptions {
+                        abandonment_timeout_ms,
+                        abandonment_freelancer_bps: 2_500,
+                        deadlock_timeout_ms,
+                        ..Default::default()
+                    };
+                    let _ = contract.create_escrow_with_options(
+                        parties[freelancer],
+                        milestones,
+                        arbiter.map(|index| parties[index]),
+                        options,
+                    );
+                }
+                Op::Fund { caller, escrow_id, short } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let total = contract.escrows.get(escrow_id).map_or(0, |escrow| escrow.total_amount);
+                    let value = if short { total.saturating_sub(1) } else { total };
+                    ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+                    let _ = contract.fund_escrow(escrow_id, None, None, None);
+                    ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+                }
+                Op::Release { caller, escrow_id, milestone_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.release_milestone(escrow_id, milestone_id);
+                }
+                Op::RequestCancel { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.request_cancel(escrow_id, None);
+                }
+                Op::ApproveCancel { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.approve_cancel(escrow_id, None);
+                }
+                Op::ResolveDispute { caller, escrow_id, freelancer_pct } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let held = contract.escrows.get(escrow_id).map_or(0, |escrow| settlement(&escrow).refundable);
+                    // Shares above 100% do not add up and must be refused
+                    let freelancer_share = held * freelancer_pct / 100;
+                    let _ = contract.resolve_dispute_by_arbiter(escrow_id, freelancer_share, held.saturating_sub(freelancer_share));
+                }
+                Op::ResolveAbandoned { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.resolve_abandoned(escrow_id);
+                }
+                Op::ResolveDeadlock { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.resolve_deadlock(escrow_id);
+                }
+                Op::RaiseDispute { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.raise_dispute(escrow_id, "Disagreement".to_string(), None);
+                }
+                Op::Accept { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.accept_escrow(escrow_id);
+                }
+                Op::Decline { caller, escrow_id } => {
+                    set_caller(actor(contract, parties, caller, escrow_id));
+                    let _ = contract.decline_escrow(escrow_id);
+                }
+                Op::Wait { ms } => {
+                    *now += ms;
+                    set_timestamp(*now);
+                }
+            }
+        }
+
+        #[ink::test]
+        fn test_released_count_tracks_releases() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            let released_count = |contract: &EscrowMultiRelease| contract.get_escrow(escrow_id).unwrap().released_count;
+            assert_eq!(released_count(&contract), 0);
+
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(released_count(&contract), 1);
+            assert_eq!(contract.get_escrow_summary(escrow_id).unwrap().released_count, 1);
+
+            // A failed release leaves the counter alone
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::MilestoneAlreadyReleased));
+            assert_eq!(released_count(&contract), 1);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            // Reaching the milestone count completes the escrow
+            contract.release_milestone(escrow_id, 0).unwrap();
+            let summary = contract.get_escrow_summary(escrow_id).unwrap();
+            assert_eq!(summary.released_count, summary.milestone_count);
+            assert_eq!(summary.status, EscrowStatus::Completed);
+        }
+
+        #[ink::test]
+        fn test_describe_escrow_states() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let describe = |contract: &EscrowMultiRelease, escrow_id| contract.describe_escrow(escrow_id).unwrap();
+            assert_eq!(contract.describe_escrow(0), None);
+
+            // Created, then funded, partially released and completed
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(describe(&contract, escrow_id), "Created: 0/2 milestones released, 0 of 3000 paid, awaiting funding");
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(describe(&contract, escrow_id), "Funded: 0/2 milestones released, 0 of 3000 paid");
+
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(describe(&contract, escrow_id), "Funded: 1/2 milestones released, 1000 of 3000 paid");
+
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(describe(&contract, escrow_id), "Completed: 2/2 milestones released, 3000 of 3000 paid");
+
+            // Pending request, then cancelled
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(
+                describe(&contract, escrow_id),
+                "Funded: 1/2 milestones released, 1000 of 3000 paid, cancel requested by freelancer"
+            );
+            set_caller(accounts.alice);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(describe(&contract, escrow_id), "Cancelled: 1/2 milestones released, 1000 of 3000 paid, mutual cancel");
+
+            // Resolved by the arbiter
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            assert_eq!(
+                describe(&contract, escrow_id),
+                "Disputed: 0/2 milestones released, 0 of 3000 paid, cancel requested by client"
+            );
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 2000, 1000).unwrap();
+            assert_eq!(describe(&contract, escrow_id), "Resolved: 0/2 milestones released, 0 of 3000 paid, arbiter decision");
+        }
+
+        #[ink::test]
+        fn test_get_config_defaults_and_updates() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            let config = contract.get_config();
+            assert_eq!(config.owner, accounts.alice);
+            assert_eq!(config.version, CONTRACT_VERSION);
+            assert_eq!(
+                config.settings,
+                EscrowSettings { amendment_window_ms: DEFAULT_AMENDMENT_WINDOW_MS, ..EscrowSettings::default() }
+            );
+            assert_eq!(config.bps_denominator, BPS_DENOMINATOR);
+            assert_eq!(config.max_milestone_dependencies, MAX_MILESTONE_DEPENDENCIES as u32);
+            assert_eq!(config.max_description_len, MAX_DESCRIPTION_LEN as u32);
+
+            // A setter changes its field only and reports all settings
+            contract.set_dispute_grace(5_000).unwrap();
+            let event = recorded_event::<ConfigUpdated>(0);
+            assert_eq!(
+                event.settings,
+                EscrowSettings {
+                    dispute_grace_ms: 5_000,
+                    max_escrow_amount: 0,
+                    amendment_window_ms: DEFAULT_AMENDMENT_WINDOW_MS,
+                    fee_bps: 0,
+                    insurance_bps: 0,
+                }
+            );
+            assert_eq!(contract.get_config().settings, event.settings);
+
+            contract.set_max_escrow_amount(9_000).unwrap();
+            let event = recorded_event::<ConfigUpdated>(2);
+            assert_eq!(
+                event.settings,
+                EscrowSettings {
+                    dispute_grace_ms: 5_000,
+                    max_escrow_amount: 9_000,
+                    amendment_window_ms: DEFAULT_AMENDMENT_WINDOW_MS,
+                    fee_bps: 0,
+                    insurance_bps: 0,
+                }
+            );
+            assert_eq!(contract.get_config().settings, event.settings);
+
+            // Rejected setters change nothing
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_dispute_grace(0), Err(EscrowError::NotOwner));
+            assert_eq!(contract.get_config().settings.dispute_grace_ms, 5_000);
+        }
+
+        #[ink::test]
+        fn test_totals_over_lifecycle() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            assert_eq!(contract.get_totals(accounts.alice), (0, 0));
+
+            // Funding counts as spending, releases as earnings
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3000));
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_totals(accounts.bob), (1000, 0));
+
+            // A partial refund on cancellation reduces spending, even when sent elsewhere
+            contract.set_refund_address(escrow_id, Some(accounts.django)).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_totals(accounts.alice), (0, 1000));
+            assert_eq!(contract.get_totals(accounts.django), (0, 0));
+
+            // An arbiter split adds to both sides accordingly
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 2500, 500).unwrap();
+
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3500));
+            assert_eq!(contract.get_totals(accounts.bob), (3500, 0));
+            assert_eq!(contract.get_totals(accounts.charlie), (0, 0));
+
+            let report = contract.reconcile();
+            assert_eq!(report.sum_total_spent, 3500);
+            assert_eq!(report.sum_total_earned, 3500);
+        }
+
+        #[ink::test]
+        fn test_amendment_raising_total_with_attached_funds() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            // Released milestones can't change, and only the freelancer proposes raises
+            let raise = Amendment {
+                amount_changes: vec![(1, 2500)],
+                new_milestones: vec![Milestone {
+                    id: 2,
+                    amount: 1500,
+                    released: false,
+                    description: "Milestone 3".to_string(),
+                    depends_on: vec![1],
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: 1500,
+                }],
+                ..Amendment::default()
+            };
+            assert_eq!(
+                contract.propose_amendment(escrow_id, raise.clone()),
+                Err(EscrowError::InvalidAmendment)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.propose_amendment(
+                    escrow_id,
+                    Amendment { amount_changes: vec![(0, 500)], ..Amendment::default() }
+                ),
+                Err(EscrowError::MilestoneAlreadyReleased)
+            );
+            let amendment_id = contract.propose_amendment(escrow_id, raise).unwrap();
+            assert_eq!(contract.get_amendment(escrow_id).unwrap().total_amount, 5000);
+
+            // Only the client accepts, attaching exactly the increase
+            assert_eq!(contract.accept_amendment(escrow_id, amendment_id), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1999);
+            assert_eq!(contract.accept_amendment(escrow_id, amendment_id), Err(EscrowError::InsufficientFunds));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2001);
+            assert_eq!(contract.accept_amendment(escrow_id, amendment_id), Err(EscrowError::InvalidAmount));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2000);
+            assert_eq!(contract.accept_amendment(escrow_id, 7), Err(EscrowError::AmendmentNotFound));
+            contract.accept_amendment(escrow_id, amendment_id).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!((escrow.total_amount, escrow.deposited), (5000, 5000));
+            assert_eq!(escrow.milestones.len(), 3);
+            assert_eq!(escrow.milestones[1].amount, 2500);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 4000);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 5000));
+            assert!(contract.get_amendment(escrow_id).is_none());
+            check_invariants(&contract, escrow_id);
+
+            let accepted = recorded_event::<AmendmentAccepted>(6);
+            assert_eq!((accepted.amendment_id, accepted.previous_total, accepted.total_amount), (0, 3000, 5000));
+
+            // The amended milestones pay out in full
+            contract.release_milestone(escrow_id, 1).unwrap();
+            contract.release_milestone(escrow_id, 2).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            assert_eq!(contract.get_totals(accounts.bob), (5000, 0));
+        }
+
+        #[ink::test]
+        fn test_amendment_lowering_total_refunds_client() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // The client may propose a cut; the freelancer accepts without value
+            let amendment_id = contract
+                .propose_amendment(escrow_id, Amendment { amount_changes: vec![(1, 1200)], ..Amendment::default() })
+                .unwrap();
+            let client_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            contract.accept_amendment(escrow_id, amendment_id).unwrap();
+
+            let client_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(client_after, client_before + 800);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().total_amount, 2200);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2200);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 2200));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_amendment_extending_timeouts_only() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let options = EscrowOptions { abandonment_timeout_ms: 1_000, ..EscrowOptions::default() };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Not before funding
+            let extend = Amendment { abandonment_timeout_ms: Some(5_000), ..Amendment::default() };
+            assert_eq!(contract.propose_amendment(escrow_id, extend.clone()), Err(EscrowError::InvalidStatus));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Empty or shortening amendments are refused
+            assert_eq!(
+                contract.propose_amendment(escrow_id, Amendment::default()),
+                Err(EscrowError::InvalidAmendment)
+            );
+            assert_eq!(
+                contract.propose_amendment(
+                    escrow_id,
+                    Amendment { abandonment_timeout_ms: Some(500), ..Amendment::default() }
+                ),
+                Err(EscrowError::InvalidAmendment)
+            );
+            assert_eq!(
+                contract.propose_amendment(
+                    escrow_id,
+                    Amendment { deadlock_timeout_ms: Some(500), ..Amendment::default() }
+                ),
+                Err(EscrowError::InvalidAmendment)
+            );
+
+            let amendment_id = contract.propose_amendment(escrow_id, extend).unwrap();
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            contract.accept_amendment(escrow_id, amendment_id).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.abandonment_timeout_ms, 5_000);
+            assert_eq!(escrow.total_amount, 3000);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 3000);
+        }
+
+        #[ink::test]
+        fn test_amendment_expiry() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            contract.set_amendment_window(1_000).unwrap();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            set_timestamp(500);
+            let extend = Amendment { deadlock_timeout_ms: Some(0), ..Amendment::default() };
+            let amendment_id = contract.propose_amendment(escrow_id, extend.clone()).unwrap();
+            assert_eq!(contract.get_amendment(escrow_id).unwrap().expires_at, Some(1_500));
+
+            let proposed = recorded_event::<AmendmentProposed>(5);
+            assert_eq!(proposed.proposed_by, accounts.alice);
+            assert_eq!(proposed.expires_at, Some(1_500));
+
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            set_timestamp(1_501);
+            assert_eq!(contract.accept_amendment(escrow_id, amendment_id), Err(EscrowError::AmendmentExpired));
+
+            // A fresh proposal replaces the expired one
+            set_caller(accounts.alice);
+            let renewed = contract.propose_amendment(escrow_id, extend).unwrap();
+            assert_eq!(renewed, amendment_id + 1);
+            set_caller(accounts.bob);
+            assert_eq!(contract.accept_amendment(escrow_id, amendment_id), Err(EscrowError::AmendmentNotFound));
+            contract.accept_amendment(escrow_id, renewed).unwrap();
+
+            // Only the owner sets the window; 0 disables expiry
+            assert_eq!(contract.set_amendment_window(0), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            contract.set_amendment_window(0).unwrap();
+            let id = contract
+                .propose_amendment(escrow_id, Amendment { abandonment_timeout_ms: Some(0), ..Amendment::default() })
+                .unwrap();
+            assert_eq!(contract.get_amendment(escrow_id).unwrap().expires_at, None);
+            set_caller(accounts.bob);
+            set_timestamp(u64::MAX);
+            contract.accept_amendment(escrow_id, id).unwrap();
+        }
+
+        #[ink::test]
+        fn test_amendment_rejection_and_withdrawal() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            let extend = Amendment { abandonment_timeout_ms: Some(0), ..Amendment::default() };
+
+            // The other party rejects
+            let rejected = contract.propose_amendment(escrow_id, extend.clone()).unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(contract.reject_amendment(escrow_id, rejected), Err(EscrowError::Unauthorized));
+            assert_eq!(contract.propose_amendment(escrow_id, extend.clone()), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            contract.reject_amendment(escrow_id, rejected).unwrap();
+            assert!(contract.get_amendment(escrow_id).is_none());
+            assert_eq!(contract.accept_amendment(escrow_id, rejected), Err(EscrowError::AmendmentNotFound));
+
+            let event = recorded_event::<AmendmentRejected>(5);
+            assert_eq!((event.rejected_by, event.amendment_id), (accounts.bob, rejected));
+
+            // The proposer withdraws
+            let withdrawn = contract.propose_amendment(escrow_id, extend).unwrap();
+            contract.reject_amendment(escrow_id, withdrawn).unwrap();
+            assert_eq!(contract.reject_amendment(escrow_id, withdrawn), Err(EscrowError::AmendmentNotFound));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().abandonment_timeout_ms, 0);
+        }
+
+        #[ink::test]
+        fn test_accept_escrow_partial_then_fund() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            // 2 depends on 0, 1 is independent
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let milestones = dependent_milestones(&[&[], &[], &[0]]);
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+
+            // Only the freelancer declines, and only milestones nothing kept depends on
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![1]), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![]), Err(EscrowError::InvalidDecline));
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![1, 1]), Err(EscrowError::InvalidDecline));
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![0]), Err(EscrowError::InvalidDecline));
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![3]), Err(EscrowError::MilestoneNotFound));
+
+            contract.accept_escrow_partial(escrow_id, vec![1]).unwrap();
+            let declined = recorded_event::<MilestoneDeclined>(2);
+            assert_eq!((declined.escrow_id, declined.milestone_id, declined.amount), (escrow_id, 1, 1000));
+            // Declining part of the plan accepts the rest
+            assert_eq!(recorded_event::<EscrowAccepted>(4).freelancer, accounts.bob);
+
+            // Remaining milestones are re-sequenced with their dependency
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Created);
+            assert_eq!(escrow.total_amount, 2000);
+            let ids: Vec<u32> = escrow.milestones.iter().map(|m| m.id).collect();
+            assert_eq!(ids, vec![0, 1]);
+            assert_eq!(escrow.milestones[1].depends_on, vec![0]);
+            check_invariants(&contract, escrow_id);
+
+            // The client funds the reduced escrow, knowing its new terms
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2000);
+            assert_eq!(
+                contract.fund_escrow(escrow_id, None, Some(3000), Some(3)),
+                Err(EscrowError::TermsChanged)
+            );
+            contract.fund_escrow(escrow_id, None, Some(2000), Some(2)).unwrap();
+            assert_eq!(contract.release_milestone(escrow_id, 1), Err(EscrowError::DependenciesNotMet));
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            check_invariants(&contract, escrow_id);
+
+            // Funded escrows can no longer be trimmed
+            set_caller(accounts.bob);
+            assert_eq!(contract.accept_escrow_partial(escrow_id, vec![0]), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_accept_escrow_partial_decline_all() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+
+            set_caller(accounts.bob);
+            contract.accept_escrow_partial(escrow_id, vec![1, 0]).unwrap();
+
+            // One event per milestone in milestone order, then the decline
+            assert_eq!(recorded_event::<MilestoneDeclined>(2).milestone_id, 0);
+            assert_eq!(recorded_event::<MilestoneDeclined>(3).milestone_id, 1);
+            let declined = recorded_event::<EscrowDeclined>(4);
+            assert_eq!((declined.escrow_id, declined.client, declined.freelancer), (escrow_id, accounts.alice, accounts.bob));
+            assert_eq!(Some(declined.snapshot_hash), contract.finalize_snapshot(escrow_id));
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Declined));
+            assert_eq!(escrow.total_amount, 0);
+            assert!(escrow.milestones.is_empty());
+            check_invariants(&contract, escrow_id);
+
+            // Gone from every index, like a declined escrow
+            assert!(contract.get_escrows_by_client(accounts.alice).is_empty());
+            assert!(contract.get_escrows_by_freelancer(accounts.bob).is_empty());
+
+            // Nothing left to fund
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_milestones_snapshot_follows_changes() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            let mut contract = EscrowMultiRelease::new();
+            let latest_snapshot = || {
+                let event_count = ink::env::test::recorded_events().count();
+                recorded_event::<MilestonesSnapshot>(event_count - 1)
+            };
+            let current = |contract: &EscrowMultiRelease, escrow_id: u64| {
+                contract
+                    .get_milestones(escrow_id)
+                    .iter()
+                    .map(|m| (m.id, m.amount))
+                    .collect::<Vec<_>>()
+            };
+
+            // Right after EscrowCreated
+            set_caller(accounts.alice);
+            let milestones = dependent_milestones(&[&[], &[], &[]]);
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            let snapshot = recorded_event::<MilestonesSnapshot>(1);
+            assert_eq!(snapshot.escrow_id, escrow_id);
+            assert_eq!(snapshot.milestones, current(&contract, escrow_id));
+
+            // After the freelancer declines a milestone
+            set_caller(accounts.bob);
+            contract.accept_escrow_partial(escrow_id, vec![0]).unwrap();
+            let snapshot = latest_snapshot();
+            assert_eq!(snapshot.milestones, vec![(0, 1000), (1, 1000)]);
+            assert_eq!(snapshot.milestones, current(&contract, escrow_id));
+
+            // After an accepted amendment
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            set_caller(accounts.bob);
+            let changes = Amendment { amount_changes: vec![(1, 1500)], ..Amendment::default() };
+            let amendment_id = contract.propose_amendment(escrow_id, changes).unwrap();
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            contract.accept_amendment(escrow_id, amendment_id).unwrap();
+            let snapshot = latest_snapshot();
+            assert_eq!(snapshot.milestones, vec![(0, 1000), (1, 1500)]);
+            assert_eq!(snapshot.milestones, current(&contract, escrow_id));
+        }
+
+        #[ink::test]
+        fn test_milestone_cap() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let milestones = |count: usize| dependent_milestones(&vec![&[][..]; count]);
+
+            assert_eq!(
+                contract.create_escrow(accounts.bob, milestones(MAX_MILESTONES + 1), None, None),
+                Err(EscrowError::TooManyMilestones)
+            );
+            let escrow_id = contract.create_escrow(accounts.bob, milestones(MAX_MILESTONES), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(recorded_event::<MilestonesSnapshot>(1).milestones.len(), MAX_MILESTONES);
+            assert_eq!(contract.get_config().max_milestones, MAX_MILESTONES as u32);
+
+            // Amendments can't grow past the cap either
+            let total = 1000 * MAX_MILESTONES as Balance;
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(total);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            set_caller(accounts.bob);
+            let extra = Milestone {
+                id: MAX_MILESTONES as u32,
+                amount: 1000,
+                released: false,
+                description: String::new(),
+                depends_on: Vec::new(),
+                deadline: None,
+                forfeited: false,
+                proof_hash: None,
+                rejections: 0,
+                disputed: false,
+                arbitrated: false,
+                remaining: 1000,
+            };
+            let changes = Amendment { new_milestones: vec![extra], ..Amendment::default() };
+            assert_eq!(contract.propose_amendment(escrow_id, changes), Err(EscrowError::TooManyMilestones));
+        }
+
+        #[ink::test]
+        fn test_settle_remaining_pays_outstanding_at_once() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let milestones = dependent_milestones(&[&[], &[0], &[1], &[]]);
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(4000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            // Only the client settles
+            set_caller(accounts.bob);
+            assert_eq!(contract.settle_remaining(escrow_id), Err(EscrowError::Unauthorized));
+
+            let bob_before = balance(accounts.bob);
+            set_caller(accounts.alice);
+            contract.settle_remaining(escrow_id).unwrap();
+
+            // The three outstanding milestones are paid together, dependencies included
+            assert_eq!(balance(accounts.bob), bob_before + 3000);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(escrow.released_count, 4);
+            assert!(escrow.milestones.iter().all(|m| m.released));
+            check_invariants(&contract, escrow_id);
+
+            // One MilestoneReleased per settled milestone, then EscrowCompleted
+            let event_count = ink::env::test::recorded_events().count();
+            let released: Vec<u32> = (event_count - 4..event_count - 1)
+                .map(|i| recorded_event::<MilestoneReleased>(i).milestone_id)
+                .collect();
+            assert_eq!(released, vec![1, 2, 3]);
+            let completed = recorded_event::<EscrowCompleted>(event_count - 1);
+            assert_eq!((completed.escrow_id, completed.total_amount), (escrow_id, 4000));
+
+            assert_eq!(contract.settle_remaining(escrow_id), Err(EscrowError::InvalidStatus));
+        }
+
+        /// Create and fund an escrow from alice to bob whose milestones are due at `deadlines`
+        fn create_escrow_with_milestone_deadlines(contract: &mut EscrowMultiRelease, deadlines: [Option<u64>; 2]) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut milestones = create_test_milestones();
+            for (milestone, deadline) in milestones.iter_mut().zip(deadlines) {
+                milestone.deadline = deadline;
+            }
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            escrow_id
+        }
+
+        #[ink::test]
+        fn test_milestone_deadlines_must_increase() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = EscrowMultiRelease::new();
+
+            let with_deadlines = |deadlines: [Option<u64>; 2]| {
+                let mut milestones = create_test_milestones();
+                for (milestone, deadline) in milestones.iter_mut().zip(deadlines) {
+                    milestone.deadline = deadline;
+                }
+                milestones
+            };
+
+            for deadlines in [[Some(2_000), Some(1_000)], [Some(1_000), Some(1_000)]] {
+                assert_eq!(
+                    contract.create_escrow(accounts.bob, with_deadlines(deadlines), None, None),
+                    Err(EscrowError::InvalidMilestoneDeadlines)
+                );
+            }
+            // Milestones without a deadline don't take part in the order
+            for deadlines in [[Some(1_000), Some(2_000)], [None, Some(1_000)], [Some(1_000), None]] {
+                assert!(contract.create_escrow(accounts.bob, with_deadlines(deadlines), None, None).is_ok());
+            }
+        }
+
+        #[ink::test]
+        fn test_deadline_extension_approved() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), Some(5_000)]);
+
+            // Only the freelancer, only later and without overtaking the next milestone
+            assert_eq!(contract.request_deadline_extension(escrow_id, 0, 2_000), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            for new_deadline in [1_000, 5_000] {
+                assert_eq!(
+                    contract.request_deadline_extension(escrow_id, 0, new_deadline),
+                    Err(EscrowError::InvalidMilestoneDeadlines)
+                );
+            }
+            contract.request_deadline_extension(escrow_id, 0, 2_000).unwrap();
+            assert_eq!(
+                contract.get_deadline_extension(escrow_id, 0),
+                Some(DeadlineExtension { new_deadline: 2_000, requested_at: 0 })
+            );
+
+            // A second concurrent request is rejected
+            assert_eq!(
+                contract.request_deadline_extension(escrow_id, 0, 3_000),
+                Err(EscrowError::ExtensionAlreadyPending)
+            );
+            assert_eq!(contract.approve_deadline_extension(escrow_id, 0), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.approve_deadline_extension(escrow_id, 0).unwrap();
+            let extended: DeadlineExtended = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((extended.milestone_id, extended.old, extended.new), (0, 1_000, 2_000));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().milestones[0].deadline, Some(2_000));
+            assert_eq!(contract.get_deadline_extension(escrow_id, 0), None);
+            assert_eq!(contract.approve_deadline_extension(escrow_id, 0), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_deadline_extension_rejected() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), None]);
+
+            set_caller(accounts.bob);
+            contract.request_deadline_extension(escrow_id, 0, 3_000).unwrap();
+            assert_eq!(contract.reject_deadline_extension(escrow_id, 0), Err(EscrowError::Unauthorized));
+
+            // A rejection clears the request and keeps the deadline
+            set_caller(accounts.alice);
+            contract.reject_deadline_extension(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_deadline_extension(escrow_id, 0), None);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().milestones[0].deadline, Some(1_000));
+            assert_eq!(contract.reject_deadline_extension(escrow_id, 0), Err(EscrowError::InvalidStatus));
+
+            // The freelancer may ask again until the deadline passes, not after
+            set_caller(accounts.bob);
+            set_timestamp(1_000);
+            contract.request_deadline_extension(escrow_id, 0, 2_000).unwrap();
+            set_caller(accounts.alice);
+            contract.reject_deadline_extension(escrow_id, 0).unwrap();
+            set_timestamp(1_001);
+            set_caller(accounts.bob);
+            assert_eq!(contract.request_deadline_extension(escrow_id, 0, 3_000), Err(EscrowError::DeadlinePassed));
+            // Milestones without a deadline have nothing to extend
+            assert_eq!(contract.request_deadline_extension(escrow_id, 1, 3_000), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_releases_clear_deadline_extensions() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), Some(5_000)]);
+
+            set_caller(accounts.bob);
+            contract.request_deadline_extension(escrow_id, 0, 2_000).unwrap();
+            contract.request_deadline_extension(escrow_id, 1, 6_000).unwrap();
+
+            // Releasing a milestone drops its request
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_deadline_extension(escrow_id, 0), None);
+            assert!(contract.get_deadline_extension(escrow_id, 1).is_some());
+
+            // Completing the escrow drops the rest
+            contract.settle_remaining(escrow_id).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            assert_eq!(contract.get_deadline_extension(escrow_id, 1), None);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_release_milestones_in_one_batch() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let milestones = dependent_milestones(&[&[], &[0], &[1], &[]]);
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(4000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // The whole batch is validated before anything is released
+            let too_large = vec![0; MAX_BATCH_RELEASE + 1];
+            assert_eq!(contract.release_milestones(escrow_id, too_large), Err(EscrowError::BatchTooLarge));
+            assert_eq!(contract.release_milestones(escrow_id, Vec::new()), Err(EscrowError::MilestoneNotFound));
+            assert_eq!(contract.release_milestones(escrow_id, vec![0, 7]), Err(EscrowError::MilestoneNotFound));
+            assert_eq!(
+                contract.release_milestones(escrow_id, vec![0, 3, 0]),
+                Err(EscrowError::MilestoneAlreadyReleased)
+            );
+            assert_eq!(contract.release_milestones(escrow_id, vec![1, 0]), Err(EscrowError::DependenciesNotMet));
+            set_caller(accounts.bob);
+            assert_eq!(contract.release_milestones(escrow_id, vec![0]), Err(EscrowError::Unauthorized));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().released_count, 0);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 4000);
+
+            // A milestone may depend on one released earlier in the batch
+            let bob_before = balance(accounts.bob);
+            set_caller(accounts.alice);
+            contract.release_milestones(escrow_id, vec![0, 1]).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 2000);
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert_eq!(escrow.released_count, 2);
+            check_invariants(&contract, escrow_id);
+
+            // Releasing the last open milestones completes the escrow
+            contract.release_milestones(escrow_id, vec![3, 2]).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 4000);
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(escrow.released_count, 4);
+            check_invariants(&contract, escrow_id);
+
+            let event_count = ink::env::test::recorded_events().count();
+            let released: Vec<u32> = (event_count - 3..event_count - 1)
+                .map(|i| recorded_event::<MilestoneReleased>(i).milestone_id)
+                .collect();
+            assert_eq!(released, vec![3, 2]);
+            let completed = recorded_event::<EscrowCompleted>(event_count - 1);
+            assert_eq!(completed.escrow_id, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_release_partial_until_completed() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            let bob_before = balance(accounts.bob);
+
+            // Only the client, only up to what remains
+            assert_eq!(contract.release_partial(escrow_id, 1, 0), Err(EscrowError::InvalidAmount));
+            assert_eq!(contract.release_partial(escrow_id, 1, 2001), Err(EscrowError::InvalidAmount));
+            assert_eq!(contract.release_partial(escrow_id, 7, 100), Err(EscrowError::MilestoneNotFound));
+            set_caller(accounts.bob);
+            assert_eq!(contract.release_partial(escrow_id, 1, 100), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.release_partial(escrow_id, 1, 1600).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 1600);
+            let milestone = &contract.get_escrow(escrow_id).unwrap().milestones[1];
+            assert_eq!((milestone.remaining, milestone.released), (400, false));
+            let partial: MilestonePartiallyReleased = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((partial.milestone_id, partial.amount, partial.remaining), (1, 1600, 400));
+            assert_eq!(contract.release_partial(escrow_id, 1, 401), Err(EscrowError::InvalidAmount));
+            check_invariants(&contract, escrow_id);
+
+            // A full release pays what remains
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 2000);
+            let released: MilestoneReleased = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!(released.amount, 400);
+
+            // Paying the rest of the last milestone completes the escrow
+            contract.release_partial(escrow_id, 0, 250).unwrap();
+            contract.release_partial(escrow_id, 0, 750).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 3000);
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert!(escrow.milestones[0].released);
+            assert_eq!(escrow.released_count, 2);
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_cancel_refunds_rest_of_partially_released_milestone() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_partial(escrow_id, 1, 1000).unwrap();
+
+            // Half of the second milestone is paid, the rest can't be amended away
+            let changes = Amendment { amount_changes: vec![(1, 500)], ..Amendment::default() };
+            assert_eq!(contract.propose_amendment(escrow_id, changes), Err(EscrowError::MilestoneAlreadyReleased));
+
+            let alice_before = balance(accounts.alice);
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+
+            // The first milestone and the unpaid half of the second go back
+            assert_eq!(balance(accounts.alice), alice_before + 2000);
+            let cancelled: EscrowCancelled = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((cancelled.refund_to_client, cancelled.refund_to_freelancer), (2000, 1000));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_refunding_remaining_needs_freelancer_consent() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
+
+            // The client's request alone moves nothing
+            let alice_before = balance(accounts.alice);
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(balance(accounts.alice), alice_before);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            // The freelancer's approval refunds the rest
+            set_caller(accounts.bob);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(balance(accounts.alice), alice_before + 2000);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
+        }
+
+        #[ink::test]
+        fn test_last_release_emits_completed() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            let event_count = ink::env::test::recorded_events().count();
+            contract.release_milestone(escrow_id, 1).unwrap();
+
+            // MilestoneReleased, then EscrowCompleted
+            assert_eq!(ink::env::test::recorded_events().count(), event_count + 2);
+            let completed = recorded_event::<EscrowCompleted>(event_count + 1);
+            assert_eq!((completed.client, completed.freelancer), (accounts.alice, accounts.bob));
+            assert_eq!(completed.total_amount, 3000);
+        }
+
+        #[ink::test]
+        fn test_validate_milestones_reports_every_issue() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            assert!(contract.validate_milestones(create_test_milestones()).is_empty());
+
+            // A wrong ID, a released milestone and a self-dependency at once
+            let mut plan = dependent_milestones(&[&[], &[], &[], &[]]);
+            plan[1].id = 7;
+            plan[2].released = true;
+            plan[3].depends_on = vec![3];
+            let issue = |error, index| ValidationIssue { error, index: Some(index) };
+            assert_eq!(
+                contract.validate_milestones(plan.clone()),
+                vec![
+                    issue(EscrowError::InvalidAmount, 1),
+                    issue(EscrowError::InvalidStatus, 2),
+                    issue(EscrowError::InvalidDependencies, 3),
+                ]
+            );
+
+            // create_escrow fails with the first issue and stores nothing
+            assert_eq!(contract.create_escrow(accounts.bob, plan, None, None), Err(EscrowError::InvalidAmount));
+            assert!(contract.get_escrows_by_client(accounts.alice).is_empty());
+        }
+
+        #[ink::test]
+        fn test_validate_milestones_plan_issues() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+            assert_eq!(
+                contract.validate_milestones(Vec::new()),
+                vec![ValidationIssue { error: EscrowError::EmptyMilestones, index: None }]
+            );
+
+            // Plan-wide problems come first, without an index
+            contract.set_max_escrow_amount(2000).unwrap();
+            let mut plan = create_test_milestones();
+            plan[0].depends_on = vec![0];
+            assert_eq!(
+                contract.validate_milestones(plan.clone()),
+                vec![
+                    ValidationIssue { error: EscrowError::ExceedsMaxEscrow, index: None },
+                    ValidationIssue { error: EscrowError::InvalidDependencies, index: Some(0) },
+                ]
+            );
+
+            let mut zero = create_test_milestones();
+            zero.iter_mut().for_each(|m| m.amount = 0);
+            assert_eq!(
+                contract.validate_milestones(zero),
+                vec![ValidationIssue { error: EscrowError::ZeroAmount, index: None }]
+            );
+
+            // The cap depends on the caller, like in create_escrow
+            contract.add_exempt_client(accounts.charlie).unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.validate_milestones(plan),
+                vec![ValidationIssue { error: EscrowError::InvalidDependencies, index: Some(0) }]
+            );
+            assert!(contract.validate_milestones(create_test_milestones()).is_empty());
+            assert!(contract.create_escrow(accounts.bob, create_test_milestones(), None, None).is_ok());
+        }
+
+        #[ink::test]
+        fn test_create_escrow_idempotency_key() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // A retry with the same key returns the first escrow
+            let first = contract.create_escrow(accounts.bob, create_test_milestones(), None, Some([1; 32])).unwrap();
+            let retry = contract.create_escrow(accounts.bob, create_test_milestones(), None, Some([1; 32])).unwrap();
+            assert_eq!(retry, first);
+            assert_eq!(contract.get_escrows_by_client(accounts.alice), vec![first]);
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+
+            // A different key creates another escrow
+            let second = contract.create_escrow(accounts.bob, create_test_milestones(), None, Some([2; 32])).unwrap();
+            assert_ne!(second, first);
+
+            // Without a key, every call creates an escrow
+            let third = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let fourth = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            assert_ne!(third, fourth);
+            assert_eq!(contract.get_escrows_by_client(accounts.alice), vec![first, second, third, fourth]);
+
+            // Keys are scoped per caller
+            set_caller(accounts.charlie);
+            let other = contract.create_escrow(accounts.bob, create_test_milestones(), None, Some([1; 32])).unwrap();
+            assert_ne!(other, first);
+            assert_eq!(contract.get_escrow(other).unwrap().client, accounts.charlie);
+        }
+
+        /// Create a drawdown escrow from alice to bob with a 1000 cap at 30 per
+        /// hour and `arbiter`, funded at timestamp 0
+        fn create_funded_drawdown(contract: &mut EscrowMultiRelease, arbiter: Option<AccountId>) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            let escrow_id = contract.create_drawdown_escrow(accounts.bob, 1000, 30, arbiter).unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            escrow_id
+        }
+
+        /// Submit a timesheet as bob and approve it as alice
+        fn bill_hours(contract: &mut EscrowMultiRelease, escrow_id: u64, hours: u32) -> Result<()> {
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            let timesheet_id = contract.submit_timesheet(escrow_id, hours, Hash::from([hours as u8; 32]))?;
+            set_caller(accounts.alice);
+            contract.approve_timesheet(escrow_id, timesheet_id)
+        }
+
+        #[ink::test]
+        fn test_drawdown_partial_approvals_until_cap() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_drawdown(&mut contract, None);
+            let bob_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+
+            // Two timesheets pending, approved out of order
+            set_caller(accounts.bob);
+            let first = contract.submit_timesheet(escrow_id, 10, Hash::from([1; 32])).unwrap();
+            let second = contract.submit_timesheet(escrow_id, 20, Hash::from([2; 32])).unwrap();
+            assert_eq!((first, second), (0, 1));
+
+            set_caller(accounts.alice);
+            contract.approve_timesheet(escrow_id, second).unwrap();
+            let approved: TimesheetApproved = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((approved.timesheet_id, approved.milestone_id, approved.amount), (1, 0, 600));
+            assert_eq!(contract.approve_timesheet(escrow_id, second), Err(EscrowError::TimesheetAlreadyApproved));
+            assert_eq!(contract.approve_timesheet(escrow_id, 5), Err(EscrowError::TimesheetNotFound));
+            contract.approve_timesheet(escrow_id, first).unwrap();
+            assert_eq!(contract.get_timesheet(escrow_id, first).unwrap().approved_amount, Some(300));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 100);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            // 5 hours would be 150, only the remaining 100 of the cap is paid
+            bill_hours(&mut contract, escrow_id, 5).unwrap();
+            assert_eq!(contract.get_timesheet(escrow_id, 2).unwrap().approved_amount, Some(100));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(
+                escrow.milestones.iter().map(|m| (m.id, m.amount, m.released)).collect::<Vec<_>>(),
+                vec![(0, 600, true), (1, 300, true), (2, 100, true)]
+            );
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap(),
+                bob_before + 1000
+            );
+            assert_eq!(contract.get_totals(accounts.bob), (1000, 0));
+
+            // The exhausted cap takes no more timesheets
+            assert_eq!(bill_hours(&mut contract, escrow_id, 1), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_drawdown_rules() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = EscrowMultiRelease::new();
+
+            assert_eq!(contract.create_drawdown_escrow(accounts.bob, 0, 30, None), Err(EscrowError::ZeroAmount));
+            assert_eq!(contract.create_drawdown_escrow(accounts.bob, 1000, 0, None), Err(EscrowError::InvalidAmount));
+
+            // Timesheets need a funded drawdown escrow
+            let unfunded = contract.create_drawdown_escrow(accounts.bob, 1000, 30, None).unwrap();
+            assert_eq!(
+                contract.get_drawdown(unfunded),
+                Some(Drawdown { rate_per_hour: 30, timesheet_count: 0, close_requested_by: None })
+            );
+            set_caller(accounts.bob);
+            assert_eq!(contract.submit_timesheet(unfunded, 1, Hash::from([1; 32])), Err(EscrowError::InvalidStatus));
+            set_caller(accounts.alice);
+            let milestone_escrow = create_funded_escrow(&mut contract, 0);
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.submit_timesheet(milestone_escrow, 1, Hash::from([1; 32])),
+                Err(EscrowError::NotDrawdown)
+            );
+            assert_eq!(contract.close_drawdown(milestone_escrow), Err(EscrowError::NotDrawdown));
+
+            let escrow_id = create_funded_drawdown(&mut contract, None);
+            assert_eq!(contract.submit_timesheet(escrow_id, 1, Hash::from([1; 32])), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(contract.submit_timesheet(escrow_id, 0, Hash::from([1; 32])), Err(EscrowError::ZeroAmount));
+            let timesheet_id = contract.submit_timesheet(escrow_id, 1, Hash::from([1; 32])).unwrap();
+            assert_eq!(contract.approve_timesheet(escrow_id, timesheet_id), Err(EscrowError::Unauthorized));
+
+            // Milestone flows can't pay out or reshape the cap
+            set_caller(accounts.alice);
+            assert_eq!(contract.settle_remaining(escrow_id), Err(EscrowError::InvalidStatus));
+            let changes = Amendment {
+                amount_changes: Vec::new(),
+                new_milestones: Vec::new(),
+                abandonment_timeout_ms: Some(0),
+                deadlock_timeout_ms: None,
+            };
+            assert_eq!(contract.propose_amendment(escrow_id, changes), Err(EscrowError::InvalidStatus));
+
+            // Timesheets are bounded
+            set_caller(accounts.bob);
+            for _ in 1..MAX_TIMESHEETS {
+                contract.submit_timesheet(escrow_id, 1, Hash::from([1; 32])).unwrap();
+            }
+            assert_eq!(contract.submit_timesheet(escrow_id, 1, Hash::from([1; 32])), Err(EscrowError::TooManyTimesheets));
+        }
+
+        #[ink::test]
+        fn test_close_drawdown_refunds_unused_cap() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_drawdown(&mut contract, None);
+            bill_hours(&mut contract, escrow_id, 10).unwrap();
+
+            // A pending timesheet is not paid on closure
+            set_caller(accounts.bob);
+            contract.submit_timesheet(escrow_id, 4, Hash::from([4; 32])).unwrap();
+
+            set_caller(accounts.django);
+            assert_eq!(contract.close_drawdown(escrow_id), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            contract.close_drawdown(escrow_id).unwrap();
+            let requested: DrawdownCloseRequested = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!(requested.requested_by, accounts.alice);
+            let event_count = ink::env::test::recorded_events().count();
+            contract.close_drawdown(escrow_id).unwrap();
+            assert_eq!(ink::env::test::recorded_events().count(), event_count);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            set_caller(accounts.bob);
+            contract.close_drawdown(escrow_id).unwrap();
+
+            let closed: DrawdownClosed = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!(closed.drawn, 300);
+            assert_eq!(closed.refund_to_client, 700);
+            let completed: EscrowCompleted = recorded_event(ink::env::test::recorded_events().count() - 2);
+            assert_eq!(completed.total_amount, 300);
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(escrow.total_amount, 300);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 300));
+
+            set_caller(accounts.alice);
+            assert_eq!(contract.approve_timesheet(escrow_id, 1), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_drawdown_dispute_settles_remainder() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_drawdown(&mut contract, Some(accounts.charlie));
+            bill_hours(&mut contract, escrow_id, 10).unwrap();
+
+            set_caller(accounts.bob);
+            contract.submit_timesheet(escrow_id, 20, Hash::from([2; 32])).unwrap();
+            contract.raise_dispute(escrow_id, "Timesheet not approved".to_string(), None).unwrap();
+
+            // Disputed timesheets wait for the arbiter, who splits what was not drawn
+            set_caller(accounts.alice);
+            assert_eq!(contract.approve_timesheet(escrow_id, 1), Err(EscrowError::InvalidStatus));
+            set_caller(accounts.charlie);
+            assert_eq!(contract.resolve_dispute_by_arbiter(escrow_id, 600, 200), Err(EscrowError::InvalidAmount));
+            contract.resolve_dispute_by_arbiter(escrow_id, 600, 100).unwrap();
+
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Resolved);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.bob), (900, 0));
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_terminal_events_carry_snapshot_hash() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 10);
+
+            // Open escrows have no snapshot
+            assert_eq!(contract.finalize_snapshot(escrow_id), None);
+            assert_eq!(contract.finalize_snapshot(escrow_id + 1), None);
+
+            set_timestamp(20);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let completed: EscrowCompleted = recorded_event(events - 1);
+            let snapshot = contract.get_final_snapshot(escrow_id).unwrap();
+            assert_eq!(Some(completed.snapshot_hash), contract.finalize_snapshot(escrow_id));
+            assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+            assert_eq!(snapshot.status, EscrowStatus::Completed);
+            assert_eq!((snapshot.created_at, snapshot.funded_at, snapshot.closed_at), (0, Some(10), 20));
+            assert_eq!((snapshot.paid_to_freelancer, snapshot.refunded_to_client), (3000, 0));
+            assert_eq!(snapshot.milestones.len(), 2);
+            assert!(snapshot.milestones.iter().all(|(_, _, released, _)| *released));
+
+            // The arbiter's split is recorded and hashed into DisputeResolved
+            set_balance(accounts.charlie, 1_000_000);
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            set_timestamp(30);
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let resolved: DisputeResolved = recorded_event(events - 1);
+            let snapshot = contract.get_final_snapshot(escrow_id).unwrap();
+            assert_eq!(Some(resolved.snapshot_hash), contract.finalize_snapshot(escrow_id));
+            assert_eq!(snapshot.termination_reason, Some(TerminationReason::ArbiterDecision));
+            assert_eq!((snapshot.paid_to_freelancer, snapshot.refunded_to_client), (1000, 2000));
+            assert_eq!(snapshot.closed_at, 30);
+            assert_ne!(resolved.snapshot_hash, completed.snapshot_hash);
+        }
+
+        #[ink::test]
+        fn test_snapshot_encoding_is_pinned() {
+            // Archived hashes must keep verifying after upgrades: if this
+            // fixture changes, the snapshot format needs a new version instead
+            let contract = EscrowMultiRelease::new();
+            let snapshot = EscrowSnapshot {
+                version: 1,
+                escrow_id: 7,
+                client: AccountId::from([0x01; 32]),
+                freelancer: AccountId::from([0x02; 32]),
+                arbiter: Some(AccountId::from([0x03; 32])),
+                refund_address: AccountId::from([0x04; 32]),
+                total_amount: 3000,
+                deposited: 3000,
+                milestones: vec![
+                    (0, 1000, true, "Design".to_string()),
+                    (1, 2000, false, "Build".to_string()),
+                ],
+                status: EscrowStatus::Resolved,
+                termination_reason: Some(TerminationReason::ArbiterDecision),
+                created_at: 1_000,
+                funded_at: Some(2_000),
+                closed_at: 3_000,
+                paid_to_freelancer: 1500,
+                refunded_to_client: 1500,
+            };
+            assert_eq!(scale::Encode::encode(&snapshot).len(), 286);
+            assert_eq!(
+                contract.snapshot_hash(&snapshot),
+                Hash::from([
+                    0x5d, 0x32, 0x36, 0x0d, 0x62, 0x01, 0x3b, 0x5b, 0x38, 0x4c, 0x62, 0x44, 0xaf, 0x1b, 0x89, 0x83,
+                    0xad, 0x40, 0x0e, 0xb1, 0xa3, 0x8b, 0x44, 0x82, 0x68, 0xad, 0xa6, 0x7c, 0x33, 0xb7, 0x0c, 0x10,
+                ])
+            );
+        }
+
+        #[ink::test]
+        fn test_rate_counterparty() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // Open escrows can't be rated yet
+            assert_eq!(contract.rate_counterparty(escrow_id, 5, None), Err(EscrowError::InvalidStatus));
+
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(contract.rate_counterparty(escrow_id, 5, None), Err(EscrowError::Unauthorized));
+
+            set_caller(accounts.alice);
+            assert_eq!(contract.rate_counterparty(escrow_id, 0, None), Err(EscrowError::InvalidRating));
+            assert_eq!(contract.rate_counterparty(escrow_id, 6, None), Err(EscrowError::InvalidRating));
+            assert_eq!(
+                contract.rate_counterparty(escrow_id, 5, Some("a".repeat(MAX_RATING_URI_LEN + 1))),
+                Err(EscrowError::InvalidRating)
+            );
+            assert_eq!(contract.rate_counterparty(escrow_id, 5, Some("ipfs://review".to_string())), Ok(()));
+            assert_eq!(contract.rate_counterparty(escrow_id, 4, None), Err(EscrowError::AlreadyRated));
+
+            let events = ink::env::test::recorded_events().count();
+            let rated: CounterpartyRated = recorded_event(events - 1);
+            assert_eq!((rated.escrow_id, rated.rater, rated.ratee, rated.score), (escrow_id, accounts.alice, accounts.bob, 5));
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.rate_counterparty(escrow_id, 3, None), Ok(()));
+
+            let ratings = contract.get_ratings(escrow_id);
+            assert_eq!(ratings.len(), 2);
+            assert_eq!((ratings[0].rater, ratings[0].ratee, ratings[0].score), (accounts.alice, accounts.bob, 5));
+            assert_eq!(ratings[0].comment_uri, Some("ipfs://review".to_string()));
+            assert_eq!((ratings[1].rater, ratings[1].ratee, ratings[1].score), (accounts.bob, accounts.alice, 3));
+
+            // A second engagement adds to the running totals
+            let second_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(second_id, 0).unwrap();
+            contract.release_milestone(second_id, 1).unwrap();
+            contract.rate_counterparty(second_id, 2, None).unwrap();
+            assert_eq!(contract.get_rating_summary(accounts.bob), (7, 2));
+            assert_eq!(contract.get_rating_summary(accounts.alice), (3, 1));
+            assert_eq!(contract.get_rating_summary(accounts.charlie), (0, 0));
+            assert_eq!(contract.get_ratings(second_id).len(), 1);
+        }
+
+        #[ink::test]
+        fn test_rating_blocked_on_unfunded_cancel() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            let mut contract = EscrowMultiRelease::new();
+
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
+            assert_eq!(contract.rate_counterparty(escrow_id, 1, None), Err(EscrowError::InvalidStatus));
+
+            // A cancellation after funding can be rated
+            let funded_id = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(funded_id, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(funded_id, None).unwrap();
+            assert_eq!(contract.rate_counterparty(funded_id, 1, None), Ok(()));
+            assert_eq!(contract.get_rating_summary(accounts.alice), (1, 1));
+            assert!(contract.get_ratings(escrow_id).is_empty());
+        }
+
+        /// Create an escrow from alice to bob, accepted by bob, that can be funded until `funding_deadline`
+        fn create_escrow_with_deadline(contract: &mut EscrowMultiRelease, funding_deadline: u64) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let options = EscrowOptions {
+                funding_deadline: Some(funding_deadline),
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            escrow_id
+        }
+
+        #[ink::test]
+        fn test_expire_escrow_after_funding_deadline() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_deadline(&mut contract, 1_000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1200);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // Anyone can expire, but only once the deadline has passed
+            set_caller(accounts.django);
+            set_timestamp(1_000);
+            assert_eq!(contract.expire_escrow(escrow_id), Err(EscrowError::TimeoutNotReached));
+
+            set_timestamp(1_001);
+            contract.expire_escrow(escrow_id).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Expired);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Expired));
+            let events = ink::env::test::recorded_events().count();
+            let expired: EscrowExpired = recorded_event(events - 1);
+            assert_eq!(expired.escrow_id, escrow_id);
+            assert_eq!(expired.refund_to_client, 1200);
+            assert_eq!(Some(expired.snapshot_hash), contract.finalize_snapshot(escrow_id));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 0));
+            assert_eq!(contract.describe_escrow(escrow_id).unwrap().split(':').next(), Some("Expired"));
+            check_invariants(&contract, escrow_id);
+
+            // An expired escrow can neither be funded nor expired again
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1800);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.expire_escrow(escrow_id), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_funding_deadline_only_binds_unfunded_escrows() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+
+            // Without a deadline an escrow never expires
+            set_caller(accounts.alice);
+            let open = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            set_timestamp(u64::MAX);
+            assert_eq!(contract.expire_escrow(open), Err(EscrowError::InvalidStatus));
+
+            // Past the deadline the escrow only waits to be expired
+            set_timestamp(0);
+            let lapsed = create_escrow_with_deadline(&mut contract, 1_000);
+            set_timestamp(1_001);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(lapsed, None, None, None), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.get_escrow(lapsed).unwrap().deposited, 0);
+
+            // Funded in time, the deadline no longer applies
+            set_timestamp(0);
+            let funded = create_escrow_with_deadline(&mut contract, 1_000);
+            contract.fund_escrow(funded, None, None, None).unwrap();
+            set_timestamp(2_000);
+            assert_eq!(contract.expire_escrow(funded), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.get_escrow(funded).unwrap().status, EscrowStatus::Funded);
+            check_invariants(&contract, funded);
+        }
+
+        #[ink::test]
+        fn test_reclaim_expired_milestone() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), None]);
+
+            set_timestamp(1_000);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+
+            set_timestamp(1_001);
+            set_caller(accounts.bob);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 1), Err(EscrowError::InvalidStatus));
+            contract.reclaim_expired_milestone(escrow_id, 0).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let reclaimed: MilestoneReclaimed = recorded_event(events - 1);
+            assert_eq!((reclaimed.milestone_id, reclaimed.amount), (0, 1000));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert!(escrow.milestones[0].forfeited);
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2000);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 2000));
+            check_invariants(&contract, escrow_id);
 
-            // Validate milestones
-            if milestones.is_empty() {
-                return Err(EscrowError::EmptyMilestones);
-            }
+            // A forfeited milestone is gone for good
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::MilestoneForfeited));
+            assert_eq!(contract.release_milestone(escrow_id, 0), Err(EscrowError::MilestoneForfeited));
 
-            // Calculate total amount and validate
-            let total_amount: Balance = milestones
-                .iter()
-                .map(|m| m.amount)
-                .sum();
+            // Releasing the rest still completes the escrow
+            contract.release_milestone(escrow_id, 1).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Completed);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.bob).0, 2000);
+            check_invariants(&contract, escrow_id);
+        }
 
-            if total_amount == 0 {
-                return Err(EscrowError::ZeroAmount);
-            }
+        #[ink::test]
+        fn test_pending_extension_holds_off_reclaim() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
 
-            // Validate milestone IDs are unique and sequential
-            for (idx, milestone) in milestones.iter().enumerate() {
-                if milestone.id != idx as u32 {
-                    return Err(EscrowError::InvalidAmount); // Reuse for validation error
-                }
-                if milestone.released {
-                    return Err(EscrowError::InvalidStatus); // Milestones should start unreleased
-                }
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), None]);
+
+            set_timestamp(999);
+            set_caller(accounts.bob);
+            contract.request_deadline_extension(escrow_id, 0, 10_000).unwrap();
+
+            // Inside the grace window the client can't race the expiry
+            set_caller(accounts.alice);
+            set_timestamp(1_001);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+            set_timestamp(1_000 + DEADLINE_EXTENSION_GRACE_MS);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+
+            // Once the window passes unanswered, the reclaim goes through and clears the request
+            set_timestamp(1_001 + DEADLINE_EXTENSION_GRACE_MS);
+            contract.reclaim_expired_milestone(escrow_id, 0).unwrap();
+            assert!(contract.get_escrow(escrow_id).unwrap().milestones[0].forfeited);
+            assert_eq!(contract.get_deadline_extension(escrow_id, 0), None);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_rejected_extension_keeps_reclaim_grace() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), None]);
+
+            set_timestamp(999);
+            set_caller(accounts.bob);
+            contract.request_deadline_extension(escrow_id, 0, 10_000).unwrap();
+
+            // Rejecting right after the deadline doesn't open the reclaim early
+            set_caller(accounts.alice);
+            set_timestamp(1_001);
+            contract.reject_deadline_extension(escrow_id, 0).unwrap();
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+            set_timestamp(1_000 + DEADLINE_EXTENSION_GRACE_MS);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+
+            set_timestamp(1_001 + DEADLINE_EXTENSION_GRACE_MS);
+            contract.reclaim_expired_milestone(escrow_id, 0).unwrap();
+            assert!(contract.get_escrow(escrow_id).unwrap().milestones[0].forfeited);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_approved_extension_moves_reclaim() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), None]);
+
+            set_caller(accounts.bob);
+            contract.request_deadline_extension(escrow_id, 0, 2_000).unwrap();
+            set_caller(accounts.alice);
+            contract.approve_deadline_extension(escrow_id, 0).unwrap();
+
+            // The new deadline replaces both the old one and the grace
+            set_timestamp(1_500);
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::TimeoutNotReached));
+            set_timestamp(2_001);
+            contract.reclaim_expired_milestone(escrow_id, 0).unwrap();
+
+            // A reclaimed milestone takes no more requests
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.request_deadline_extension(escrow_id, 0, 3_000),
+                Err(EscrowError::MilestoneForfeited)
+            );
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_reclaiming_last_open_milestone_cancels_escrow() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_escrow_with_milestone_deadlines(&mut contract, [Some(1_000), Some(2_000)]);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.reclaim_expired_milestone(escrow_id, 0), Err(EscrowError::MilestoneAlreadyReleased));
+
+            set_timestamp(2_001);
+            contract.reclaim_expired_milestone(escrow_id, 1).unwrap();
+
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Expired));
+            let events = ink::env::test::recorded_events().count();
+            let cancelled: EscrowCancelled = recorded_event(events - 1);
+            assert_eq!((cancelled.refund_to_client, cancelled.refund_to_freelancer), (2000, 1000));
+            assert_eq!(Some(cancelled.snapshot_hash), contract.finalize_snapshot(escrow_id));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_submit_milestone() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let unfunded = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            let proof = Hash::from([7; 32]);
+
+            assert_eq!(contract.submit_milestone(escrow_id, 0, proof), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(contract.submit_milestone(unfunded, 0, proof), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.submit_milestone(escrow_id, 7, proof), Err(EscrowError::MilestoneNotFound));
+            contract.submit_milestone(escrow_id, 0, proof).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let submitted: MilestoneSubmitted = recorded_event(events - 1);
+            assert_eq!((submitted.escrow_id, submitted.milestone_id, submitted.proof_hash), (escrow_id, 0, proof));
+            assert_eq!(contract.get_milestone(escrow_id, 0).unwrap().proof_hash, Some(proof));
+
+            // Without the flag, unsubmitted milestones release as before
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 1).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(contract.submit_milestone(escrow_id, 1, proof), Err(EscrowError::MilestoneAlreadyReleased));
+        }
+
+        #[ink::test]
+        fn test_release_requires_submission() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let options = EscrowOptions {
+                require_submission_before_release: true,
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            let simulation = contract.simulate_release(escrow_id, 0, accounts.alice);
+            assert_eq!(simulation.failed_check, Some(ValidationCheck::Submission));
+            assert_predicts(&simulation, contract.release_milestone(escrow_id, 0));
+            assert_eq!(contract.release_milestone(escrow_id, 0), Err(EscrowError::MilestoneNotSubmitted));
+
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 0, Hash::from([1; 32])).unwrap();
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2000);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_reject_milestone() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 0, Hash::from([1; 32])).unwrap();
+
+            let reason = || "Missing the mobile layout".to_string();
+            assert_eq!(contract.reject_milestone(escrow_id, 0, reason()), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(contract.reject_milestone(escrow_id, 1, reason()), Err(EscrowError::MilestoneNotSubmitted));
+            assert_eq!(contract.reject_milestone(escrow_id, 0, String::new()), Err(EscrowError::InvalidReason));
+            assert_eq!(
+                contract.reject_milestone(escrow_id, 0, "x".repeat(MAX_REASON_LEN + 1)),
+                Err(EscrowError::InvalidReason)
+            );
+            contract.reject_milestone(escrow_id, 0, reason()).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let rejected: MilestoneRejected = recorded_event(events - 1);
+            assert_eq!((rejected.escrow_id, rejected.milestone_id, rejected.reason), (escrow_id, 0, reason()));
+            assert_eq!(contract.get_milestone(escrow_id, 0).unwrap().proof_hash, None);
+            assert_eq!(contract.get_milestone_rejections(escrow_id, 0), Some(1));
+            assert_eq!(contract.reject_milestone(escrow_id, 0, reason()), Err(EscrowError::MilestoneNotSubmitted));
+
+            // The reworked submission can be rejected again, or released
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 0, Hash::from([2; 32])).unwrap();
+            set_caller(accounts.alice);
+            contract.reject_milestone(escrow_id, 0, reason()).unwrap();
+            // Without an arbiter nothing escalates
+            assert!(!contract.get_milestone(escrow_id, 0).unwrap().disputed);
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 0, Hash::from([3; 32])).unwrap();
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.reject_milestone(escrow_id, 0, reason()), Err(EscrowError::MilestoneAlreadyReleased));
+            assert_eq!(contract.get_milestone_rejections(escrow_id, 0), Some(2));
+            assert_eq!(contract.get_milestone_rejections(escrow_id, 7), None);
+        }
+
+        #[ink::test]
+        fn test_rejections_escalate_milestone() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            let reason = || "Missing the mobile layout".to_string();
+            let submit = |contract: &mut EscrowMultiRelease, byte: u8| {
+                set_caller(accounts.bob);
+                contract.submit_milestone(escrow_id, 0, Hash::from([byte; 32])).unwrap();
+                set_caller(accounts.alice);
+            };
+
+            // Reject, resubmit, reject: the second rejection escalates
+            submit(&mut contract, 1);
+            contract.reject_milestone(escrow_id, 0, reason()).unwrap();
+            assert!(!contract.get_milestone(escrow_id, 0).unwrap().disputed);
+            submit(&mut contract, 2);
+            contract.reject_milestone(escrow_id, 0, reason()).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let escalated: MilestoneEscalated = recorded_event(events - 1);
+            assert_eq!((escalated.escrow_id, escalated.milestone_id, escalated.rejections), (escrow_id, 0, 2));
+            assert!(contract.get_milestone(escrow_id, 0).unwrap().disputed);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+            assert_eq!(contract.get_actionable_escrows(accounts.charlie, Role::Arbiter), vec![escrow_id]);
+            check_invariants(&contract, escrow_id);
+
+            // Only the arbiter moves the escalated milestone
+            assert_eq!(contract.release_milestone(escrow_id, 0), Err(EscrowError::MilestoneDisputed));
+            assert_eq!(contract.settle_remaining(escrow_id), Err(EscrowError::MilestoneDisputed));
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.submit_milestone(escrow_id, 0, Hash::from([3; 32])),
+                Err(EscrowError::MilestoneDisputed)
+            );
+
+            // The sibling milestone keeps flowing
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 1000);
+
+            // Sent back for rework, the milestone never escalates again
+            assert_eq!(contract.resolve_milestone_dispute(escrow_id, 0, false), Err(EscrowError::Unauthorized));
+            set_caller(accounts.charlie);
+            assert_eq!(contract.resolve_milestone_dispute(escrow_id, 1, false), Err(EscrowError::InvalidStatus));
+            contract.resolve_milestone_dispute(escrow_id, 0, false).unwrap();
+            let events = ink::env::test::recorded_events().count();
+            let resolved: MilestoneDisputeResolved = recorded_event(events - 1);
+            assert_eq!((resolved.escrow_id, resolved.milestone_id, resolved.released), (escrow_id, 0, false));
+            let milestone = contract.get_milestone(escrow_id, 0).unwrap();
+            assert!(!milestone.disputed && milestone.arbitrated && !milestone.released);
+            assert!(contract.get_actionable_escrows(accounts.charlie, Role::Arbiter).is_empty());
+
+            set_caller(accounts.alice);
+            for byte in [4, 5] {
+                submit(&mut contract, byte);
+                contract.reject_milestone(escrow_id, 0, reason()).unwrap();
             }
+            assert_eq!(contract.get_milestone_rejections(escrow_id, 0), Some(4));
+            assert!(!contract.get_milestone(escrow_id, 0).unwrap().disputed);
 
-            // Create escrow
-            let escrow_id = self.next_escrow_id;
-            let created_at = self.env().block_timestamp();
+            submit(&mut contract, 6);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            check_invariants(&contract, escrow_id);
+        }
 
-            let escrow = Escrow {
-                id: escrow_id,
-                client: caller,
-                freelancer,
-                arbiter,
-                total_amount,
-                deposited: 0,
-                milestones,
-                status: EscrowStatus::Created,
-                cancel_requested_by: None,
-                created_at,
+        #[ink::test]
+        fn test_resolve_milestone_dispute_releases() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let options = EscrowOptions {
+                escalation_threshold: Some(1),
+                ..Default::default()
             };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), Some(accounts.charlie), options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
 
-            // Store escrow
-            self.escrows.insert(escrow_id, &escrow);
+            set_caller(accounts.bob);
+            contract.submit_milestone(escrow_id, 1, Hash::from([1; 32])).unwrap();
+            set_caller(accounts.alice);
+            contract.reject_milestone(escrow_id, 1, "Wrong palette".to_string()).unwrap();
+            assert!(contract.get_milestone(escrow_id, 1).unwrap().disputed);
 
-            // Add to client's escrows list
-            let mut client_list = self.client_escrows.get(caller).unwrap_or_default();
-            client_list.push(escrow_id);
-            self.client_escrows.insert(caller, &client_list);
+            set_caller(accounts.charlie);
+            contract.resolve_milestone_dispute(escrow_id, 1, true).unwrap();
+            let events = ink::env::test::recorded_events().count();
+            let resolved: MilestoneDisputeResolved = recorded_event(events - 2);
+            assert_eq!((resolved.milestone_id, resolved.released), (1, true));
+            let released: MilestoneReleased = recorded_event(events - 1);
+            assert_eq!((released.milestone_id, released.amount), (1, 2000));
+
+            let milestone = contract.get_milestone(escrow_id, 1).unwrap();
+            assert!(milestone.released && milestone.arbitrated && !milestone.disputed);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 1000);
+            assert_eq!(contract.resolve_milestone_dispute(escrow_id, 1, true), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+        }
 
-            // Add to freelancer's escrows list
-            let mut freelancer_list = self.freelancer_escrows.get(escrow.freelancer).unwrap_or_default();
-            freelancer_list.push(escrow_id);
-            self.freelancer_escrows.insert(escrow.freelancer, &freelancer_list);
+        #[ink::test]
+        fn test_raise_dispute() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
 
-            // Increment escrow counter
-            self.next_escrow_id += 1;
+            let mut contract = EscrowMultiRelease::new();
+            contract.set_dispute_grace(1_000).unwrap();
+            let reason = || "Milestone 1 was never delivered".to_string();
+
+            // Without an arbiter nobody could settle it
+            let no_arbiter = create_funded_escrow(&mut contract, 0);
+            assert_eq!(contract.raise_dispute(no_arbiter, reason(), None), Err(EscrowError::InvalidArbiter));
+
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            assert_eq!(contract.raise_dispute(escrow_id, reason(), None), Err(EscrowError::InvalidStatus));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(contract.raise_dispute(escrow_id, reason(), None), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(contract.raise_dispute(escrow_id, reason(), None), Err(EscrowError::DisputeTooEarly));
+
+            // The grace period only binds the client
+            set_caller(accounts.bob);
+            assert_eq!(contract.raise_dispute(escrow_id, String::new(), None), Err(EscrowError::InvalidReason));
+            contract.raise_dispute(escrow_id, reason(), None).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let raised: DisputeRaised = recorded_event(events - 1);
+            assert_eq!((raised.escrow_id, raised.raised_by, raised.reason), (escrow_id, accounts.bob, reason()));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Disputed);
+            assert_eq!((escrow.disputed_by, escrow.dispute_reason), (Some(accounts.bob), Some(reason())));
+            assert_eq!(escrow.cancel_requested_by, None);
+            assert_eq!(contract.raise_dispute(escrow_id, reason(), None), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 3000, 0).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Resolved);
+        }
+
+        #[ink::test]
+        fn test_withdraw_cancel_request() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            assert_eq!(contract.withdraw_cancel_request(escrow_id), Err(EscrowError::InvalidStatus));
+
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(contract.withdraw_cancel_request(escrow_id), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            contract.withdraw_cancel_request(escrow_id).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let withdrawn: CancelRequestWithdrawn = recorded_event(events - 1);
+            assert_eq!((withdrawn.escrow_id, withdrawn.withdrawn_by), (escrow_id, accounts.bob));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert_eq!((escrow.cancel_requested_by, escrow.cancel_requested_at), (None, None));
+            assert!(contract.get_actionable_escrows(accounts.alice, Role::Client).is_empty());
+            assert_eq!(contract.withdraw_cancel_request(escrow_id), Err(EscrowError::InvalidStatus));
+
+            // Nothing is left to approve, and the escrow releases normally
+            set_caller(accounts.alice);
+            assert_eq!(contract.approve_cancel(escrow_id, None), Err(EscrowError::InvalidStatus));
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_withdrawn_request_unblocks_funding() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            contract.request_cancel(escrow_id, None).unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidStatus));
+
+            contract.withdraw_cancel_request(escrow_id).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Created);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2000);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_freelancer_accepts_before_funding() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::PendingAcceptance);
+            assert_eq!(contract.get_actionable_escrows(accounts.bob, Role::Freelancer), vec![escrow_id]);
+            assert!(contract.describe_escrow(escrow_id).unwrap().ends_with("awaiting freelancer"));
+
+            // Not fundable until the freelancer accepts
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.accept_escrow(escrow_id), Err(EscrowError::Unauthorized));
+            assert_eq!(contract.accept_escrow(99), Err(EscrowError::EscrowNotFound));
+
+            set_caller(accounts.bob);
+            contract.accept_escrow(escrow_id).unwrap();
+            let accepted = recorded_event::<EscrowAccepted>(2);
+            assert_eq!((accepted.escrow_id, accepted.client, accepted.freelancer), (escrow_id, accounts.alice, accounts.bob));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Created);
+            assert!(contract.get_actionable_escrows(accounts.bob, Role::Freelancer).is_empty());
+            assert_eq!(contract.accept_escrow(escrow_id), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.decline_escrow(escrow_id), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
+
+            set_caller(accounts.alice);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+            check_invariants(&contract, escrow_id);
+        }
+
+        #[ink::test]
+        fn test_freelancer_declines_escrow() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let kept = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            let declined = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                .unwrap();
+
+            assert_eq!(contract.decline_escrow(declined), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            contract.decline_escrow(declined).unwrap();
+
+            let events = ink::env::test::recorded_events().count();
+            let event: EscrowDeclined = recorded_event(events - 1);
+            assert_eq!((event.escrow_id, event.client, event.freelancer), (declined, accounts.alice, accounts.bob));
+            assert_eq!(Some(event.snapshot_hash), contract.finalize_snapshot(declined));
+
+            // Cancelled and gone from every index
+            let escrow = contract.get_escrow(declined).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Declined));
+            assert_eq!(contract.get_escrows_by_client(accounts.alice), vec![kept]);
+            assert_eq!(contract.get_escrows_by_freelancer(accounts.bob), vec![kept]);
+            assert_eq!(contract.get_escrows_by_arbiter(accounts.charlie), vec![kept]);
+            check_invariants(&contract, declined);
+
+            assert_eq!(contract.decline_escrow(declined), Err(EscrowError::InvalidStatus));
+            assert_eq!(contract.accept_escrow(declined), Err(EscrowError::InvalidStatus));
+            set_caller(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(declined, None, None, None), Err(EscrowError::InvalidStatus));
+        }
+
+        #[ink::test]
+        fn test_update_milestones_before_funding() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            let mut milestones = dependent_milestones(&[&[], &[0], &[]]);
+            set_caller(accounts.bob);
+            assert_eq!(contract.update_milestones(escrow_id, milestones.clone()), Err(EscrowError::Unauthorized));
 
-            // Emit event
-            self.env().emit_event(EscrowCreated {
-                escrow_id,
-                client: caller,
-                freelancer: escrow.freelancer,
-                arbiter: escrow.arbiter,
-                total_amount,
-            });
+            // Same checks as at creation
+            set_caller(accounts.alice);
+            assert_eq!(contract.update_milestones(escrow_id, Vec::new()), Err(EscrowError::EmptyMilestones));
+            milestones[1].id = 2;
+            assert_eq!(contract.update_milestones(escrow_id, milestones.clone()), Err(EscrowError::InvalidAmount));
+            milestones[1].id = 1;
+            milestones[2].released = true;
+            assert_eq!(contract.update_milestones(escrow_id, milestones.clone()), Err(EscrowError::InvalidStatus));
+            milestones[2].released = false;
+            let zero: Vec<Milestone> = milestones.iter().cloned().map(|m| Milestone { amount: 0, ..m }).collect();
+            assert_eq!(contract.update_milestones(escrow_id, zero), Err(EscrowError::ZeroAmount));
+            assert_eq!(contract.update_milestones(99, milestones.clone()), Err(EscrowError::EscrowNotFound));
+
+            contract.update_milestones(escrow_id, milestones).unwrap();
+            let events = ink::env::test::recorded_events().count();
+            let updated: MilestonesUpdated = recorded_event(events - 2);
+            assert_eq!((updated.escrow_id, updated.client, updated.freelancer), (escrow_id, accounts.alice, accounts.bob));
+            assert_eq!(updated.new_total, 3000);
+            let snapshot: MilestonesSnapshot = recorded_event(events - 1);
+            assert_eq!(snapshot.milestones, vec![(0, 1000), (1, 1000), (2, 1000)]);
+
+            // The freelancer has to accept the new plan before it can be funded
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!((escrow.status, escrow.total_amount, escrow.milestones.len()), (EscrowStatus::PendingAcceptance, 3000, 3));
+            check_invariants(&contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidStatus));
+            accept_as_freelancer(&mut contract, escrow_id);
+            contract.fund_escrow(escrow_id, None, Some(3000), Some(3)).unwrap();
 
-            Ok(escrow_id)
+            // Never after funding
+            assert_eq!(contract.update_milestones(escrow_id, create_test_milestones()), Err(EscrowError::InvalidStatus));
+            check_invariants(&contract, escrow_id);
         }
 
-        /// Fund an escrow with the required amount
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow to fund
-        ///
-        /// # Errors
-        /// * `EscrowNotFound` - If escrow doesn't exist
-        /// * `Unauthorized` - If caller is not the client
-        /// * `InvalidStatus` - If escrow is not in Created status
-        /// * `InsufficientFunds` - If transferred amount is less than total_amount
-        ///
-        /// # Events
-        /// * `EscrowFunded` - Emitted when escrow is successfully funded
-        #[ink(message, payable)]
-        pub fn fund_escrow(&mut self, escrow_id: u64) -> Result<()> {
-            let caller = self.env().caller();
-            let transferred = self.env().transferred_value();
+        #[ink::test]
+        fn test_update_milestones_rejected_once_money_moved() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
 
-            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let mut contract = EscrowMultiRelease::new();
 
-            // Verify caller is the client
-            if escrow.client != caller {
-                return Err(EscrowError::Unauthorized);
-            }
+            // A partial deposit locks the plan
+            set_caller(accounts.alice);
+            let partial = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, partial);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
+            contract.fund_escrow(partial, None, None, None).unwrap();
+            assert_eq!(contract.update_milestones(partial, create_test_milestones()), Err(EscrowError::InvalidStatus));
 
-            // Verify escrow is in Created status
-            if escrow.status != EscrowStatus::Created {
-                return Err(EscrowError::InvalidStatus);
-            }
+            // Disputed, completed and cancelled escrows can't be edited either
+            let disputed = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, disputed);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(disputed, None, None, None).unwrap();
+            contract.raise_dispute(disputed, "Work not delivered".to_string(), None).unwrap();
+            let completed = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(completed, 0).unwrap();
+            contract.release_milestone(completed, 1).unwrap();
+            let cancelled = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            set_caller(accounts.bob);
+            contract.decline_escrow(cancelled).unwrap();
 
-            // Verify transferred amount matches total_amount
-            if transferred < escrow.total_amount {
-                return Err(EscrowError::InsufficientFunds);
+            set_caller(accounts.alice);
+            for escrow_id in [disputed, completed, cancelled] {
+                assert_eq!(contract.update_milestones(escrow_id, create_test_milestones()), Err(EscrowError::InvalidStatus));
             }
+        }
 
-            // Update escrow
-            escrow.deposited = escrow.total_amount;
-            escrow.status = EscrowStatus::Funded;
-            self.escrows.insert(escrow_id, &escrow);
+        #[ink::test]
+        fn test_add_milestone_to_funded_escrow() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
 
-            // Emit event
-            self.env().emit_event(EscrowFunded {
-                escrow_id,
-                amount: transferred,
-            });
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.release_milestone(escrow_id, 0).unwrap();
 
-            Ok(())
-        }
+            let add = |contract: &mut EscrowMultiRelease, value: Balance, amount: Balance| {
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+                contract.add_milestone(escrow_id, "Extra deliverable".to_string(), amount)
+            };
+            assert_eq!(add(&mut contract, 499, 500), Err(EscrowError::InsufficientFunds));
+            assert_eq!(add(&mut contract, 0, 0), Err(EscrowError::ZeroAmount));
+            set_caller(accounts.bob);
+            assert_eq!(add(&mut contract, 500, 500), Err(EscrowError::Unauthorized));
 
-        /// Release payment for a specific milestone
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow
-        /// * `milestone_id` - ID of the milestone to release
-        ///
-        /// # Errors
-        /// * `EscrowNotFound` - If escrow doesn't exist
-        /// * `Unauthorized` - If caller is not the client
-        /// * `InvalidStatus` - If escrow is not in Funded or Disputed status
-        /// * `MilestoneNotFound` - If milestone doesn't exist
-        /// * `MilestoneAlreadyReleased` - If milestone was already released
-        ///
-        /// # Events
-        /// * `MilestoneReleased` - Emitted when milestone is successfully released
-        #[ink(message)]
-        pub fn release_milestone(&mut self, escrow_id: u64, milestone_id: u32) -> Result<()> {
-            let caller = self.env().caller();
+            // Overpayment goes back to the client
+            set_caller(accounts.alice);
+            assert_eq!(add(&mut contract, 700, 500), Ok(2));
+            let events = ink::env::test::recorded_events().count();
+            let refunded: OverpaymentRefunded = recorded_event(events - 3);
+            assert_eq!((refunded.escrow_id, refunded.amount), (escrow_id, 200));
+            let added: MilestoneAdded = recorded_event(events - 2);
+            assert_eq!((added.escrow_id, added.client, added.freelancer), (escrow_id, accounts.alice, accounts.bob));
+            assert_eq!((added.milestone_id, added.amount), (2, 500));
+            let snapshot: MilestonesSnapshot = recorded_event(events - 1);
+            assert_eq!(snapshot.milestones, vec![(0, 1000), (1, 2000), (2, 500)]);
 
-            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!((escrow.total_amount, escrow.deposited), (3500, 3500));
+            assert_eq!(escrow.milestones[2].description, "Extra deliverable");
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2500);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3500));
+            check_invariants(&contract, escrow_id);
 
-            // Verify caller is the client
-            if escrow.client != caller {
-                return Err(EscrowError::Unauthorized);
-            }
+            // The escrow completes once the new milestone is released too
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+            contract.release_milestone(escrow_id, 2).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            check_invariants(&contract, escrow_id);
+            assert_eq!(add(&mut contract, 500, 500), Err(EscrowError::InvalidStatus));
+        }
 
-            // Verify escrow is in valid status
-            if escrow.status != EscrowStatus::Funded && escrow.status != EscrowStatus::Disputed {
-                return Err(EscrowError::InvalidStatus);
-            }
+        #[ink::test]
+        fn test_add_milestone_needs_a_funded_escrow() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
 
-            // Find milestone and get amount
-            let milestone_index = escrow
-                .milestones
-                .iter()
-                .position(|m| m.id == milestone_id)
-                .ok_or(EscrowError::MilestoneNotFound)?;
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let unfunded = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let disputed = contract.create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, disputed);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(disputed, None, None, None).unwrap();
+            contract.raise_dispute(disputed, "Work not delivered".to_string(), None).unwrap();
+            let cancelling = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(cancelling, None).unwrap();
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            for escrow_id in [unfunded, disputed, cancelling] {
+                assert_eq!(
+                    contract.add_milestone(escrow_id, String::new(), 500),
+                    Err(EscrowError::InvalidStatus)
+                );
+            }
+            assert_eq!(contract.add_milestone(99, String::new(), 500), Err(EscrowError::EscrowNotFound));
+        }
 
-            let milestone = &escrow.milestones[milestone_index];
+        #[ink::test]
+        fn test_token_escrow_takes_no_native_value() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
 
-            if milestone.released {
-                return Err(EscrowError::MilestoneAlreadyReleased);
-            }
+            let mut contract = EscrowMultiRelease::new();
+            let options = EscrowOptions {
+                token: Some(accounts.django),
+                ..EscrowOptions::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().token, Some(accounts.django));
+            let created: EscrowCreated = recorded_event(0);
+            assert_eq!(created.token, Some(accounts.django));
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // Tokens are pulled on funding, so no deposit needs value
+            let prediction = contract.simulate_fund(escrow_id, accounts.alice, 0);
+            assert!(prediction.ok);
+
+            let prediction = contract.simulate_fund(escrow_id, accounts.alice, 3000);
+            assert_eq!(prediction.error, Some(EscrowError::InvalidAmount));
+            assert_eq!(prediction.failed_check, Some(ValidationCheck::Amount));
 
-            let milestone_amount = milestone.amount;
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            assert_eq!(contract.fund_escrow(escrow_id, None, None, None), Err(EscrowError::InvalidAmount));
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.deposited, 0);
+            assert_eq!(escrow.status, EscrowStatus::Created);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 0);
+        }
 
-            // Mark milestone as released
-            escrow.milestones[milestone_index].released = true;
+        #[ink::test]
+        fn test_protocol_fee_on_releases() {
+            let accounts = default_accounts();
+            let contract_account = accounts.django;
+            let baseline: Balance = 1_000_000;
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(contract_account, baseline);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_account);
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
 
-            // Transfer funds to freelancer
-            if self.env().transfer(escrow.freelancer, milestone_amount).is_err() {
-                return Err(EscrowError::InsufficientFunds);
-            }
+            set_caller(accounts.alice);
+            assert!(matches!(EscrowMultiRelease::new_with_fee(MAX_FEE_BPS + 1), Err(EscrowError::FeeTooHigh)));
+            let mut contract = EscrowMultiRelease::new_with_fee(250).unwrap();
+            assert_eq!(contract.get_fee(), 250);
+
+            // An odd amount: 2.5% of 1001 rounds down to 25
+            let mut milestones = create_test_milestones();
+            milestones[0].amount = 1001;
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().fee_bps, 250);
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3001);
+            set_balance(contract_account, balance_of(contract_account) + 3001);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            let bob_before = balance_of(accounts.bob);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(balance_of(accounts.bob) - bob_before, 976);
+            let events = ink::env::test::recorded_events().count();
+            let released: MilestoneReleased = recorded_event(events - 1);
+            assert_eq!(released.amount, 1001);
+            assert_eq!(released.protocol_fee, 25);
+            assert_eq!(released.net_to_freelancer, 976);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2000);
+            assert_eq!(contract.get_totals(accounts.bob), (976, 0));
+
+            let report = contract.reconcile();
+            assert_eq!(report.collected_fees, 25);
+            assert_eq!(report.sum_fees_charged, 25);
+            assert_eq!(report.contract_balance_expected, balance_of(contract_account) - baseline);
+
+            // Refunds are not charged
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            let alice_before = balance_of(accounts.alice);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(balance_of(accounts.alice) - alice_before, 2000);
+            assert_eq!(contract.reconcile().collected_fees, 25);
+            check_invariants(&contract, escrow_id);
+
+            // The fee applies to escrows created afterwards
+            assert_eq!(contract.set_fee(100), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_fee(MAX_FEE_BPS + 1), Err(EscrowError::FeeTooHigh));
+            contract.set_fee(100).unwrap();
+            assert_eq!(contract.get_config().settings.fee_bps, 100);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().fee_bps, 250);
+            let later = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            assert_eq!(contract.get_escrow(later).unwrap().fee_bps, 100);
+
+            // Only the owner sweeps the fees, once
+            set_caller(accounts.bob);
+            assert_eq!(contract.withdraw_fees(accounts.bob), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            let charlie_before = balance_of(accounts.charlie);
+            contract.withdraw_fees(accounts.charlie).unwrap();
+            assert_eq!(balance_of(accounts.charlie) - charlie_before, 25);
+            let events = ink::env::test::recorded_events().count();
+            let withdrawn: FeesWithdrawn = recorded_event(events - 1);
+            assert_eq!(withdrawn.to, accounts.charlie);
+            assert_eq!(withdrawn.token, None);
+            assert_eq!(withdrawn.amount, 25);
+            assert_eq!(contract.withdraw_fees(accounts.charlie), Err(EscrowError::ZeroAmount));
+
+            let report = contract.reconcile();
+            assert_eq!(report.collected_fees, 0);
+            assert_eq!(report.sum_fees_charged, 25);
+            assert_eq!(report.contract_balance_expected, balance_of(contract_account) - baseline);
+            assert_eq!(report.sum_total_spent, report.sum_total_earned + report.sum_fees_charged);
+        }
 
-            // Check if all milestones are released
-            let all_released = escrow.milestones.iter().all(|m| m.released);
-            if all_released {
-                escrow.status = EscrowStatus::Completed;
-            }
+        #[ink::test]
+        fn test_fee_share_funds_insurance_pool() {
+            let accounts = default_accounts();
+            let contract_account = accounts.django;
+            let baseline: Balance = 1_000_000;
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(contract_account, baseline);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_account);
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
 
-            // Update escrow
-            self.escrows.insert(escrow_id, &escrow);
+            set_caller(accounts.alice);
+            let mut contract = EscrowMultiRelease::new_with_fee(1000).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_insurance_share(2000), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_insurance_share(BPS_DENOMINATOR + 1), Err(EscrowError::InvalidBps));
+            contract.set_insurance_share(2000).unwrap();
+            assert_eq!(contract.get_config().settings.insurance_bps, 2000);
 
-            // Emit event
-            self.env().emit_event(MilestoneReleased {
-                escrow_id,
-                milestone_id,
-                amount: milestone_amount,
-            });
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            set_balance(contract_account, balance_of(contract_account) + 3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
 
-            Ok(())
+            // 20% of the 100 fee goes to the pool
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_insurance_pool(), 20);
+            let report = contract.reconcile();
+            assert_eq!(report.collected_fees, 80);
+            assert_eq!(report.sum_fees_charged, 100);
+            assert_eq!(report.contract_balance_expected, balance_of(contract_account) - baseline);
+            check_invariants(&contract, escrow_id);
         }
 
-        /// Request cancellation of an escrow
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow to cancel
-        ///
-        /// # Errors
-        /// * `EscrowNotFound` - If escrow doesn't exist
-        /// * `Unauthorized` - If caller is not client or freelancer
-        /// * `InvalidStatus` - If escrow is in invalid status for cancellation
-        ///
-        /// # Events
-        /// * `CancelRequested` - Emitted when cancellation is requested
-        /// * `EscrowCancelled` - Emitted if both parties requested (mutual cancellation)
-        #[ink(message)]
-        pub fn request_cancel(&mut self, escrow_id: u64) -> Result<()> {
-            let caller = self.env().caller();
+        #[ink::test]
+        fn test_breakdowns_with_protocol_and_arbiter_fees() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.django, 1_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.django);
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
+            let last_event = || ink::env::test::recorded_events().count() - 1;
 
-            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            // Odd amounts, a 3.33% protocol fee and a 501 arbiter fee
+            set_caller(accounts.alice);
+            let mut contract = EscrowMultiRelease::new_with_fee(333).unwrap();
+            let mut milestones = create_test_milestones();
+            milestones[0].amount = 1001;
+            milestones[1].amount = 2003;
+            let options = EscrowOptions {
+                arbiter_fee: 501,
+                ..EscrowOptions::default()
+            };
+            let create = |contract: &mut EscrowMultiRelease| {
+                set_caller(accounts.alice);
+                let escrow_id = contract
+                    .create_escrow_with_options(accounts.bob, milestones.clone(), Some(accounts.charlie), options.clone())
+                    .unwrap();
+                accept_as_freelancer(contract, escrow_id);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3505);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+                escrow_id
+            };
+            let disputed = create(&mut contract);
+            let cancelled = create(&mut contract);
+
+            // Releases: gross = protocol fee + net, and the freelancer receives the net
+            let bob_before = balance_of(accounts.bob);
+            contract.release_milestone(disputed, 0).unwrap();
+            let released: MilestoneReleased = recorded_event(last_event());
+            assert_eq!((released.amount, released.protocol_fee, released.net_to_freelancer), (1001, 33, 968));
+            assert_eq!(released.amount, released.protocol_fee + released.net_to_freelancer);
+            assert_eq!(balance_of(accounts.bob) - bob_before, released.net_to_freelancer);
+
+            contract.release_partial(disputed, 1, 777).unwrap();
+            let partial: MilestonePartiallyReleased = recorded_event(last_event());
+            assert_eq!((partial.amount, partial.protocol_fee, partial.net_to_freelancer), (777, 25, 752));
+            assert_eq!(partial.amount, partial.protocol_fee + partial.net_to_freelancer);
+
+            // Dispute shares are paid in full; the arbiter fee comes on top of them
+            contract.raise_dispute(disputed, "Work not delivered".to_string(), None).unwrap();
+            let held = contract.get_ledger_balance(disputed);
+            assert_eq!(held, 2003 - 777 + 501);
+            set_caller(accounts.charlie);
+            let (bob_before, charlie_before) = (balance_of(accounts.bob), balance_of(accounts.charlie));
+            contract.resolve_dispute_by_arbiter(disputed, 613, 613).unwrap();
+            let resolved: DisputeResolved = recorded_event(last_event());
+            assert_eq!(resolved.freelancer_share + resolved.client_refund + resolved.arbiter_fee, held);
+            assert_eq!(balance_of(accounts.bob) - bob_before, resolved.freelancer_share);
+            assert_eq!(balance_of(accounts.charlie) - charlie_before, 501);
+
+            // Cancellation refunds the unreleased milestones and the arbiter fee in full
+            set_caller(accounts.alice);
+            contract.release_milestone(cancelled, 0).unwrap();
+            contract.request_cancel(cancelled, None).unwrap();
+            set_caller(accounts.bob);
+            let alice_before = balance_of(accounts.alice);
+            contract.approve_cancel(cancelled, None).unwrap();
+            let closed: EscrowCancelled = recorded_event(last_event());
+            assert_eq!((closed.refund_to_client, closed.refund_to_freelancer), (2003 + 501, 1001));
+            assert_eq!(closed.refund_to_client + closed.refund_to_freelancer, 3505);
+            assert_eq!(balance_of(accounts.alice) - alice_before, closed.refund_to_client);
+
+            let report = contract.reconcile();
+            assert_eq!(report.sum_fees_charged, 33 + 25 + 33);
+            assert_eq!(report.sum_total_spent, report.sum_total_earned + report.sum_fees_charged);
+            check_invariants(&contract, disputed);
+            check_invariants(&contract, cancelled);
+        }
 
-            // Verify caller is client or freelancer
-            if escrow.client != caller && escrow.freelancer != caller {
-                return Err(EscrowError::Unauthorized);
-            }
+        /// Contract with an arbitrated escrow from alice to bob with a 500 arbiter fee,
+        /// with django as the contract account so transfers move real balances
+        fn arbiter_fee_setup() -> (EscrowMultiRelease, u64) {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.django, 1_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.django);
 
-            // Verify escrow is in valid status
-            if escrow.status == EscrowStatus::Completed || escrow.status == EscrowStatus::Cancelled {
-                return Err(EscrowError::InvalidStatus);
-            }
+            set_caller(accounts.alice);
+            let mut contract = EscrowMultiRelease::new();
+            let options = EscrowOptions {
+                arbiter_fee: 500,
+                ..EscrowOptions::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), Some(accounts.charlie), options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            (contract, escrow_id)
+        }
 
-            // Check if other party already requested
-            if let Some(previous_request) = escrow.cancel_requested_by {
-                if previous_request != caller {
-                    // Both parties requested - mutual cancellation
-                    // Calculate released and unreleased amounts
-                    let released_amount: Balance = escrow
-                        .milestones
-                        .iter()
-                        .filter(|m| m.released)
-                        .map(|m| m.amount)
-                        .sum();
-
-                    let unreleased_amount = escrow.deposited.saturating_sub(released_amount);
-
-                    // Refund unreleased amount to client
-                    if unreleased_amount > 0 {
-                        if self.env().transfer(escrow.client, unreleased_amount).is_err() {
-                            return Err(EscrowError::InsufficientFunds);
-                        }
-                    }
+        #[ink::test]
+        fn test_arbiter_fee_paid_on_dispute_resolution() {
+            let accounts = default_accounts();
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
 
-                    escrow.status = EscrowStatus::Cancelled;
+            // The fee is deposited on top of the milestones
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.get_funding_progress(escrow_id), Some((3000, 3500)));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Created);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 3500);
 
-                    // Emit events
-                    self.env().emit_event(EscrowCancelled {
-                        escrow_id,
-                        refund_to_client: unreleased_amount,
-                        refund_to_freelancer: released_amount,
-                    });
-                }
-            } else {
-                // First cancellation request
-                escrow.cancel_requested_by = Some(caller);
-                
-                // Set to Disputed status (requires arbiter or mutual agreement)
-                escrow.status = EscrowStatus::Disputed;
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
 
-                self.env().emit_event(CancelRequested {
-                    escrow_id,
-                    requested_by: caller,
-                });
-            }
+            // The shares cover the funds held besides the fee
+            set_caller(accounts.charlie);
+            assert_eq!(contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2500), Err(EscrowError::InvalidAmount));
+            let charlie_before = balance_of(accounts.charlie);
+            let bob_before = balance_of(accounts.bob);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000).unwrap();
+            assert_eq!(balance_of(accounts.charlie) - charlie_before, 500);
+            assert_eq!(balance_of(accounts.bob) - bob_before, 1000);
+
+            let events = ink::env::test::recorded_events().count();
+            let resolved: DisputeResolved = recorded_event(events - 1);
+            assert_eq!(resolved.arbiter_fee, 500);
+            assert_eq!(resolved.freelancer_share, 1000);
+            assert_eq!(resolved.client_refund, 2000);
+            assert_eq!(contract.get_totals(accounts.charlie), (500, 0));
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
+        }
 
-            // Update escrow
-            self.escrows.insert(escrow_id, &escrow);
+        #[ink::test]
+        fn test_arbiter_fee_refunded_without_dispute() {
+            let accounts = default_accounts();
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
 
-            Ok(())
+            // Only an escrow with an arbiter takes an arbiter fee
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            let options = EscrowOptions {
+                arbiter_fee: 500,
+                ..EscrowOptions::default()
+            };
+            assert_eq!(
+                contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options),
+                Err(EscrowError::InvalidArbiter)
+            );
+
+            // Completing the escrow sends the fee back with the last release
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(contract.get_ledger_balance(escrow_id), 2500);
+            let alice_before = balance_of(accounts.alice);
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(balance_of(accounts.alice) - alice_before, 500);
+
+            let events = ink::env::test::recorded_events().count();
+            let completed: EscrowCompleted = recorded_event(events - 1);
+            assert_eq!(completed.arbiter_fee_refunded, 500);
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Completed);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            assert_eq!(contract.get_totals(accounts.alice), (0, 3000));
+            assert_eq!(contract.get_totals(accounts.charlie), (0, 0));
+            check_invariants(&contract, escrow_id);
+
+            // A mutual cancellation refunds it with the unreleased milestones
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            set_caller(accounts.bob);
+            let alice_before = balance_of(accounts.alice);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(balance_of(accounts.alice) - alice_before, 3500);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
+
+            // So does reclaiming the last open milestone, which also takes it off the deposit
+            let (mut contract, _) = arbiter_fee_setup();
+            let mut milestones = create_test_milestones();
+            milestones[0].deadline = Some(1_000);
+            milestones[1].deadline = Some(2_000);
+            let options = EscrowOptions {
+                arbiter_fee: 500,
+                ..EscrowOptions::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, milestones, Some(accounts.charlie), options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+            set_timestamp(2_001);
+            let alice_before = balance_of(accounts.alice);
+            contract.reclaim_expired_milestone(escrow_id, 1).unwrap();
+            assert_eq!(balance_of(accounts.alice) - alice_before, 2500);
+
+            let events = ink::env::test::recorded_events().count();
+            let cancelled: EscrowCancelled = recorded_event(events - 1);
+            assert_eq!(cancelled.refund_to_client, 2500);
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(escrow.deposited, 3000);
+            assert_eq!(contract.get_ledger_balance(escrow_id), 0);
+            check_invariants(&contract, escrow_id);
         }
 
-        /// Approve cancellation (mutual agreement)
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow to approve cancellation
-        ///
-        /// # Errors
-        /// * `EscrowNotFound` - If escrow doesn't exist
-        /// * `Unauthorized` - If caller is not the other party
-        /// * `InvalidStatus` - If no cancellation was requested
-        ///
-        /// # Events
-        /// * `EscrowCancelled` - Emitted when cancellation is approved
-        #[ink(message)]
-        pub fn approve_cancel(&mut self, escrow_id: u64) -> Result<()> {
-            let caller = self.env().caller();
+        #[ink::test]
+        fn test_arbiter_replacement_needs_both_parties() {
+            let accounts = default_accounts();
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.raise_dispute(escrow_id, "Arbiter unreachable".to_string(), None).unwrap();
+
+            // Neither party nor the current arbiter can take over
+            for arbiter in [accounts.alice, accounts.bob, accounts.charlie] {
+                assert_eq!(contract.propose_arbiter_change(escrow_id, arbiter), Err(EscrowError::InvalidArbiter));
+            }
+            set_caller(accounts.charlie);
+            assert_eq!(contract.propose_arbiter_change(escrow_id, accounts.eve), Err(EscrowError::Unauthorized));
 
-            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            // A newer proposal from the client replaces its first one
+            set_caller(accounts.alice);
+            contract.propose_arbiter_change(escrow_id, accounts.frank).unwrap();
+            contract.propose_arbiter_change(escrow_id, accounts.eve).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.arbiter, Some(accounts.charlie));
+            assert_eq!(escrow.arbiter_proposal, Some((accounts.alice, accounts.eve)));
+            let last = ink::env::test::recorded_events().count() - 1;
+            let proposed: ArbiterChangeProposed = recorded_event(last);
+            assert_eq!(proposed.proposed_by, accounts.alice);
+            assert_eq!(proposed.new_arbiter, accounts.eve);
 
-            // Verify cancellation was requested
-            let requested_by = escrow.cancel_requested_by.ok_or(EscrowError::InvalidStatus)?;
+            // The proposer cannot approve its own proposal
+            assert_eq!(contract.approve_arbiter_change(escrow_id), Err(EscrowError::Unauthorized));
 
-            // Verify caller is the other party
-            if requested_by == escrow.client {
-                if caller != escrow.freelancer {
-                    return Err(EscrowError::Unauthorized);
-                }
-            } else if requested_by == escrow.freelancer {
-                if caller != escrow.client {
-                    return Err(EscrowError::Unauthorized);
-                }
-            } else {
-                return Err(EscrowError::Unauthorized);
-            }
+            set_caller(accounts.bob);
+            contract.approve_arbiter_change(escrow_id).unwrap();
+            let escrow = contract.get_escrow(escrow_id).unwrap();
+            assert_eq!(escrow.arbiter, Some(accounts.eve));
+            assert_eq!(escrow.arbiter_proposal, None);
+            let last = ink::env::test::recorded_events().count() - 1;
+            let changed: ArbiterChanged = recorded_event(last);
+            assert_eq!((changed.old, changed.new), (accounts.charlie, accounts.eve));
+            assert!(contract.get_escrows_by_arbiter(accounts.charlie).is_empty());
+            assert_eq!(contract.get_escrows_by_arbiter(accounts.eve), vec![escrow_id]);
+            assert_eq!(contract.approve_arbiter_change(escrow_id), Err(EscrowError::InvalidStatus));
+
+            // Only the new arbiter settles the dispute, and it earns the fee
+            set_caller(accounts.charlie);
+            assert_eq!(contract.resolve_dispute_by_arbiter(escrow_id, 1500, 1500), Err(EscrowError::Unauthorized));
+            set_caller(accounts.eve);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1500, 1500).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Resolved);
+        }
 
-            // Calculate released and unreleased amounts
-            let released_amount: Balance = escrow
-                .milestones
-                .iter()
-                .filter(|m| m.released)
-                .map(|m| m.amount)
-                .sum();
+        #[ink::test]
+        fn test_submit_evidence_while_disputed() {
+            let accounts = default_accounts();
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
 
-            let unreleased_amount = escrow.deposited.saturating_sub(released_amount);
+            // Nothing to point the arbiter to before a dispute
+            let hash = Hash::from([7; 32]);
+            assert_eq!(
+                contract.submit_evidence(escrow_id, hash, "ipfs://early".to_string()),
+                Err(EscrowError::InvalidStatus)
+            );
 
-            // Refund unreleased amount to client
-            if unreleased_amount > 0 {
-                if self.env().transfer(escrow.client, unreleased_amount).is_err() {
-                    return Err(EscrowError::InsufficientFunds);
-                }
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+
+            // Only the parties, only valid URIs
+            set_caller(accounts.charlie);
+            assert_eq!(contract.submit_evidence(escrow_id, hash, "ipfs://e".to_string()), Err(EscrowError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(contract.submit_evidence(escrow_id, hash, String::new()), Err(EscrowError::InvalidNoteUri));
+            assert_eq!(
+                contract.submit_evidence(escrow_id, hash, "a".repeat(MAX_NOTE_URI_LEN + 1)),
+                Err(EscrowError::InvalidNoteUri)
+            );
+
+            set_timestamp(42);
+            contract.submit_evidence(escrow_id, hash, "ipfs://client-0".to_string()).unwrap();
+            let submitted: EvidenceSubmitted = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!(submitted.submitter, accounts.alice);
+            assert_eq!(submitted.hash, hash);
+            assert_eq!(submitted.uri, "ipfs://client-0");
+
+            // The cap applies per party
+            for i in 1..MAX_EVIDENCE_PER_PARTY {
+                contract.submit_evidence(escrow_id, hash, format!("ipfs://client-{}", i)).unwrap();
             }
+            assert_eq!(
+                contract.submit_evidence(escrow_id, hash, "ipfs://one-too-many".to_string()),
+                Err(EscrowError::TooManyEvidenceEntries)
+            );
+            set_caller(accounts.bob);
+            contract.submit_evidence(escrow_id, Hash::from([8; 32]), "ipfs://freelancer".to_string()).unwrap();
 
-            escrow.status = EscrowStatus::Cancelled;
-            self.escrows.insert(escrow_id, &escrow);
+            let evidence = contract.get_evidence(escrow_id);
+            assert_eq!(evidence.len(), MAX_EVIDENCE_PER_PARTY + 1);
+            assert_eq!(
+                evidence[0],
+                Evidence { submitter: accounts.alice, hash, uri: "ipfs://client-0".to_string(), submitted_at: 42 }
+            );
+            assert_eq!(evidence[MAX_EVIDENCE_PER_PARTY].submitter, accounts.bob);
 
-            // Emit event
-            self.env().emit_event(EscrowCancelled {
-                escrow_id,
-                refund_to_client: unreleased_amount,
-                refund_to_freelancer: released_amount,
-            });
+            // The evidence outlives the dispute, new entries don't
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1500, 1500).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.submit_evidence(escrow_id, hash, "ipfs://late".to_string()),
+                Err(EscrowError::InvalidStatus)
+            );
+            assert_eq!(contract.get_evidence(escrow_id).len(), MAX_EVIDENCE_PER_PARTY + 1);
+        }
 
-            Ok(())
+        #[ink::test]
+        fn test_payout_address_receives_freelancer_payouts() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.eve, 0);
+            set_balance(accounts.frank, 10_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let balance = |account| ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap();
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+
+            // Only the freelancer, and never to the client
+            assert_eq!(contract.set_payout_address(escrow_id, accounts.eve), Err(EscrowError::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.set_payout_address(escrow_id, accounts.alice),
+                Err(EscrowError::InvalidPayoutAddress)
+            );
+            contract.set_payout_address(escrow_id, accounts.eve).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().payout_address, Some(accounts.eve));
+            let changed: PayoutAddressChanged = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((changed.escrow_id, changed.new_address), (escrow_id, accounts.eve));
+
+            // The payment goes to the override, the earnings to the freelancer
+            let bob_before = balance(accounts.bob);
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 0).unwrap();
+            assert_eq!(balance(accounts.eve), 1000);
+            assert_eq!(balance(accounts.bob), bob_before);
+            assert_eq!(contract.get_totals(accounts.bob).0, 1000);
+            assert_eq!(contract.get_totals(accounts.eve).0, 0);
+
+            // Pointing it back at the freelancer clears the override
+            set_caller(accounts.bob);
+            contract.set_payout_address(escrow_id, accounts.bob).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().payout_address, None);
+            set_caller(accounts.alice);
+            contract.release_milestone(escrow_id, 1).unwrap();
+            assert_eq!(balance(accounts.bob), bob_before + 2000);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_payout_address(escrow_id, accounts.eve), Err(EscrowError::InvalidStatus));
         }
 
-        /// Resolve a dispute by the designated arbiter
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow in dispute
-        /// * `freelancer_share` - Amount to give to freelancer
-        /// * `client_refund` - Amount to refund to client
-        ///
-        /// # Errors
-        /// * `EscrowNotFound` - If escrow doesn't exist
-        /// * `Unauthorized` - If caller is not the arbiter
-        /// * `InvalidStatus` - If escrow is not in Disputed status
-        /// * `InvalidAmount` - If freelancer_share + client_refund != deposited
-        ///
-        /// # Events
-        /// * `DisputeResolved` - Emitted when dispute is resolved
-        #[ink(message)]
-        pub fn resolve_dispute_by_arbiter(
-            &mut self,
-            escrow_id: u64,
-            freelancer_share: Balance,
-            client_refund: Balance,
-        ) -> Result<()> {
-            let caller = self.env().caller();
+        #[ink::test]
+        fn test_payout_address_receives_dispute_share() {
+            let accounts = default_accounts();
+            let (mut contract, escrow_id) = arbiter_fee_setup();
+            set_balance(accounts.eve, 0);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3500);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
 
-            let mut escrow = self.escrows.get(escrow_id).ok_or(EscrowError::EscrowNotFound)?;
+            set_caller(accounts.bob);
+            contract.set_payout_address(escrow_id, accounts.eve).unwrap();
+            contract.raise_dispute(escrow_id, "Unpaid work".to_string(), None).unwrap();
 
-            // Verify caller is the arbiter
-            let arbiter = escrow.arbiter.ok_or(EscrowError::InvalidArbiter)?;
-            if arbiter != caller {
-                return Err(EscrowError::Unauthorized);
-            }
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(escrow_id, 1200, 1800).unwrap();
+            assert_eq!(ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve).unwrap(), 1200);
+        }
 
-            // Verify escrow is in Disputed status
-            if escrow.status != EscrowStatus::Disputed {
-                return Err(EscrowError::InvalidStatus);
-            }
+        #[ink::test]
+        fn test_paginated_escrow_listings() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
 
-            // Verify amounts sum to deposited amount
-            if freelancer_share.saturating_add(client_refund) != escrow.deposited {
-                return Err(EscrowError::InvalidAmount);
+            let mut contract = EscrowMultiRelease::new();
+            let created: Vec<u64> = (0..61)
+                .map(|_| contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap())
+                .collect();
+
+            let pages = [
+                contract.get_escrows_by_client_paginated(accounts.alice, 0, 50),
+                contract.get_escrows_by_freelancer_paginated(accounts.bob, 0, 50),
+            ];
+            for (page, total) in pages {
+                assert_eq!(total, 61);
+                assert_eq!(page, created[..50].to_vec());
             }
 
-            // Transfer funds
-            if freelancer_share > 0 {
-                if self.env().transfer(escrow.freelancer, freelancer_share).is_err() {
-                    return Err(EscrowError::InsufficientFunds);
-                }
-            }
+            // Page boundaries follow the creation order
+            let (page, total) = contract.get_escrows_by_client_paginated(accounts.alice, 50, 50);
+            assert_eq!((page, total), (created[50..].to_vec(), 61));
+            let (page, _) = contract.get_escrows_by_freelancer_paginated(accounts.bob, 10, 5);
+            assert_eq!(page, created[10..15].to_vec());
+
+            // Oversized limits are capped
+            let (page, _) = contract.get_escrows_by_client_paginated(accounts.alice, 0, 1_000);
+            assert_eq!(page.len(), MAX_PAGE_SIZE as usize);
+
+            // Past the end, or for unknown accounts, pages are empty
+            assert_eq!(contract.get_escrows_by_client_paginated(accounts.alice, 61, 10), (Vec::new(), 61));
+            assert_eq!(contract.get_escrows_by_freelancer_paginated(accounts.bob, u32::MAX, 10), (Vec::new(), 61));
+            assert_eq!(contract.get_escrows_by_client_paginated(accounts.bob, 0, 10), (Vec::new(), 0));
+            assert_eq!(contract.get_escrows_by_client_paginated(accounts.alice, 0, 0), (Vec::new(), 61));
+        }
 
-            if client_refund > 0 {
-                if self.env().transfer(escrow.client, client_refund).is_err() {
-                    return Err(EscrowError::InsufficientFunds);
-                }
+        #[ink::test]
+        fn test_disputed_escrows_by_arbiter() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let mut arbitrated = Vec::new();
+            for _ in 0..3 {
+                let escrow_id = contract
+                    .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                    .unwrap();
+                accept_as_freelancer(&mut contract, escrow_id);
+                arbitrated.push(escrow_id);
+            }
+            let unarbitrated = create_funded_escrow(&mut contract, 0);
+
+            // The same arbiter serves every arbitrated escrow, nobody serves the other
+            assert_eq!(contract.get_escrows_by_arbiter(accounts.charlie), arbitrated);
+            assert!(contract.get_disputed_escrows_by_arbiter(accounts.charlie).is_empty());
+            assert_eq!(contract.get_roles(accounts.charlie, unarbitrated), Vec::<Role>::new());
+            for account in [accounts.alice, accounts.bob, accounts.django] {
+                assert!(contract.get_escrows_by_arbiter(account).is_empty());
             }
 
-            escrow.status = EscrowStatus::Cancelled;
-            self.escrows.insert(escrow_id, &escrow);
+            // Only the disputed ones show up on the dashboard
+            for &escrow_id in &arbitrated[1..] {
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            }
+            set_caller(accounts.bob);
+            contract.raise_dispute(arbitrated[2], "Work not accepted".to_string(), None).unwrap();
+            assert_eq!(contract.get_disputed_escrows_by_arbiter(accounts.charlie), vec![arbitrated[2]]);
+            assert!(contract.get_disputed_escrows_by_arbiter(accounts.django).is_empty());
+
+            // Without an arbiter a dispute can't be raised in the first place
+            assert_eq!(
+                contract.raise_dispute(unarbitrated, "Work not accepted".to_string(), None),
+                Err(EscrowError::InvalidArbiter)
+            );
+        }
 
-            // Emit event
-            self.env().emit_event(DisputeResolved {
-                escrow_id,
-                freelancer_share,
-                client_refund,
-            });
+        #[ink::test]
+        fn test_escrows_by_status() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
 
-            Ok(())
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let created = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, created);
+            let funded = create_funded_escrow(&mut contract, 0);
+            let completed = create_funded_escrow(&mut contract, 0);
+            contract.release_milestones(completed, vec![0, 1]).unwrap();
+            let cancelled = create_funded_escrow(&mut contract, 0);
+            contract.request_cancel(cancelled, None).unwrap();
+            set_caller(accounts.bob);
+            contract.approve_cancel(cancelled, None).unwrap();
+            set_caller(accounts.alice);
+            let funded_later = create_funded_escrow(&mut contract, 0);
+
+            let by_client = |status| contract.get_escrows_by_client_and_status(accounts.alice, status, 0, 50);
+            assert_eq!(by_client(EscrowStatus::Created), vec![created]);
+            assert_eq!(by_client(EscrowStatus::Funded), vec![funded, funded_later]);
+            assert_eq!(by_client(EscrowStatus::Completed), vec![completed]);
+            assert_eq!(by_client(EscrowStatus::Cancelled), vec![cancelled]);
+            assert!(by_client(EscrowStatus::Disputed).is_empty());
+            assert_eq!(
+                contract.get_escrows_by_freelancer_and_status(accounts.bob, EscrowStatus::Funded, 0, 50),
+                vec![funded, funded_later]
+            );
+
+            // The window bounds the escrows scanned, not the matches returned
+            let funded_in = |offset, limit| {
+                contract.get_escrows_by_client_and_status(accounts.alice, EscrowStatus::Funded, offset, limit)
+            };
+            assert_eq!(funded_in(0, 2), vec![funded]);
+            assert!(funded_in(2, 2).is_empty());
+            assert_eq!(funded_in(4, 2), vec![funded_later]);
+            assert!(funded_in(5, 50).is_empty());
+            assert!(funded_in(0, 0).is_empty());
+            assert!(contract
+                .get_escrows_by_freelancer_and_status(accounts.alice, EscrowStatus::Funded, 0, 50)
+                .is_empty());
         }
 
-        /// Get escrow details by ID
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow to query
-        ///
-        /// # Returns
-        /// * `Option<Escrow>` - Escrow if it exists, None otherwise
-        #[ink(message)]
-        pub fn get_escrow(&self, escrow_id: u64) -> Option<Escrow> {
-            self.escrows.get(escrow_id)
+        /// Accept an escrow as its freelancer, then act as its client again
+        fn accept_as_freelancer(contract: &mut EscrowMultiRelease, escrow_id: u64) {
+            let escrow = contract.escrows.get(escrow_id).expect("escrow missing");
+            set_caller(escrow.freelancer);
+            contract.accept_escrow(escrow_id).unwrap();
+            set_caller(escrow.client);
         }
 
-        /// Get all escrow IDs for a client
-        ///
-        /// # Arguments
-        /// * `client` - Account ID of the client
-        ///
-        /// # Returns
-        /// * `Vec<u64>` - Vector of escrow IDs
-        #[ink(message)]
-        pub fn get_escrows_by_client(&self, client: AccountId) -> Vec<u64> {
-            self.client_escrows.get(client).unwrap_or_default()
+        /// Create an escrow from alice to bob with strict confirmation, funded at timestamp 0
+        fn create_strict_escrow(contract: &mut EscrowMultiRelease) -> u64 {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let options = EscrowOptions {
+                strict_confirmation: true,
+                ..Default::default()
+            };
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), None, options)
+                .unwrap();
+            accept_as_freelancer(contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            escrow_id
         }
 
-        /// Get all escrow IDs for a freelancer
-        ///
-        /// # Arguments
-        /// * `freelancer` - Account ID of the freelancer
-        ///
-        /// # Returns
-        /// * `Vec<u64>` - Vector of escrow IDs
-        #[ink(message)]
-        pub fn get_escrows_by_freelancer(&self, freelancer: AccountId) -> Vec<u64> {
-            self.freelancer_escrows.get(freelancer).unwrap_or_default()
-        }
+        #[ink::test]
+        fn test_confirmation_hash_is_per_escrow_and_action() {
+            let contract = EscrowMultiRelease::new();
 
-        /// Get all milestones for an escrow
-        ///
-        /// # Arguments
-        /// * `escrow_id` - ID of the escrow
-        ///
-        /// # Returns
-        /// * `Vec<Milestone>` - Vector of milestones
-        #[ink(message)]
-        pub fn get_milestones(&self, escrow_id: u64) -> Vec<Milestone> {
-            self.escrows
-                .get(escrow_id)
-                .map(|escrow| escrow.milestones)
-                .unwrap_or_default()
+            let request = contract.get_confirmation_hash(0, ConfirmableAction::RequestCancel);
+            assert_eq!(request, contract.get_confirmation_hash(0, ConfirmableAction::RequestCancel));
+            assert_ne!(request, contract.get_confirmation_hash(0, ConfirmableAction::ApproveCancel));
+            assert_ne!(request, contract.get_confirmation_hash(0, ConfirmableAction::RaiseDispute));
+            assert_ne!(request, contract.get_confirmation_hash(1, ConfirmableAction::RequestCancel));
         }
-    }
 
-    // ========================================
-    // UNIT TESTS
-    // ========================================
+        #[ink::test]
+        fn test_strict_request_cancel_requires_confirmation() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_strict_escrow(&mut contract);
+            assert!(contract.get_escrow_summary(escrow_id).unwrap().strict_confirmation);
 
-        /// Helper function to get default accounts
-        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
-            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+            set_caller(accounts.bob);
+            let wrong = contract.get_confirmation_hash(escrow_id, ConfirmableAction::ApproveCancel);
+            assert_eq!(contract.request_cancel(escrow_id, None), Err(EscrowError::ConfirmationMismatch));
+            assert_eq!(contract.request_cancel(escrow_id, Some(wrong)), Err(EscrowError::ConfirmationMismatch));
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            let confirmation = contract.get_confirmation_hash(escrow_id, ConfirmableAction::RequestCancel);
+            contract.request_cancel(escrow_id, Some(confirmation)).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().cancel_requested_by, Some(accounts.bob));
         }
 
-        /// Helper function to set caller
-        fn set_caller(account: AccountId) {
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        #[ink::test]
+        fn test_strict_approve_cancel_requires_confirmation() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_strict_escrow(&mut contract);
+
+            set_caller(accounts.bob);
+            let confirmation = contract.get_confirmation_hash(escrow_id, ConfirmableAction::RequestCancel);
+            contract.request_cancel(escrow_id, Some(confirmation)).unwrap();
+
+            // A hash for another escrow or another action does not confirm
+            set_caller(accounts.alice);
+            let other_escrow = contract.get_confirmation_hash(escrow_id + 1, ConfirmableAction::ApproveCancel);
+            assert_eq!(contract.approve_cancel(escrow_id, None), Err(EscrowError::ConfirmationMismatch));
+            assert_eq!(contract.approve_cancel(escrow_id, Some(confirmation)), Err(EscrowError::ConfirmationMismatch));
+            assert_eq!(contract.approve_cancel(escrow_id, Some(other_escrow)), Err(EscrowError::ConfirmationMismatch));
+
+            // Mutual cancellation through a second request is guarded as well
+            assert_eq!(contract.request_cancel(escrow_id, None), Err(EscrowError::ConfirmationMismatch));
+
+            let confirmation = contract.get_confirmation_hash(escrow_id, ConfirmableAction::ApproveCancel);
+            contract.approve_cancel(escrow_id, Some(confirmation)).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
         }
 
-        /// Helper function to set balance
-        /// In ink! 5.0, minimum balance is 1,000,000 (except for 0 which reaps the account)
-        fn set_balance(account: AccountId, balance: Balance) {
-            let min_balance: Balance = 1_000_000;
-            // If balance > 0, ensure it's at least the minimum
-            let actual_balance = if balance > 0 && balance < min_balance {
-                min_balance
-            } else {
-                balance
+        #[ink::test]
+        fn test_strict_raise_dispute_requires_confirmation() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            let options = EscrowOptions {
+                strict_confirmation: true,
+                ..Default::default()
             };
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(account, actual_balance);
+            let escrow_id = contract
+                .create_escrow_with_options(accounts.bob, create_test_milestones(), Some(accounts.charlie), options)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+
+            // A cancellation hash does not confirm a dispute
+            set_caller(accounts.bob);
+            let wrong = contract.get_confirmation_hash(escrow_id, ConfirmableAction::RequestCancel);
+            let reason = || "Work not paid".to_string();
+            assert_eq!(contract.raise_dispute(escrow_id, reason(), None), Err(EscrowError::ConfirmationMismatch));
+            assert_eq!(
+                contract.raise_dispute(escrow_id, reason(), Some(wrong)),
+                Err(EscrowError::ConfirmationMismatch)
+            );
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Funded);
+
+            let confirmation = contract.get_confirmation_hash(escrow_id, ConfirmableAction::RaiseDispute);
+            contract.raise_dispute(escrow_id, reason(), Some(confirmation)).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Disputed);
         }
 
-        /// Helper function to create test milestones
-        fn create_test_milestones() -> Vec<Milestone> {
-            vec![
-                Milestone {
-                    id: 0,
-                    amount: 1000,
-                    released: false,
-                    description: "Milestone 1".to_string(),
-                },
-                Milestone {
-                    id: 1,
-                    amount: 2000,
-                    released: false,
-                    description: "Milestone 2".to_string(),
-                },
-            ]
+        #[ink::test]
+        fn test_non_strict_escrow_ignores_confirmation() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
+
+            let mut contract = EscrowMultiRelease::new();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            assert!(!contract.get_escrow_summary(escrow_id).unwrap().strict_confirmation);
+
+            set_caller(accounts.bob);
+            contract.request_cancel(escrow_id, Some(Hash::from([0xAB; 32]))).unwrap();
+            set_caller(accounts.alice);
+            contract.approve_cancel(escrow_id, None).unwrap();
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
+
+            // Disputes too, with or without a hash
+            let arbitrated = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                .unwrap();
+            accept_as_freelancer(&mut contract, arbitrated);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(arbitrated, None, None, None).unwrap();
+            set_caller(accounts.bob);
+            let reason = "Work not paid".to_string();
+            contract.raise_dispute(arbitrated, reason, Some(Hash::from([0xAB; 32]))).unwrap();
+            assert_eq!(contract.get_escrow(arbitrated).unwrap().status, EscrowStatus::Disputed);
         }
 
         #[ink::test]
-        fn new_works() {
-            let contract = EscrowMultiRelease::new();
-            assert_eq!(contract.get_escrows_by_client(default_accounts().alice), Vec::<u64>::new());
+        fn test_roles_are_scoped_per_escrow() {
+            let accounts = default_accounts();
+            let mut contract = EscrowMultiRelease::new();
+
+            // Charlie is client of the first, freelancer of the second, arbiter of the third
+            set_caller(accounts.charlie);
+            let as_client = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, as_client);
+            set_caller(accounts.alice);
+            let as_freelancer = contract.create_escrow(accounts.charlie, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, as_freelancer);
+            let as_arbiter = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                .unwrap();
+            accept_as_freelancer(&mut contract, as_arbiter);
+
+            assert_eq!(contract.get_roles(accounts.charlie, as_client), vec![Role::Client]);
+            assert_eq!(contract.get_roles(accounts.charlie, as_freelancer), vec![Role::Freelancer]);
+            assert_eq!(contract.get_roles(accounts.charlie, as_arbiter), vec![Role::Arbiter]);
+            assert_eq!(contract.get_roles(accounts.django, as_arbiter), Vec::<Role>::new());
+            assert_eq!(contract.get_roles(accounts.charlie, 99), Vec::<Role>::new());
+            assert_eq!(contract.get_escrows_by_client(accounts.charlie), vec![as_client]);
+            assert_eq!(contract.get_escrows_by_freelancer(accounts.charlie), vec![as_freelancer]);
+            assert_eq!(contract.get_escrows_by_arbiter(accounts.charlie), vec![as_arbiter]);
+
+            // Arbiter duty only
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(as_arbiter, None, None, None).unwrap();
+            set_caller(accounts.bob);
+            contract.request_cancel(as_arbiter, None).unwrap();
+            assert!(contract.get_actionable_escrows(accounts.charlie, Role::Arbiter).is_empty());
+            contract.raise_dispute(as_arbiter, "Work not delivered".to_string(), None).unwrap();
+            assert_eq!(contract.get_actionable_escrows(accounts.charlie, Role::Arbiter), vec![as_arbiter]);
+            assert!(contract.get_actionable_escrows(accounts.charlie, Role::Client).is_empty());
+            assert!(contract.get_actionable_escrows(accounts.charlie, Role::Freelancer).is_empty());
+            assert_eq!(contract.get_actionable_escrows(accounts.alice, Role::Client), vec![as_arbiter]);
+
+            // Client and freelancer duties land in their own lists
+            contract.request_cancel(as_client, None).unwrap();
+            set_caller(accounts.alice);
+            contract.request_cancel(as_freelancer, None).unwrap();
+            assert_eq!(contract.get_actionable_escrows(accounts.charlie, Role::Client), vec![as_client]);
+            assert_eq!(contract.get_actionable_escrows(accounts.charlie, Role::Freelancer), vec![as_freelancer]);
+            assert_eq!(contract.get_actionable_escrows(accounts.charlie, Role::Arbiter), vec![as_arbiter]);
+            assert_eq!(contract.has_pending_actions(accounts.charlie), PendingActions::Yes);
+
+            // The requester has nothing to do
+            assert!(contract.get_actionable_escrows(accounts.bob, Role::Freelancer).is_empty());
+
+            // An arbiter can't use party-only messages
+            set_caller(accounts.charlie);
+            assert_eq!(contract.request_cancel(as_arbiter, None), Err(EscrowError::Unauthorized));
         }
 
         #[ink::test]
-        fn test_create_escrow_success() {
+        fn test_actionable_escrows_list_submissions_to_review() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-
-            let result = contract.create_escrow(accounts.bob, milestones.clone(), None);
+            let reviewed = create_funded_escrow(&mut contract, 0);
+            create_funded_escrow(&mut contract, 0);
+            assert!(contract.get_actionable_escrows(accounts.alice, Role::Client).is_empty());
 
-            assert!(result.is_ok());
-            let escrow_id = result.unwrap();
+            // Only the escrow with a submission waits on the client, not on the freelancer
+            set_caller(accounts.bob);
+            contract.submit_milestone(reviewed, 1, Hash::from([0x02; 32])).unwrap();
+            assert_eq!(contract.get_actionable_escrows(accounts.alice, Role::Client), vec![reviewed]);
+            assert!(contract.get_actionable_escrows(accounts.bob, Role::Freelancer).is_empty());
 
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.client, accounts.alice);
-            assert_eq!(escrow.freelancer, accounts.bob);
-            assert_eq!(escrow.total_amount, 3000);
-            assert_eq!(escrow.status, EscrowStatus::Created);
-            assert_eq!(escrow.milestones.len(), 2);
+            // Releasing the submitted milestone leaves nothing to review
+            set_caller(accounts.alice);
+            contract.release_milestone(reviewed, 1).unwrap();
+            assert!(contract.get_actionable_escrows(accounts.alice, Role::Client).is_empty());
         }
 
         #[ink::test]
-        fn test_create_escrow_empty_milestones_fails() {
+        fn test_roles_on_one_escrow() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
 
             let mut contract = EscrowMultiRelease::new();
+            let escrow_id = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.alice), None)
+                .unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
 
-            let result = contract.create_escrow(accounts.bob, vec![], None);
+            assert_eq!(contract.get_roles(accounts.alice, escrow_id), vec![Role::Client, Role::Arbiter]);
+            assert_eq!(contract.get_roles(accounts.bob, escrow_id), vec![Role::Freelancer]);
 
-            assert_eq!(result, Err(EscrowError::EmptyMilestones));
+            // Alice's own request leaves nothing for her as client, but the dispute is hers to arbitrate
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.request_cancel(escrow_id, None).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
+            assert!(contract.get_actionable_escrows(accounts.alice, Role::Client).is_empty());
+            assert_eq!(contract.get_actionable_escrows(accounts.alice, Role::Arbiter), vec![escrow_id]);
+            assert_eq!(contract.get_actionable_escrows(accounts.bob, Role::Freelancer), vec![escrow_id]);
         }
 
         #[ink::test]
-        fn test_fund_escrow_success() {
+        fn test_max_escrow_amount_boundary() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
-            set_balance(accounts.alice, 10_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+            assert_eq!(contract.get_max_escrow_amount(), 0);
 
-            // Fund escrow
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            let result = contract.fund_escrow(escrow_id);
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_max_escrow_amount(3000), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            contract.set_max_escrow_amount(3000).unwrap();
+            let event = recorded_event::<MaxEscrowUpdated>(0);
+            assert_eq!(event.max_escrow_amount, 3000);
+
+            // Exactly at the cap is fine, one unit above is not
+            assert!(contract.create_escrow(accounts.bob, create_test_milestones(), None, None).is_ok());
+            let mut over = create_test_milestones();
+            over[1].amount += 1;
+            assert_eq!(contract.create_escrow(accounts.bob, over.clone(), None, None), Err(EscrowError::ExceedsMaxEscrow));
+
+            // 0 lifts the cap
+            contract.set_max_escrow_amount(0).unwrap();
+            assert!(contract.create_escrow(accounts.bob, over, None, None).is_ok());
+        }
 
-            assert!(result.is_ok());
+        #[ink::test]
+        fn test_exempt_clients_bypass_max_escrow() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
 
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.deposited, 3000);
-            assert_eq!(escrow.status, EscrowStatus::Funded);
+            let mut contract = EscrowMultiRelease::new();
+            contract.set_max_escrow_amount(1000).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.add_exempt_client(accounts.charlie), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            contract.add_exempt_client(accounts.charlie).unwrap();
+            assert!(contract.is_exempt_client(accounts.charlie));
+
+            set_caller(accounts.charlie);
+            assert!(contract.create_escrow(accounts.bob, create_test_milestones(), None, None).is_ok());
+
+            set_caller(accounts.alice);
+            contract.remove_exempt_client(accounts.charlie).unwrap();
+            assert!(!contract.is_exempt_client(accounts.charlie));
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.create_escrow(accounts.bob, create_test_milestones(), None, None),
+                Err(EscrowError::ExceedsMaxEscrow)
+            );
         }
 
         #[ink::test]
-        fn test_fund_escrow_unauthorized_fails() {
+        fn test_lowered_max_escrow_is_grandfathered() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
+            set_balance(accounts.bob, 1_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+            let released = create_funded_escrow(&mut contract, 0);
+            let cancelled = create_funded_escrow(&mut contract, 0);
+
+            set_caller(accounts.alice);
+            contract.set_max_escrow_amount(1000).unwrap();
+
+            // Existing escrows above the new cap still release and cancel
+            contract.release_milestone(released, 0).unwrap();
+            contract.release_milestone(released, 1).unwrap();
+            assert_eq!(contract.get_escrow(released).unwrap().status, EscrowStatus::Completed);
 
-            // Try to fund as Bob (should fail)
             set_caller(accounts.bob);
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            let result = contract.fund_escrow(escrow_id);
+            contract.request_cancel(cancelled, None).unwrap();
+            set_caller(accounts.alice);
+            contract.approve_cancel(cancelled, None).unwrap();
+            assert_eq!(contract.get_escrow(cancelled).unwrap().status, EscrowStatus::Cancelled);
+
+            // New ones are gated
+            assert_eq!(
+                contract.create_escrow(accounts.bob, create_test_milestones(), None, None),
+                Err(EscrowError::ExceedsMaxEscrow)
+            );
+        }
 
-            assert_eq!(result, Err(EscrowError::Unauthorized));
+        /// Assert a simulation predicted the outcome of the real call
+        fn assert_predicts(simulation: &SimulationResult, actual: Result<()>) {
+            assert_eq!(simulation.ok, actual.is_ok());
+            assert_eq!(simulation.error, actual.err());
         }
 
         #[ink::test]
-        fn test_fund_escrow_insufficient_funds_fails() {
+        fn test_simulate_fund_matches_message() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
-
-            // Try to fund with insufficient amount
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
-            let result = contract.fund_escrow(escrow_id);
+            let escrow_id = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
+
+            // (caller, escrow, transferred, failed check)
+            let cases = [
+                (accounts.alice, 99, 3000, Some(ValidationCheck::EscrowExists)),
+                (accounts.bob, escrow_id, 3000, Some(ValidationCheck::Caller)),
+                (accounts.alice, escrow_id, 0, Some(ValidationCheck::Amount)),
+                (accounts.alice, escrow_id, 3000, None),
+                (accounts.alice, escrow_id, 3000, Some(ValidationCheck::Status)),
+            ];
+            for (caller, id, transferred, failed_check) in cases {
+                let simulation = contract.simulate_fund(id, caller, transferred);
+                assert_eq!(simulation.failed_check, failed_check);
+                set_caller(caller);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(transferred);
+                assert_predicts(&simulation, contract.fund_escrow(id, None, None, None));
+            }
 
-            assert_eq!(result, Err(EscrowError::InsufficientFunds));
+            // Amount and status details
+            let short = contract.simulate_fund(escrow_id, accounts.alice, 10);
+            assert_eq!(short.actual_status, Some(EscrowStatus::Funded));
+            assert_eq!(short.expected_status, vec![EscrowStatus::Created]);
+            let fresh = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            accept_as_freelancer(&mut contract, fresh);
+            let short = contract.simulate_fund(fresh, accounts.alice, 0);
+            assert_eq!(short.error, Some(EscrowError::ZeroAmount));
+            assert_eq!((short.required_amount, short.provided_amount), (Some(3000), Some(0)));
+            assert!(short.expected_status.is_empty());
         }
 
         #[ink::test]
-        fn test_release_milestone_success() {
+        fn test_simulate_release_matches_message() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
             set_balance(accounts.bob, 1_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+            set_caller(accounts.alice);
+            let unfunded = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            contract.set_auto_release_threshold(escrow_id, 1000).unwrap();
+
+            // (caller, escrow, milestone, failed check)
+            let cases = [
+                (accounts.alice, 99, 0, Some(ValidationCheck::EscrowExists)),
+                (accounts.charlie, escrow_id, 0, Some(ValidationCheck::Caller)),
+                (accounts.alice, unfunded, 0, Some(ValidationCheck::Status)),
+                (accounts.alice, escrow_id, 7, Some(ValidationCheck::MilestoneExists)),
+                (accounts.bob, escrow_id, 1, Some(ValidationCheck::Caller)),
+                (accounts.bob, escrow_id, 0, None),
+                (accounts.alice, escrow_id, 0, Some(ValidationCheck::MilestoneUnreleased)),
+                (accounts.alice, escrow_id, 1, None),
+                (accounts.alice, escrow_id, 1, Some(ValidationCheck::Status)),
+            ];
+            for (caller, id, milestone_id, failed_check) in cases {
+                let simulation = contract.simulate_release(id, milestone_id, caller);
+                assert_eq!(simulation.failed_check, failed_check);
+                set_caller(caller);
+                assert_predicts(&simulation, contract.release_milestone(id, milestone_id));
+            }
 
-            // Fund escrow
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
+            let done = contract.simulate_release(escrow_id, 1, accounts.alice);
+            assert_eq!(done.actual_status, Some(EscrowStatus::Completed));
+            assert_eq!(done.expected_status, vec![EscrowStatus::Funded, EscrowStatus::Disputed]);
+        }
 
-            // Release first milestone
-            let result = contract.release_milestone(escrow_id, 0);
+        #[ink::test]
+        fn test_simulate_cancel_matches_message() {
+            let accounts = default_accounts();
+            set_balance(accounts.alice, 10_000_000);
 
-            assert!(result.is_ok());
+            let mut contract = EscrowMultiRelease::new();
+            set_caller(accounts.alice);
+            contract.set_dispute_grace(1_000).unwrap();
+            let escrow_id = create_strict_escrow(&mut contract);
+            let confirmation = Some(contract.get_confirmation_hash(escrow_id, ConfirmableAction::RequestCancel));
+
+            // (caller, escrow, confirmation, timestamp, failed check)
+            let cases = [
+                (accounts.alice, 99, confirmation, 0, Some(ValidationCheck::EscrowExists)),
+                (accounts.charlie, escrow_id, confirmation, 0, Some(ValidationCheck::Caller)),
+                (accounts.alice, escrow_id, None, 0, Some(ValidationCheck::Confirmation)),
+                (accounts.alice, escrow_id, confirmation, 0, Some(ValidationCheck::DisputeGrace)),
+                (accounts.alice, escrow_id, confirmation, 1_000, None),
+                (accounts.bob, escrow_id, confirmation, 1_000, None),
+                (accounts.bob, escrow_id, confirmation, 1_000, Some(ValidationCheck::Status)),
+            ];
+            for (caller, id, confirmation, timestamp, failed_check) in cases {
+                set_timestamp(timestamp);
+                let simulation = contract.simulate_cancel(id, caller, confirmation);
+                assert_eq!(simulation.failed_check, failed_check);
+                set_caller(caller);
+                assert_predicts(&simulation, contract.request_cancel(id, confirmation));
+            }
+            assert_eq!(contract.get_escrow(escrow_id).unwrap().status, EscrowStatus::Cancelled);
+        }
 
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert!(escrow.milestones[0].released);
-            assert!(!escrow.milestones[1].released);
-            assert_eq!(escrow.status, EscrowStatus::Funded); // Not all released yet
+        #[test]
+        fn test_bps_share_rounds_down() {
+            assert_eq!(bps_share(2001, 0), 0);
+            assert_eq!(bps_share(2001, 5000), 1000);
+            assert_eq!(bps_share(2001, 10_000), 2001);
+            assert_eq!(bps_share(3, 3333), 0);
+            assert_eq!(bps_share(10_001, 1), 1);
+            assert_eq!(bps_share(Balance::MAX, 10_000), Balance::MAX);
+            assert_eq!(bps_share(Balance::MAX, 5000), Balance::MAX / 2);
+            // Out-of-range values are clamped rather than paying out more than the amount
+            assert_eq!(bps_share(100, u16::MAX), 100);
         }
 
-        #[ink::test]
-        fn test_release_all_milestones_completes_escrow() {
+        /// Helper function to run an escrow with a 1000 + 2001 split to abandonment
+        /// after the first release, returning the emitted `EscrowCancelled`
+        fn abandon_with_bps(bps: u16) -> EscrowCancelled {
             let accounts = default_accounts();
             set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
             set_balance(accounts.bob, 1_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
-
-            // Fund escrow
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
+            let mut milestones = create_test_milestones();
+            milestones[1].amount = 2001;
+            let options = EscrowOptions {
+                abandonment_timeout_ms: 1_000,
+                abandonment_freelancer_bps: bps,
+                ..Default::default()
+            };
+            let escrow_id = contract.create_escrow_with_options(accounts.bob, milestones, None, options).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
 
-            // Release all milestones
+            set_timestamp(0);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3001);
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            set_timestamp(500);
             contract.release_milestone(escrow_id, 0).unwrap();
-            contract.release_milestone(escrow_id, 1).unwrap();
+
+            // The release restarts the clock
+            set_caller(accounts.bob);
+            set_timestamp(1_499);
+            assert_eq!(contract.resolve_abandoned(escrow_id), Err(EscrowError::TimeoutNotReached));
+
+            set_timestamp(1_500);
+            let freelancer_before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            contract.resolve_abandoned(escrow_id).unwrap();
+            let freelancer_after = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
 
             let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.status, EscrowStatus::Completed);
-            assert!(escrow.milestones.iter().all(|m| m.released));
+            assert_eq!(escrow.status, EscrowStatus::Resolved);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::Timeout));
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 0);
+
+            // Events: [0] EscrowCreated, [1] MilestonesSnapshot, [2] EscrowAccepted, [3] EscrowFunded, [4] MilestoneReleased, [5] EscrowCancelled
+            let cancelled: EscrowCancelled = recorded_event(5);
+            assert_eq!(freelancer_after - freelancer_before, cancelled.freelancer_compensation);
+            assert_eq!(cancelled.refund_to_freelancer, 1000);
+            assert_eq!(cancelled.refund_to_client + cancelled.freelancer_compensation, 2001);
+            cancelled
         }
 
         #[ink::test]
-        fn test_release_milestone_unauthorized_fails() {
+        fn test_resolve_abandoned_zero_bps_refunds_client() {
+            let cancelled = abandon_with_bps(0);
+            assert_eq!(cancelled.freelancer_compensation, 0);
+            assert_eq!(cancelled.refund_to_client, 2001);
+        }
+
+        #[ink::test]
+        fn test_resolve_abandoned_half_split_rounds_for_client() {
+            let cancelled = abandon_with_bps(5000);
+            assert_eq!(cancelled.freelancer_compensation, 1000);
+            assert_eq!(cancelled.refund_to_client, 1001);
+        }
+
+        #[ink::test]
+        fn test_resolve_abandoned_full_bps_pays_freelancer() {
+            let cancelled = abandon_with_bps(10_000);
+            assert_eq!(cancelled.freelancer_compensation, 2001);
+            assert_eq!(cancelled.refund_to_client, 0);
+        }
+
+        #[ink::test]
+        fn test_resolve_abandoned_requires_timeout() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
-
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
 
-            // Try to release as Bob (should fail)
-            set_caller(accounts.bob);
-            let result = contract.release_milestone(escrow_id, 0);
+            // No timeout configured
+            let escrow_id = create_funded_escrow(&mut contract, 0);
+            set_timestamp(u64::MAX);
+            assert_eq!(contract.resolve_abandoned(escrow_id), Err(EscrowError::InvalidStatus));
 
-            assert_eq!(result, Err(EscrowError::Unauthorized));
+            // Split above 100%
+            let options = EscrowOptions {
+                abandonment_timeout_ms: 1_000,
+                abandonment_freelancer_bps: BPS_DENOMINATOR + 1,
+                ..Default::default()
+            };
+            assert_eq!(
+                contract.create_escrow_with_options(accounts.bob, create_test_milestones(), None, options),
+                Err(EscrowError::InvalidBps)
+            );
         }
 
         #[ink::test]
-        fn test_request_cancel_mutual_agreement() {
+        fn test_resolve_dispute_after_partial_release() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
             set_balance(accounts.alice, 10_000_000);
             set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
 
             let mut contract = EscrowMultiRelease::new();
             let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie), None).unwrap();
+            accept_as_freelancer(&mut contract, escrow_id);
 
             ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
-
-            // Alice requests cancel
-            contract.request_cancel(escrow_id).unwrap();
-
-            // Bob requests cancel (mutual agreement)
-            set_caller(accounts.bob);
-            contract.request_cancel(escrow_id).unwrap();
+            contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            contract.release_milestone(escrow_id, 0).unwrap();
+            contract.raise_dispute(escrow_id, "Work not delivered".to_string(), None).unwrap();
 
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            // The released milestone cannot be paid out a second time
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000),
+                Err(EscrowError::InvalidAmount)
+            );
+            assert!(contract.resolve_dispute_by_arbiter(escrow_id, 500, 1500).is_ok());
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 0);
         }
 
         #[ink::test]
-        fn test_approve_cancel_success() {
+        fn test_reconcile_matches_contract_balance() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
+            let contract_account = accounts.django;
+            let baseline: Balance = 1_000_000;
             set_balance(accounts.alice, 10_000_000);
             set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(contract_account, baseline);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_account);
 
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
 
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
+            let contract_balance = || {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(contract_account)
+                    .unwrap()
+            };
+            // The off-chain env does not credit transferred value, so do it by hand
+            let fund = |contract: &mut EscrowMultiRelease, escrow_id: u64, amount: Balance| {
+                set_caller(accounts.alice);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(amount);
+                set_balance(contract_account, contract_balance() + amount);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+            };
+            let assert_reconciled = |contract: &EscrowMultiRelease| {
+                let report = contract.reconcile();
+                assert_eq!(report.contract_balance_expected, contract_balance() - baseline);
+                assert_eq!(
+                    report.contract_balance_expected,
+                    report.sum_deposits_outstanding
+                        + report.sum_pending_withdrawals
+                        + report.collected_fees
+                        + report.insurance_pool
+                );
+            };
 
-            // Alice requests cancel
-            contract.request_cancel(escrow_id).unwrap();
+            set_caller(accounts.alice);
+            let released = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let cancelled = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            let disputed = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                .unwrap();
+            let unfunded = contract.create_escrow(accounts.bob, create_test_milestones(), None, None).unwrap();
+            for escrow_id in [released, cancelled, disputed] {
+                accept_as_freelancer(&mut contract, escrow_id);
+            }
+            assert_eq!(contract.reconcile().contract_balance_expected, 0);
 
-            // Bob approves cancel
+            fund(&mut contract, released, 3000);
+            fund(&mut contract, cancelled, 3000);
+            fund(&mut contract, disputed, 3000);
+            assert_eq!(contract.reconcile().sum_deposits_outstanding, 9000);
+            assert_reconciled(&contract);
+
+            // Partial release
+            set_caller(accounts.alice);
+            contract.release_milestone(released, 0).unwrap();
+            assert_reconciled(&contract);
+
+            // Mutual cancellation after a partial release refunds the rest
+            contract.release_milestone(cancelled, 0).unwrap();
+            contract.request_cancel(cancelled, None).unwrap();
             set_caller(accounts.bob);
-            let result = contract.approve_cancel(escrow_id);
+            contract.approve_cancel(cancelled, None).unwrap();
+            assert_reconciled(&contract);
 
-            assert!(result.is_ok());
+            // Arbitrated dispute pays out the full deposit
+            set_caller(accounts.alice);
+            contract.raise_dispute(disputed, "Work not delivered".to_string(), None).unwrap();
+            set_caller(accounts.charlie);
+            contract.resolve_dispute_by_arbiter(disputed, 1000, 2000).unwrap();
+            assert_reconciled(&contract);
 
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            // Completing the first escrow leaves nothing outstanding
+            set_caller(accounts.alice);
+            contract.release_milestone(released, 1).unwrap();
+            assert_reconciled(&contract);
+
+            let report = contract.reconcile();
+            assert_eq!(report.sum_deposits_outstanding, 0);
+            assert_eq!(report.sum_pending_withdrawals, 0);
+            assert_eq!(report.collected_fees, 0);
+            assert_eq!(contract.get_escrow(unfunded).unwrap().deposited, 0);
         }
 
         #[ink::test]
-        fn test_resolve_dispute_by_arbiter() {
+        fn test_failed_payouts_need_contract_funds() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
+            // The contract account has no balance yet, so every transfer from it fails
+            let contract_account = AccountId::from([0x42; 32]);
             set_balance(accounts.alice, 10_000_000);
             set_balance(accounts.bob, 1_000_000);
             set_balance(accounts.charlie, 1_000_000);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_account);
 
+            set_caller(accounts.alice);
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie)).unwrap();
-
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
+            let mut escrow_ids = Vec::new();
+            for _ in 0..2 {
+                set_caller(accounts.alice);
+                let escrow_id = contract
+                    .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                    .unwrap();
+                accept_as_freelancer(&mut contract, escrow_id);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
+                contract.fund_escrow(escrow_id, None, None, None).unwrap();
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+                escrow_ids.push(escrow_id);
+            }
+            let (released, disputed) = (escrow_ids[0], escrow_ids[1]);
+            set_caller(accounts.bob);
+            contract.raise_dispute(disputed, "Work was delivered".to_string(), None).unwrap();
 
-            // Request cancel (creates dispute)
-            contract.request_cancel(escrow_id).unwrap();
+            // A release the freelancer does not get reverts instead of becoming a shortfall
+            set_caller(accounts.alice);
+            assert_eq!(contract.release_milestone(released, 0), Err(EscrowError::InsufficientFunds));
+            assert_eq!(contract.get_shortfall(released), 0);
+            assert_eq!(contract.get_insurance_pool(), 0);
 
-            // Arbiter resolves dispute
+            // Dispute payouts are only owed from the pool if the contract holds them
+            set_balance(contract_account, 0);
             set_caller(accounts.charlie);
-            let result = contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000);
-
-            assert!(result.is_ok());
-
-            let escrow = contract.get_escrow(escrow_id).unwrap();
-            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(
+                contract.resolve_dispute_by_arbiter(disputed, 1000, 2000),
+                Err(EscrowError::InsufficientFunds)
+            );
+            assert_eq!(contract.get_shortfall(disputed), 0);
+            assert_eq!(contract.get_insurance_pool(), 0);
         }
 
         #[ink::test]
-        fn test_resolve_dispute_unauthorized_fails() {
+        fn test_insurance_pool_top_up_and_compensate_checks() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
+            let contract_account = AccountId::from([0x42; 32]);
+            let baseline: Balance = 1_000_000;
             set_balance(accounts.alice, 10_000_000);
+            set_balance(contract_account, baseline);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract_account);
+            let balance_of = |account: AccountId| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account).unwrap()
+            };
 
+            set_caller(accounts.alice);
             let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, Some(accounts.charlie)).unwrap();
+            let escrow_id = contract
+                .create_escrow(accounts.bob, create_test_milestones(), Some(accounts.charlie), None)
+                .unwrap();
+
+            // Anyone can top up the pool
+            set_caller(accounts.django);
+            assert_eq!(contract.fund_insurance_pool(), Err(EscrowError::ZeroAmount));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            set_balance(contract_account, balance_of(contract_account) + 100);
+            contract.fund_insurance_pool().unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.get_insurance_pool(), 100);
+            let funded: InsurancePoolFunded = recorded_event(ink::env::test::recorded_events().count() - 1);
+            assert_eq!((funded.from, funded.amount), (accounts.django, 100));
+            assert_eq!(contract.reconcile().contract_balance_expected, balance_of(contract_account) - baseline);
+
+            // Only the owner compensates, only a party, and never beyond the shortfall
+            assert_eq!(contract.compensate(escrow_id, accounts.bob, 100), Err(EscrowError::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(contract.compensate(escrow_id, accounts.eve, 100), Err(EscrowError::Unauthorized));
+            assert_eq!(contract.compensate(escrow_id, accounts.bob, 0), Err(EscrowError::ZeroAmount));
+            assert_eq!(contract.compensate(escrow_id, accounts.bob, 100), Err(EscrowError::ExceedsShortfall));
+            assert_eq!(contract.get_insurance_pool(), 100);
+        }
+    }
 
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(3000);
-            contract.fund_escrow(escrow_id).unwrap();
+    // ========================================
+    // E2E TESTS
+    // ========================================
 
-            contract.request_cancel(escrow_id).unwrap();
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{ChainBackend, ContractsBackend};
+        use mock_psp22::mock_psp22::{MockPsp22, MockPsp22Ref};
+        use mock_psp22::PSP22 as _;
 
-            // Try to resolve as non-arbiter (should fail)
-            set_caller(accounts.bob);
-            let result = contract.resolve_dispute_by_arbiter(escrow_id, 1000, 2000);
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-            assert_eq!(result, Err(EscrowError::Unauthorized));
+        /// Milestone amounts, large enough to stay above the existential deposit
+        const FIRST_MILESTONE: Balance = 1_000_000_000;
+        const SECOND_MILESTONE: Balance = 2_000_000_000;
+        const TOTAL: Balance = FIRST_MILESTONE + SECOND_MILESTONE;
+
+        /// Helper function to create e2e milestones
+        fn e2e_milestones() -> Vec<Milestone> {
+            vec![
+                Milestone {
+                    id: 0,
+                    amount: FIRST_MILESTONE,
+                    released: false,
+                    description: "Milestone 1".to_string(),
+                    depends_on: Vec::new(),
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: FIRST_MILESTONE,
+                },
+                Milestone {
+                    id: 1,
+                    amount: SECOND_MILESTONE,
+                    released: false,
+                    description: "Milestone 2".to_string(),
+                    depends_on: Vec::new(),
+                    deadline: None,
+                    forfeited: false,
+                    proof_hash: None,
+                    rejections: 0,
+                    disputed: false,
+                    arbitrated: false,
+                    remaining: SECOND_MILESTONE,
+                },
+            ]
         }
 
-        #[ink::test]
-        fn test_get_escrows_by_client() {
-            let accounts = default_accounts();
-            set_caller(accounts.alice);
-            set_balance(accounts.alice, 10_000_000);
+        #[ink_e2e::test]
+        async fn e2e_create_fund_and_release<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy contract
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // Create escrow and accept it as the freelancer
+            let create = call_builder.create_escrow(bob, e2e_milestones(), None, None);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow failed")
+                .return_value()
+                .expect("create_escrow returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            // Fund escrow with real transferred value
+            let contract_before = client.free_balance(contract.account_id).await?;
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            let fund_result = client
+                .call(&ink_e2e::alice(), &fund)
+                .value(TOTAL)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+            let events = fund_result.contract_emitted_events()?;
+            assert_eq!(fund_result.return_value(), Ok(()));
+
+            let contract_after = client.free_balance(contract.account_id).await?;
+            assert_eq!(contract_after - contract_before, TOTAL);
+
+            let funded = <EscrowFunded as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!(funded.escrow_id, escrow_id);
+            assert_eq!(funded.client, alice);
+            assert_eq!(funded.freelancer, bob);
+            assert_eq!(funded.amount, TOTAL);
+
+            // Release first milestone and check the freelancer's balance delta
+            let bob_before = client.free_balance(bob).await?;
+            let release = call_builder.release_milestone(escrow_id, 0);
+            let release_result = client
+                .call(&ink_e2e::alice(), &release)
+                .submit()
+                .await
+                .expect("release_milestone failed");
+            let events = release_result.contract_emitted_events()?;
+            assert_eq!(release_result.return_value(), Ok(()));
+
+            let bob_after = client.free_balance(bob).await?;
+            assert_eq!(bob_after - bob_before, FIRST_MILESTONE);
+
+            let released = <MilestoneReleased as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!(released.client, alice);
+            assert_eq!(released.freelancer, bob);
+            assert_eq!(released.amount, FIRST_MILESTONE);
+
+            // Escrow still funded with one milestone outstanding
+            let get = call_builder.get_escrow(escrow_id);
+            let escrow = client
+                .call(&ink_e2e::alice(), &get)
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("escrow not found");
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert!(escrow.milestones[0].released);
+            assert!(!escrow.milestones[1].released);
 
-            let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
+            Ok(())
+        }
 
-            let escrow_id1 = contract.create_escrow(accounts.bob, milestones.clone(), None).unwrap();
-            let escrow_id2 = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+        #[ink_e2e::test]
+        async fn e2e_mutual_cancellation_refunds_client<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy contract
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // Create, accept, fund and release the first milestone
+            let create = call_builder.create_escrow(bob, e2e_milestones(), None, None);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow failed")
+                .return_value()
+                .expect("create_escrow returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            client
+                .call(&ink_e2e::alice(), &fund)
+                .value(TOTAL)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+
+            let release = call_builder.release_milestone(escrow_id, 0);
+            client
+                .call(&ink_e2e::alice(), &release)
+                .submit()
+                .await
+                .expect("release_milestone failed");
+
+            // Client requests cancellation
+            let request = call_builder.request_cancel(escrow_id, None);
+            client
+                .call(&ink_e2e::alice(), &request)
+                .submit()
+                .await
+                .expect("request_cancel failed");
+
+            // Freelancer approves; the client pays no fees for this call,
+            // so their balance delta is exactly the refund
+            let alice_before = client.free_balance(alice).await?;
+            let approve = call_builder.approve_cancel(escrow_id, None);
+            let approve_result = client
+                .call(&ink_e2e::bob(), &approve)
+                .submit()
+                .await
+                .expect("approve_cancel failed");
+            let events = approve_result.contract_emitted_events()?;
+            assert_eq!(approve_result.return_value(), Ok(()));
+
+            let alice_after = client.free_balance(alice).await?;
+            assert_eq!(alice_after - alice_before, SECOND_MILESTONE);
+
+            let cancelled = <EscrowCancelled as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!(cancelled.escrow_id, escrow_id);
+            assert_eq!(cancelled.client, alice);
+            assert_eq!(cancelled.freelancer, bob);
+            assert_eq!(cancelled.refund_to_client, SECOND_MILESTONE);
+            assert_eq!(cancelled.refund_to_freelancer, FIRST_MILESTONE);
+
+            let get = call_builder.get_escrow(escrow_id);
+            let escrow = client
+                .call(&ink_e2e::alice(), &get)
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("escrow not found");
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::MutualCancel));
 
-            let escrows = contract.get_escrows_by_client(accounts.alice);
-            assert_eq!(escrows.len(), 2);
-            assert!(escrows.contains(&escrow_id1));
-            assert!(escrows.contains(&escrow_id2));
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_get_escrows_by_freelancer() {
-            let accounts = default_accounts();
-            set_caller(accounts.alice);
-            set_balance(accounts.alice, 10_000_000);
+        #[ink_e2e::test]
+        async fn e2e_dispute_resolved_by_arbiter<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy contract
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+
+            // Create with Charlie as arbiter, accept and fund
+            let create = call_builder.create_escrow(bob, e2e_milestones(), Some(charlie), None);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow failed")
+                .return_value()
+                .expect("create_escrow returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            client
+                .call(&ink_e2e::alice(), &fund)
+                .value(TOTAL)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+
+            // Client opens the dispute
+            let dispute = call_builder.raise_dispute(escrow_id, "Work not delivered".to_string(), None);
+            client
+                .call(&ink_e2e::alice(), &dispute)
+                .submit()
+                .await
+                .expect("raise_dispute failed");
+
+            // A party cannot resolve its own dispute
+            let resolve = call_builder.resolve_dispute_by_arbiter(escrow_id, FIRST_MILESTONE, SECOND_MILESTONE);
+            let unauthorized = client
+                .call(&ink_e2e::bob(), &resolve)
+                .dry_run()
+                .await?;
+            assert_eq!(unauthorized.return_value(), Err(EscrowError::Unauthorized));
+
+            // Arbiter splits the deposit
+            let alice_before = client.free_balance(alice).await?;
+            let bob_before = client.free_balance(bob).await?;
+            let contract_before = client.free_balance(contract.account_id).await?;
+
+            let resolve_result = client
+                .call(&ink_e2e::charlie(), &resolve)
+                .submit()
+                .await
+                .expect("resolve_dispute_by_arbiter failed");
+            let events = resolve_result.contract_emitted_events()?;
+            assert_eq!(resolve_result.return_value(), Ok(()));
+
+            let alice_after = client.free_balance(alice).await?;
+            let bob_after = client.free_balance(bob).await?;
+            let contract_after = client.free_balance(contract.account_id).await?;
+            assert_eq!(bob_after - bob_before, FIRST_MILESTONE);
+            assert_eq!(alice_after - alice_before, SECOND_MILESTONE);
+            assert_eq!(contract_before - contract_after, TOTAL);
+
+            let resolved = <DisputeResolved as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!(resolved.escrow_id, escrow_id);
+            assert_eq!(resolved.client, alice);
+            assert_eq!(resolved.freelancer, bob);
+            assert_eq!(resolved.freelancer_share, FIRST_MILESTONE);
+            assert_eq!(resolved.client_refund, SECOND_MILESTONE);
+
+            let get = call_builder.get_escrow(escrow_id);
+            let escrow = client
+                .call(&ink_e2e::alice(), &get)
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("escrow not found");
+            assert_eq!(escrow.status, EscrowStatus::Resolved);
+            assert_eq!(escrow.termination_reason, Some(TerminationReason::ArbiterDecision));
 
-            let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
+            Ok(())
+        }
 
-            let escrow_id = contract.create_escrow(accounts.bob, milestones, None).unwrap();
+        #[ink_e2e::test]
+        async fn e2e_undelivered_dispute_share_is_compensated<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy contract
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            // Never funded, so a payout below the existential deposit cannot create it
+            let unreachable = AccountId::from([0x01; 32]);
+
+            // Create with Charlie as arbiter, accept and fund
+            let create = call_builder.create_escrow(bob, e2e_milestones(), Some(charlie), None);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow failed")
+                .return_value()
+                .expect("create_escrow returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            client
+                .call(&ink_e2e::alice(), &fund)
+                .value(TOTAL)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+
+            // Bob redirects his payouts, then opens a dispute
+            let redirect = call_builder.set_payout_address(escrow_id, unreachable);
+            client
+                .call(&ink_e2e::bob(), &redirect)
+                .submit()
+                .await
+                .expect("set_payout_address failed");
+
+            let dispute = call_builder.raise_dispute(escrow_id, "Work was delivered".to_string(), None);
+            client
+                .call(&ink_e2e::bob(), &dispute)
+                .submit()
+                .await
+                .expect("raise_dispute failed");
+
+            // The freelancer's share cannot be delivered: the dispute still resolves
+            let resolve = call_builder.resolve_dispute_by_arbiter(escrow_id, 1, TOTAL - 1);
+            let resolve_result = client
+                .call(&ink_e2e::charlie(), &resolve)
+                .submit()
+                .await
+                .expect("resolve_dispute_by_arbiter failed");
+            let events = resolve_result.contract_emitted_events()?;
+            assert_eq!(resolve_result.return_value(), Ok(()));
+
+            let shortfall = <ShortfallRecorded as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!((shortfall.escrow_id, shortfall.to, shortfall.amount), (escrow_id, unreachable, 1));
+
+            let get_shortfall = call_builder.get_shortfall(escrow_id);
+            let recorded = client.call(&ink_e2e::alice(), &get_shortfall).dry_run().await?;
+            assert_eq!(recorded.return_value(), 1);
+            let get_pool = call_builder.get_insurance_pool();
+            let pool = client.call(&ink_e2e::alice(), &get_pool).dry_run().await?;
+            assert_eq!(pool.return_value(), 1);
+
+            // The owner pays Bob from the pool
+            let compensate = call_builder.compensate(escrow_id, bob, 1);
+            let bob_before = client.free_balance(bob).await?;
+            client
+                .call(&ink_e2e::alice(), &compensate)
+                .submit()
+                .await
+                .expect("compensate failed")
+                .return_value()
+                .expect("compensate returned error");
+            let bob_after = client.free_balance(bob).await?;
+            assert_eq!(bob_after - bob_before, 1);
+
+            let recorded = client.call(&ink_e2e::alice(), &get_shortfall).dry_run().await?;
+            assert_eq!(recorded.return_value(), 0);
+            let pool = client.call(&ink_e2e::alice(), &get_pool).dry_run().await?;
+            assert_eq!(pool.return_value(), 0);
 
-            let escrows = contract.get_escrows_by_freelancer(accounts.bob);
-            assert_eq!(escrows.len(), 1);
-            assert_eq!(escrows[0], escrow_id);
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_get_milestones() {
-            let accounts = default_accounts();
-            set_caller(accounts.alice);
-            set_balance(accounts.alice, 10_000_000);
+        #[ink_e2e::test]
+        async fn e2e_token_escrow_fund_and_release<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy a token minting the whole supply to Alice, and the escrow
+            let mut token_constructor = MockPsp22Ref::new(TOTAL);
+            let token = client
+                .instantiate("mock_psp22", &ink_e2e::alice(), &mut token_constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<MockPsp22>();
+
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // Create a token-denominated escrow and accept it as the freelancer
+            let options = EscrowOptions {
+                token: Some(token.account_id),
+                ..EscrowOptions::default()
+            };
+            let create = call_builder.create_escrow_with_options(bob, e2e_milestones(), None, options);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow_with_options failed")
+                .return_value()
+                .expect("create_escrow_with_options returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            // Funding needs an allowance, and takes no native value
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            let unapproved = client.call(&ink_e2e::alice(), &fund).dry_run().await?;
+            assert_eq!(unapproved.return_value(), Err(EscrowError::TokenTransferFailed));
+
+            let with_value = client
+                .call(&ink_e2e::alice(), &fund)
+                .value(TOTAL)
+                .dry_run()
+                .await?;
+            assert_eq!(with_value.return_value(), Err(EscrowError::InvalidAmount));
+
+            let approve = token_call.approve(contract.account_id, TOTAL);
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve failed");
+
+            let contract_native_before = client.free_balance(contract.account_id).await?;
+            let fund_result = client
+                .call(&ink_e2e::alice(), &fund)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+            assert_eq!(fund_result.return_value(), Ok(()));
+
+            let contract_native_after = client.free_balance(contract.account_id).await?;
+            assert_eq!(contract_native_after, contract_native_before);
+
+            let held = token_call.balance_of(contract.account_id);
+            let held = client.call(&ink_e2e::alice(), &held).dry_run().await?.return_value();
+            assert_eq!(held, TOTAL);
+            let alice_tokens = token_call.balance_of(alice);
+            let alice_tokens = client.call(&ink_e2e::alice(), &alice_tokens).dry_run().await?.return_value();
+            assert_eq!(alice_tokens, 0);
+
+            // Release the first milestone in tokens
+            let release = call_builder.release_milestone(escrow_id, 0);
+            let release_result = client
+                .call(&ink_e2e::alice(), &release)
+                .submit()
+                .await
+                .expect("release_milestone failed");
+            assert_eq!(release_result.return_value(), Ok(()));
+
+            let bob_tokens = token_call.balance_of(bob);
+            let bob_tokens = client.call(&ink_e2e::alice(), &bob_tokens).dry_run().await?.return_value();
+            assert_eq!(bob_tokens, FIRST_MILESTONE);
+
+            let get = call_builder.get_escrow(escrow_id);
+            let escrow = client
+                .call(&ink_e2e::alice(), &get)
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("escrow not found");
+            assert_eq!(escrow.token, Some(token.account_id));
+            assert_eq!(escrow.status, EscrowStatus::Funded);
+            assert!(escrow.milestones[0].released);
 
-            let mut contract = EscrowMultiRelease::new();
-            let milestones = create_test_milestones();
-            let escrow_id = contract.create_escrow(accounts.bob, milestones.clone(), None).unwrap();
+            Ok(())
+        }
 
-            let retrieved_milestones = contract.get_milestones(escrow_id);
-            assert_eq!(retrieved_milestones.len(), 2);
-            assert_eq!(retrieved_milestones[0].id, 0);
-            assert_eq!(retrieved_milestones[1].id, 1);
+        #[ink_e2e::test]
+        async fn e2e_token_escrow_cancellation_refunds_client<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy a token minting the whole supply to Alice, and the escrow
+            let mut token_constructor = MockPsp22Ref::new(TOTAL);
+            let token = client
+                .instantiate("mock_psp22", &ink_e2e::alice(), &mut token_constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<MockPsp22>();
+
+            let mut constructor = EscrowMultiReleaseRef::new();
+            let contract = client
+                .instantiate("escrow", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<EscrowMultiRelease>();
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // Create, accept, approve and fund in tokens
+            let options = EscrowOptions {
+                token: Some(token.account_id),
+                ..EscrowOptions::default()
+            };
+            let create = call_builder.create_escrow_with_options(bob, e2e_milestones(), None, options);
+            let escrow_id = client
+                .call(&ink_e2e::alice(), &create)
+                .submit()
+                .await
+                .expect("create_escrow_with_options failed")
+                .return_value()
+                .expect("create_escrow_with_options returned error");
+
+            let accept = call_builder.accept_escrow(escrow_id);
+            client
+                .call(&ink_e2e::bob(), &accept)
+                .submit()
+                .await
+                .expect("accept_escrow failed");
+
+            let approve = token_call.approve(contract.account_id, TOTAL);
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve failed");
+
+            let fund = call_builder.fund_escrow(escrow_id, None, None, None);
+            client
+                .call(&ink_e2e::alice(), &fund)
+                .submit()
+                .await
+                .expect("fund_escrow failed");
+
+            // Cancel by mutual agreement before anything is released
+            let request = call_builder.request_cancel(escrow_id, None);
+            client
+                .call(&ink_e2e::alice(), &request)
+                .submit()
+                .await
+                .expect("request_cancel failed");
+
+            let approve_cancel = call_builder.approve_cancel(escrow_id, None);
+            let approve_result = client
+                .call(&ink_e2e::bob(), &approve_cancel)
+                .submit()
+                .await
+                .expect("approve_cancel failed");
+            let events = approve_result.contract_emitted_events()?;
+            assert_eq!(approve_result.return_value(), Ok(()));
+
+            // The whole deposit went back to the client in tokens
+            let alice_tokens = token_call.balance_of(alice);
+            let alice_tokens = client.call(&ink_e2e::alice(), &alice_tokens).dry_run().await?.return_value();
+            assert_eq!(alice_tokens, TOTAL);
+            let held = token_call.balance_of(contract.account_id);
+            let held = client.call(&ink_e2e::alice(), &held).dry_run().await?.return_value();
+            assert_eq!(held, 0);
+
+            let cancelled = <EscrowCancelled as scale::Decode>::decode(&mut &events[0].event.data[..])?;
+            assert_eq!(cancelled.escrow_id, escrow_id);
+            assert_eq!(cancelled.refund_to_client, TOTAL);
+
+            let get = call_builder.get_escrow(escrow_id);
+            let escrow = client
+                .call(&ink_e2e::alice(), &get)
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("escrow not found");
+            assert_eq!(escrow.status, EscrowStatus::Cancelled);
+
+            Ok(())
         }
     }
 }
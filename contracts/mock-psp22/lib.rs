@@ -0,0 +1,159 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![allow(unexpected_cfgs)]
+
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+// ========================================
+// CROSS-CONTRACT INTERFACE
+// ========================================
+
+/// Errors of the PSP22 standard, encoded the way every PSP22 token encodes them
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Token-specific failure
+    Custom(String),
+    /// Sender holds less than the amount
+    InsufficientBalance,
+    /// Spender is allowed less than the amount
+    InsufficientAllowance,
+    /// Recipient is the zero address
+    ZeroRecipientAddress,
+    /// Sender is the zero address
+    ZeroSenderAddress,
+    /// Recipient contract rejected the transfer
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 fungible token messages the mock implements
+#[ink::trait_definition]
+#[allow(clippy::upper_case_acronyms)]
+pub trait PSP22 {
+    /// Total amount of tokens in existence
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+
+    /// Tokens held by `owner`
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> u128;
+
+    /// Tokens `spender` may still move on behalf of `owner`
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> u128;
+
+    /// Move `value` tokens from the caller to `to`
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Move `value` tokens from `from` to `to` out of the caller's allowance
+    #[ink(message)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, value: u128, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Allow `spender` to move up to `value` of the caller's tokens
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error>;
+}
+
+/// Minimal PSP22 token used to exercise token-denominated escrows in e2e
+/// tests. It keeps balances and allowances only: no metadata, no events and
+/// no receiver checks.
+#[ink::contract]
+pub mod mock_psp22 {
+    use super::{PSP22Error, PSP22};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    // ========================================
+    // STORAGE MODULE
+    // ========================================
+
+    /// Storage holding balances and allowances
+    #[ink(storage)]
+    pub struct MockPsp22 {
+        /// Tokens minted at construction
+        total_supply: Balance,
+        /// Tokens held by each account
+        balances: Mapping<AccountId, Balance>,
+        /// Tokens each spender may move per owner
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    // ========================================
+    // CONTRACT IMPLEMENTATION
+    // ========================================
+
+    impl MockPsp22 {
+        /// Constructor that mints `total_supply` tokens to the caller
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut balances = Mapping::default();
+            balances.insert(Self::env().caller(), &total_supply);
+            Self {
+                total_supply,
+                balances,
+                allowances: Mapping::default(),
+            }
+        }
+
+        /// Move `value` tokens from `from` to `to`
+        fn move_tokens(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let from_balance = self.balances.get(from).unwrap_or(0);
+            if from_balance < value {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+            self.balances.insert(from, &(from_balance - value));
+            let to_balance = self.balances.get(to).unwrap_or(0);
+            self.balances.insert(to, &to_balance.saturating_add(value));
+            Ok(())
+        }
+    }
+
+    impl PSP22 for MockPsp22 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.move_tokens(caller, to, value)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let allowance = self.allowances.get((from, caller)).unwrap_or(0);
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+            self.move_tokens(from, to, value)?;
+            self.allowances.insert((from, caller), &(allowance - value));
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.allowances.insert((caller, spender), &value);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,441 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![allow(unexpected_cfgs)]
+
+/// Minimal contract that consumes the SkillChain registry through the
+/// `ClaimChecker` trait and subscribes to it through `ClaimSubscriber`. It
+/// exists to exercise cross-contract calls in e2e tests and as a reference for
+/// integrators gating on registry claims.
+#[ink::contract]
+pub mod registry_consumer {
+    use ink::prelude::string::String;
+    use skillchain::{ClaimChecker, ClaimSubscriber, VerificationResult};
+
+    // ========================================
+    // STORAGE MODULE
+    // ========================================
+
+    /// Storage holding a typed reference to the registry
+    #[ink(storage)]
+    pub struct RegistryConsumer {
+        /// SkillChain registry called through the `ClaimChecker` trait
+        registry: ink::contract_ref!(ClaimChecker),
+        /// Account of the registry, the only caller whose notifications count
+        registry_account: AccountId,
+        /// Number of approvals the registry notified
+        notification_count: u32,
+        /// Claim ID and receiver of the latest notified approval
+        last_notification: Option<(u64, AccountId)>,
+    }
+
+    // ========================================
+    // CONTRACT IMPLEMENTATION
+    // ========================================
+
+    impl RegistryConsumer {
+        /// Constructor that points the consumer at a deployed registry
+        #[ink(constructor)]
+        pub fn new(registry: AccountId) -> Self {
+            Self {
+                registry: registry.into(),
+                registry_account: registry,
+                notification_count: 0,
+                last_notification: None,
+            }
+        }
+
+        /// Verify a claim by calling the registry cross-contract
+        ///
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to verify
+        /// * `expected_receiver` - Account the claim must belong to
+        /// * `expected_proof` - Proof hash the claim must carry
+        ///
+        /// # Returns
+        /// * `VerificationResult` - Result reported by the registry
+        #[ink(message)]
+        pub fn check_claim(
+            &self,
+            claim_id: u64,
+            expected_receiver: AccountId,
+            expected_proof: Hash,
+        ) -> VerificationResult {
+            self.registry
+                .verify_claim(claim_id, expected_receiver, expected_proof)
+        }
+
+        /// Count an account's approved claims of a type since a timestamp by
+        /// calling the registry cross-contract
+        ///
+        /// # Arguments
+        /// * `account` - Receiver of the claims
+        /// * `claim_type` - Claim type to count
+        /// * `since` - Earliest approval timestamp counted, inclusive
+        ///
+        /// # Returns
+        /// * `u32` - Count reported by the registry, saturating at its bound
+        #[ink(message)]
+        pub fn approved_count_since(&self, account: AccountId, claim_type: String, since: Timestamp) -> u32 {
+            self.registry
+                .get_approved_count_since(account, claim_type, since)
+        }
+
+        /// Count the claims received by an account by calling the registry
+        /// cross-contract, without loading more than its bounded page
+        ///
+        /// # Arguments
+        /// * `account` - Receiver of the claims
+        ///
+        /// # Returns
+        /// * `u32` - Total number of claims reported by the registry
+        #[ink(message)]
+        pub fn received_claim_count(&self, account: AccountId) -> u32 {
+            self.registry.get_claims_bounded(account).total
+        }
+
+        /// Check that an account is registered and holds an approved claim of
+        /// a type, using only the registry's boolean checks
+        ///
+        /// # Arguments
+        /// * `account` - Account to gate
+        /// * `claim_type` - Claim type the account must hold
+        ///
+        /// # Returns
+        /// * `bool` - True if the account has a profile and an approved claim of the type
+        #[ink(message)]
+        pub fn is_eligible(&self, account: AccountId, claim_type: String) -> bool {
+            self.registry.profile_exists(account) && self.registry.has_approved_claim_of_type(account, claim_type)
+        }
+
+        /// Check that a claim ID was issued by calling the registry cross-contract
+        ///
+        /// # Arguments
+        /// * `claim_id` - ID of the claim
+        ///
+        /// # Returns
+        /// * `bool` - True if the registry knows the claim
+        #[ink(message)]
+        pub fn claim_exists(&self, claim_id: u64) -> bool {
+            self.registry.claim_exists(claim_id)
+        }
+
+        /// Get the approvals notified by the registry
+        ///
+        /// # Returns
+        /// * `(u32, Option<(u64, AccountId)>)` - Number of notifications, and the
+        ///   claim ID and receiver of the latest one
+        #[ink(message)]
+        pub fn get_notifications(&self) -> (u32, Option<(u64, AccountId)>) {
+            (self.notification_count, self.last_notification)
+        }
+    }
+
+    impl ClaimSubscriber for RegistryConsumer {
+        /// Record an approval reported by the registry, ignoring other callers
+        #[ink(message)]
+        fn on_claim_approved(&mut self, claim_id: u64, receiver: AccountId) {
+            if self.env().caller() != self.registry_account {
+                return;
+            }
+            self.notification_count = self.notification_count.saturating_add(1);
+            self.last_notification = Some((claim_id, receiver));
+        }
+    }
+
+    // ========================================
+    // E2E TESTS
+    // ========================================
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+        use skillchain::skillchain::{SkillChainRegistry, SkillChainRegistryRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn e2e_verify_claim_cross_contract<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy registry
+            let mut registry_constructor = SkillChainRegistryRef::new();
+            let registry = client
+                .instantiate("skillchain", &ink_e2e::alice(), &mut registry_constructor)
+                .submit()
+                .await
+                .expect("registry instantiate failed");
+            let mut registry_call = registry.call_builder::<SkillChainRegistry>();
+
+            // Deploy consumer pointing at the registry
+            let mut consumer_constructor = RegistryConsumerRef::new(registry.account_id);
+            let consumer = client
+                .instantiate("registry_consumer", &ink_e2e::alice(), &mut consumer_constructor)
+                .submit()
+                .await
+                .expect("consumer instantiate failed");
+            let consumer_call = consumer.call_builder::<RegistryConsumer>();
+
+            // Alice issues a claim to Bob
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let proof = Hash::from([0x01; 32]);
+            let add_claim = registry_call.add_claim(bob, "hackathon_win".to_string(), proof, None, None);
+            let claim_id = client
+                .call(&ink_e2e::alice(), &add_claim)
+                .submit()
+                .await
+                .expect("add_claim failed")
+                .return_value()
+                .expect("add_claim returned error");
+
+            // Pending claim is reported as not approved
+            let check = consumer_call.check_claim(claim_id, bob, proof);
+            let result = client.call(&ink_e2e::alice(), &check).dry_run().await?;
+            assert_eq!(result.return_value(), VerificationResult::NotApproved);
+
+            // Approve and verify again through the consumer
+            let approve = registry_call.approve_claim(claim_id);
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve_claim failed");
+
+            let check = consumer_call.check_claim(claim_id, bob, proof);
+            let result = client.call(&ink_e2e::alice(), &check).dry_run().await?;
+            assert_eq!(result.return_value(), VerificationResult::Valid);
+
+            // Wrong proof is detected cross-contract
+            let check = consumer_call.check_claim(claim_id, bob, Hash::from([0x02; 32]));
+            let result = client.call(&ink_e2e::alice(), &check).dry_run().await?;
+            assert_eq!(result.return_value(), VerificationResult::WrongProof);
+
+            // The approval is counted cross-contract for its claim type only
+            let count = consumer_call.approved_count_since(bob, "hackathon_win".to_string(), 0);
+            let result = client.call(&ink_e2e::alice(), &count).dry_run().await?;
+            assert_eq!(result.return_value(), 1);
+
+            let count = consumer_call.approved_count_since(bob, "contribution".to_string(), 0);
+            let result = client.call(&ink_e2e::alice(), &count).dry_run().await?;
+            assert_eq!(result.return_value(), 0);
+
+            // A cutoff in the future excludes it
+            let count = consumer_call.approved_count_since(bob, "hackathon_win".to_string(), u64::MAX);
+            let result = client.call(&ink_e2e::alice(), &count).dry_run().await?;
+            assert_eq!(result.return_value(), 0);
+
+            // The bounded claim list is readable cross-contract
+            let received = consumer_call.received_claim_count(bob);
+            let result = client.call(&ink_e2e::alice(), &received).dry_run().await?;
+            assert_eq!(result.return_value(), 1);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn e2e_subscriber_notified_on_approval<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy registry
+            let mut registry_constructor = SkillChainRegistryRef::new();
+            let registry = client
+                .instantiate("skillchain", &ink_e2e::alice(), &mut registry_constructor)
+                .submit()
+                .await
+                .expect("registry instantiate failed");
+            let mut registry_call = registry.call_builder::<SkillChainRegistry>();
+
+            // A second registry stands in for a subscriber that can't handle the callback
+            let mut broken_constructor = SkillChainRegistryRef::new();
+            let broken = client
+                .instantiate("skillchain", &ink_e2e::alice(), &mut broken_constructor)
+                .submit()
+                .await
+                .expect("broken subscriber instantiate failed");
+
+            // Deploy the consumer as a working subscriber
+            let mut consumer_constructor = RegistryConsumerRef::new(registry.account_id);
+            let consumer = client
+                .instantiate("registry_consumer", &ink_e2e::alice(), &mut consumer_constructor)
+                .submit()
+                .await
+                .expect("consumer instantiate failed");
+            let consumer_call = consumer.call_builder::<RegistryConsumer>();
+
+            // Bob has both watch him, the broken one first
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            for callback in [broken.account_id, consumer.account_id] {
+                let subscribe = registry_call.subscribe(bob, callback);
+                client
+                    .call(&ink_e2e::bob(), &subscribe)
+                    .submit()
+                    .await
+                    .expect("subscribe failed")
+                    .return_value()
+                    .expect("subscribe returned error");
+            }
+
+            let get = registry_call.get_subscriptions(bob);
+            let result = client.call(&ink_e2e::alice(), &get).dry_run().await?;
+            assert_eq!(result.return_value().len(), 2);
+
+            // Alice issues and approves a claim to Bob
+            let proof = Hash::from([0x01; 32]);
+            let add_claim = registry_call.add_claim(bob, "hackathon_win".to_string(), proof, None, None);
+            let claim_id = client
+                .call(&ink_e2e::alice(), &add_claim)
+                .submit()
+                .await
+                .expect("add_claim failed")
+                .return_value()
+                .expect("add_claim returned error");
+
+            let approve = registry_call.approve_claim(claim_id);
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve_claim failed")
+                .return_value()
+                .expect("a failing subscriber must not block the approval");
+
+            // The consumer was notified despite the broken subscriber before it
+            let notifications = consumer_call.get_notifications();
+            let result = client.call(&ink_e2e::alice(), &notifications).dry_run().await?;
+            assert_eq!(result.return_value(), (1, Some((claim_id, bob))));
+
+            let check = consumer_call.check_claim(claim_id, bob, proof);
+            let result = client.call(&ink_e2e::alice(), &check).dry_run().await?;
+            assert_eq!(result.return_value(), VerificationResult::Valid);
+
+            // Calls from anyone but the registry are ignored
+            let mut consumer_call = consumer.call_builder::<RegistryConsumer>();
+            let spoof = consumer_call.on_claim_approved(99, bob);
+            client
+                .call(&ink_e2e::bob(), &spoof)
+                .submit()
+                .await
+                .expect("on_claim_approved failed");
+
+            let notifications = consumer_call.get_notifications();
+            let result = client.call(&ink_e2e::alice(), &notifications).dry_run().await?;
+            assert_eq!(result.return_value(), (1, Some((claim_id, bob))));
+
+            // After Bob evicts the consumer, approvals no longer reach it
+            let unsubscribe = registry_call.unsubscribe(bob, consumer.account_id);
+            client
+                .call(&ink_e2e::bob(), &unsubscribe)
+                .submit()
+                .await
+                .expect("unsubscribe failed")
+                .return_value()
+                .expect("unsubscribe returned error");
+
+            let add_claim = registry_call.add_claim(bob, "contribution".to_string(), proof, None, None);
+            let second_id = client
+                .call(&ink_e2e::alice(), &add_claim)
+                .submit()
+                .await
+                .expect("add_claim failed")
+                .return_value()
+                .expect("add_claim returned error");
+            let approve = registry_call.approve_claim(second_id);
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve_claim failed");
+
+            let notifications = consumer_call.get_notifications();
+            let result = client.call(&ink_e2e::alice(), &notifications).dry_run().await?;
+            assert_eq!(result.return_value(), (1, Some((claim_id, bob))));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn e2e_existence_checks_stay_cheap<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            // Deploy registry
+            let mut registry_constructor = SkillChainRegistryRef::new();
+            let registry = client
+                .instantiate("skillchain", &ink_e2e::alice(), &mut registry_constructor)
+                .submit()
+                .await
+                .expect("registry instantiate failed");
+            let mut registry_call = registry.call_builder::<SkillChainRegistry>();
+
+            // Deploy consumer pointing at the registry
+            let mut consumer_constructor = RegistryConsumerRef::new(registry.account_id);
+            let consumer = client
+                .instantiate("registry_consumer", &ink_e2e::alice(), &mut consumer_constructor)
+                .submit()
+                .await
+                .expect("consumer instantiate failed");
+            let consumer_call = consumer.call_builder::<RegistryConsumer>();
+
+            // Bob is not eligible before registering
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let eligible = consumer_call.is_eligible(bob, "contribution".to_string());
+            let result = client.call(&ink_e2e::alice(), &eligible).dry_run().await?;
+            assert!(!result.return_value());
+
+            let register = registry_call.register_profile("ipfs://bob".to_string());
+            client
+                .call(&ink_e2e::bob(), &register)
+                .submit()
+                .await
+                .expect("register_profile failed")
+                .return_value()
+                .expect("register_profile returned error");
+
+            // Alice issues Bob many claims and approves them all
+            let mut claim_ids = Vec::new();
+            for i in 0..50u8 {
+                let add_claim = registry_call.add_claim(bob, "contribution".to_string(), Hash::from([i; 32]), None, None);
+                let claim_id = client
+                    .call(&ink_e2e::alice(), &add_claim)
+                    .submit()
+                    .await
+                    .expect("add_claim failed")
+                    .return_value()
+                    .expect("add_claim returned error");
+                claim_ids.push(claim_id);
+            }
+            let approve = registry_call.approve_claims_batch(claim_ids.clone());
+            client
+                .call(&ink_e2e::alice(), &approve)
+                .submit()
+                .await
+                .expect("approve_claims_batch failed")
+                .return_value()
+                .expect("approve_claims_batch returned error");
+
+            let eligible = consumer_call.is_eligible(bob, "contribution".to_string());
+            let eligible_result = client.call(&ink_e2e::alice(), &eligible).dry_run().await?;
+            let eligible_gas = eligible_result.exec_result.gas_consumed.ref_time();
+            assert!(eligible_result.return_value());
+
+            let eligible = consumer_call.is_eligible(bob, "hackathon_win".to_string());
+            let result = client.call(&ink_e2e::alice(), &eligible).dry_run().await?;
+            assert!(!result.return_value());
+
+            let exists = consumer_call.claim_exists(claim_ids[49]);
+            let result = client.call(&ink_e2e::alice(), &exists).dry_run().await?;
+            assert!(result.return_value());
+
+            let exists = consumer_call.claim_exists(claim_ids[49] + 1);
+            let result = client.call(&ink_e2e::alice(), &exists).dry_run().await?;
+            assert!(!result.return_value());
+
+            // The boolean checks cost less than loading the claims
+            let received = consumer_call.received_claim_count(bob);
+            let received_result = client.call(&ink_e2e::alice(), &received).dry_run().await?;
+            let received_gas = received_result.exec_result.gas_consumed.ref_time();
+            assert_eq!(received_result.return_value(), 50);
+            assert!(eligible_gas < received_gas);
+
+            Ok(())
+        }
+    }
+}
@@ -3,11 +3,200 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::arithmetic_side_effects)]
 
+use ink::prelude::string::String;
+use ink::primitives::{AccountId, Hash};
+
+// ========================================
+// CROSS-CONTRACT INTERFACE
+// ========================================
+
+/// Outcome of verifying a claim against an expected receiver and proof hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VerificationResult {
+    /// Claim exists, is approved and matches the expected receiver and proof
+    Valid,
+    /// Claim belongs to a different receiver
+    WrongReceiver,
+    /// Claim proof hash differs from the expected one
+    WrongProof,
+    /// Claim has not been approved by its issuer yet
+    NotApproved,
+    /// Claim was revoked by its issuer
+    Revoked,
+    /// Claim has expired
+    Expired,
+    /// Claim was approved longer ago than the registry's sunset horizon
+    Stale,
+    /// No claim exists with the given ID
+    NotFound,
+}
+
+/// Interface for contracts notified when a watched account's claim is approved
+///
+/// Calls are best-effort and run with `skillchain::SUBSCRIBER_REF_TIME_LIMIT`;
+/// a subscriber can't call back into the registry while being notified.
+#[ink::trait_definition]
+pub trait ClaimSubscriber {
+    /// Handle the approval of claim `claim_id` received by `receiver`
+    #[ink(message)]
+    fn on_claim_approved(&mut self, claim_id: u64, receiver: AccountId);
+}
+
+/// Read-only interface for contracts that gate on registry claims
+#[ink::trait_definition]
+pub trait ClaimChecker {
+    /// Verify in one call that a claim is approved, belongs to `expected_receiver`
+    /// and carries `expected_proof` as its proof hash
+    #[ink(message)]
+    fn verify_claim(
+        &self,
+        claim_id: u64,
+        expected_receiver: AccountId,
+        expected_proof: Hash,
+    ) -> VerificationResult;
+
+    /// Count the approved claims of `claim_type` received by `account` at or
+    /// after `since` (block timestamp in milliseconds)
+    ///
+    /// Only the latest `MAX_APPROVAL_TIMES` approvals per account and claim type
+    /// are kept, so the count saturates at that bound. Approvals older than the
+    /// registry's sunset horizon are not counted.
+    #[ink(message)]
+    fn get_approved_count_since(&self, account: AccountId, claim_type: String, since: u64) -> u32;
+
+    /// Get the first `MAX_BOUNDED_CLAIMS` claims received by `account`, with
+    /// a flag telling whether more exist
+    ///
+    /// Bounded so a caller's weight cannot be inflated by an account holding
+    /// thousands of claims.
+    #[ink(message)]
+    fn get_claims_bounded(&self, account: AccountId) -> skillchain::BoundedClaims;
+
+    /// Check whether `account` registered a profile, without loading it
+    #[ink(message)]
+    fn profile_exists(&self, account: AccountId) -> bool;
+
+    /// Check whether a claim with this ID was ever issued, without loading it
+    #[ink(message)]
+    fn claim_exists(&self, claim_id: u64) -> bool;
+
+    /// Check whether `account` holds an approved claim of `claim_type`,
+    /// answered from counters instead of a claim scan
+    #[ink(message)]
+    fn has_approved_claim_of_type(&self, account: AccountId, claim_type: String) -> bool;
+}
+
 #[ink::contract]
-mod skillchain {
+pub mod skillchain {
+    use super::{ClaimChecker, ClaimSubscriber, VerificationResult};
+    use ink::codegen::TraitCallBuilder;
     use ink::storage::Mapping;
     use ink::prelude::{string::String, vec::Vec};
 
+    /// Version of the `Claim` layout, bumped on every release that changes its fields
+    pub const CLAIM_SCHEMA_VERSION: u16 = 3;
+
+    /// Milliseconds in one activity day
+    pub const MS_PER_DAY: u64 = 86_400_000;
+
+    /// Maximum number of days covered by a single `get_activity` call
+    pub const MAX_ACTIVITY_RANGE_DAYS: u32 = 370;
+
+    /// Maximum number of claims in a single `approve_claims_batch` call
+    pub const MAX_APPROVAL_BATCH: usize = 50;
+
+    /// Maximum number of claims returned by a single `get_unseen_claims` call
+    pub const MAX_UNSEEN_CLAIMS: usize = 50;
+
+    /// Maximum number of claims returned by a single `get_claims_page` call
+    pub const MAX_CLAIMS_PAGE: usize = 50;
+
+    /// Number of most recent claims of an account sorted by `SortOrder::ApprovedAtDesc`
+    pub const MAX_SORT_SCAN: usize = 200;
+
+    /// Maximum number of claim types listed in a `ProfileSnapshot`
+    pub const MAX_SNAPSHOT_CLAIM_TYPES: usize = 10;
+
+    /// DID method prefix allowed at deployment
+    pub const DEFAULT_DID_METHOD: &str = "did:kilt:";
+
+    /// Maximum length in bytes of a DID URI
+    pub const MAX_DID_LEN: usize = 256;
+
+    /// Maximum number of allowed DID methods
+    pub const MAX_DID_METHODS: usize = 16;
+
+    /// Metadata URI schemes allowed at deployment
+    pub const DEFAULT_URI_SCHEMES: [&str; 3] = ["ipfs://", "ar://", "https://"];
+
+    /// Maximum length in bytes of a metadata URI
+    pub const MAX_METADATA_URI_LEN: usize = 512;
+
+    /// Maximum number of allowed metadata URI schemes
+    pub const MAX_URI_SCHEMES: usize = 16;
+
+    /// Maximum number of claim templates per issuer
+    pub const MAX_TEMPLATES_PER_ISSUER: usize = 32;
+
+    /// Approved claims needed for the Silver tier, unless the admin changes it
+    pub const DEFAULT_SILVER_THRESHOLD: u32 = 10;
+
+    /// Approved claims needed for the Gold tier, unless the admin changes it
+    pub const DEFAULT_GOLD_THRESHOLD: u32 = 50;
+
+    /// Badges earned when the number of approved claims received reaches a threshold
+    pub const CLAIM_COUNT_BADGES: [(u32, BadgeKind); 3] = [
+        (1, BadgeKind::FirstApprovedClaim),
+        (10, BadgeKind::TenApprovedClaims),
+        (50, BadgeKind::FiftyApprovedClaims),
+    ];
+
+    /// Maximum number of members of an organization profile
+    pub const MAX_ORG_MEMBERS: u32 = 50;
+
+    /// Maximum length in bytes of a claim rejection reason URI
+    pub const MAX_REJECTION_URI_LEN: usize = 256;
+
+    /// Maximum number of claims returned by a single `get_rejected_claims_by_issuer` call
+    pub const MAX_REJECTED_PAGE: u32 = 50;
+
+    /// Maximum number of claims returned by `get_claims` and `get_claims_bounded`
+    pub const MAX_BOUNDED_CLAIMS: usize = 100;
+
+    /// Maximum number of issuers on a receiver's allowlist
+    pub const MAX_ALLOWED_ISSUERS: usize = 50;
+
+    /// Maximum number of viewers holding an access grant per profile owner
+    pub const MAX_ACCESS_GRANTS: usize = 20;
+
+    /// Maximum number of subscriptions per watched account
+    pub const MAX_SUBSCRIPTIONS: usize = 5;
+
+    /// Reference time each subscriber may use when notified of an approval
+    pub const SUBSCRIBER_REF_TIME_LIMIT: u64 = 5_000_000_000;
+
+    /// Number of latest approval timestamps kept per (account, claim type)
+    pub const MAX_APPROVAL_TIMES: usize = 100;
+
+    /// Number of latest revoked claim IDs kept per issuer
+    pub const MAX_RECENT_REVOCATIONS: usize = 50;
+
+    /// Maximum number of reserved claim types
+    pub const MAX_RESERVED_CLAIM_TYPES: usize = 32;
+
+    /// Maximum number of claims attesting to a single claim
+    pub const MAX_ATTESTATIONS: usize = 50;
+
+    /// Minimum length in bytes of a claim type namespace
+    pub const MIN_NAMESPACE_LEN: usize = 3;
+
+    /// Maximum length in bytes of a claim type namespace
+    pub const MAX_NAMESPACE_LEN: usize = 32;
+
+    /// Maximum number of delegates issuing under a namespace
+    pub const MAX_NAMESPACE_DELEGATES: usize = 10;
+
     // ========================================
     // TYPES MODULE
     // ========================================
@@ -22,6 +211,117 @@ mod skillchain {
         pub metadata_uri: String,
         /// Optional KILT DID URI (e.g., "did:kilt:light:...")
         pub did: Option<String>,
+        /// Whether this is an organization profile whose members act for it
+        pub is_org: bool,
+        /// Badges earned so far, oldest first
+        pub badges: Vec<Badge>,
+        /// Whether the DID is disclosed to accounts other than the owner
+        pub did_public: bool,
+    }
+
+    /// Coarse reputation tier derived from the number of approved claims received
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Tier {
+        /// Below the Silver threshold
+        Bronze,
+        /// At or above the Silver threshold
+        Silver,
+        /// At or above the Gold threshold
+        Gold,
+    }
+
+    /// Kind of profile badge
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum BadgeKind {
+        /// First approved claim received
+        FirstApprovedClaim,
+        /// Ten approved claims received
+        TenApprovedClaims,
+        /// Fifty approved claims received
+        FiftyApprovedClaims,
+        /// First approved claim from an issuer with a linked DID
+        VerifiedIssuerClaim,
+    }
+
+    /// Badge earned by a profile
+    ///
+    /// Badges are permanent: revoking the claims that earned them does not remove them.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Badge {
+        /// Kind of badge
+        pub kind: BadgeKind,
+        /// Block timestamp of the approval that earned it
+        pub earned_at: Timestamp,
+    }
+
+    /// Role of a member in an organization profile
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum MemberRole {
+        /// Can issue and approve claims as the organization
+        Member,
+        /// Can also add and remove members
+        Admin,
+    }
+
+    /// Which issuers may add claims to a receiver
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ClaimPolicy {
+        /// Any account
+        #[default]
+        Open,
+        /// The admin, automated issuers and the receiver's allowlist
+        TrustedIssuersOnly,
+        /// Only the receiver's allowlist
+        AllowlistOnly,
+    }
+
+    /// Uniqueness rules of a claim type (e.g. a personhood claim)
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ClaimTypeUniqueness {
+        /// A receiver may hold at most one active claim of the type
+        pub unique_per_receiver: bool,
+        /// A proof hash may back active claims of the type for one receiver only
+        pub unique_proof: bool,
+    }
+
+    /// Contract notified when a watched account's claim is approved
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Subscription {
+        /// Account that registered the subscription
+        pub subscriber: AccountId,
+        /// Contract implementing `ClaimSubscriber`
+        pub callback_contract: AccountId,
+    }
+
+    /// What happens to a claim whose issuer and receiver link the same DID
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum SelfAttestationPolicy {
+        /// Keep the claim but mark it `self_attested`, so it earns no reputation
+        #[default]
+        Flag,
+        /// Refuse to add or approve it with `SelfAttestation`
+        Reject,
+    }
+
+    /// Order of the claims returned by paginated getters
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum SortOrder {
+        /// Oldest claim first
+        IdAsc,
+        /// Newest claim first
+        IdDesc,
+        /// Most recently approved first, then unapproved claims newest first;
+        /// only the `MAX_SORT_SCAN` newest claims are considered
+        ApprovedAtDesc,
     }
 
     /// Status of a claim
@@ -32,6 +332,22 @@ mod skillchain {
         Pending,
         /// Claim has been approved by the issuer
         Approved,
+        /// Claim has been revoked by the issuer
+        Revoked,
+        /// Claim lapsed before it could be used
+        Expired,
+        /// Claim was declined by its receiver
+        Rejected,
+    }
+
+    /// Kind of account that issued a claim
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum IssuerKind {
+        /// Regular account issuing by hand
+        Account,
+        /// Contract on the admin's automated issuer list
+        Contract,
     }
 
     /// Represents a claim in the SkillChain protocol
@@ -50,6 +366,80 @@ mod skillchain {
         pub proof_hash: Hash,
         /// Current status of the claim
         pub status: ClaimStatus,
+        /// Block timestamp of the approval (if approved)
+        pub approved_at: Option<Timestamp>,
+        /// Block timestamp when the claim was added
+        pub created_at: Timestamp,
+        /// Whether the issuer was an automated issuer when the claim was added
+        pub issuer_kind: IssuerKind,
+        /// Template the claim was issued from (if any)
+        pub template_id: Option<u64>,
+        /// Credential level copied from the template (if any)
+        pub level: Option<u8>,
+        /// URI pointing to off-chain credential metadata, copied from the template (if any)
+        pub metadata_uri: Option<String>,
+        /// Block timestamp after which the claim no longer verifies (None = never)
+        pub expires_at: Option<Timestamp>,
+        /// URI explaining why the receiver rejected the claim (if rejected with a reason)
+        pub rejection_reason_uri: Option<String>,
+        /// Block timestamp from which the claim is visible and approvable (None = immediately)
+        pub activates_at: Option<Timestamp>,
+        /// `CLAIM_SCHEMA_VERSION` the claim was created with
+        pub schema_version: u16,
+        /// Claim of the same receiver this claim vouches for (if any)
+        pub attests_to: Option<u64>,
+        /// Whether issuer and receiver linked the same DID when the claim was
+        /// added or approved; such claims don't count toward reputation
+        pub self_attested: bool,
+    }
+
+    /// Issuer-defined preset for a repeatedly granted credential
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ClaimTemplate {
+        /// Unique identifier for the template
+        pub id: u64,
+        /// Account that owns the template and issues from it
+        pub issuer: AccountId,
+        /// Type of the claims issued from it
+        pub claim_type: String,
+        /// Credential level
+        pub level: u8,
+        /// Validity of issued claims in milliseconds from issuance (0 = no expiry)
+        pub validity_ms: u64,
+        /// URI pointing to off-chain credential metadata
+        pub metadata_uri: String,
+    }
+
+    /// Reputation of an account bundled for a single query
+    ///
+    /// Size is bounded by the profile strings plus at most
+    /// `MAX_SNAPSHOT_CLAIM_TYPES` entries in `approved_by_type`.
+    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ProfileSnapshot {
+        /// Profile, including the linked DID (if any)
+        pub profile: Profile,
+        /// Approved claims per claim type, most frequent first
+        pub approved_by_type: Vec<(String, u32)>,
+        /// Whether the profile has a linked DID
+        pub verified: bool,
+        /// Number of approved claims received
+        pub reputation_score: u32,
+        /// Block timestamp of the latest claim received (if any, time-locked claims excluded)
+        pub last_claim_at: Option<Timestamp>,
+    }
+
+    /// First claims of an account, bounded for cross-contract callers
+    #[derive(Debug, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BoundedClaims {
+        /// Active claims in ID order, at most `MAX_BOUNDED_CLAIMS`
+        pub claims: Vec<Claim>,
+        /// Whether claims were left out because of the bound
+        pub truncated: bool,
+        /// Number of claims received by the account, time-locked ones included
+        pub total: u32,
     }
 
     // ========================================
@@ -72,6 +462,125 @@ mod skillchain {
         ClaimAlreadyApproved,
         /// Invalid DID format
         InvalidDid,
+        /// Claim has already been revoked
+        ClaimAlreadyRevoked,
+        /// Claim has expired
+        ClaimExpired,
+        /// Pending claim is older than the approval deadline
+        ClaimStale,
+        /// Only the admin can perform this operation
+        NotAdmin,
+        /// Batch exceeds the maximum allowed size
+        BatchTooLarge,
+        /// DID method prefix is malformed, or too many methods are allowed
+        InvalidDidMethod,
+        /// DID method is already allowed
+        DidMethodAlreadyAllowed,
+        /// DID method is not in the allowed list
+        DidMethodNotAllowed,
+        /// Caller is not on the automated issuer list
+        NotAutomatedIssuer,
+        /// Seen watermark would move backwards
+        SeenWatermarkRegression,
+        /// Template not found with the specified ID
+        TemplateNotFound,
+        /// Only the template's issuer can use or change it
+        NotTemplateOwner,
+        /// Issuer already holds `MAX_TEMPLATES_PER_ISSUER` templates
+        TooManyTemplates,
+        /// Only the receiver can reject their claim
+        NotClaimReceiver,
+        /// Claim has already been rejected by its receiver
+        ClaimAlreadyRejected,
+        /// Rejection reason URI is empty or longer than `MAX_REJECTION_URI_LEN`
+        InvalidRejectionReason,
+        /// No organization profile exists for the account
+        OrgNotFound,
+        /// Caller is not a member of the organization
+        NotOrgMember,
+        /// Caller is neither the organization nor one of its admins
+        NotOrgAdmin,
+        /// Organization already has `MAX_ORG_MEMBERS` members
+        TooManyMembers,
+        /// Claim is time-locked and not active yet
+        ClaimNotYetActive,
+        /// Activation time is not before the claim's expiry
+        InvalidActivation,
+        /// A write would change the receiver of an existing claim
+        ReceiverImmutable,
+        /// Claims of this type can't be issued or approved while it is frozen
+        ClaimTypeFrozen,
+        /// Only the admin and automated issuers may issue claims of this type
+        ReservedClaimType,
+        /// Claim type is already reserved
+        ClaimTypeAlreadyReserved,
+        /// Claim type is not reserved
+        ClaimTypeNotReserved,
+        /// `MAX_RESERVED_CLAIM_TYPES` claim types are already reserved
+        TooManyReservedTypes,
+        /// The attested claim belongs to a different receiver
+        AttestationReceiverMismatch,
+        /// The attested claim already has `MAX_ATTESTATIONS` attestations
+        TooManyAttestations,
+        /// Commitment differs from the account's current `export_profile_commitment`
+        CommitmentMismatch,
+        /// Metadata URI is empty, too long or uses a scheme that is not allowed
+        InvalidUri,
+        /// URI scheme prefix is malformed, or too many schemes are allowed
+        InvalidUriScheme,
+        /// URI scheme is already allowed
+        UriSchemeAlreadyAllowed,
+        /// URI scheme is not in the allowed list
+        UriSchemeNotAllowed,
+        /// Silver threshold is zero or not below the Gold threshold
+        InvalidTierThresholds,
+        /// The receiver's claim policy doesn't admit the issuer
+        IssuerNotPermitted,
+        /// Issuer is already on the receiver's allowlist
+        IssuerAlreadyAllowed,
+        /// Issuer is not on the receiver's allowlist
+        IssuerNotListed,
+        /// Allowlist already holds `MAX_ALLOWED_ISSUERS` issuers
+        TooManyAllowedIssuers,
+        /// The receiver already holds an active claim of this singleton type
+        SingletonViolation,
+        /// The proof hash already backs an active claim of this type for another receiver
+        ProofAlreadyBound,
+        /// Callback account is not a contract
+        NotAContract,
+        /// Callback contract already watches the account
+        AlreadySubscribed,
+        /// Account already has `MAX_SUBSCRIPTIONS` subscriptions
+        TooManySubscriptions,
+        /// No subscription of the callback contract to the account
+        SubscriptionNotFound,
+        /// Caller is neither the subscriber nor the watched account
+        NotSubscriber,
+        /// Caller is neither the watched account nor a subscriber it approved
+        SubscriberNotApproved,
+        /// Grant expiry is not in the future, or the viewer is the owner
+        InvalidGrant,
+        /// Owner already has `MAX_ACCESS_GRANTS` viewers
+        TooManyGrants,
+        /// Viewer holds no grant from the owner
+        GrantNotFound,
+        /// Namespace is not 3-32 lowercase letters, digits or dashes, or a
+        /// namespaced claim type is not `namespace/name`
+        InvalidNamespace,
+        /// Namespace is already registered
+        NamespaceTaken,
+        /// Namespace is not registered
+        NamespaceNotFound,
+        /// Caller is neither the namespace owner nor one of its delegates
+        NotNamespaceOwner,
+        /// Account is already a delegate of the namespace
+        AlreadyNamespaceDelegate,
+        /// Account is not a delegate of the namespace
+        NotNamespaceDelegate,
+        /// Namespace already has `MAX_NAMESPACE_DELEGATES` delegates
+        TooManyNamespaceDelegates,
+        /// Issuer and receiver link the same DID and the policy rejects self-attestation
+        SelfAttestation,
     }
 
     /// Result type for contract operations
@@ -99,6 +608,9 @@ mod skillchain {
         #[ink(topic)]
         pub receiver: AccountId,
         pub claim_type: String,
+        pub activates_at: Option<Timestamp>,
+        pub schema_version: u16,
+        pub attests_to: Option<u64>,
     }
 
     /// Emitted when a claim is approved
@@ -108,6 +620,38 @@ mod skillchain {
         pub claim_id: u64,
     }
 
+    /// Emitted when a claim is revoked by its issuer
+    #[ink(event)]
+    pub struct ClaimRevoked {
+        #[ink(topic)]
+        pub claim_id: u64,
+        #[ink(topic)]
+        pub issuer: AccountId,
+        /// Issuer's revocation accumulator after this revocation
+        pub revocation_accumulator: Hash,
+    }
+
+    /// Emitted when a receiver rejects a claim
+    #[ink(event)]
+    pub struct ClaimRejected {
+        #[ink(topic)]
+        pub claim_id: u64,
+        #[ink(topic)]
+        pub issuer: AccountId,
+        #[ink(topic)]
+        pub receiver: AccountId,
+        pub rejection_reason_uri: Option<String>,
+    }
+
+    /// Emitted when a stale pending claim is swept to `Expired`
+    #[ink(event)]
+    pub struct ClaimExpired {
+        #[ink(topic)]
+        pub claim_id: u64,
+        #[ink(topic)]
+        pub receiver: AccountId,
+    }
+
     /// Emitted when a DID is linked to a profile
     #[ink(event)]
     pub struct DidLinked {
@@ -116,500 +660,6971 @@ mod skillchain {
         pub did: String,
     }
 
-    // ========================================
-    // STORAGE MODULE
-    // ========================================
+    /// Emitted when a profile owner shows or hides their DID
+    #[ink(event)]
+    pub struct DidVisibilityChanged {
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub public: bool,
+    }
 
-    /// Main storage structure for the SkillChain Registry
-    #[ink(storage)]
-    pub struct SkillChainRegistry {
-        /// Mapping from AccountId to Profile
-        profiles: Mapping<AccountId, Profile>,
-        /// Mapping from claim ID to Claim
-        claims: Mapping<u64, Claim>,
-        /// Counter for the next claim ID
-        next_claim_id: u64,
-        /// Mapping from AccountId to their received claim IDs
-        user_claims: Mapping<AccountId, Vec<u64>>,
+    /// Emitted when a profile owner changes who may issue claims to them
+    #[ink(event)]
+    pub struct ClaimPolicySet {
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub policy: ClaimPolicy,
     }
 
-    // ========================================
-    // CONTRACT IMPLEMENTATION
-    // ========================================
+    /// Emitted when a profile owner adds an issuer to their allowlist
+    #[ink(event)]
+    pub struct IssuerAllowed {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub issuer: AccountId,
+    }
 
-    impl Default for SkillChainRegistry {
-        fn default() -> Self {
-            Self::new()
-        }
+    /// Emitted when a profile owner removes an issuer from their allowlist
+    #[ink(event)]
+    pub struct IssuerDisallowed {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub issuer: AccountId,
     }
 
-    impl SkillChainRegistry {
-        /// Constructor that initializes the contract
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            Self {
-                profiles: Mapping::default(),
-                claims: Mapping::default(),
-                next_claim_id: 0,
-                user_claims: Mapping::default(),
-            }
-        }
+    /// Emitted when a profile owner grants a viewer temporary access
+    #[ink(event)]
+    pub struct AccessGranted {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub viewer: AccountId,
+        pub expires_at: Timestamp,
+    }
 
-        /// Register a new profile for the caller
-        /// 
-        /// # Arguments
-        /// * `metadata_uri` - URI pointing to off-chain profile metadata (IPFS, Arweave, etc.)
-        /// 
-        /// # Errors
-        /// * `ProfileAlreadyExists` - If the caller already has a registered profile
-        /// 
-        /// # Events
-        /// * `ProfileRegistered` - Emitted when profile is successfully created
-        #[ink(message)]
-        pub fn register_profile(&mut self, metadata_uri: String) -> Result<()> {
-            let caller = self.env().caller();
+    /// Emitted when a profile owner revokes a viewer's access
+    #[ink(event)]
+    pub struct AccessRevoked {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub viewer: AccountId,
+    }
 
-            // Check if profile already exists
-            if self.profiles.contains(caller) {
-                return Err(ContractError::ProfileAlreadyExists);
-            }
+    /// Emitted when an account registers a claim type namespace
+    #[ink(event)]
+    pub struct NamespaceRegistered {
+        pub namespace: String,
+        #[ink(topic)]
+        pub owner: AccountId,
+    }
 
-            // Create new profile
-            let profile = Profile {
-                owner: caller,
-                metadata_uri: metadata_uri.clone(),
-                did: None,
-            };
+    /// Emitted when a namespace owner hands the namespace to another account
+    #[ink(event)]
+    pub struct NamespaceTransferred {
+        pub namespace: String,
+        #[ink(topic)]
+        pub from: AccountId,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
 
-            // Store profile
-            self.profiles.insert(caller, &profile);
+    /// Emitted when a namespace owner lets another account issue under it
+    #[ink(event)]
+    pub struct NamespaceDelegateAdded {
+        pub namespace: String,
+        #[ink(topic)]
+        pub delegate: AccountId,
+    }
 
-            // Emit event
-            self.env().emit_event(ProfileRegistered {
-                owner: caller,
-                metadata_uri,
-            });
+    /// Emitted when a namespace owner removes a delegate
+    #[ink(event)]
+    pub struct NamespaceDelegateRemoved {
+        pub namespace: String,
+        #[ink(topic)]
+        pub delegate: AccountId,
+    }
 
-            Ok(())
-        }
+    /// Emitted when a claim is marked self-attested because issuer and
+    /// receiver link the same DID
+    #[ink(event)]
+    pub struct SelfAttestationFlagged {
+        #[ink(topic)]
+        pub claim_id: u64,
+        #[ink(topic)]
+        pub issuer: AccountId,
+        #[ink(topic)]
+        pub receiver: AccountId,
+    }
 
-        /// Add a new claim to another user
-        /// 
-        /// # Arguments
-        /// * `receiver` - Account that will receive the claim
-        /// * `claim_type` - Type of claim (e.g., "hackathon_win", "job_completed")
-        /// * `proof_hash` - Hash of the proof stored off-chain
-        /// 
-        /// # Returns
-        /// * `u64` - The ID of the newly created claim
-        /// 
-        /// # Events
-        /// * `ClaimAdded` - Emitted when claim is successfully created
-        #[ink(message)]
-        pub fn add_claim(
-            &mut self,
-            receiver: AccountId,
-            claim_type: String,
-            proof_hash: Hash,
-        ) -> Result<u64> {
-            let caller = self.env().caller();
-            let claim_id = self.next_claim_id;
+    /// Emitted when a contract subscribes to a watched account's approvals
+    #[ink(event)]
+    pub struct Subscribed {
+        #[ink(topic)]
+        pub account_watched: AccountId,
+        pub callback_contract: AccountId,
+        pub subscriber: AccountId,
+    }
 
-            // Create new claim with Pending status
-            let claim = Claim {
-                id: claim_id,
-                issuer: caller,
-                receiver,
-                claim_type: claim_type.clone(),
-                proof_hash,
-                status: ClaimStatus::Pending,
-            };
+    /// Emitted when a watched account approves or revokes a subscriber
+    #[ink(event)]
+    pub struct SubscriberApprovalSet {
+        #[ink(topic)]
+        pub account_watched: AccountId,
+        pub subscriber: AccountId,
+        pub approved: bool,
+    }
 
-            // Store claim
-            self.claims.insert(claim_id, &claim);
+    /// Emitted when a subscription is removed
+    #[ink(event)]
+    pub struct Unsubscribed {
+        #[ink(topic)]
+        pub account_watched: AccountId,
+        pub callback_contract: AccountId,
+    }
 
-            // Add claim ID to receiver's claims list
-            let mut user_claim_ids = self.user_claims.get(receiver).unwrap_or_default();
-            user_claim_ids.push(claim_id);
-            self.user_claims.insert(receiver, &user_claim_ids);
+    /// Emitted when notifying a subscriber of an approval fails
+    #[ink(event)]
+    pub struct SubscriberCallFailed {
+        #[ink(topic)]
+        pub receiver: AccountId,
+        pub claim_id: u64,
+        pub callback_contract: AccountId,
+    }
 
-            // Increment claim counter
-            self.next_claim_id += 1;
+    /// Emitted when the admin changes the reputation tier thresholds
+    #[ink(event)]
+    pub struct TierThresholdsUpdated {
+        pub silver: u32,
+        pub gold: u32,
+    }
 
-            // Emit event
-            self.env().emit_event(ClaimAdded {
-                claim_id,
-                issuer: caller,
-                receiver,
-                claim_type,
-            });
+    /// Emitted when the admin adds an automated issuer
+    #[ink(event)]
+    pub struct AutomatedIssuerAdded {
+        #[ink(topic)]
+        pub issuer: AccountId,
+    }
+
+    /// Emitted when the admin removes an automated issuer
+    #[ink(event)]
+    pub struct AutomatedIssuerRemoved {
+        #[ink(topic)]
+        pub issuer: AccountId,
+    }
+
+    /// Emitted when an issuer creates a claim template
+    #[ink(event)]
+    pub struct TemplateCreated {
+        #[ink(topic)]
+        pub template_id: u64,
+        #[ink(topic)]
+        pub issuer: AccountId,
+        pub claim_type: String,
+    }
+
+    /// Emitted when a claim is issued from a template
+    #[ink(event)]
+    pub struct TemplateUsed {
+        #[ink(topic)]
+        pub template_id: u64,
+        #[ink(topic)]
+        pub claim_id: u64,
+        #[ink(topic)]
+        pub receiver: AccountId,
+    }
+
+    /// Emitted when an organization adds a member or changes a member's role
+    #[ink(event)]
+    pub struct MemberAdded {
+        #[ink(topic)]
+        pub org: AccountId,
+        #[ink(topic)]
+        pub member: AccountId,
+        pub role: MemberRole,
+    }
+
+    /// Emitted when an organization removes a member
+    #[ink(event)]
+    pub struct MemberRemoved {
+        #[ink(topic)]
+        pub org: AccountId,
+        #[ink(topic)]
+        pub member: AccountId,
+    }
+
+    /// Emitted when a profile earns a badge
+    #[ink(event)]
+    pub struct BadgeEarned {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub kind: BadgeKind,
+    }
+
+    /// Emitted when the admin freezes a claim type
+    #[ink(event)]
+    pub struct ClaimTypeFrozen {
+        pub claim_type: String,
+    }
+
+    /// Emitted when the admin unfreezes a claim type
+    #[ink(event)]
+    pub struct ClaimTypeUnfrozen {
+        pub claim_type: String,
+    }
+
+    /// Emitted when the admin reserves a claim type
+    #[ink(event)]
+    pub struct ClaimTypeReserved {
+        pub claim_type: String,
+    }
+
+    /// Emitted when the admin releases a reserved claim type
+    #[ink(event)]
+    pub struct ClaimTypeUnreserved {
+        pub claim_type: String,
+    }
+
+    /// Emitted when the admin changes the uniqueness rules of a claim type
+    #[ink(event)]
+    pub struct ClaimTypeUniquenessSet {
+        pub claim_type: String,
+        pub uniqueness: ClaimTypeUniqueness,
+    }
+
+    /// Emitted when the admin records an account's migration to another registry
+    #[ink(event)]
+    pub struct MigrationRecorded {
+        #[ink(topic)]
+        pub account: AccountId,
+        #[ink(topic)]
+        pub destination: AccountId,
+        pub commitment: Hash,
+    }
+
+    /// Emitted when the admin allows a DID method
+    #[ink(event)]
+    pub struct DidMethodAllowed {
+        pub prefix: String,
+    }
+
+    /// Emitted when the admin disallows a DID method
+    #[ink(event)]
+    pub struct DidMethodDisallowed {
+        pub prefix: String,
+    }
+
+    /// Emitted when the admin allows a metadata URI scheme
+    #[ink(event)]
+    pub struct UriSchemeAllowed {
+        pub prefix: String,
+    }
+
+    /// Emitted when the admin disallows a metadata URI scheme
+    #[ink(event)]
+    pub struct UriSchemeDisallowed {
+        pub prefix: String,
+    }
+
+    // ========================================
+    // STORAGE MODULE
+    // ========================================
+
+    /// Approved claim count per claim type, in first-approval order
+    type ClaimTypeCounts = Vec<(String, u32)>;
+
+    /// Latest approval timestamps of one account and claim type, oldest first
+    type ApprovalTimes = Vec<Timestamp>;
+
+    /// IDs of the claims backed by one proof, all of one receiver
+    type BoundClaims = Vec<u64>;
+
+    /// Main storage structure for the SkillChain Registry
+    #[ink(storage)]
+    pub struct SkillChainRegistry {
+        /// Mapping from AccountId to Profile
+        profiles: Mapping<AccountId, Profile>,
+        /// Mapping from claim ID to Claim
+        claims: Mapping<u64, Claim>,
+        /// Counter for the next claim ID
+        next_claim_id: u64,
+        /// Mapping from AccountId to their received claim IDs
+        user_claims: Mapping<AccountId, Vec<u64>>,
+        /// Approved claims received per (account, day since epoch)
+        activity: Mapping<(AccountId, u32), u16>,
+        /// Account allowed to change contract settings
+        admin: AccountId,
+        /// Maximum age in milliseconds of an approvable pending claim (0 = disabled)
+        pending_ttl_ms: u64,
+        /// Age in milliseconds after which approved claims stop counting (0 = never)
+        stale_after_ms: u64,
+        /// Whether a claim can only be approved once its receiver has a profile
+        require_receiver_profile: bool,
+        /// How claims between accounts sharing a DID are handled
+        self_attestation_policy: SelfAttestationPolicy,
+        /// Approved claims received per account and claim type
+        approved_by_type: Mapping<AccountId, ClaimTypeCounts>,
+        /// Block timestamp of the latest claim received per account
+        last_claim_at: Mapping<AccountId, Timestamp>,
+        /// DID method prefixes accepted by `link_did` (e.g. "did:kilt:")
+        did_methods: Vec<String>,
+        /// URI scheme prefixes accepted for metadata URIs (e.g. "ipfs://")
+        uri_schemes: Vec<String>,
+        /// Contract accounts allowed to issue claims programmatically
+        automated_issuers: Mapping<AccountId, ()>,
+        /// Highest claim ID each account has marked as seen
+        last_seen: Mapping<AccountId, u64>,
+        /// Mapping from template ID to ClaimTemplate
+        templates: Mapping<u64, ClaimTemplate>,
+        /// Counter for the next template ID
+        next_template_id: u64,
+        /// Mapping from issuer to their template IDs
+        issuer_templates: Mapping<AccountId, Vec<u64>>,
+        /// Number of rejected claims per issuer
+        rejected_count: Mapping<AccountId, u32>,
+        /// Rejected claim IDs per (issuer, rejection index)
+        rejected_claims: Mapping<(AccountId, u32), u64>,
+        /// Role of each member per (organization, member)
+        org_members: Mapping<(AccountId, AccountId), MemberRole>,
+        /// Number of members per organization
+        org_member_count: Mapping<AccountId, u32>,
+        /// Latest approval timestamps per (account, claim type), oldest first
+        approval_times: Mapping<(AccountId, String), ApprovalTimes>,
+        /// Rolling hash over revoked claim IDs and number of revocations per issuer
+        revocation_state: Mapping<AccountId, (Hash, u32)>,
+        /// Latest revoked claim IDs per issuer, oldest first
+        recent_revocations: Mapping<AccountId, Vec<u64>>,
+        /// Claim types that can't be issued or approved
+        frozen_claim_types: Mapping<String, ()>,
+        /// Claim types only the admin and automated issuers may issue
+        reserved_claim_types: Vec<String>,
+        /// IDs of the claims attesting to a claim, oldest first
+        attestations: Mapping<u64, Vec<u64>>,
+        /// Destination registry and profile commitment per migrated account
+        migrations: Mapping<AccountId, (AccountId, Hash)>,
+        /// Claim policy per receiver (absent = `Open`)
+        claim_policies: Mapping<AccountId, ClaimPolicy>,
+        /// Issuers each receiver admits under its claim policy
+        allowed_issuers: Mapping<AccountId, Vec<AccountId>>,
+        /// Approved claims needed for the Silver and Gold tiers
+        tier_thresholds: (u32, u32),
+        /// Whether `get_profile_snapshot` only answers the account itself and automated issuers
+        reputation_restricted: bool,
+        /// Uniqueness rules per claim type (absent = no rules)
+        claim_type_uniqueness: Mapping<String, ClaimTypeUniqueness>,
+        /// Latest claim of a `unique_per_receiver` type per (receiver, claim type)
+        singleton_claims: Mapping<(AccountId, String), u64>,
+        /// Claims backed by a proof per (`unique_proof` claim type, proof hash), all of one receiver
+        bound_proofs: Mapping<(String, Hash), BoundClaims>,
+        /// Contracts notified of approvals per watched account, oldest first
+        subscriptions: Mapping<AccountId, Vec<Subscription>>,
+        /// Accounts allowed to subscribe contracts per (watched account, subscriber)
+        subscriber_approvals: Mapping<(AccountId, AccountId), ()>,
+        /// Access expiry per (owner, viewer); expired grants count as absent
+        access_grants: Mapping<(AccountId, AccountId), Timestamp>,
+        /// Viewers holding a grant per owner, oldest first
+        access_viewers: Mapping<AccountId, Vec<AccountId>>,
+        /// Owner of each registered claim type namespace
+        namespace_owners: Mapping<String, AccountId>,
+        /// Accounts the namespace owner lets issue under it, cleared on transfer
+        namespace_delegates: Mapping<String, Vec<AccountId>>,
+    }
+
+    // ========================================
+    // CONTRACT IMPLEMENTATION
+    // ========================================
 
-            Ok(claim_id)
+    impl Default for SkillChainRegistry {
+        fn default() -> Self {
+            Self::new()
         }
+    }
 
-        /// Approve a claim (only by the issuer)
+    impl SkillChainRegistry {
+        /// Constructor that initializes the contract, with the caller as admin
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                profiles: Mapping::default(),
+                claims: Mapping::default(),
+                next_claim_id: 0,
+                user_claims: Mapping::default(),
+                activity: Mapping::default(),
+                admin: Self::env().caller(),
+                pending_ttl_ms: 0,
+                stale_after_ms: 0,
+                require_receiver_profile: false,
+                self_attestation_policy: SelfAttestationPolicy::default(),
+                approved_by_type: Mapping::default(),
+                last_claim_at: Mapping::default(),
+                did_methods: ink::prelude::vec![String::from(DEFAULT_DID_METHOD)],
+                uri_schemes: DEFAULT_URI_SCHEMES.iter().map(|scheme| String::from(*scheme)).collect(),
+                automated_issuers: Mapping::default(),
+                last_seen: Mapping::default(),
+                templates: Mapping::default(),
+                next_template_id: 0,
+                issuer_templates: Mapping::default(),
+                rejected_count: Mapping::default(),
+                rejected_claims: Mapping::default(),
+                org_members: Mapping::default(),
+                org_member_count: Mapping::default(),
+                approval_times: Mapping::default(),
+                revocation_state: Mapping::default(),
+                recent_revocations: Mapping::default(),
+                frozen_claim_types: Mapping::default(),
+                reserved_claim_types: Vec::new(),
+                attestations: Mapping::default(),
+                migrations: Mapping::default(),
+                claim_policies: Mapping::default(),
+                allowed_issuers: Mapping::default(),
+                tier_thresholds: (DEFAULT_SILVER_THRESHOLD, DEFAULT_GOLD_THRESHOLD),
+                reputation_restricted: false,
+                claim_type_uniqueness: Mapping::default(),
+                singleton_claims: Mapping::default(),
+                bound_proofs: Mapping::default(),
+                subscriptions: Mapping::default(),
+                subscriber_approvals: Mapping::default(),
+                access_grants: Mapping::default(),
+                access_viewers: Mapping::default(),
+                namespace_owners: Mapping::default(),
+                namespace_delegates: Mapping::default(),
+            }
+        }
+
+        /// Register a new profile for the caller
         /// 
         /// # Arguments
-        /// * `claim_id` - ID of the claim to approve
+        /// * `metadata_uri` - URI pointing to off-chain profile metadata (IPFS, Arweave, etc.)
         /// 
         /// # Errors
-        /// * `ClaimNotFound` - If the claim doesn't exist
-        /// * `UnauthorizedApproval` - If the caller is not the issuer
-        /// * `ClaimAlreadyApproved` - If the claim is already approved
+        /// * `ProfileAlreadyExists` - If the caller already has a registered profile
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
         /// 
         /// # Events
-        /// * `ClaimApproved` - Emitted when claim is successfully approved
+        /// * `ProfileRegistered` - Emitted when profile is successfully created
         #[ink(message)]
-        pub fn approve_claim(&mut self, claim_id: u64) -> Result<()> {
+        pub fn register_profile(&mut self, metadata_uri: String) -> Result<()> {
             let caller = self.env().caller();
+            self.insert_profile(caller, metadata_uri, false)
+        }
 
-            // Get claim
-            let mut claim = self.claims.get(claim_id).ok_or(ContractError::ClaimNotFound)?;
-
-            // Verify caller is the issuer
-            if claim.issuer != caller {
-                return Err(ContractError::UnauthorizedApproval);
-            }
-
-            // Check if already approved
-            if claim.status == ClaimStatus::Approved {
-                return Err(ContractError::ClaimAlreadyApproved);
-            }
-
-            // Update status to Approved
-            claim.status = ClaimStatus::Approved;
-            self.claims.insert(claim_id, &claim);
-
-            // Emit event
-            self.env().emit_event(ClaimApproved { claim_id });
-
-            Ok(())
+        /// Register an organization profile for the caller
+        /// 
+        /// The caller owns the organization and manages its members, who can
+        /// then issue and approve claims with the organization as issuer.
+        /// 
+        /// # Arguments
+        /// * `metadata_uri` - URI pointing to off-chain organization metadata
+        /// 
+        /// # Errors
+        /// * `ProfileAlreadyExists` - If the caller already has a registered profile
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
+        /// 
+        /// # Events
+        /// * `ProfileRegistered` - Emitted when profile is successfully created
+        #[ink(message)]
+        pub fn register_org_profile(&mut self, metadata_uri: String) -> Result<()> {
+            let caller = self.env().caller();
+            self.insert_profile(caller, metadata_uri, true)
         }
 
-        /// Get profile for a specific account
+        /// Register a profile for the caller and link a DID in one call
+        /// 
+        /// Everything is validated before anything is written, so an invalid DID
+        /// leaves the caller without a profile.
         /// 
         /// # Arguments
-        /// * `account_id` - Account to query
+        /// * `metadata_uri` - URI pointing to off-chain profile metadata
+        /// * `did` - Optional DID URI to link right away
         /// 
-        /// # Returns
-        /// * `Option<Profile>` - Profile if it exists, None otherwise
+        /// # Errors
+        /// * `ProfileAlreadyExists` - If the caller already has a registered profile
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
+        /// * `InvalidDid` - If the DID format is invalid or its method is not allowed
+        /// 
+        /// # Events
+        /// * `ProfileRegistered` - Emitted when profile is created
+        /// * `DidLinked` - Emitted right after, if a DID was given
         #[ink(message)]
-        pub fn get_profile(&self, account_id: AccountId) -> Option<Profile> {
-            self.profiles.get(account_id)
+        pub fn onboard(&mut self, metadata_uri: String, did: Option<String>) -> Result<()> {
+            let caller = self.env().caller();
+            self.ensure_can_onboard(caller, &metadata_uri, did.as_deref())?;
+            self.onboard_account(caller, metadata_uri, did)
         }
 
-        /// Get all claims for a specific account
+        /// Onboard `owner` and grant them an approved claim in one call
+        /// (only by automated issuers)
+        /// 
+        /// Lets a platform relayer or contract register a profile, link a DID
+        /// and issue the first credential for a meta-transaction user. Nothing
+        /// is written unless all three steps can succeed.
         /// 
         /// # Arguments
-        /// * `account_id` - Account to query
+        /// * `owner` - Account to onboard
+        /// * `metadata_uri` - URI pointing to off-chain profile metadata
+        /// * `did` - Optional DID URI to link to the new profile
+        /// * `claim_type` - Type of the claim to grant
+        /// * `proof_hash` - Hash of the proof stored off-chain
         /// 
         /// # Returns
-        /// * `Vec<Claim>` - Vector of all claims received by the account
+        /// * `u64` - The ID of the newly created claim
+        /// 
+        /// # Errors
+        /// * `NotAutomatedIssuer` - If the caller is not on the automated issuer list
+        /// * `ProfileAlreadyExists` - If `owner` already has a registered profile
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
+        /// * `InvalidDid` - If the DID format is invalid or its method is not allowed
+        /// * `ClaimTypeFrozen` - If `claim_type` is frozen
+        /// 
+        /// # Events
+        /// * `ProfileRegistered`, then `DidLinked` if a DID was given
+        /// * `ClaimAdded` and `ClaimApproved` for the granted claim
         #[ink(message)]
-        pub fn get_claims(&self, account_id: AccountId) -> Vec<Claim> {
-            // Get claim IDs for the account
-            let claim_ids = self.user_claims.get(account_id).unwrap_or_default();
+        pub fn onboard_with_claim(
+            &mut self,
+            owner: AccountId,
+            metadata_uri: String,
+            did: Option<String>,
+            claim_type: String,
+            proof_hash: Hash,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            if !self.automated_issuers.contains(caller) {
+                return Err(ContractError::NotAutomatedIssuer);
+            }
+            self.ensure_can_onboard(owner, &metadata_uri, did.as_deref())?;
+            self.ensure_not_frozen(&claim_type)?;
 
-            // Collect all claims
-            claim_ids
-                .iter()
-                .filter_map(|&id| self.claims.get(id))
-                .collect()
+            self.onboard_account(owner, metadata_uri, did)?;
+            let mut claim = self.insert_claim(caller, owner, claim_type, proof_hash, None, None, None)?;
+            let now = claim.created_at;
+            self.apply_approval(&mut claim, now)?;
+
+            Ok(claim.id)
         }
 
-        /// Get total number of claims in the system
+        /// Add a member to an organization or change their role
+        /// (only by the organization or one of its admins)
         /// 
-        /// # Returns
-        /// * `u64` - Total number of claims created
+        /// # Arguments
+        /// * `org` - Organization account
+        /// * `member` - Account to add
+        /// * `role` - Role of the member
+        /// 
+        /// # Errors
+        /// * `OrgNotFound` - If `org` has no organization profile
+        /// * `NotOrgAdmin` - If the caller is neither the organization nor an admin
+        /// * `TooManyMembers` - If the organization already has `MAX_ORG_MEMBERS` members
+        /// 
+        /// # Events
+        /// * `MemberAdded` - Emitted when the member is stored
         #[ink(message)]
-        pub fn get_total_claims(&self) -> u64 {
-            self.next_claim_id
+        pub fn add_member(&mut self, org: AccountId, member: AccountId, role: MemberRole) -> Result<()> {
+            self.ensure_org_admin(org)?;
+
+            if !self.org_members.contains((org, member)) {
+                let count = self.org_member_count.get(org).unwrap_or(0);
+                if count >= MAX_ORG_MEMBERS {
+                    return Err(ContractError::TooManyMembers);
+                }
+                self.org_member_count.insert(org, &(count + 1));
+            }
+
+            self.org_members.insert((org, member), &role);
+            self.env().emit_event(MemberAdded { org, member, role });
+
+            Ok(())
         }
 
-        /// Link a KILT DID to the caller's profile
+        /// Remove a member from an organization (only by the organization or one of its admins)
+        /// 
+        /// Claims the member already issued keep the organization as issuer.
         /// 
         /// # Arguments
-        /// * `did` - KILT DID URI (e.g., "did:kilt:light:...")
+        /// * `org` - Organization account
+        /// * `member` - Account to remove
         /// 
         /// # Errors
-        /// * `ProfileNotFound` - If the caller doesn't have a profile
-        /// * `InvalidDid` - If the DID format is invalid
+        /// * `OrgNotFound` - If `org` has no organization profile
+        /// * `NotOrgAdmin` - If the caller is neither the organization nor an admin
+        /// * `NotOrgMember` - If the account is not a member
         /// 
         /// # Events
-        /// * `DidLinked` - Emitted when DID is successfully linked
+        /// * `MemberRemoved` - Emitted when the member is removed
         #[ink(message)]
-        pub fn link_did(&mut self, did: String) -> Result<()> {
-            let caller = self.env().caller();
-
-            // Check if profile exists
-            let mut profile = self.profiles.get(caller).ok_or(ContractError::ProfileNotFound)?;
+        pub fn remove_member(&mut self, org: AccountId, member: AccountId) -> Result<()> {
+            self.ensure_org_admin(org)?;
 
-            // Basic DID format validation (must start with "did:kilt:")
-            if !did.starts_with("did:kilt:") {
-                return Err(ContractError::InvalidDid);
+            if self.org_members.take((org, member)).is_none() {
+                return Err(ContractError::NotOrgMember);
             }
+            let count = self.org_member_count.get(org).unwrap_or(0);
+            self.org_member_count.insert(org, &count.saturating_sub(1));
 
-            // Update profile with DID
-            profile.did = Some(did.clone());
-            self.profiles.insert(caller, &profile);
-
-            // Emit event
-            self.env().emit_event(DidLinked {
-                owner: caller,
-                did: did.clone(),
-            });
+            self.env().emit_event(MemberRemoved { org, member });
 
             Ok(())
         }
 
-        /// Get the DID linked to a profile
+        /// Get the role of an account in an organization
         /// 
         /// # Arguments
-        /// * `account_id` - Account to query
+        /// * `org` - Organization account
+        /// * `member` - Account to query
         /// 
         /// # Returns
-        /// * `Option<String>` - DID if linked, None otherwise
+        /// * `Option<MemberRole>` - Role if the account is a member, None otherwise
         #[ink(message)]
-        pub fn get_did(&self, account_id: AccountId) -> Option<String> {
-            self.profiles.get(account_id).and_then(|p| p.did.clone())
+        pub fn get_member_role(&self, org: AccountId, member: AccountId) -> Option<MemberRole> {
+            self.org_members.get((org, member))
         }
-    }
 
-    // ========================================
-    // UNIT TESTS
-    // ========================================
+        /// Add a claim with an organization as issuer (only by its members)
+        /// 
+        /// # Arguments
+        /// * `org` - Organization issuing the claim
+        /// * `receiver` - Account that will receive the claim
+        /// * `claim_type` - Type of claim (e.g., "employment")
+        /// * `proof_hash` - Hash of the proof stored off-chain
+        /// 
+        /// # Returns
+        /// * `u64` - The ID of the newly created claim
+        /// 
+        /// # Errors
+        /// * `NotOrgMember` - If the caller is not a member of `org`
+        /// * `ClaimTypeFrozen` - If `claim_type` is frozen
+        /// * `ReservedClaimType` - If `claim_type` is reserved and the caller is neither
+        ///   the admin nor an automated issuer
+        /// 
+        /// # Events
+        /// * `ClaimAdded` - Emitted with the organization as issuer
+        #[ink(message)]
+        pub fn add_claim_as_org(
+            &mut self,
+            org: AccountId,
+            receiver: AccountId,
+            claim_type: String,
+            proof_hash: Hash,
+        ) -> Result<u64> {
+            self.ensure_org_member(org)?;
+
+            let claim = self.insert_claim(org, receiver, claim_type, proof_hash, None, None, None)?;
+            Ok(claim.id)
+        }
+
+        /// Approve a claim issued by an organization (only by its members)
+        /// 
+        /// # Arguments
+        /// * `org` - Organization that issued the claim
+        /// * `claim_id` - ID of the claim to approve
+        /// 
+        /// # Errors
+        /// * `NotOrgMember` - If the caller is not a member of `org`
+        /// * Any error of `approve_claim`, with `org` as the approving issuer
+        /// 
+        /// # Events
+        /// * `ClaimApproved` - Emitted when claim is successfully approved
+        #[ink(message)]
+        pub fn approve_claim_as_org(&mut self, org: AccountId, claim_id: u64) -> Result<()> {
+            self.ensure_org_member(org)?;
+            self.approve_claim_of(org, claim_id)
+        }
+
+        /// Fail unless `owner` can be onboarded with `metadata_uri` and `did`
+        fn ensure_can_onboard(&self, owner: AccountId, metadata_uri: &str, did: Option<&str>) -> Result<()> {
+            if self.profiles.contains(owner) {
+                return Err(ContractError::ProfileAlreadyExists);
+            }
+            self.ensure_valid_uri(metadata_uri)?;
+            if did.is_some_and(|did| !self.is_valid_did(did)) {
+                return Err(ContractError::InvalidDid);
+            }
+            Ok(())
+        }
+
+        /// Store a profile for `owner`, link `did` if given and emit the events
+        fn onboard_account(&mut self, owner: AccountId, metadata_uri: String, did: Option<String>) -> Result<()> {
+            self.insert_profile(owner, metadata_uri, false)?;
+            if let Some(did) = did {
+                let mut profile = self.profiles.get(owner).ok_or(ContractError::ProfileNotFound)?;
+                self.set_did(&mut profile, did);
+            }
+            Ok(())
+        }
+
+        /// Store a profile for `owner` and emit `ProfileRegistered`
+        fn insert_profile(&mut self, owner: AccountId, metadata_uri: String, is_org: bool) -> Result<()> {
+            // Check if profile already exists
+            if self.profiles.contains(owner) {
+                return Err(ContractError::ProfileAlreadyExists);
+            }
+            self.ensure_valid_uri(&metadata_uri)?;
+
+            // Create new profile
+            let profile = Profile {
+                owner,
+                metadata_uri: metadata_uri.clone(),
+                did: None,
+                is_org,
+                badges: Vec::new(),
+                did_public: true,
+            };
+
+            // Store profile
+            self.profiles.insert(owner, &profile);
+
+            // Emit event
+            self.env().emit_event(ProfileRegistered {
+                owner,
+                metadata_uri,
+            });
+
+            Ok(())
+        }
+
+        /// Add a new claim to another user
+        /// 
+        /// # Arguments
+        /// * `receiver` - Account that will receive the claim
+        /// * `claim_type` - Type of claim (e.g., "hackathon_win", "job_completed")
+        /// * `proof_hash` - Hash of the proof stored off-chain
+        /// * `activates_at` - Optional time before which the claim is hidden and can't be approved
+        /// * `attests_to` - Optional ID of a claim of the same receiver this claim vouches for
+        /// 
+        /// # Returns
+        /// * `u64` - The ID of the newly created claim
+        /// 
+        /// # Errors
+        /// * `ClaimTypeFrozen` - If `claim_type` is frozen
+        /// * `ReservedClaimType` - If `claim_type` is reserved and the caller is neither
+        ///   the admin nor an automated issuer
+        /// * `InvalidNamespace` - If `claim_type` has a `/` but is not `namespace/name`
+        /// * `NamespaceNotFound` - If the namespace of `claim_type` is not registered
+        /// * `NotNamespaceOwner` - If the caller neither owns nor is a delegate of that namespace
+        /// * `ClaimNotFound` - If the attested claim doesn't exist
+        /// * `AttestationReceiverMismatch` - If the attested claim belongs to another receiver
+        /// * `TooManyAttestations` - If the attested claim has `MAX_ATTESTATIONS` attestations
+        /// * `SingletonViolation` - If the receiver already holds an active claim of a
+        ///   `unique_per_receiver` type
+        /// * `ProofAlreadyBound` - If `proof_hash` backs an active claim of a `unique_proof`
+        ///   type for another receiver
+        /// 
+        /// # Events
+        /// * `ClaimAdded` - Emitted when claim is successfully created
+        #[ink(message)]
+        pub fn add_claim(
+            &mut self,
+            receiver: AccountId,
+            claim_type: String,
+            proof_hash: Hash,
+            activates_at: Option<Timestamp>,
+            attests_to: Option<u64>,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            let claim =
+                self.insert_claim(caller, receiver, claim_type, proof_hash, None, activates_at, attests_to)?;
+            Ok(claim.id)
+        }
+
+        /// Add and approve a claim in one call (only by automated issuers)
+        /// 
+        /// Regular issuers must use `add_claim` followed by `approve_claim`.
+        /// 
+        /// # Arguments
+        /// * `receiver` - Account that will receive the claim
+        /// * `claim_type` - Type of claim (e.g., "job_completed")
+        /// * `proof_hash` - Hash of the proof stored off-chain
+        /// 
+        /// # Returns
+        /// * `u64` - The ID of the newly created claim
+        /// 
+        /// # Errors
+        /// * `NotAutomatedIssuer` - If the caller is not on the automated issuer list
+        /// * `ProfileNotFound` - If approval requires a receiver profile and `receiver` has none
+        /// * `ClaimTypeFrozen` - If `claim_type` is frozen
+        /// 
+        /// # Events
+        /// * `ClaimAdded` - Emitted when claim is created
+        /// * `ClaimApproved` - Emitted right after
+        #[ink(message)]
+        pub fn add_and_approve_claim(
+            &mut self,
+            receiver: AccountId,
+            claim_type: String,
+            proof_hash: Hash,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            if !self.automated_issuers.contains(caller) {
+                return Err(ContractError::NotAutomatedIssuer);
+            }
+            self.ensure_receiver_ready(receiver)?;
+
+            let mut claim = self.insert_claim(caller, receiver, claim_type, proof_hash, None, None, None)?;
+            let now = claim.created_at;
+            self.apply_approval(&mut claim, now)?;
+
+            Ok(claim.id)
+        }
+
+        /// Approve a claim (only by the issuer)
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to approve
+        /// 
+        /// # Errors
+        /// * `ClaimNotFound` - If the claim doesn't exist
+        /// * `UnauthorizedApproval` - If the caller is not the issuer
+        /// * `ClaimAlreadyApproved` - If the claim is already approved
+        /// * `ClaimAlreadyRevoked` - If the claim was revoked
+        /// * `ClaimExpired` - If the claim has expired
+        /// * `ClaimAlreadyRejected` - If the receiver rejected the claim
+        /// * `ClaimNotYetActive` - If the claim is time-locked and not active yet
+        /// * `ClaimStale` - If the claim is older than the pending TTL
+        /// * `ClaimTypeFrozen` - If the claim's type is frozen
+        /// * `ProfileNotFound` - If approval requires a receiver profile and the receiver has none
+        /// 
+        /// # Events
+        /// * `ClaimApproved` - Emitted when claim is successfully approved
+        #[ink(message)]
+        pub fn approve_claim(&mut self, claim_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+            self.approve_claim_of(caller, claim_id)
+        }
+
+        /// Approve a claim on behalf of `issuer`, which must have issued it
+        fn approve_claim_of(&mut self, issuer: AccountId, claim_id: u64) -> Result<()> {
+            // Get claim
+            let mut claim = self.claims.get(claim_id).ok_or(ContractError::ClaimNotFound)?;
+
+            let now = self.env().block_timestamp();
+            let self_attested = self.check_approvable(issuer, &claim, now)?;
+            self.flag_self_attested(&mut claim, self_attested);
+
+            self.apply_approval(&mut claim, now)?;
+
+            Ok(())
+        }
+
+        /// Approve several claims issued by the caller in one call
+        /// 
+        /// Claims that are already approved are skipped. Any other problem
+        /// fails the whole batch before a single claim is approved.
+        /// 
+        /// # Arguments
+        /// * `claim_ids` - IDs of the claims to approve (at most `MAX_APPROVAL_BATCH`)
+        /// 
+        /// # Returns
+        /// * `u32` - Number of claims actually approved
+        /// 
+        /// # Errors
+        /// * `BatchTooLarge` - If more than `MAX_APPROVAL_BATCH` IDs are given
+        /// * `ClaimNotFound` - If any claim doesn't exist
+        /// * `UnauthorizedApproval` - If the caller did not issue any of the claims
+        /// * `ClaimAlreadyRevoked` - If any claim was revoked
+        /// * `ClaimExpired` - If any claim has expired
+        /// * `ClaimAlreadyRejected` - If any claim was rejected by its receiver
+        /// * `ClaimNotYetActive` - If any claim is time-locked and not active yet
+        /// * `ClaimStale` - If any claim is older than the pending TTL
+        /// * `ClaimTypeFrozen` - If the type of any claim is frozen
+        /// * `ProfileNotFound` - If approval requires a receiver profile and any receiver has none
+        /// 
+        /// # Events
+        /// * `ClaimApproved` - Emitted for each approved claim
+        #[ink(message)]
+        pub fn approve_claims_batch(&mut self, claim_ids: Vec<u64>) -> Result<u32> {
+            if claim_ids.len() > MAX_APPROVAL_BATCH {
+                return Err(ContractError::BatchTooLarge);
+            }
+
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+
+            // Validate every claim before touching storage
+            let mut to_approve: Vec<Claim> = Vec::new();
+            for claim_id in claim_ids {
+                let mut claim = self.claims.get(claim_id).ok_or(ContractError::ClaimNotFound)?;
+                let self_attested = match self.check_approvable(caller, &claim, now) {
+                    Ok(self_attested) => self_attested,
+                    // Already approved claims are skipped
+                    Err(ContractError::ClaimAlreadyApproved) => continue,
+                    Err(error) => return Err(error),
+                };
+
+                // Listing a claim twice approves it once
+                if to_approve.iter().all(|c| c.id != claim_id) {
+                    self.flag_self_attested(&mut claim, self_attested);
+                    to_approve.push(claim);
+                }
+            }
+
+            let approved = to_approve.len() as u32;
+            for mut claim in to_approve {
+                self.apply_approval(&mut claim, now)?;
+            }
+
+            Ok(approved)
+        }
+
+        /// Revoke a claim (only by the issuer)
+        /// 
+        /// Both pending and approved claims can be revoked. The claim stays in
+        /// storage with `Revoked` status so its history remains visible.
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to revoke
+        /// 
+        /// # Errors
+        /// * `ClaimNotFound` - If the claim doesn't exist
+        /// * `UnauthorizedApproval` - If the caller is not the issuer
+        /// * `ClaimAlreadyRevoked` - If the claim is already revoked
+        /// * `ClaimExpired` - If the claim has expired
+        /// * `ClaimAlreadyRejected` - If the receiver rejected the claim
+        /// 
+        /// # Events
+        /// * `ClaimRevoked` - Emitted when claim is successfully revoked
+        #[ink(message)]
+        pub fn revoke_claim(&mut self, claim_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            // Get claim
+            let mut claim = self.claims.get(claim_id).ok_or(ContractError::ClaimNotFound)?;
+
+            // Verify caller is the issuer
+            if claim.issuer != caller {
+                return Err(ContractError::UnauthorizedApproval);
+            }
+
+            match claim.status {
+                ClaimStatus::Pending | ClaimStatus::Approved => {}
+                ClaimStatus::Revoked => return Err(ContractError::ClaimAlreadyRevoked),
+                ClaimStatus::Expired => return Err(ContractError::ClaimExpired),
+                ClaimStatus::Rejected => return Err(ContractError::ClaimAlreadyRejected),
+            }
+
+            // Remove an approved claim from the activity of its approval day
+            if claim.status == ClaimStatus::Approved && !claim.self_attested {
+                if let Some(approved_at) = claim.approved_at {
+                    let bucket = (claim.receiver, Self::day_of(approved_at));
+                    let count = self.activity.get(bucket).unwrap_or(0);
+                    self.activity.insert(bucket, &count.saturating_sub(1));
+                    self.forget_approval_time(claim.receiver, &claim.claim_type, approved_at);
+                }
+                self.count_approved_type(claim.receiver, &claim.claim_type, false);
+            }
+
+            // Update status to Revoked
+            claim.status = ClaimStatus::Revoked;
+            self.store_claim(&claim)?;
+
+            let revocation_accumulator = self.record_revocation(caller, claim_id);
+
+            // Emit event
+            self.env().emit_event(ClaimRevoked {
+                claim_id,
+                issuer: caller,
+                revocation_accumulator,
+            });
+
+            Ok(())
+        }
+
+        /// Reject a pending claim (only by the receiver)
+        /// 
+        /// The claim stays in storage with `Rejected` status and is listed for
+        /// its issuer by `get_rejected_claims_by_issuer`. The issuer may issue a
+        /// corrected claim afterwards.
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to reject
+        /// * `rejection_reason_uri` - Optional URI explaining the rejection
+        /// 
+        /// # Errors
+        /// * `ClaimNotFound` - If the claim doesn't exist
+        /// * `NotClaimReceiver` - If the caller is not the receiver
+        /// * `ClaimAlreadyApproved` - If the claim is already approved
+        /// * `ClaimAlreadyRevoked` - If the claim was revoked
+        /// * `ClaimExpired` - If the claim has expired
+        /// * `ClaimAlreadyRejected` - If the claim was already rejected
+        /// * `ClaimNotYetActive` - If the claim is time-locked and not active yet
+        /// * `InvalidRejectionReason` - If the URI is empty or longer than `MAX_REJECTION_URI_LEN`
+        /// 
+        /// # Events
+        /// * `ClaimRejected` - Emitted when the claim is rejected
+        #[ink(message)]
+        pub fn reject_claim(&mut self, claim_id: u64, rejection_reason_uri: Option<String>) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut claim = self.claims.get(claim_id).ok_or(ContractError::ClaimNotFound)?;
+
+            // Verify caller is the receiver
+            if claim.receiver != caller {
+                return Err(ContractError::NotClaimReceiver);
+            }
+
+            // Only pending claims can be rejected
+            match claim.status {
+                ClaimStatus::Pending => {}
+                ClaimStatus::Approved => return Err(ContractError::ClaimAlreadyApproved),
+                ClaimStatus::Revoked => return Err(ContractError::ClaimAlreadyRevoked),
+                ClaimStatus::Expired => return Err(ContractError::ClaimExpired),
+                ClaimStatus::Rejected => return Err(ContractError::ClaimAlreadyRejected),
+            }
+
+            if !Self::is_active(&claim, self.env().block_timestamp()) {
+                return Err(ContractError::ClaimNotYetActive);
+            }
+
+            if rejection_reason_uri
+                .as_ref()
+                .is_some_and(|uri| uri.is_empty() || uri.len() > MAX_REJECTION_URI_LEN)
+            {
+                return Err(ContractError::InvalidRejectionReason);
+            }
+
+            claim.status = ClaimStatus::Rejected;
+            claim.rejection_reason_uri = rejection_reason_uri.clone();
+            self.store_claim(&claim)?;
+
+            // Index the rejection for the issuer
+            let index = self.rejected_count.get(claim.issuer).unwrap_or(0);
+            self.rejected_claims.insert((claim.issuer, index), &claim_id);
+            self.rejected_count.insert(claim.issuer, &(index + 1));
+
+            self.env().emit_event(ClaimRejected {
+                claim_id,
+                issuer: claim.issuer,
+                receiver: caller,
+                rejection_reason_uri,
+            });
+
+            Ok(())
+        }
+
+        /// Expire stale pending claims of a receiver
+        /// 
+        /// Anyone can call this. Each swept claim is removed from the receiver's
+        /// claim index but stays in storage with `Expired` status for history.
+        /// Does nothing while the pending TTL is disabled.
+        /// 
+        /// # Arguments
+        /// * `receiver` - Account whose claims to sweep
+        /// * `limit` - Maximum number of claims to expire in this call
+        /// 
+        /// # Returns
+        /// * `u32` - Number of claims expired
+        /// 
+        /// # Events
+        /// * `ClaimExpired` - Emitted for each expired claim
+        #[ink(message)]
+        pub fn sweep_stale_claims(&mut self, receiver: AccountId, limit: u32) -> u32 {
+            let now = self.env().block_timestamp();
+            let claim_ids = self.user_claims.get(receiver).unwrap_or_default();
+
+            let mut swept = 0u32;
+            let mut kept = Vec::with_capacity(claim_ids.len());
+
+            for claim_id in claim_ids {
+                if swept < limit {
+                    if let Some(mut claim) = self.claims.get(claim_id) {
+                        if self.is_stale(&claim, now) {
+                            claim.status = ClaimStatus::Expired;
+                            if self.store_claim(&claim).is_err() {
+                                kept.push(claim_id);
+                                continue;
+                            }
+                            self.env().emit_event(ClaimExpired { claim_id, receiver });
+                            swept += 1;
+                            continue;
+                        }
+                    }
+                }
+                kept.push(claim_id);
+            }
+
+            if swept > 0 {
+                self.user_claims.insert(receiver, &kept);
+            }
+
+            swept
+        }
+
+        /// Set the maximum age of an approvable pending claim (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `ttl_ms` - Maximum age in milliseconds, or 0 to disable the deadline
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        #[ink(message)]
+        pub fn set_pending_ttl(&mut self, ttl_ms: u64) -> Result<()> {
+            self.ensure_admin()?;
+            self.pending_ttl_ms = ttl_ms;
+            Ok(())
+        }
+
+        /// Get the maximum age of an approvable pending claim
+        /// 
+        /// # Returns
+        /// * `u64` - Maximum age in milliseconds, 0 if disabled
+        #[ink(message)]
+        pub fn get_pending_ttl(&self) -> u64 {
+            self.pending_ttl_ms
+        }
+
+        /// Set the age after which approved claims stop counting (only by the admin)
+        /// 
+        /// Stale claims stay readable as history, but no longer count toward
+        /// reputation, tiers or `get_approved_count_since`, and `verify_claim`
+        /// reports them as `Stale`. The rule is computed on read from
+        /// `approved_at`, so changing the horizon applies to every claim at once.
+        /// 
+        /// # Arguments
+        /// * `stale_after_ms` - Age in milliseconds, or 0 to disable the horizon
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        #[ink(message)]
+        pub fn set_stale_after(&mut self, stale_after_ms: u64) -> Result<()> {
+            self.ensure_admin()?;
+            self.stale_after_ms = stale_after_ms;
+            Ok(())
+        }
+
+        /// Get the age after which approved claims stop counting
+        /// 
+        /// # Returns
+        /// * `u64` - Age in milliseconds, 0 if disabled
+        #[ink(message)]
+        pub fn get_stale_after(&self) -> u64 {
+            self.stale_after_ms
+        }
+
+        /// Require a claim's receiver to have a profile before the claim can be
+        /// approved (only by the admin)
+        /// 
+        /// Claims can still be added for accounts without a profile; they stay
+        /// pending until the receiver registers.
+        /// 
+        /// # Arguments
+        /// * `required` - Whether approval needs a receiver profile
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        #[ink(message)]
+        pub fn set_require_receiver_profile(&mut self, required: bool) -> Result<()> {
+            self.ensure_admin()?;
+            self.require_receiver_profile = required;
+            Ok(())
+        }
+
+        /// Check whether approval requires the receiver to have a profile
+        /// 
+        /// # Returns
+        /// * `bool` - True if `approve_claim` fails for receivers without a profile
+        #[ink(message)]
+        pub fn get_require_receiver_profile(&self) -> bool {
+            self.require_receiver_profile
+        }
+
+        /// Set how claims between accounts sharing a DID are handled (only by the admin)
+        /// 
+        /// Issuer and receiver are compared when a claim is added and again
+        /// when it is approved, since either may link a DID in between. Claims
+        /// where either side has no DID are never affected. Claims flagged
+        /// earlier stay flagged when the policy changes.
+        /// 
+        /// # Arguments
+        /// * `policy` - `Flag` to keep such claims out of reputation, `Reject` to refuse them
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        #[ink(message)]
+        pub fn set_self_attestation_policy(&mut self, policy: SelfAttestationPolicy) -> Result<()> {
+            self.ensure_admin()?;
+            self.self_attestation_policy = policy;
+            Ok(())
+        }
+
+        /// Get how claims between accounts sharing a DID are handled
+        /// 
+        /// # Returns
+        /// * `SelfAttestationPolicy` - Current policy, `Flag` by default
+        #[ink(message)]
+        pub fn get_self_attestation_policy(&self) -> SelfAttestationPolicy {
+            self.self_attestation_policy
+        }
+
+        /// Check whether an approved claim is older than the sunset horizon
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to check
+        /// 
+        /// # Returns
+        /// * `bool` - True if the claim is approved and its approval reached the
+        ///   horizon; false for other statuses, missing claims or without a horizon
+        #[ink(message)]
+        pub fn is_claim_stale(&self, claim_id: u64) -> bool {
+            self.claims
+                .get(claim_id)
+                .is_some_and(|claim| self.is_sunset(&claim))
+        }
+
+        /// Get the admin account
+        /// 
+        /// # Returns
+        /// * `AccountId` - Account allowed to change contract settings
+        #[ink(message)]
+        pub fn get_admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Get profile for a specific account
+        /// 
+        /// The DID of a profile that hides it is only returned to its owner and
+        /// the viewers it granted access to.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Option<Profile>` - Profile if it exists, None otherwise
+        #[ink(message)]
+        pub fn get_profile(&self, account_id: AccountId) -> Option<Profile> {
+            self.profiles.get(account_id).map(|profile| self.disclosed(profile))
+        }
+
+        /// Get the badges earned by an account
+        /// 
+        /// Badges are never removed, even if the claims that earned them are revoked.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<Badge>` - Earned badges, oldest first (empty without a profile)
+        #[ink(message)]
+        pub fn get_badges(&self, account_id: AccountId) -> Vec<Badge> {
+            self.profiles.get(account_id).map(|p| p.badges).unwrap_or_default()
+        }
+
+        /// Get a compact reputation snapshot of an account
+        /// 
+        /// Built from counters maintained on approval and revocation, so the
+        /// cost does not grow with the number of claims received. Stale claims
+        /// don't count toward the score or the per-type counts. While the
+        /// admin restricts reputation, only the account itself, its access
+        /// grantees and automated issuers get an answer; everyone else can use
+        /// `get_reputation_tier`.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Option<ProfileSnapshot>` - Snapshot if the account has a profile and the
+        ///   caller may see it, None otherwise
+        #[ink(message)]
+        pub fn get_profile_snapshot(&self, account_id: AccountId) -> Option<ProfileSnapshot> {
+            let caller = self.env().caller();
+            if self.reputation_restricted && !self.may_view_as(account_id) && !self.automated_issuers.contains(caller) {
+                return None;
+            }
+
+            let profile = self.profiles.get(account_id)?;
+            let verified = profile.did.is_some();
+
+            let mut approved_by_type = self.counted_by_type(account_id);
+            let reputation_score = Self::total_approved(&approved_by_type);
+
+            // Most frequent first, ties by name so the order is stable
+            approved_by_type.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            approved_by_type.truncate(MAX_SNAPSHOT_CLAIM_TYPES);
+
+            Some(ProfileSnapshot {
+                verified,
+                profile: self.disclosed(profile),
+                approved_by_type,
+                reputation_score,
+                last_claim_at: self.last_claim_at.get(account_id),
+            })
+        }
+
+        /// Get the coarse reputation tier of an account
+        /// 
+        /// Computed on read from the approved claim counters, so it follows
+        /// approvals, revocations, threshold and horizon changes without being
+        /// stored. Stale claims don't count. Accounts without approved claims
+        /// are Bronze.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Tier` - Gold, Silver or Bronze
+        #[ink(message)]
+        pub fn get_reputation_tier(&self, account_id: AccountId) -> Tier {
+            let approved_by_type = self.counted_by_type(account_id);
+            let score = Self::total_approved(&approved_by_type);
+            let (silver, gold) = self.tier_thresholds;
+
+            if score >= gold {
+                Tier::Gold
+            } else if score >= silver {
+                Tier::Silver
+            } else {
+                Tier::Bronze
+            }
+        }
+
+        /// Set the approved claim counts needed for the Silver and Gold tiers (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `silver` - Approved claims needed for Silver
+        /// * `gold` - Approved claims needed for Gold
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `InvalidTierThresholds` - If `silver` is zero or not below `gold`
+        /// 
+        /// # Events
+        /// * `TierThresholdsUpdated` - Emitted when the thresholds are set
+        #[ink(message)]
+        pub fn set_tier_thresholds(&mut self, silver: u32, gold: u32) -> Result<()> {
+            self.ensure_admin()?;
+
+            if silver == 0 || silver >= gold {
+                return Err(ContractError::InvalidTierThresholds);
+            }
+
+            self.tier_thresholds = (silver, gold);
+            self.env().emit_event(TierThresholdsUpdated { silver, gold });
+
+            Ok(())
+        }
+
+        /// Get the approved claim counts needed for the Silver and Gold tiers
+        /// 
+        /// # Returns
+        /// * `(u32, u32)` - Silver and Gold thresholds
+        #[ink(message)]
+        pub fn get_tier_thresholds(&self) -> (u32, u32) {
+            self.tier_thresholds
+        }
+
+        /// Restrict `get_profile_snapshot` to the account itself and automated
+        /// issuers, or open it again (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `restricted` - Whether detailed reputation is restricted
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        #[ink(message)]
+        pub fn set_reputation_restricted(&mut self, restricted: bool) -> Result<()> {
+            self.ensure_admin()?;
+            self.reputation_restricted = restricted;
+            Ok(())
+        }
+
+        /// Whether `get_profile_snapshot` is restricted
+        /// 
+        /// # Returns
+        /// * `bool` - True if only the account itself and automated issuers get snapshots
+        #[ink(message)]
+        pub fn is_reputation_restricted(&self) -> bool {
+            self.reputation_restricted
+        }
+
+        /// Get the claims of a specific account
+        /// 
+        /// Returns at most `MAX_BOUNDED_CLAIMS` claims, oldest first. Use
+        /// `get_claims_page` to read the rest, or `get_claims_bounded` to learn
+        /// whether anything was left out.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<Claim>` - First active claims received by the account
+        #[ink(message)]
+        pub fn get_claims(&self, account_id: AccountId) -> Vec<Claim> {
+            self.bounded_claims(account_id).claims
+        }
+
+        /// Mark all claims up to and including `up_to_claim_id` as seen by the caller
+        /// 
+        /// # Arguments
+        /// * `up_to_claim_id` - Highest claim ID the caller has seen
+        /// 
+        /// # Errors
+        /// * `ClaimNotFound` - If no claim with that ID has been issued yet
+        /// * `SeenWatermarkRegression` - If it is below the caller's current watermark
+        #[ink(message)]
+        pub fn mark_claims_seen(&mut self, up_to_claim_id: u64) -> Result<()> {
+            let caller = self.env().caller();
+
+            // Marking future IDs would hide claims not issued yet
+            if up_to_claim_id >= self.next_claim_id {
+                return Err(ContractError::ClaimNotFound);
+            }
+
+            // Watermark only moves forward
+            if let Some(current) = self.last_seen.get(caller) {
+                if up_to_claim_id < current {
+                    return Err(ContractError::SeenWatermarkRegression);
+                }
+            }
+
+            self.last_seen.insert(caller, &up_to_claim_id);
+
+            Ok(())
+        }
+
+        /// Get the highest claim ID an account has marked as seen
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Option<u64>` - Watermark, or None if the account never marked claims as seen
+        #[ink(message)]
+        pub fn get_last_seen(&self, account_id: AccountId) -> Option<u64> {
+            self.last_seen.get(account_id)
+        }
+
+        /// Get claims received by an account since its seen watermark
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// * `limit` - Maximum number of claims to return (capped at `MAX_UNSEEN_CLAIMS`)
+        /// * `order` - Order of the returned claims
+        /// 
+        /// # Returns
+        /// * `Vec<Claim>` - First `limit` unseen claims in `order`
+        #[ink(message)]
+        pub fn get_unseen_claims(&self, account_id: AccountId, limit: u32, order: SortOrder) -> Vec<Claim> {
+            let last_seen = self.last_seen.get(account_id);
+            let limit = (limit as usize).min(MAX_UNSEEN_CLAIMS);
+
+            let claim_ids: Vec<u64> = self
+                .user_claims
+                .get(account_id)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|&id| last_seen.is_none_or(|seen| id > seen))
+                .collect();
+
+            self.ordered_claims(claim_ids, 0, limit, order)
+        }
+
+        /// Get a page of the claims received by an account
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// * `offset` - Number of claims to skip in `order`
+        /// * `limit` - Maximum number of claims to return (capped at `MAX_CLAIMS_PAGE`)
+        /// * `order` - Order of the returned claims
+        /// 
+        /// # Returns
+        /// * `Vec<Claim>` - Active claims in `order`
+        #[ink(message)]
+        pub fn get_claims_page(&self, account_id: AccountId, offset: u32, limit: u32, order: SortOrder) -> Vec<Claim> {
+            let limit = (limit as usize).min(MAX_CLAIMS_PAGE);
+            let claim_ids = self.user_claims.get(account_id).unwrap_or_default();
+
+            self.ordered_claims(claim_ids, offset as usize, limit, order)
+        }
+
+        /// Get claims of an issuer that were rejected by their receivers
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to query
+        /// * `offset` - Number of rejections to skip, oldest first
+        /// * `limit` - Maximum number of claims to return (capped at `MAX_REJECTED_PAGE`)
+        /// 
+        /// # Returns
+        /// * `Vec<Claim>` - Rejected claims in rejection order
+        #[ink(message)]
+        pub fn get_rejected_claims_by_issuer(&self, issuer: AccountId, offset: u32, limit: u32) -> Vec<Claim> {
+            let count = self.rejected_count.get(issuer).unwrap_or(0);
+            let end = offset.saturating_add(limit.min(MAX_REJECTED_PAGE)).min(count);
+
+            (offset..end)
+                .filter_map(|index| self.rejected_claims.get((issuer, index)))
+                .filter_map(|claim_id| self.claims.get(claim_id))
+                .collect()
+        }
+
+        /// Get an issuer's revocation accumulator and revocation count
+        /// 
+        /// The accumulator starts at the zero hash and becomes
+        /// `blake2x256(accumulator ++ claim_id)` on every revocation, so a
+        /// verifier can recompute it from the ordered list of revoked claim IDs
+        /// (see `ClaimRevoked`) and compare a single hash.
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to query
+        /// 
+        /// # Returns
+        /// * `(Hash, u32)` - Current accumulator and number of revocations
+        #[ink(message)]
+        pub fn get_revocation_state(&self, issuer: AccountId) -> (Hash, u32) {
+            self.revocation_state.get(issuer).unwrap_or_default()
+        }
+
+        /// Get the claims an issuer revoked most recently
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<u64>` - Up to `MAX_RECENT_REVOCATIONS` revoked claim IDs, oldest first
+        #[ink(message)]
+        pub fn get_recent_revocations(&self, issuer: AccountId) -> Vec<u64> {
+            self.recent_revocations.get(issuer).unwrap_or_default()
+        }
+
+        /// Compute a commitment to an account's profile and approved claims
+        /// 
+        /// Blake2x256 hash of the SCALE encoding of the tuple
+        /// `(owner: AccountId, metadata_uri: String, did: Option<String>,
+        /// is_org: bool, claims: Vec<(u64, Hash)>)`, where `claims` holds the ID
+        /// and proof hash of every approved claim received by the account, in
+        /// issuance order. An account without a profile encodes an empty
+        /// `metadata_uri`, no DID and `false`. Badges and the DID visibility are
+        /// left out. The result stays the same until the profile changes or a
+        /// claim of the account is approved or revoked.
+        /// 
+        /// # Arguments
+        /// * `account` - Account to export
+        /// 
+        /// # Returns
+        /// * `Hash` - Commitment a future registry can verify imports against
+        #[ink(message)]
+        pub fn export_profile_commitment(&self, account: AccountId) -> Hash {
+            let (metadata_uri, did, is_org) = match self.profiles.get(account) {
+                Some(profile) => (profile.metadata_uri, profile.did, profile.is_org),
+                None => (String::new(), None, false),
+            };
+            let claims: Vec<(u64, Hash)> = self
+                .user_claims
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|claim_id| self.claims.get(claim_id))
+                .filter(|claim| claim.status == ClaimStatus::Approved)
+                .map(|claim| (claim.id, claim.proof_hash))
+                .collect();
+
+            Hash::from(
+                self.env()
+                    .hash_encoded::<ink::env::hash::Blake2x256, _>(&(account, metadata_uri, did, is_org, claims)),
+            )
+        }
+
+        /// Record that an account migrates to another registry (only by the admin)
+        /// 
+        /// The commitment must match the account's current
+        /// `export_profile_commitment`, so a stale export can't be recorded.
+        /// Recording again replaces the previous record.
+        /// 
+        /// # Arguments
+        /// * `account` - Migrating account
+        /// * `destination` - Registry contract the account moves to
+        /// * `commitment` - The account's exported profile commitment
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `CommitmentMismatch` - If the commitment differs from the current export
+        /// 
+        /// # Events
+        /// * `MigrationRecorded` - Emitted when the migration is stored
+        #[ink(message)]
+        pub fn record_migration(&mut self, account: AccountId, destination: AccountId, commitment: Hash) -> Result<()> {
+            self.ensure_admin()?;
+            if commitment != self.export_profile_commitment(account) {
+                return Err(ContractError::CommitmentMismatch);
+            }
+
+            self.migrations.insert(account, &(destination, commitment));
+            self.env().emit_event(MigrationRecorded {
+                account,
+                destination,
+                commitment,
+            });
+
+            Ok(())
+        }
+
+        /// Get an account's recorded migration
+        /// 
+        /// # Arguments
+        /// * `account` - Account to query
+        /// 
+        /// # Returns
+        /// * `Option<(AccountId, Hash)>` - Destination registry and commitment, if recorded
+        #[ink(message)]
+        pub fn get_migration(&self, account: AccountId) -> Option<(AccountId, Hash)> {
+            self.migrations.get(account)
+        }
+
+        /// Get the claims attesting to a claim
+        /// 
+        /// The reverse of `Claim::attests_to`. Revoking a claim doesn't touch
+        /// its attestations; use `verify_claim_with_parent` to check both.
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the attested claim
+        /// 
+        /// # Returns
+        /// * `Vec<u64>` - Up to `MAX_ATTESTATIONS` attesting claim IDs, oldest first
+        #[ink(message)]
+        pub fn get_attestations_for(&self, claim_id: u64) -> Vec<u64> {
+            self.attestations.get(claim_id).unwrap_or_default()
+        }
+
+        /// Verify a claim and report the status of the claim it attests to
+        /// 
+        /// The attested claim is checked against its own receiver and proof, so
+        /// its result only reflects its status (approval, revocation, expiry).
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to verify
+        /// * `expected_receiver` - Account the claim must belong to
+        /// * `expected_proof` - Proof hash the claim must carry
+        /// 
+        /// # Returns
+        /// * `(VerificationResult, Option<VerificationResult>)` - Result of `verify_claim`
+        ///   and, if the claim is valid and attests to another claim, that claim's result
+        #[ink(message)]
+        pub fn verify_claim_with_parent(
+            &self,
+            claim_id: u64,
+            expected_receiver: AccountId,
+            expected_proof: Hash,
+        ) -> (VerificationResult, Option<VerificationResult>) {
+            let result = self.verify_claim(claim_id, expected_receiver, expected_proof);
+            if result != VerificationResult::Valid {
+                return (result, None);
+            }
+
+            let parent = self
+                .get_claim(claim_id)
+                .and_then(|claim| claim.attests_to)
+                .map(|parent_id| match self.get_claim(parent_id) {
+                    Some(parent) => self.verify_claim(parent_id, parent.receiver, parent.proof_hash),
+                    None => VerificationResult::NotFound,
+                });
+            (result, parent)
+        }
+
+        /// Get the number of claims of an issuer rejected by their receivers
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to query
+        /// 
+        /// # Returns
+        /// * `u32` - Number of rejected claims
+        #[ink(message)]
+        pub fn get_rejected_count(&self, issuer: AccountId) -> u32 {
+            self.rejected_count.get(issuer).unwrap_or(0)
+        }
+
+        /// Get the claim schema version new claims are created with
+        /// 
+        /// # Returns
+        /// * `u16` - Value of `CLAIM_SCHEMA_VERSION`
+        #[ink(message)]
+        pub fn get_schema_version(&self) -> u16 {
+            CLAIM_SCHEMA_VERSION
+        }
+
+        /// Get total number of claims in the system
+        /// 
+        /// # Returns
+        /// * `u64` - Total number of claims created
+        #[ink(message)]
+        pub fn get_total_claims(&self) -> u64 {
+            self.next_claim_id
+        }
+
+        /// Get approved claims received per day, for activity graphs
+        /// 
+        /// Days are counted since the Unix epoch (`timestamp / MS_PER_DAY`).
+        /// The range is inclusive and covers at most `MAX_ACTIVITY_RANGE_DAYS`
+        /// days starting at `start_day`; later days are ignored.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// * `start_day` - First day of the range
+        /// * `end_day` - Last day of the range
+        /// 
+        /// # Returns
+        /// * `Vec<(u32, u16)>` - (day, approved claims) for every day with activity
+        #[ink(message)]
+        pub fn get_activity(&self, account_id: AccountId, start_day: u32, end_day: u32) -> Vec<(u32, u16)> {
+            let last_day = end_day.min(start_day.saturating_add(MAX_ACTIVITY_RANGE_DAYS - 1));
+
+            (start_day..=last_day)
+                .filter_map(|day| {
+                    self.activity
+                        .get((account_id, day))
+                        .filter(|&count| count > 0)
+                        .map(|count| (day, count))
+                })
+                .collect()
+        }
+
+        /// Link a DID to the caller's profile
+        /// 
+        /// The DID must start with an allowed method prefix (see `get_did_methods`),
+        /// be at most `MAX_DID_LEN` bytes, and have a non-empty method-specific
+        /// identifier made of DID syntax characters.
+        /// 
+        /// # Arguments
+        /// * `did` - DID URI (e.g., "did:kilt:light:...")
+        /// 
+        /// # Errors
+        /// * `ProfileNotFound` - If the caller doesn't have a profile
+        /// * `InvalidDid` - If the DID format is invalid or its method is not allowed
+        /// 
+        /// # Events
+        /// * `DidLinked` - Emitted when DID is successfully linked
+        #[ink(message)]
+        pub fn link_did(&mut self, did: String) -> Result<()> {
+            let caller = self.env().caller();
+
+            // Check if profile exists
+            let mut profile = self.profiles.get(caller).ok_or(ContractError::ProfileNotFound)?;
+
+            // DID format validation against the allowed methods
+            if !self.is_valid_did(&did) {
+                return Err(ContractError::InvalidDid);
+            }
+
+            self.set_did(&mut profile, did);
+
+            Ok(())
+        }
+
+        /// Link a validated DID to `profile`, store it and emit `DidLinked`
+        fn set_did(&mut self, profile: &mut Profile, did: String) {
+            profile.did = Some(did.clone());
+            self.profiles.insert(profile.owner, profile);
+
+            self.env().emit_event(DidLinked {
+                owner: profile.owner,
+                did,
+            });
+        }
+
+        /// Show or hide the caller's DID from other accounts
+        /// 
+        /// A hidden DID is still linked: the owner and its access grantees keep
+        /// seeing it, and verifiers that already know it can check it with
+        /// `prove_did_linked`.
+        /// It only stops `get_did`, `get_profile` and `get_profile_snapshot`
+        /// from disclosing it; the `DidLinked` event and raw storage remain public.
+        /// 
+        /// # Arguments
+        /// * `public` - Whether other accounts may read the DID
+        /// 
+        /// # Errors
+        /// * `ProfileNotFound` - If the caller doesn't have a profile
+        /// 
+        /// # Events
+        /// * `DidVisibilityChanged` - Emitted when the visibility is set
+        #[ink(message)]
+        pub fn set_did_visibility(&mut self, public: bool) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut profile = self.profiles.get(caller).ok_or(ContractError::ProfileNotFound)?;
+            profile.did_public = public;
+            self.profiles.insert(caller, &profile);
+
+            self.env().emit_event(DidVisibilityChanged { owner: caller, public });
+
+            Ok(())
+        }
+
+        /// Choose which issuers may add claims to the caller
+        /// 
+        /// Under `TrustedIssuersOnly` the admin, automated issuers and the
+        /// caller's allowlist may issue; under `AllowlistOnly` only the
+        /// allowlist may, automated issuers included. The policy is checked
+        /// when a claim is added, so claims added earlier are untouched.
+        /// 
+        /// # Arguments
+        /// * `policy` - New claim policy
+        /// 
+        /// # Errors
+        /// * `ProfileNotFound` - If the caller doesn't have a profile
+        /// 
+        /// # Events
+        /// * `ClaimPolicySet` - Emitted when the policy is set
+        #[ink(message)]
+        pub fn set_claim_policy(&mut self, policy: ClaimPolicy) -> Result<()> {
+            let caller = self.env().caller();
+
+            if !self.profiles.contains(caller) {
+                return Err(ContractError::ProfileNotFound);
+            }
+
+            self.claim_policies.insert(caller, &policy);
+            self.env().emit_event(ClaimPolicySet { owner: caller, policy });
+
+            Ok(())
+        }
+
+        /// Add an issuer to the caller's allowlist
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to admit under a restrictive claim policy
+        /// 
+        /// # Errors
+        /// * `ProfileNotFound` - If the caller doesn't have a profile
+        /// * `IssuerAlreadyAllowed` - If the issuer is already on the allowlist
+        /// * `TooManyAllowedIssuers` - If the allowlist holds `MAX_ALLOWED_ISSUERS` issuers
+        /// 
+        /// # Events
+        /// * `IssuerAllowed` - Emitted when the issuer is added
+        #[ink(message)]
+        pub fn allow_issuer(&mut self, issuer: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+
+            if !self.profiles.contains(caller) {
+                return Err(ContractError::ProfileNotFound);
+            }
+
+            let mut allowed = self.allowed_issuers.get(caller).unwrap_or_default();
+            if allowed.contains(&issuer) {
+                return Err(ContractError::IssuerAlreadyAllowed);
+            }
+            if allowed.len() >= MAX_ALLOWED_ISSUERS {
+                return Err(ContractError::TooManyAllowedIssuers);
+            }
+
+            allowed.push(issuer);
+            self.allowed_issuers.insert(caller, &allowed);
+            self.env().emit_event(IssuerAllowed { owner: caller, issuer });
+
+            Ok(())
+        }
+
+        /// Remove an issuer from the caller's allowlist
+        /// 
+        /// Pending claims the issuer already added stay approvable.
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to remove
+        /// 
+        /// # Errors
+        /// * `IssuerNotListed` - If the issuer is not on the allowlist
+        /// 
+        /// # Events
+        /// * `IssuerDisallowed` - Emitted when the issuer is removed
+        #[ink(message)]
+        pub fn disallow_issuer(&mut self, issuer: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+
+            let mut allowed = self.allowed_issuers.get(caller).unwrap_or_default();
+            let index = allowed
+                .iter()
+                .position(|account| *account == issuer)
+                .ok_or(ContractError::IssuerNotListed)?;
+
+            allowed.remove(index);
+            self.allowed_issuers.insert(caller, &allowed);
+            self.env().emit_event(IssuerDisallowed { owner: caller, issuer });
+
+            Ok(())
+        }
+
+        /// Get an account's claim policy
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `ClaimPolicy` - Current policy, `Open` unless the account set one
+        #[ink(message)]
+        pub fn get_claim_policy(&self, account_id: AccountId) -> ClaimPolicy {
+            self.claim_policies.get(account_id).unwrap_or_default()
+        }
+
+        /// Get the issuers on an account's allowlist
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<AccountId>` - Allowed issuers, oldest first
+        #[ink(message)]
+        pub fn get_allowed_issuers(&self, account_id: AccountId) -> Vec<AccountId> {
+            self.allowed_issuers.get(account_id).unwrap_or_default()
+        }
+
+        /// Subscribe a contract to the approvals of an account's claims
+        /// 
+        /// On every approval of a claim received by `account_watched`, the
+        /// registry calls `on_claim_approved` on `callback_contract`. Calls are
+        /// best-effort: a failing subscriber never blocks the approval. Only
+        /// the watched account, or a subscriber it approved with
+        /// `set_subscriber_approval`, can subscribe contracts to its approvals.
+        /// 
+        /// # Arguments
+        /// * `account_watched` - Receiver whose approvals are reported
+        /// * `callback_contract` - Contract implementing `ClaimSubscriber`
+        /// 
+        /// # Errors
+        /// * `SubscriberNotApproved` - If the caller is neither the watched account nor approved by it
+        /// * `NotAContract` - If `callback_contract` is not a contract
+        /// * `AlreadySubscribed` - If `callback_contract` already watches the account
+        /// * `TooManySubscriptions` - If the account has `MAX_SUBSCRIPTIONS` subscriptions
+        /// 
+        /// # Events
+        /// * `Subscribed` - Emitted when the subscription is stored
+        #[ink(message)]
+        pub fn subscribe(&mut self, account_watched: AccountId, callback_contract: AccountId) -> Result<()> {
+            let subscriber = self.env().caller();
+            if subscriber != account_watched && !self.subscriber_approvals.contains((account_watched, subscriber)) {
+                return Err(ContractError::SubscriberNotApproved);
+            }
+
+            if !self.env().is_contract(&callback_contract) {
+                return Err(ContractError::NotAContract);
+            }
+
+            let mut subscriptions = self.subscriptions.get(account_watched).unwrap_or_default();
+            if subscriptions.iter().any(|s| s.callback_contract == callback_contract) {
+                return Err(ContractError::AlreadySubscribed);
+            }
+            if subscriptions.len() >= MAX_SUBSCRIPTIONS {
+                return Err(ContractError::TooManySubscriptions);
+            }
+
+            subscriptions.push(Subscription { subscriber, callback_contract });
+            self.subscriptions.insert(account_watched, &subscriptions);
+
+            self.env().emit_event(Subscribed {
+                account_watched,
+                callback_contract,
+                subscriber,
+            });
+
+            Ok(())
+        }
+
+        /// Allow or stop an account subscribing contracts to the caller's approvals
+        /// 
+        /// Revoking keeps the subscriptions the account already registered;
+        /// the caller removes them with `unsubscribe`.
+        /// 
+        /// # Arguments
+        /// * `subscriber` - Account to approve or revoke
+        /// * `approved` - Whether the account may subscribe
+        /// 
+        /// # Events
+        /// * `SubscriberApprovalSet` - Emitted with the new approval
+        #[ink(message)]
+        pub fn set_subscriber_approval(&mut self, subscriber: AccountId, approved: bool) -> Result<()> {
+            let account_watched = self.env().caller();
+            if approved {
+                self.subscriber_approvals.insert((account_watched, subscriber), &());
+            } else {
+                self.subscriber_approvals.remove((account_watched, subscriber));
+            }
+
+            self.env().emit_event(SubscriberApprovalSet {
+                account_watched,
+                subscriber,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Check whether an account may subscribe contracts to another's approvals
+        /// 
+        /// # Arguments
+        /// * `account_watched` - Watched account
+        /// * `subscriber` - Account to check
+        /// 
+        /// # Returns
+        /// * `bool` - True for the watched account itself and the subscribers it approved
+        #[ink(message)]
+        pub fn is_subscriber_approved(&self, account_watched: AccountId, subscriber: AccountId) -> bool {
+            subscriber == account_watched || self.subscriber_approvals.contains((account_watched, subscriber))
+        }
+
+        /// Remove a subscription (by its subscriber or the watched account)
+        /// 
+        /// # Arguments
+        /// * `account_watched` - Receiver whose approvals are reported
+        /// * `callback_contract` - Contract to stop notifying
+        /// 
+        /// # Errors
+        /// * `SubscriptionNotFound` - If `callback_contract` doesn't watch the account
+        /// * `NotSubscriber` - If the caller is neither the subscriber nor the watched account
+        /// 
+        /// # Events
+        /// * `Unsubscribed` - Emitted when the subscription is removed
+        #[ink(message)]
+        pub fn unsubscribe(&mut self, account_watched: AccountId, callback_contract: AccountId) -> Result<()> {
+            let mut subscriptions = self.subscriptions.get(account_watched).unwrap_or_default();
+            let index = subscriptions
+                .iter()
+                .position(|s| s.callback_contract == callback_contract)
+                .ok_or(ContractError::SubscriptionNotFound)?;
+
+            let caller = self.env().caller();
+            if caller != subscriptions[index].subscriber && caller != account_watched {
+                return Err(ContractError::NotSubscriber);
+            }
+
+            subscriptions.remove(index);
+            if subscriptions.is_empty() {
+                self.subscriptions.remove(account_watched);
+            } else {
+                self.subscriptions.insert(account_watched, &subscriptions);
+            }
+
+            self.env().emit_event(Unsubscribed {
+                account_watched,
+                callback_contract,
+            });
+
+            Ok(())
+        }
+
+        /// Get the subscriptions to an account's approvals
+        /// 
+        /// # Arguments
+        /// * `account_watched` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<Subscription>` - Subscriptions, oldest first
+        #[ink(message)]
+        pub fn get_subscriptions(&self, account_watched: AccountId) -> Vec<Subscription> {
+            self.subscriptions.get(account_watched).unwrap_or_default()
+        }
+
+        /// Let a viewer read the caller's private data until a deadline
+        /// 
+        /// Until `expires_at`, the viewer is treated like the owner by every
+        /// getter that hides data from other accounts: the hidden DID and the
+        /// restricted profile snapshot. Granting again replaces the deadline.
+        /// 
+        /// # Arguments
+        /// * `viewer` - Account to grant access to
+        /// * `expires_at` - Block timestamp from which the grant no longer applies
+        /// 
+        /// # Errors
+        /// * `ProfileNotFound` - If the caller doesn't have a profile
+        /// * `InvalidGrant` - If `expires_at` is not in the future or `viewer` is the caller
+        /// * `TooManyGrants` - If `MAX_ACCESS_GRANTS` other viewers hold a grant
+        /// 
+        /// # Events
+        /// * `AccessGranted` - Emitted when the grant is stored
+        #[ink(message)]
+        pub fn grant_access(&mut self, viewer: AccountId, expires_at: Timestamp) -> Result<()> {
+            let caller = self.env().caller();
+
+            if !self.profiles.contains(caller) {
+                return Err(ContractError::ProfileNotFound);
+            }
+            if viewer == caller || expires_at <= self.env().block_timestamp() {
+                return Err(ContractError::InvalidGrant);
+            }
+
+            let mut viewers = self.access_viewers.get(caller).unwrap_or_default();
+            if !viewers.contains(&viewer) {
+                if viewers.len() >= MAX_ACCESS_GRANTS {
+                    return Err(ContractError::TooManyGrants);
+                }
+                viewers.push(viewer);
+                self.access_viewers.insert(caller, &viewers);
+            }
+
+            self.access_grants.insert((caller, viewer), &expires_at);
+            self.env().emit_event(AccessGranted {
+                owner: caller,
+                viewer,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke a viewer's access to the caller's private data
+        /// 
+        /// # Arguments
+        /// * `viewer` - Account to revoke
+        /// 
+        /// # Errors
+        /// * `GrantNotFound` - If the viewer holds no grant, expired or not
+        /// 
+        /// # Events
+        /// * `AccessRevoked` - Emitted when the grant is removed
+        #[ink(message)]
+        pub fn revoke_access(&mut self, viewer: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+
+            if self.access_grants.take((caller, viewer)).is_none() {
+                return Err(ContractError::GrantNotFound);
+            }
+            let mut viewers = self.access_viewers.get(caller).unwrap_or_default();
+            viewers.retain(|v| *v != viewer);
+            self.access_viewers.insert(caller, &viewers);
+
+            self.env().emit_event(AccessRevoked { owner: caller, viewer });
+
+            Ok(())
+        }
+
+        /// Remove the expired access grants of an owner
+        /// 
+        /// Anyone can call this. Expired grants already count as absent, so
+        /// this only frees storage and room for new viewers.
+        /// 
+        /// # Arguments
+        /// * `owner` - Account whose grants to clean up
+        /// 
+        /// # Returns
+        /// * `u32` - Number of grants removed
+        #[ink(message)]
+        pub fn purge_expired_grants(&mut self, owner: AccountId) -> u32 {
+            let now = self.env().block_timestamp();
+            let viewers = self.access_viewers.get(owner).unwrap_or_default();
+
+            let mut purged = 0u32;
+            let mut kept = Vec::with_capacity(viewers.len());
+            for viewer in viewers {
+                if self.access_grants.get((owner, viewer)).is_some_and(|expires_at| now < expires_at) {
+                    kept.push(viewer);
+                } else {
+                    self.access_grants.remove((owner, viewer));
+                    purged += 1;
+                }
+            }
+
+            if kept.is_empty() {
+                self.access_viewers.remove(owner);
+            } else {
+                self.access_viewers.insert(owner, &kept);
+            }
+
+            purged
+        }
+
+        /// Get the expiry of a viewer's unexpired access grant
+        /// 
+        /// # Arguments
+        /// * `owner` - Account that granted access
+        /// * `viewer` - Account holding the grant
+        /// 
+        /// # Returns
+        /// * `Option<Timestamp>` - Expiry of the grant, None if absent or expired
+        #[ink(message)]
+        pub fn get_access_grant(&self, owner: AccountId, viewer: AccountId) -> Option<Timestamp> {
+            let now = self.env().block_timestamp();
+            self.access_grants
+                .get((owner, viewer))
+                .filter(|expires_at| now < *expires_at)
+        }
+
+        /// Add a contract to the automated issuer list (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `issuer` - Contract account allowed to issue claims programmatically
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// 
+        /// # Events
+        /// * `AutomatedIssuerAdded` - Emitted when the issuer is added
+        #[ink(message)]
+        pub fn add_automated_issuer(&mut self, issuer: AccountId) -> Result<()> {
+            self.ensure_admin()?;
+
+            self.automated_issuers.insert(issuer, &());
+            self.env().emit_event(AutomatedIssuerAdded { issuer });
+
+            Ok(())
+        }
+
+        /// Remove a contract from the automated issuer list (only by the admin)
+        /// 
+        /// Claims it already issued keep their `issuer_kind`.
+        /// 
+        /// # Arguments
+        /// * `issuer` - Contract account to remove
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// 
+        /// # Events
+        /// * `AutomatedIssuerRemoved` - Emitted when the issuer is removed
+        #[ink(message)]
+        pub fn remove_automated_issuer(&mut self, issuer: AccountId) -> Result<()> {
+            self.ensure_admin()?;
+
+            self.automated_issuers.remove(issuer);
+            self.env().emit_event(AutomatedIssuerRemoved { issuer });
+
+            Ok(())
+        }
+
+        /// Freeze a claim type (only by the admin)
+        /// 
+        /// While frozen, claims of the type can't be added (by any issuance
+        /// path) or approved; batch approvals containing one fail as a whole.
+        /// Existing claims stay readable and revocable.
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to freeze
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// 
+        /// # Events
+        /// * `ClaimTypeFrozen` - Emitted when the type is frozen
+        #[ink(message)]
+        pub fn freeze_claim_type(&mut self, claim_type: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            self.frozen_claim_types.insert(&claim_type, &());
+            self.env().emit_event(ClaimTypeFrozen { claim_type });
+
+            Ok(())
+        }
+
+        /// Unfreeze a claim type (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to unfreeze
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// 
+        /// # Events
+        /// * `ClaimTypeUnfrozen` - Emitted when the type is unfrozen
+        #[ink(message)]
+        pub fn unfreeze_claim_type(&mut self, claim_type: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            self.frozen_claim_types.remove(&claim_type);
+            self.env().emit_event(ClaimTypeUnfrozen { claim_type });
+
+            Ok(())
+        }
+
+        /// Reserve a claim type for the admin and automated issuers (only by the admin)
+        /// 
+        /// Claims of a reserved type carry platform authority (e.g. "kyc_verified"):
+        /// other accounts can't issue them or create templates for them. Claims
+        /// issued before the type was reserved are left untouched.
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to reserve
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `ClaimTypeAlreadyReserved` - If the type is already reserved
+        /// * `TooManyReservedTypes` - If `MAX_RESERVED_CLAIM_TYPES` types are reserved
+        /// 
+        /// # Events
+        /// * `ClaimTypeReserved` - Emitted when the type is reserved
+        #[ink(message)]
+        pub fn reserve_claim_type(&mut self, claim_type: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            if self.reserved_claim_types.contains(&claim_type) {
+                return Err(ContractError::ClaimTypeAlreadyReserved);
+            }
+            if self.reserved_claim_types.len() >= MAX_RESERVED_CLAIM_TYPES {
+                return Err(ContractError::TooManyReservedTypes);
+            }
+
+            self.reserved_claim_types.push(claim_type.clone());
+            self.env().emit_event(ClaimTypeReserved { claim_type });
+
+            Ok(())
+        }
+
+        /// Let every issuer use a reserved claim type again (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to release
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `ClaimTypeNotReserved` - If the type is not reserved
+        /// 
+        /// # Events
+        /// * `ClaimTypeUnreserved` - Emitted when the type is released
+        #[ink(message)]
+        pub fn unreserve_claim_type(&mut self, claim_type: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            let index = self
+                .reserved_claim_types
+                .iter()
+                .position(|reserved| *reserved == claim_type)
+                .ok_or(ContractError::ClaimTypeNotReserved)?;
+
+            self.reserved_claim_types.remove(index);
+            self.env().emit_event(ClaimTypeUnreserved { claim_type });
+
+            Ok(())
+        }
+
+        /// Get the reserved claim types
+        /// 
+        /// # Returns
+        /// * `Vec<String>` - Reserved types, in reservation order
+        #[ink(message)]
+        pub fn get_reserved_claim_types(&self) -> Vec<String> {
+            self.reserved_claim_types.clone()
+        }
+
+        /// Set the uniqueness rules of a claim type (only by the admin)
+        /// 
+        /// Rules apply to claims added afterwards, through any issuance path.
+        /// A claim occupies its slot while it is pending or approved and
+        /// unexpired, so revoking, rejecting or sweeping it frees the slot.
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to configure
+        /// * `uniqueness` - Rules to enforce (all false = none)
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// 
+        /// # Events
+        /// * `ClaimTypeUniquenessSet` - Emitted when the rules are updated
+        #[ink(message)]
+        pub fn set_claim_type_uniqueness(
+            &mut self,
+            claim_type: String,
+            uniqueness: ClaimTypeUniqueness,
+        ) -> Result<()> {
+            self.ensure_admin()?;
+
+            if uniqueness == ClaimTypeUniqueness::default() {
+                self.claim_type_uniqueness.remove(&claim_type);
+            } else {
+                self.claim_type_uniqueness.insert(&claim_type, &uniqueness);
+            }
+            self.env().emit_event(ClaimTypeUniquenessSet { claim_type, uniqueness });
+
+            Ok(())
+        }
+
+        /// Get the uniqueness rules of a claim type
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to query
+        /// 
+        /// # Returns
+        /// * `ClaimTypeUniqueness` - Rules of the type (all false if none were set)
+        #[ink(message)]
+        pub fn get_claim_type_uniqueness(&self, claim_type: String) -> ClaimTypeUniqueness {
+            self.claim_type_uniqueness.get(&claim_type).unwrap_or_default()
+        }
+
+        /// Register a claim type namespace for the caller
+        /// 
+        /// Claim types of the form `namespace/name` can then only be issued by
+        /// the namespace owner or its delegates. Claim types without a `/`
+        /// stay open to every issuer.
+        /// 
+        /// # Arguments
+        /// * `namespace` - 3 to 32 lowercase letters, digits or dashes
+        /// 
+        /// # Errors
+        /// * `InvalidNamespace` - If the namespace is malformed
+        /// * `NamespaceTaken` - If the namespace is already registered
+        /// 
+        /// # Events
+        /// * `NamespaceRegistered` - Emitted when the namespace is registered
+        #[ink(message)]
+        pub fn register_namespace(&mut self, namespace: String) -> Result<()> {
+            if !Self::is_valid_namespace(&namespace) {
+                return Err(ContractError::InvalidNamespace);
+            }
+            if self.namespace_owners.contains(&namespace) {
+                return Err(ContractError::NamespaceTaken);
+            }
+
+            let caller = self.env().caller();
+            self.namespace_owners.insert(&namespace, &caller);
+            self.env().emit_event(NamespaceRegistered { namespace, owner: caller });
+
+            Ok(())
+        }
+
+        /// Hand a namespace to another account (only by its owner)
+        /// 
+        /// The delegates of the previous owner lose access to the namespace.
+        /// Claims already issued under it are not affected.
+        /// 
+        /// # Arguments
+        /// * `namespace` - Namespace to transfer
+        /// * `new_owner` - Account receiving the namespace
+        /// 
+        /// # Errors
+        /// * `NamespaceNotFound` - If the namespace is not registered
+        /// * `NotNamespaceOwner` - If the caller doesn't own the namespace
+        /// 
+        /// # Events
+        /// * `NamespaceTransferred` - Emitted when the namespace changes owner
+        #[ink(message)]
+        pub fn transfer_namespace(&mut self, namespace: String, new_owner: AccountId) -> Result<()> {
+            let owner = self.ensure_namespace_owner(&namespace)?;
+
+            self.namespace_owners.insert(&namespace, &new_owner);
+            self.namespace_delegates.remove(&namespace);
+            self.env().emit_event(NamespaceTransferred { namespace, from: owner, to: new_owner });
+
+            Ok(())
+        }
+
+        /// Let another account issue claims under a namespace (only by its owner)
+        /// 
+        /// # Arguments
+        /// * `namespace` - Namespace owned by the caller
+        /// * `delegate` - Account allowed to issue under it
+        /// 
+        /// # Errors
+        /// * `NamespaceNotFound` - If the namespace is not registered
+        /// * `NotNamespaceOwner` - If the caller doesn't own the namespace
+        /// * `AlreadyNamespaceDelegate` - If the account is already a delegate
+        /// * `TooManyNamespaceDelegates` - If the namespace has `MAX_NAMESPACE_DELEGATES` delegates
+        /// 
+        /// # Events
+        /// * `NamespaceDelegateAdded` - Emitted when the delegate is added
+        #[ink(message)]
+        pub fn add_namespace_delegate(&mut self, namespace: String, delegate: AccountId) -> Result<()> {
+            self.ensure_namespace_owner(&namespace)?;
+
+            let mut delegates = self.namespace_delegates.get(&namespace).unwrap_or_default();
+            if delegates.contains(&delegate) {
+                return Err(ContractError::AlreadyNamespaceDelegate);
+            }
+            if delegates.len() >= MAX_NAMESPACE_DELEGATES {
+                return Err(ContractError::TooManyNamespaceDelegates);
+            }
+
+            delegates.push(delegate);
+            self.namespace_delegates.insert(&namespace, &delegates);
+            self.env().emit_event(NamespaceDelegateAdded { namespace, delegate });
+
+            Ok(())
+        }
+
+        /// Stop a delegate from issuing under a namespace (only by its owner)
+        /// 
+        /// # Arguments
+        /// * `namespace` - Namespace owned by the caller
+        /// * `delegate` - Delegate to remove
+        /// 
+        /// # Errors
+        /// * `NamespaceNotFound` - If the namespace is not registered
+        /// * `NotNamespaceOwner` - If the caller doesn't own the namespace
+        /// * `NotNamespaceDelegate` - If the account is not a delegate
+        /// 
+        /// # Events
+        /// * `NamespaceDelegateRemoved` - Emitted when the delegate is removed
+        #[ink(message)]
+        pub fn remove_namespace_delegate(&mut self, namespace: String, delegate: AccountId) -> Result<()> {
+            self.ensure_namespace_owner(&namespace)?;
+
+            let mut delegates = self.namespace_delegates.get(&namespace).unwrap_or_default();
+            let Some(position) = delegates.iter().position(|d| *d == delegate) else {
+                return Err(ContractError::NotNamespaceDelegate);
+            };
+
+            delegates.remove(position);
+            if delegates.is_empty() {
+                self.namespace_delegates.remove(&namespace);
+            } else {
+                self.namespace_delegates.insert(&namespace, &delegates);
+            }
+            self.env().emit_event(NamespaceDelegateRemoved { namespace, delegate });
+
+            Ok(())
+        }
+
+        /// Get the owner of a claim type namespace
+        /// 
+        /// # Arguments
+        /// * `namespace` - Namespace to query
+        /// 
+        /// # Returns
+        /// * `Option<AccountId>` - Owner if the namespace is registered
+        #[ink(message)]
+        pub fn get_namespace_owner(&self, namespace: String) -> Option<AccountId> {
+            self.namespace_owners.get(&namespace)
+        }
+
+        /// Get the delegates of a claim type namespace
+        /// 
+        /// # Arguments
+        /// * `namespace` - Namespace to query
+        /// 
+        /// # Returns
+        /// * `Vec<AccountId>` - Delegates, oldest first
+        #[ink(message)]
+        pub fn get_namespace_delegates(&self, namespace: String) -> Vec<AccountId> {
+            self.namespace_delegates.get(&namespace).unwrap_or_default()
+        }
+
+        /// Check whether a claim type is frozen
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Claim type to query
+        /// 
+        /// # Returns
+        /// * `bool` - True if claims of the type can't be issued or approved
+        #[ink(message)]
+        pub fn is_claim_type_frozen(&self, claim_type: String) -> bool {
+            self.frozen_claim_types.contains(&claim_type)
+        }
+
+        /// Check whether an account is an automated issuer
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `bool` - True if the account is on the automated issuer list
+        #[ink(message)]
+        pub fn is_automated_issuer(&self, account_id: AccountId) -> bool {
+            self.automated_issuers.contains(account_id)
+        }
+
+        /// Get a claim by ID
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to query
+        /// 
+        /// # Returns
+        /// * `Option<Claim>` - Claim if it exists and is active, None otherwise
+        #[ink(message)]
+        pub fn get_claim(&self, claim_id: u64) -> Option<Claim> {
+            self.claims
+                .get(claim_id)
+                .filter(|claim| Self::is_active(claim, self.env().block_timestamp()))
+        }
+
+        /// Allow DIDs of a method to be linked (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `prefix` - Method prefix including the trailing colon (e.g., "did:web:")
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `InvalidDidMethod` - If the prefix is malformed or `MAX_DID_METHODS` are allowed
+        /// * `DidMethodAlreadyAllowed` - If the method is already allowed
+        /// 
+        /// # Events
+        /// * `DidMethodAllowed` - Emitted when the method is added
+        #[ink(message)]
+        pub fn allow_did_method(&mut self, prefix: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            if !Self::is_valid_did_method(&prefix) || self.did_methods.len() >= MAX_DID_METHODS {
+                return Err(ContractError::InvalidDidMethod);
+            }
+            if self.did_methods.contains(&prefix) {
+                return Err(ContractError::DidMethodAlreadyAllowed);
+            }
+
+            self.did_methods.push(prefix.clone());
+            self.env().emit_event(DidMethodAllowed { prefix });
+
+            Ok(())
+        }
+
+        /// Stop accepting DIDs of a method (only by the admin)
+        /// 
+        /// Already linked DIDs are left untouched.
+        /// 
+        /// # Arguments
+        /// * `prefix` - Method prefix to remove
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `DidMethodNotAllowed` - If the method is not in the list
+        /// 
+        /// # Events
+        /// * `DidMethodDisallowed` - Emitted when the method is removed
+        #[ink(message)]
+        pub fn disallow_did_method(&mut self, prefix: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            let index = self
+                .did_methods
+                .iter()
+                .position(|method| *method == prefix)
+                .ok_or(ContractError::DidMethodNotAllowed)?;
+
+            self.did_methods.remove(index);
+            self.env().emit_event(DidMethodDisallowed { prefix });
+
+            Ok(())
+        }
+
+        /// Get the DID method prefixes accepted by `link_did`
+        /// 
+        /// # Returns
+        /// * `Vec<String>` - Allowed method prefixes
+        #[ink(message)]
+        pub fn get_did_methods(&self) -> Vec<String> {
+            self.did_methods.clone()
+        }
+
+        /// Allow metadata URIs of a scheme (only by the admin)
+        /// 
+        /// # Arguments
+        /// * `prefix` - Scheme prefix including "://" (e.g., "ceramic://")
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `InvalidUriScheme` - If the prefix is malformed or `MAX_URI_SCHEMES` are allowed
+        /// * `UriSchemeAlreadyAllowed` - If the scheme is already allowed
+        /// 
+        /// # Events
+        /// * `UriSchemeAllowed` - Emitted when the scheme is added
+        #[ink(message)]
+        pub fn allow_uri_scheme(&mut self, prefix: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            if !Self::is_valid_uri_scheme(&prefix) || self.uri_schemes.len() >= MAX_URI_SCHEMES {
+                return Err(ContractError::InvalidUriScheme);
+            }
+            if self.uri_schemes.contains(&prefix) {
+                return Err(ContractError::UriSchemeAlreadyAllowed);
+            }
+
+            self.uri_schemes.push(prefix.clone());
+            self.env().emit_event(UriSchemeAllowed { prefix });
+
+            Ok(())
+        }
+
+        /// Stop accepting metadata URIs of a scheme (only by the admin)
+        /// 
+        /// Already stored URIs are left untouched.
+        /// 
+        /// # Arguments
+        /// * `prefix` - Scheme prefix to remove
+        /// 
+        /// # Errors
+        /// * `NotAdmin` - If the caller is not the admin
+        /// * `UriSchemeNotAllowed` - If the scheme is not in the list
+        /// 
+        /// # Events
+        /// * `UriSchemeDisallowed` - Emitted when the scheme is removed
+        #[ink(message)]
+        pub fn disallow_uri_scheme(&mut self, prefix: String) -> Result<()> {
+            self.ensure_admin()?;
+
+            let index = self
+                .uri_schemes
+                .iter()
+                .position(|scheme| *scheme == prefix)
+                .ok_or(ContractError::UriSchemeNotAllowed)?;
+
+            self.uri_schemes.remove(index);
+            self.env().emit_event(UriSchemeDisallowed { prefix });
+
+            Ok(())
+        }
+
+        /// Get the URI scheme prefixes accepted for metadata URIs
+        /// 
+        /// # Returns
+        /// * `Vec<String>` - Allowed scheme prefixes
+        #[ink(message)]
+        pub fn get_uri_schemes(&self) -> Vec<String> {
+            self.uri_schemes.clone()
+        }
+
+        /// Get the DID linked to a profile
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// 
+        /// # Returns
+        /// * `Option<String>` - DID if linked and visible to the caller, None otherwise
+        #[ink(message)]
+        pub fn get_did(&self, account_id: AccountId) -> Option<String> {
+            self.profiles.get(account_id).and_then(|p| self.disclosed(p).did)
+        }
+
+        /// Check whether a specific DID is linked to a profile
+        /// 
+        /// Works regardless of the DID's visibility, so a verifier can confirm
+        /// a DID it already knows without being able to enumerate hidden ones.
+        /// 
+        /// # Arguments
+        /// * `account_id` - Account to query
+        /// * `did` - DID the verifier expects
+        /// 
+        /// # Returns
+        /// * `bool` - True if `did` is the DID linked to the account
+        #[ink(message)]
+        pub fn prove_did_linked(&self, account_id: AccountId, did: String) -> bool {
+            self.profiles
+                .get(account_id)
+                .is_some_and(|p| p.did.as_ref() == Some(&did))
+        }
+
+        /// Create a claim template owned by the caller
+        /// 
+        /// # Arguments
+        /// * `claim_type` - Type of the claims issued from it
+        /// * `level` - Credential level
+        /// * `validity_ms` - Validity of issued claims in milliseconds (0 = no expiry)
+        /// * `metadata_uri` - URI pointing to off-chain credential metadata
+        /// 
+        /// # Returns
+        /// * `u64` - The ID of the new template
+        /// 
+        /// # Errors
+        /// * `TooManyTemplates` - If the caller already holds `MAX_TEMPLATES_PER_ISSUER` templates
+        /// * `ReservedClaimType` - If `claim_type` is reserved and the caller is neither
+        ///   the admin nor an automated issuer
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
+        /// 
+        /// # Events
+        /// * `TemplateCreated` - Emitted when the template is stored
+        #[ink(message)]
+        pub fn create_claim_template(
+            &mut self,
+            claim_type: String,
+            level: u8,
+            validity_ms: u64,
+            metadata_uri: String,
+        ) -> Result<u64> {
+            let caller = self.env().caller();
+            self.ensure_may_use_type(&claim_type)?;
+            self.ensure_valid_uri(&metadata_uri)?;
+
+            let mut template_ids = self.issuer_templates.get(caller).unwrap_or_default();
+            if template_ids.len() >= MAX_TEMPLATES_PER_ISSUER {
+                return Err(ContractError::TooManyTemplates);
+            }
+
+            let template_id = self.next_template_id;
+            let template = ClaimTemplate {
+                id: template_id,
+                issuer: caller,
+                claim_type: claim_type.clone(),
+                level,
+                validity_ms,
+                metadata_uri,
+            };
+
+            self.templates.insert(template_id, &template);
+            template_ids.push(template_id);
+            self.issuer_templates.insert(caller, &template_ids);
+            self.next_template_id += 1;
+
+            self.env().emit_event(TemplateCreated {
+                template_id,
+                issuer: caller,
+                claim_type,
+            });
+
+            Ok(template_id)
+        }
+
+        /// Change the fields of a template (only by its issuer)
+        /// 
+        /// Claims already issued from the template keep the values they were issued with.
+        /// 
+        /// # Arguments
+        /// * `template_id` - ID of the template
+        /// * `level` - New credential level
+        /// * `validity_ms` - New validity in milliseconds (0 = no expiry)
+        /// * `metadata_uri` - New metadata URI
+        /// 
+        /// # Errors
+        /// * `TemplateNotFound` - If the template doesn't exist
+        /// * `NotTemplateOwner` - If the caller is not the template's issuer
+        /// * `InvalidUri` - If `metadata_uri` is empty, too long or its scheme is not allowed
+        #[ink(message)]
+        pub fn update_template(
+            &mut self,
+            template_id: u64,
+            level: u8,
+            validity_ms: u64,
+            metadata_uri: String,
+        ) -> Result<()> {
+            let mut template = self.owned_template(template_id)?;
+            self.ensure_valid_uri(&metadata_uri)?;
+
+            template.level = level;
+            template.validity_ms = validity_ms;
+            template.metadata_uri = metadata_uri;
+            self.templates.insert(template_id, &template);
+
+            Ok(())
+        }
+
+        /// Delete a template (only by its issuer)
+        /// 
+        /// # Arguments
+        /// * `template_id` - ID of the template
+        /// 
+        /// # Errors
+        /// * `TemplateNotFound` - If the template doesn't exist
+        /// * `NotTemplateOwner` - If the caller is not the template's issuer
+        #[ink(message)]
+        pub fn delete_template(&mut self, template_id: u64) -> Result<()> {
+            let template = self.owned_template(template_id)?;
+
+            self.templates.remove(template_id);
+            let mut template_ids = self.issuer_templates.get(template.issuer).unwrap_or_default();
+            template_ids.retain(|&id| id != template_id);
+            self.issuer_templates.insert(template.issuer, &template_ids);
+
+            Ok(())
+        }
+
+        /// Issue a pending claim with the type, level, expiry and metadata of a template
+        /// 
+        /// # Arguments
+        /// * `template_id` - ID of the caller's template
+        /// * `receiver` - Account that will receive the claim
+        /// * `proof_hash` - Hash of the proof stored off-chain
+        /// * `activates_at` - Optional time before which the claim is hidden and can't be approved
+        /// 
+        /// # Returns
+        /// * `u64` - The ID of the newly created claim
+        /// 
+        /// # Errors
+        /// * `TemplateNotFound` - If the template doesn't exist
+        /// * `NotTemplateOwner` - If the caller is not the template's issuer
+        /// * `InvalidActivation` - If the claim would expire before it activates
+        /// * `ClaimTypeFrozen` - If the template's claim type is frozen
+        /// * `ReservedClaimType` - If the template's claim type is reserved and the caller
+        ///   is neither the admin nor an automated issuer
+        /// 
+        /// # Events
+        /// * `ClaimAdded` - Emitted when claim is successfully created
+        /// * `TemplateUsed` - Emitted with the template and claim IDs
+        #[ink(message)]
+        pub fn issue_from_template(
+            &mut self,
+            template_id: u64,
+            receiver: AccountId,
+            proof_hash: Hash,
+            activates_at: Option<Timestamp>,
+        ) -> Result<u64> {
+            let template = self.owned_template(template_id)?;
+
+            let claim = self.insert_claim(
+                template.issuer,
+                receiver,
+                template.claim_type.clone(),
+                proof_hash,
+                Some(&template),
+                activates_at,
+                None,
+            )?;
+
+            self.env().emit_event(TemplateUsed {
+                template_id,
+                claim_id: claim.id,
+                receiver,
+            });
+
+            Ok(claim.id)
+        }
+
+        /// Get all templates of an issuer
+        /// 
+        /// # Arguments
+        /// * `issuer` - Account to query
+        /// 
+        /// # Returns
+        /// * `Vec<ClaimTemplate>` - Templates in creation order
+        #[ink(message)]
+        pub fn list_templates(&self, issuer: AccountId) -> Vec<ClaimTemplate> {
+            self.issuer_templates
+                .get(issuer)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.templates.get(id))
+                .collect()
+        }
+
+        /// Load a template, failing unless the caller is its issuer
+        fn owned_template(&self, template_id: u64) -> Result<ClaimTemplate> {
+            let template = self.templates.get(template_id).ok_or(ContractError::TemplateNotFound)?;
+            if template.issuer != self.env().caller() {
+                return Err(ContractError::NotTemplateOwner);
+            }
+            Ok(template)
+        }
+
+        /// Fail unless the caller is `org` itself or one of its admins
+        fn ensure_org_admin(&self, org: AccountId) -> Result<()> {
+            if !self.profiles.get(org).is_some_and(|profile| profile.is_org) {
+                return Err(ContractError::OrgNotFound);
+            }
+            let caller = self.env().caller();
+            if caller != org && self.org_members.get((org, caller)) != Some(MemberRole::Admin) {
+                return Err(ContractError::NotOrgAdmin);
+            }
+            Ok(())
+        }
+
+        /// Fail with `ProfileNotFound` if approval requires a receiver profile
+        /// and `receiver` has none
+        fn ensure_receiver_ready(&self, receiver: AccountId) -> Result<()> {
+            if self.require_receiver_profile && !self.profiles.contains(receiver) {
+                return Err(ContractError::ProfileNotFound);
+            }
+            Ok(())
+        }
+
+        /// Apply the self-attestation policy to a claim from `issuer` to `receiver`
+        /// 
+        /// Returns whether the claim must be flagged, or fails with
+        /// `SelfAttestation` if the policy rejects it. The stored DIDs are
+        /// compared as linked, whether or not their owners hide them.
+        fn check_self_attestation(&self, issuer: AccountId, receiver: AccountId) -> Result<bool> {
+            if issuer == receiver {
+                return Ok(false);
+            }
+            let Some(issuer_did) = self.profiles.get(issuer).and_then(|profile| profile.did) else {
+                return Ok(false);
+            };
+            let shared = self
+                .profiles
+                .get(receiver)
+                .and_then(|profile| profile.did)
+                .is_some_and(|receiver_did| receiver_did == issuer_did);
+            if shared && self.self_attestation_policy == SelfAttestationPolicy::Reject {
+                return Err(ContractError::SelfAttestation);
+            }
+            Ok(shared)
+        }
+
+        /// Fail with `ClaimTypeFrozen` if the admin froze `claim_type`
+        fn ensure_not_frozen(&self, claim_type: &str) -> Result<()> {
+            if self.frozen_claim_types.contains(String::from(claim_type)) {
+                return Err(ContractError::ClaimTypeFrozen);
+            }
+            Ok(())
+        }
+
+        /// Fail with `SingletonViolation` or `ProofAlreadyBound` if a new claim
+        /// would break the uniqueness rules of its type
+        fn ensure_unique(
+            &self,
+            uniqueness: &ClaimTypeUniqueness,
+            receiver: AccountId,
+            claim_type: &str,
+            proof_hash: Hash,
+        ) -> Result<()> {
+            let now = self.env().block_timestamp();
+            if uniqueness.unique_per_receiver {
+                let held = self
+                    .singleton_claims
+                    .get((receiver, String::from(claim_type)))
+                    .and_then(|claim_id| self.claims.get(claim_id));
+                if held.is_some_and(|claim| self.holds_slot(&claim, now)) {
+                    return Err(ContractError::SingletonViolation);
+                }
+            }
+            if uniqueness.unique_proof {
+                let bound = self.bound_proofs.get((String::from(claim_type), proof_hash)).unwrap_or_default();
+                let taken = bound
+                    .into_iter()
+                    .filter_map(|claim_id| self.claims.get(claim_id))
+                    .any(|claim| claim.receiver != receiver && self.holds_slot(&claim, now));
+                if taken {
+                    return Err(ContractError::ProofAlreadyBound);
+                }
+            }
+            Ok(())
+        }
+
+        /// Whether a claim still counts against the uniqueness rules of its type:
+        /// pending and not stale, or approved and not expired
+        fn holds_slot(&self, claim: &Claim, now: Timestamp) -> bool {
+            match claim.status {
+                ClaimStatus::Pending => !self.is_stale(claim, now),
+                ClaimStatus::Approved => claim.expires_at.is_none_or(|expires_at| now < expires_at),
+                ClaimStatus::Revoked | ClaimStatus::Expired | ClaimStatus::Rejected => false,
+            }
+        }
+
+        /// Fail with `ReservedClaimType` if `claim_type` is reserved and the caller
+        /// is neither the admin nor an automated issuer
+        fn ensure_may_use_type(&self, claim_type: &str) -> Result<()> {
+            let caller = self.env().caller();
+            let privileged = caller == self.admin || self.automated_issuers.contains(caller);
+            if !privileged && self.reserved_claim_types.iter().any(|reserved| reserved == claim_type) {
+                return Err(ContractError::ReservedClaimType);
+            }
+            Ok(())
+        }
+
+        /// Fail unless `issuer` may issue `claim_type`
+        /// 
+        /// Claim types without a `/` are open. A namespaced type must be
+        /// `namespace/name` with a registered namespace owned or delegated
+        /// to `issuer`.
+        fn ensure_may_use_namespace(&self, issuer: AccountId, claim_type: &str) -> Result<()> {
+            let Some((namespace, name)) = claim_type.split_once('/') else {
+                return Ok(());
+            };
+            if name.is_empty() || name.contains('/') || !Self::is_valid_namespace(namespace) {
+                return Err(ContractError::InvalidNamespace);
+            }
+
+            let namespace = String::from(namespace);
+            let owner = self.namespace_owners.get(&namespace).ok_or(ContractError::NamespaceNotFound)?;
+            if issuer != owner && !self.namespace_delegates.get(&namespace).unwrap_or_default().contains(&issuer) {
+                return Err(ContractError::NotNamespaceOwner);
+            }
+            Ok(())
+        }
+
+        /// Get the owner of `namespace`, failing unless it is the caller
+        fn ensure_namespace_owner(&self, namespace: &str) -> Result<AccountId> {
+            let owner = self
+                .namespace_owners
+                .get(String::from(namespace))
+                .ok_or(ContractError::NamespaceNotFound)?;
+            if owner != self.env().caller() {
+                return Err(ContractError::NotNamespaceOwner);
+            }
+            Ok(owner)
+        }
+
+        /// Fail with `IssuerNotPermitted` if the receiver's claim policy doesn't admit `issuer`
+        fn ensure_issuer_permitted(&self, issuer: AccountId, receiver: AccountId) -> Result<()> {
+            let permitted = match self.claim_policies.get(receiver).unwrap_or_default() {
+                ClaimPolicy::Open => true,
+                ClaimPolicy::TrustedIssuersOnly => {
+                    issuer == self.admin
+                        || self.automated_issuers.contains(issuer)
+                        || self.is_allowed_issuer(issuer, receiver)
+                }
+                ClaimPolicy::AllowlistOnly => self.is_allowed_issuer(issuer, receiver),
+            };
+            if !permitted {
+                return Err(ContractError::IssuerNotPermitted);
+            }
+            Ok(())
+        }
+
+        /// Whether `issuer` is on the allowlist of `receiver`
+        fn is_allowed_issuer(&self, issuer: AccountId, receiver: AccountId) -> bool {
+            self.allowed_issuers
+                .get(receiver)
+                .is_some_and(|allowed| allowed.contains(&issuer))
+        }
+
+        /// Fail with `NotOrgMember` unless the caller is a current member of `org`
+        fn ensure_org_member(&self, org: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.org_members.contains((org, caller)) {
+                return Err(ContractError::NotOrgMember);
+            }
+            Ok(())
+        }
+
+        /// Store a new pending claim from `issuer`, index it and emit `ClaimAdded`
+        #[allow(clippy::too_many_arguments)]
+        fn insert_claim(
+            &mut self,
+            issuer: AccountId,
+            receiver: AccountId,
+            claim_type: String,
+            proof_hash: Hash,
+            template: Option<&ClaimTemplate>,
+            activates_at: Option<Timestamp>,
+            attests_to: Option<u64>,
+        ) -> Result<Claim> {
+            self.ensure_not_frozen(&claim_type)?;
+            self.ensure_may_use_type(&claim_type)?;
+            self.ensure_may_use_namespace(issuer, &claim_type)?;
+            self.ensure_issuer_permitted(issuer, receiver)?;
+            let self_attested = self.check_self_attestation(issuer, receiver)?;
+            let uniqueness = self.claim_type_uniqueness.get(&claim_type).unwrap_or_default();
+            self.ensure_unique(&uniqueness, receiver, &claim_type, proof_hash)?;
+
+            // An attestation must vouch for an existing claim of the same receiver
+            let mut attesting = Vec::new();
+            if let Some(parent_id) = attests_to {
+                let parent = self.claims.get(parent_id).ok_or(ContractError::ClaimNotFound)?;
+                if parent.receiver != receiver {
+                    return Err(ContractError::AttestationReceiverMismatch);
+                }
+                attesting = self.attestations.get(parent_id).unwrap_or_default();
+                if attesting.len() >= MAX_ATTESTATIONS {
+                    return Err(ContractError::TooManyAttestations);
+                }
+            }
+
+            let claim_id = self.next_claim_id;
+            let now = self.env().block_timestamp();
+
+            let expires_at = template
+                .filter(|t| t.validity_ms > 0)
+                .map(|t| now.saturating_add(t.validity_ms));
+
+            // A claim must be able to verify at some point
+            if let (Some(activates_at), Some(expires_at)) = (activates_at, expires_at) {
+                if activates_at >= expires_at {
+                    return Err(ContractError::InvalidActivation);
+                }
+            }
+
+            let issuer_kind = if self.automated_issuers.contains(issuer) {
+                IssuerKind::Contract
+            } else {
+                IssuerKind::Account
+            };
+
+            // Create new claim with Pending status
+            let claim = Claim {
+                id: claim_id,
+                issuer,
+                receiver,
+                claim_type: claim_type.clone(),
+                proof_hash,
+                status: ClaimStatus::Pending,
+                approved_at: None,
+                created_at: now,
+                issuer_kind,
+                template_id: template.map(|t| t.id),
+                level: template.map(|t| t.level),
+                metadata_uri: template.map(|t| t.metadata_uri.clone()),
+                expires_at,
+                rejection_reason_uri: None,
+                activates_at,
+                schema_version: CLAIM_SCHEMA_VERSION,
+                attests_to,
+                self_attested,
+            };
+
+            // Store claim
+            self.claims.insert(claim_id, &claim);
+            if let Some(parent_id) = attests_to {
+                attesting.push(claim_id);
+                self.attestations.insert(parent_id, &attesting);
+            }
+
+            if uniqueness.unique_per_receiver {
+                self.singleton_claims.insert((receiver, claim_type.clone()), &claim_id);
+            }
+            if uniqueness.unique_proof {
+                // Drop claims that no longer hold the proof so the list stays short
+                let key = (claim_type.clone(), proof_hash);
+                let mut bound = self.bound_proofs.get(&key).unwrap_or_default();
+                bound.retain(|id| self.claims.get(id).is_some_and(|c| self.holds_slot(&c, now)));
+                bound.push(claim_id);
+                self.bound_proofs.insert(&key, &bound);
+            }
+
+            // Add claim ID to receiver's claims list
+            let mut user_claim_ids = self.user_claims.get(receiver).unwrap_or_default();
+            user_claim_ids.push(claim_id);
+            self.user_claims.insert(receiver, &user_claim_ids);
+            if Self::is_active(&claim, now) {
+                self.last_claim_at.insert(receiver, &now);
+            }
+
+            // Increment claim counter
+            self.next_claim_id += 1;
+
+            // Emit event
+            self.env().emit_event(ClaimAdded {
+                claim_id,
+                issuer,
+                receiver,
+                claim_type,
+                activates_at,
+                schema_version: CLAIM_SCHEMA_VERSION,
+                attests_to,
+            });
+            if self_attested {
+                self.env().emit_event(SelfAttestationFlagged {
+                    claim_id,
+                    issuer,
+                    receiver,
+                });
+            }
+
+            Ok(claim)
+        }
+
+        /// Check that `issuer` may approve `claim` at `now` and return whether
+        /// its issuer and receiver link the same DID
+        fn check_approvable(&self, issuer: AccountId, claim: &Claim, now: Timestamp) -> Result<bool> {
+            // Verify the claim belongs to the issuer
+            if claim.issuer != issuer {
+                return Err(ContractError::UnauthorizedApproval);
+            }
+
+            // Only pending claims can be approved
+            match claim.status {
+                ClaimStatus::Pending => {}
+                ClaimStatus::Approved => return Err(ContractError::ClaimAlreadyApproved),
+                ClaimStatus::Revoked => return Err(ContractError::ClaimAlreadyRevoked),
+                ClaimStatus::Expired => return Err(ContractError::ClaimExpired),
+                ClaimStatus::Rejected => return Err(ContractError::ClaimAlreadyRejected),
+            }
+
+            if !Self::is_active(claim, now) {
+                return Err(ContractError::ClaimNotYetActive);
+            }
+
+            // Reject claims that outlived the approval deadline
+            if self.is_stale(claim, now) {
+                return Err(ContractError::ClaimStale);
+            }
+
+            self.ensure_not_frozen(&claim.claim_type)?;
+            self.ensure_receiver_ready(claim.receiver)?;
+            self.check_self_attestation(claim.issuer, claim.receiver)
+        }
+
+        /// Mark a newly found self-attested claim and emit `SelfAttestationFlagged`
+        fn flag_self_attested(&self, claim: &mut Claim, self_attested: bool) {
+            if self_attested && !claim.self_attested {
+                claim.self_attested = true;
+                self.env().emit_event(SelfAttestationFlagged {
+                    claim_id: claim.id,
+                    issuer: claim.issuer,
+                    receiver: claim.receiver,
+                });
+            }
+        }
+
+        /// Mark a validated claim approved, count it in the receiver's activity
+        /// and emit `ClaimApproved`
+        fn apply_approval(&mut self, claim: &mut Claim, now: Timestamp) -> Result<()> {
+            claim.status = ClaimStatus::Approved;
+            claim.approved_at = Some(now);
+            self.store_claim(claim)?;
+
+            // Count the approval in the receiver's activity, unless self-attested
+            if !claim.self_attested {
+                let bucket = (claim.receiver, Self::day_of(now));
+                let count = self.activity.get(bucket).unwrap_or(0);
+                self.activity.insert(bucket, &count.saturating_add(1));
+                self.count_approved_type(claim.receiver, &claim.claim_type, true);
+                self.record_approval_time(claim.receiver, &claim.claim_type, now);
+            }
+
+            self.env().emit_event(ClaimApproved { claim_id: claim.id });
+
+            if !claim.self_attested {
+                self.award_badges(claim, now);
+            }
+            self.notify_subscribers(claim);
+
+            Ok(())
+        }
+
+        /// Call `on_claim_approved` on every subscriber watching the claim's receiver
+        /// 
+        /// A subscriber that fails, traps or runs out of its reference time
+        /// only gets a `SubscriberCallFailed` event.
+        fn notify_subscribers(&self, claim: &Claim) {
+            for subscription in self.subscriptions.get(claim.receiver).unwrap_or_default() {
+                let mut subscriber: ink::contract_ref!(ClaimSubscriber) = subscription.callback_contract.into();
+                let outcome = subscriber
+                    .call_mut()
+                    .on_claim_approved(claim.id, claim.receiver)
+                    .ref_time_limit(SUBSCRIBER_REF_TIME_LIMIT)
+                    .try_invoke();
+
+                if !matches!(outcome, Ok(Ok(()))) {
+                    self.env().emit_event(SubscriberCallFailed {
+                        receiver: claim.receiver,
+                        claim_id: claim.id,
+                        callback_contract: subscription.callback_contract,
+                    });
+                }
+            }
+        }
+
+        /// Fold a revoked claim into the issuer's accumulator and recent list,
+        /// returning the new accumulator
+        fn record_revocation(&mut self, issuer: AccountId, claim_id: u64) -> Hash {
+            let (previous, count) = self.revocation_state.get(issuer).unwrap_or_default();
+            let accumulator = Hash::from(
+                self.env()
+                    .hash_encoded::<ink::env::hash::Blake2x256, _>(&(previous, claim_id)),
+            );
+            self.revocation_state
+                .insert(issuer, &(accumulator, count.saturating_add(1)));
+
+            let mut recent = self.recent_revocations.get(issuer).unwrap_or_default();
+            recent.push(claim_id);
+            if recent.len() > MAX_RECENT_REVOCATIONS {
+                recent.remove(0);
+            }
+            self.recent_revocations.insert(issuer, &recent);
+
+            accumulator
+        }
+
+        /// Strip a hidden DID from a profile unless the caller may view it as the owner
+        fn disclosed(&self, mut profile: Profile) -> Profile {
+            if !profile.did_public && !self.may_view_as(profile.owner) {
+                profile.did = None;
+            }
+            profile
+        }
+
+        /// Whether the caller is `owner` or holds an unexpired access grant from it
+        fn may_view_as(&self, owner: AccountId) -> bool {
+            let caller = self.env().caller();
+            caller == owner || self.get_access_grant(owner, caller).is_some()
+        }
+
+        /// Write back an existing claim, refusing to change its receiver
+        /// 
+        /// A claim stays bound to the account it was issued to; moving it
+        /// would let reputation be bought. Every update of a stored claim
+        /// must go through here.
+        fn store_claim(&mut self, claim: &Claim) -> Result<()> {
+            let stored = self.claims.get(claim.id).ok_or(ContractError::ClaimNotFound)?;
+            debug_assert_eq!(stored.receiver, claim.receiver, "claim receiver rewritten");
+            if stored.receiver != claim.receiver {
+                return Err(ContractError::ReceiverImmutable);
+            }
+
+            self.claims.insert(claim.id, claim);
+            Ok(())
+        }
+
+        /// Append the badges the receiver's profile earned with an approved claim
+        /// and emit `BadgeEarned` for each; accounts without a profile earn none
+        fn award_badges(&mut self, claim: &Claim, now: Timestamp) {
+            let Some(mut profile) = self.profiles.get(claim.receiver) else {
+                return;
+            };
+
+            let approved: u32 = self
+                .approved_by_type
+                .get(claim.receiver)
+                .unwrap_or_default()
+                .iter()
+                .map(|(_, count)| count)
+                .sum();
+            let verified_issuer = self.profiles.get(claim.issuer).is_some_and(|p| p.did.is_some());
+
+            let mut earned = Vec::new();
+            for (threshold, kind) in CLAIM_COUNT_BADGES {
+                if approved >= threshold {
+                    earned.push(kind);
+                }
+            }
+            if verified_issuer {
+                earned.push(BadgeKind::VerifiedIssuerClaim);
+            }
+            earned.retain(|kind| profile.badges.iter().all(|badge| badge.kind != *kind));
+
+            if earned.is_empty() {
+                return;
+            }
+
+            for kind in earned {
+                profile.badges.push(Badge { kind, earned_at: now });
+                self.env().emit_event(BadgeEarned {
+                    account: claim.receiver,
+                    kind,
+                });
+            }
+            self.profiles.insert(claim.receiver, &profile);
+        }
+
+        /// Add or remove one approved claim of `claim_type` in the receiver's counters
+        fn count_approved_type(&mut self, receiver: AccountId, claim_type: &str, approved: bool) {
+            let mut counts = self.approved_by_type.get(receiver).unwrap_or_default();
+
+            match counts.iter().position(|(t, _)| t == claim_type) {
+                Some(index) if approved => counts[index].1 = counts[index].1.saturating_add(1),
+                Some(index) => {
+                    counts[index].1 = counts[index].1.saturating_sub(1);
+                    if counts[index].1 == 0 {
+                        counts.remove(index);
+                    }
+                }
+                None if approved => counts.push((String::from(claim_type), 1)),
+                None => {}
+            }
+
+            self.approved_by_type.insert(receiver, &counts);
+        }
+
+        /// Whether a DID uses an allowed method and is well formed
+        fn is_valid_did(&self, did: &str) -> bool {
+            if did.len() > MAX_DID_LEN {
+                return false;
+            }
+
+            let Some(method) = self.did_methods.iter().find(|method| did.starts_with(method.as_str())) else {
+                return false;
+            };
+
+            // Method-specific identifier per the DID syntax
+            let identifier = &did[method.len()..];
+            !identifier.is_empty()
+                && !identifier.ends_with(':')
+                && identifier
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_' | b':' | b'%'))
+        }
+
+        /// Fail with `InvalidUri` unless a metadata URI is at most
+        /// `MAX_METADATA_URI_LEN` bytes and has content after an allowed scheme
+        fn ensure_valid_uri(&self, uri: &str) -> Result<()> {
+            let valid = uri.len() <= MAX_METADATA_URI_LEN
+                && self
+                    .uri_schemes
+                    .iter()
+                    .any(|scheme| uri.len() > scheme.len() && uri.starts_with(scheme.as_str()));
+            if !valid {
+                return Err(ContractError::InvalidUri);
+            }
+            Ok(())
+        }
+
+        /// Whether a scheme prefix looks like "<lowercase scheme>://"
+        fn is_valid_uri_scheme(prefix: &str) -> bool {
+            let Some(name) = prefix.strip_suffix("://") else {
+                return false;
+            };
+            name.bytes().next().is_some_and(|b| b.is_ascii_lowercase())
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.'))
+        }
+
+        /// Whether a namespace has `MIN_NAMESPACE_LEN` to `MAX_NAMESPACE_LEN`
+        /// lowercase letters, digits or dashes
+        fn is_valid_namespace(namespace: &str) -> bool {
+            (MIN_NAMESPACE_LEN..=MAX_NAMESPACE_LEN).contains(&namespace.len())
+                && namespace
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        }
+
+        /// Whether a method prefix looks like "did:<lowercase method>:"
+        fn is_valid_did_method(prefix: &str) -> bool {
+            let Some(name) = prefix.strip_prefix("did:").and_then(|rest| rest.strip_suffix(':')) else {
+                return false;
+            };
+            !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        }
+
+        /// Fail with `NotAdmin` unless the caller is the admin
+        fn ensure_admin(&self) -> Result<()> {
+            if self.env().caller() != self.admin {
+                return Err(ContractError::NotAdmin);
+            }
+            Ok(())
+        }
+
+        /// Whether a pending claim is older than the pending TTL at `now`
+        fn is_stale(&self, claim: &Claim, now: Timestamp) -> bool {
+            let pending_since = claim.activates_at.unwrap_or(claim.created_at);
+            self.pending_ttl_ms != 0
+                && claim.status == ClaimStatus::Pending
+                && now.saturating_sub(pending_since) > self.pending_ttl_ms
+        }
+
+        /// Load a page of active claims from IDs in ascending order
+        /// 
+        /// `IdAsc` and `IdDesc` walk the IDs and stop once the page is full.
+        /// `ApprovedAtDesc` sorts on read, so it only considers the
+        /// `MAX_SORT_SCAN` highest IDs; ties are broken by ID, newest first.
+        fn ordered_claims(&self, claim_ids: Vec<u64>, offset: usize, limit: usize, order: SortOrder) -> Vec<Claim> {
+            let now = self.env().block_timestamp();
+            let load = |id: u64| self.claims.get(id).filter(|claim| Self::is_active(claim, now));
+
+            match order {
+                SortOrder::IdAsc => claim_ids.into_iter().filter_map(load).skip(offset).take(limit).collect(),
+                SortOrder::IdDesc => claim_ids.into_iter().rev().filter_map(load).skip(offset).take(limit).collect(),
+                SortOrder::ApprovedAtDesc => {
+                    let mut claims: Vec<Claim> = claim_ids
+                        .into_iter()
+                        .rev()
+                        .take(MAX_SORT_SCAN)
+                        .filter_map(load)
+                        .collect();
+                    // Stable sort keeps the newest-first order among equal keys
+                    claims.sort_by_key(|claim| core::cmp::Reverse(claim.approved_at));
+                    claims.into_iter().skip(offset).take(limit).collect()
+                }
+            }
+        }
+
+        /// Whether a claim's time lock (if any) has passed
+        fn is_active(claim: &Claim, now: Timestamp) -> bool {
+            claim.activates_at.is_none_or(|activates_at| now >= activates_at)
+        }
+
+        /// Append an approval timestamp, dropping the oldest beyond `MAX_APPROVAL_TIMES`
+        fn record_approval_time(&mut self, receiver: AccountId, claim_type: &str, now: Timestamp) {
+            let key = (receiver, String::from(claim_type));
+            let mut times = self.approval_times.get(&key).unwrap_or_default();
+
+            times.push(now);
+            if times.len() > MAX_APPROVAL_TIMES {
+                times.remove(0);
+            }
+
+            self.approval_times.insert(&key, &times);
+        }
+
+        /// Remove the timestamp of a revoked approval, if it is still kept
+        fn forget_approval_time(&mut self, receiver: AccountId, claim_type: &str, approved_at: Timestamp) {
+            let key = (receiver, String::from(claim_type));
+            let mut times = self.approval_times.get(&key).unwrap_or_default();
+
+            if let Some(index) = times.iter().rposition(|&t| t == approved_at) {
+                times.remove(index);
+                self.approval_times.insert(&key, &times);
+            }
+        }
+
+        /// Collect the first `MAX_BOUNDED_CLAIMS` active claims of an account
+        fn bounded_claims(&self, account_id: AccountId) -> BoundedClaims {
+            let claim_ids = self.user_claims.get(account_id).unwrap_or_default();
+            let now = self.env().block_timestamp();
+
+            // Stop reading claims as soon as the bound is reached
+            let mut claims = Vec::new();
+            let mut scanned = 0;
+            for &id in claim_ids.iter() {
+                if claims.len() == MAX_BOUNDED_CLAIMS {
+                    break;
+                }
+                scanned += 1;
+                // Hide time-locked claims
+                if let Some(claim) = self.claims.get(id).filter(|claim| Self::is_active(claim, now)) {
+                    claims.push(claim);
+                }
+            }
+
+            BoundedClaims {
+                claims,
+                truncated: scanned < claim_ids.len(),
+                total: claim_ids.len() as u32,
+            }
+        }
+
+        /// Whether `claim` is approved and its approval reached the sunset horizon
+        fn is_sunset(&self, claim: &Claim) -> bool {
+            claim.status == ClaimStatus::Approved
+                && claim
+                    .approved_at
+                    .zip(self.fresh_since())
+                    .is_some_and(|(approved_at, fresh_since)| approved_at < fresh_since)
+        }
+
+        /// Earliest approval time that still counts (None while nothing can be stale)
+        fn fresh_since(&self) -> Option<Timestamp> {
+            let now = self.env().block_timestamp();
+            if self.stale_after_ms == 0 || now < self.stale_after_ms {
+                return None;
+            }
+            Some(now - self.stale_after_ms + 1)
+        }
+
+        /// Approved claims per claim type of an account that still count
+        /// 
+        /// Without a horizon these are the plain counters. With one, fresh
+        /// approvals are counted from the latest `MAX_APPROVAL_TIMES` approval
+        /// timestamps of each type, like `get_approved_count_since`.
+        fn counted_by_type(&self, account: AccountId) -> Vec<(String, u32)> {
+            let approved_by_type = self.approved_by_type.get(account).unwrap_or_default();
+            let Some(fresh_since) = self.fresh_since() else {
+                return approved_by_type;
+            };
+
+            approved_by_type
+                .into_iter()
+                .filter_map(|(claim_type, count)| {
+                    let fresh = self.approvals_since(account, claim_type.clone(), fresh_since).min(count);
+                    (fresh > 0).then_some((claim_type, fresh))
+                })
+                .collect()
+        }
+
+        /// Number of kept approval timestamps of a type at or after `since`
+        fn approvals_since(&self, account: AccountId, claim_type: String, since: Timestamp) -> u32 {
+            let times = self.approval_times.get((account, claim_type)).unwrap_or_default();
+
+            // Timestamps are appended in block order, so the window is a suffix
+            let first = times.partition_point(|&t| t < since);
+            (times.len() - first) as u32
+        }
+
+        /// Number of approved claims across all claim types
+        fn total_approved(approved_by_type: &[(String, u32)]) -> u32 {
+            approved_by_type
+                .iter()
+                .fold(0u32, |total, (_, count)| total.saturating_add(*count))
+        }
+
+        /// Day since the Unix epoch containing a timestamp
+        fn day_of(timestamp: Timestamp) -> u32 {
+            (timestamp / MS_PER_DAY) as u32
+        }
+    }
+
+    impl ClaimChecker for SkillChainRegistry {
+        /// Verify a claim against an expected receiver and proof hash
+        /// 
+        /// Identity mismatches are reported before status problems, so a caller
+        /// holding the wrong claim ID never learns anything about its status.
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim to verify
+        /// * `expected_receiver` - Account the claim must belong to
+        /// * `expected_proof` - Proof hash the claim must carry
+        /// 
+        /// # Returns
+        /// * `VerificationResult` - `Valid` or the first failed check
+        #[ink(message)]
+        fn verify_claim(
+            &self,
+            claim_id: u64,
+            expected_receiver: AccountId,
+            expected_proof: Hash,
+        ) -> VerificationResult {
+            // Time-locked claims stay hidden until they activate
+            let Some(claim) = self.get_claim(claim_id) else {
+                return VerificationResult::NotFound;
+            };
+
+            if claim.receiver != expected_receiver {
+                return VerificationResult::WrongReceiver;
+            }
+
+            if claim.proof_hash != expected_proof {
+                return VerificationResult::WrongProof;
+            }
+
+            if claim.expires_at.is_some_and(|expires_at| self.env().block_timestamp() >= expires_at) {
+                return VerificationResult::Expired;
+            }
+
+            match claim.status {
+                ClaimStatus::Approved if self.is_sunset(&claim) => VerificationResult::Stale,
+                ClaimStatus::Approved => VerificationResult::Valid,
+                // Rejected claims were never approved either
+                ClaimStatus::Pending | ClaimStatus::Rejected => VerificationResult::NotApproved,
+                ClaimStatus::Revoked => VerificationResult::Revoked,
+                ClaimStatus::Expired => VerificationResult::Expired,
+            }
+        }
+
+        /// Count approved claims of a type received since a timestamp
+        /// 
+        /// Backed by the latest `MAX_APPROVAL_TIMES` approval timestamps per
+        /// account and claim type. Once more approvals than that fall in the
+        /// window the oldest are no longer known, so the result saturates at
+        /// `MAX_APPROVAL_TIMES` and means "at least that many". Revoked
+        /// and stale approvals are not counted.
+        /// 
+        /// # Arguments
+        /// * `account` - Receiver of the claims
+        /// * `claim_type` - Claim type to count
+        /// * `since` - Earliest approval timestamp counted, inclusive
+        /// 
+        /// # Returns
+        /// * `u32` - Number of approvals at or after `since`, at most `MAX_APPROVAL_TIMES`
+        #[ink(message)]
+        fn get_approved_count_since(&self, account: AccountId, claim_type: String, since: Timestamp) -> u32 {
+            let since = self.fresh_since().map_or(since, |fresh_since| since.max(fresh_since));
+            self.approvals_since(account, claim_type, since)
+        }
+
+        /// Get the first claims of an account for cross-contract callers
+        /// 
+        /// `total` counts every claim ID held by the account, including
+        /// time-locked claims that are not returned, so `claims.len()` can be
+        /// below `total` without `truncated` being set.
+        /// 
+        /// # Arguments
+        /// * `account` - Account to query
+        /// 
+        /// # Returns
+        /// * `BoundedClaims` - At most `MAX_BOUNDED_CLAIMS` active claims, oldest first
+        #[ink(message)]
+        fn get_claims_bounded(&self, account: AccountId) -> BoundedClaims {
+            self.bounded_claims(account)
+        }
+
+        /// Check whether an account has a profile
+        /// 
+        /// # Arguments
+        /// * `account` - Account to query
+        /// 
+        /// # Returns
+        /// * `bool` - True if the account registered a profile
+        #[ink(message)]
+        fn profile_exists(&self, account: AccountId) -> bool {
+            self.profiles.contains(account)
+        }
+
+        /// Check whether a claim exists
+        /// 
+        /// Claims are never deleted, so this holds for every issued ID whatever
+        /// its status, time-locked claims included.
+        /// 
+        /// # Arguments
+        /// * `claim_id` - ID of the claim
+        /// 
+        /// # Returns
+        /// * `bool` - True if the claim was issued
+        #[ink(message)]
+        fn claim_exists(&self, claim_id: u64) -> bool {
+            self.claims.contains(claim_id)
+        }
+
+        /// Check whether an account holds an approved claim of a type
+        /// 
+        /// Reads the per-type approval counters, which drop on revocation, so
+        /// it agrees with `get_profile_snapshot`. With a sunset horizon set,
+        /// only approvals younger than the horizon count.
+        /// 
+        /// # Arguments
+        /// * `account` - Receiver of the claims
+        /// * `claim_type` - Claim type to look for
+        /// 
+        /// # Returns
+        /// * `bool` - True if at least one approved claim of the type counts
+        #[ink(message)]
+        fn has_approved_claim_of_type(&self, account: AccountId, claim_type: String) -> bool {
+            let approved = self
+                .approved_by_type
+                .get(account)
+                .unwrap_or_default()
+                .iter()
+                .any(|(t, count)| *t == claim_type && *count > 0);
+
+            match self.fresh_since() {
+                Some(fresh_since) if approved => self.approvals_since(account, claim_type, fresh_since) > 0,
+                _ => approved,
+            }
+        }
+    }
+
+    // ========================================
+    // UNIT TESTS
+    // ========================================
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Helper function to get default accounts
+        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        /// Helper function to set caller
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let contract = SkillChainRegistry::new();
+            assert_eq!(contract.get_total_claims(), 0);
+        }
+
+        #[ink::test]
+        fn test_register_profile_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let result = contract.register_profile("ipfs://QmTest123".to_string());
+
+            assert!(result.is_ok());
+
+            let profile = contract.get_profile(accounts.alice);
+            assert!(profile.is_some());
+            assert_eq!(profile.unwrap().owner, accounts.alice);
+        }
+
+        #[ink::test]
+        fn test_register_profile_duplicate_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            // First registration should succeed
+            let result1 = contract.register_profile("ipfs://QmTest123".to_string());
+            assert!(result1.is_ok());
+
+            // Second registration should fail
+            let result2 = contract.register_profile("ipfs://QmTest456".to_string());
+            assert_eq!(result2, Err(ContractError::ProfileAlreadyExists));
+        }
+
+        #[ink::test]
+        fn test_add_claim_success() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            let result = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            );
+
+            assert!(result.is_ok());
+            assert_eq!(result.unwrap(), 0); // First claim ID should be 0
+        }
+
+        #[ink::test]
+        fn test_add_claim_increments_id() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            let id1 = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            let id2 = contract.add_claim(
+                accounts.bob,
+                "job_completed".to_string(),
+                Hash::from([0x02; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            assert_eq!(id1, 0);
+            assert_eq!(id2, 1);
+            assert_eq!(contract.get_total_claims(), 2);
+        }
+
+        #[ink::test]
+        fn test_approve_claim_by_issuer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            // Alice (issuer) approves the claim
+            let result = contract.approve_claim(claim_id);
+            assert!(result.is_ok());
+
+            // Verify claim status is Approved
+            let claims = contract.get_claims(accounts.bob);
+            assert_eq!(claims.len(), 1);
+            assert_eq!(claims[0].status, ClaimStatus::Approved);
+        }
+
+        #[ink::test]
+        fn test_approve_claim_unauthorized_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            // Bob tries to approve Alice's claim (should fail)
+            set_caller(accounts.bob);
+            let result = contract.approve_claim(claim_id);
+            assert_eq!(result, Err(ContractError::UnauthorizedApproval));
+        }
+
+        #[ink::test]
+        fn test_approve_claim_twice_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            // First approval should succeed
+            let result1 = contract.approve_claim(claim_id);
+            assert!(result1.is_ok());
+
+            // Second approval should fail
+            let result2 = contract.approve_claim(claim_id);
+            assert_eq!(result2, Err(ContractError::ClaimAlreadyApproved));
+        }
+
+        #[ink::test]
+        fn test_get_profile_existing() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.register_profile("ipfs://QmTest123".to_string()).unwrap();
+
+            let profile = contract.get_profile(accounts.alice);
+            assert!(profile.is_some());
+            assert_eq!(profile.unwrap().metadata_uri, "ipfs://QmTest123");
+        }
+
+        #[ink::test]
+        fn test_get_profile_nonexistent() {
+            let accounts = default_accounts();
+            let contract = SkillChainRegistry::new();
+
+            let profile = contract.get_profile(accounts.alice);
+            assert!(profile.is_none());
+        }
+
+        #[ink::test]
+        fn test_get_claims_empty() {
+            let accounts = default_accounts();
+            let contract = SkillChainRegistry::new();
+
+            let claims = contract.get_claims(accounts.alice);
+            assert_eq!(claims.len(), 0);
+        }
+
+        #[ink::test]
+        fn test_get_claims_multiple() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            // Add multiple claims to Bob
+            contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            contract.add_claim(
+                accounts.bob,
+                "job_completed".to_string(),
+                Hash::from([0x02; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            contract.add_claim(
+                accounts.bob,
+                "contribution".to_string(),
+                Hash::from([0x03; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            // Get all claims for Bob
+            let claims = contract.get_claims(accounts.bob);
+            assert_eq!(claims.len(), 3);
+            assert_eq!(claims[0].claim_type, "hackathon_win");
+            assert_eq!(claims[1].claim_type, "job_completed");
+            assert_eq!(claims[2].claim_type, "contribution");
+        }
+
+        #[ink::test]
+        fn test_claim_not_found() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            
+            // Try to approve non-existent claim
+            let result = contract.approve_claim(999);
+            assert_eq!(result, Err(ContractError::ClaimNotFound));
+        }
+
+        #[ink::test]
+        fn test_revoke_claim_by_issuer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+            contract.approve_claim(claim_id).unwrap();
+
+            // Alice (issuer) revokes the approved claim
+            let result = contract.revoke_claim(claim_id);
+            assert!(result.is_ok());
+
+            let claims = contract.get_claims(accounts.bob);
+            assert_eq!(claims[0].status, ClaimStatus::Revoked);
+
+            // Revoked claims can neither be revoked again nor approved
+            assert_eq!(contract.revoke_claim(claim_id), Err(ContractError::ClaimAlreadyRevoked));
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::ClaimAlreadyRevoked));
+        }
+
+        #[ink::test]
+        fn test_revoke_claim_unauthorized_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+
+            // Bob (receiver) cannot revoke Alice's claim
+            set_caller(accounts.bob);
+            let result = contract.revoke_claim(claim_id);
+            assert_eq!(result, Err(ContractError::UnauthorizedApproval));
+        }
+
+        /// Helper function to set the block timestamp
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+        }
+
+        #[ink::test]
+        fn test_activity_counts_approvals_per_day() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let ids: Vec<u64> = (0..3u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+
+            // Two approvals on day 10, one on day 12
+            set_timestamp(10 * MS_PER_DAY + 5);
+            contract.approve_claim(ids[0]).unwrap();
+            set_timestamp(11 * MS_PER_DAY - 1);
+            contract.approve_claim(ids[1]).unwrap();
+            set_timestamp(12 * MS_PER_DAY);
+            contract.approve_claim(ids[2]).unwrap();
+
+            assert_eq!(contract.get_activity(accounts.bob, 0, 30), vec![(10, 2), (12, 1)]);
+            assert_eq!(contract.get_activity(accounts.bob, 11, 12), vec![(12, 1)]);
+            assert_eq!(contract.get_activity(accounts.alice, 0, 30), vec![]);
+            assert!(contract.claims.get(ids[0]).unwrap().approved_at.is_some());
+        }
+
+        #[ink::test]
+        fn test_activity_revocation_decrements_approval_day() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let approved = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let pending = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            set_timestamp(3 * MS_PER_DAY);
+            contract.approve_claim(approved).unwrap();
+
+            // Revoke on a later day: the original approval day loses the claim
+            set_timestamp(7 * MS_PER_DAY);
+            contract.revoke_claim(approved).unwrap();
+            assert_eq!(contract.get_activity(accounts.bob, 0, 10), vec![]);
+
+            // Revoking a pending claim leaves activity untouched
+            contract.revoke_claim(pending).unwrap();
+            assert_eq!(contract.get_activity(accounts.bob, 0, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn test_activity_range_is_capped() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let inside = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let outside = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            set_timestamp(u64::from(MAX_ACTIVITY_RANGE_DAYS - 1) * MS_PER_DAY);
+            contract.approve_claim(inside).unwrap();
+            set_timestamp(u64::from(MAX_ACTIVITY_RANGE_DAYS) * MS_PER_DAY);
+            contract.approve_claim(outside).unwrap();
+
+            // Only the first MAX_ACTIVITY_RANGE_DAYS days of the range are returned
+            assert_eq!(
+                contract.get_activity(accounts.bob, 0, u32::MAX),
+                vec![(MAX_ACTIVITY_RANGE_DAYS - 1, 1)]
+            );
+            assert_eq!(
+                contract.get_activity(accounts.bob, 1, u32::MAX),
+                vec![(MAX_ACTIVITY_RANGE_DAYS - 1, 1), (MAX_ACTIVITY_RANGE_DAYS, 1)]
+            );
+
+            // Inverted range is empty
+            assert_eq!(contract.get_activity(accounts.bob, 400, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn test_approve_claim_around_pending_ttl() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(1_000);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.set_pending_ttl(500).unwrap();
+
+            let fresh = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let stale = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            // Exactly at the deadline the claim can still be approved
+            set_timestamp(1_500);
+            assert!(contract.approve_claim(fresh).is_ok());
+
+            // One millisecond later it is stale
+            set_timestamp(1_501);
+            assert_eq!(contract.approve_claim(stale), Err(ContractError::ClaimStale));
+            assert_eq!(contract.claims.get(stale).unwrap().status, ClaimStatus::Pending);
+
+            // Disabling the TTL makes it approvable again
+            contract.set_pending_ttl(0).unwrap();
+            assert!(contract.approve_claim(stale).is_ok());
+        }
+
+        #[ink::test]
+        fn test_set_pending_ttl_only_admin() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            assert_eq!(contract.get_admin(), accounts.alice);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_pending_ttl(500), Err(ContractError::NotAdmin));
+            assert_eq!(contract.get_pending_ttl(), 0);
+        }
+
+        #[ink::test]
+        fn test_sweep_stale_claims() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.set_pending_ttl(MS_PER_DAY).unwrap();
+
+            let first = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let second = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+            set_timestamp(MS_PER_DAY);
+            let third = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x03; 32]), None, None).unwrap();
+
+            // Nothing is stale yet
+            assert_eq!(contract.sweep_stale_claims(accounts.bob, 10), 0);
+
+            // The first two are past the deadline, the third is not
+            set_timestamp(MS_PER_DAY + 1);
+            set_caller(accounts.charlie);
+            assert_eq!(contract.sweep_stale_claims(accounts.bob, 10), 2);
+
+            let remaining: Vec<u64> = contract.get_claims(accounts.bob).iter().map(|c| c.id).collect();
+            assert_eq!(remaining, vec![third]);
+            assert_eq!(contract.claims.get(first).unwrap().status, ClaimStatus::Expired);
+            assert_eq!(contract.claims.get(second).unwrap().status, ClaimStatus::Expired);
+
+            // Events: [0..3] ClaimAdded, [3] and [4] ClaimExpired
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 5);
+            let expired = <ClaimExpired as scale::Decode>::decode(&mut &events[4].data[..]).unwrap();
+            assert_eq!(expired.claim_id, second);
+            assert_eq!(expired.receiver, accounts.bob);
+
+            // Expired claims can no longer be approved
+            set_caller(accounts.alice);
+            assert_eq!(contract.approve_claim(first), Err(ContractError::ClaimExpired));
+        }
+
+        #[ink::test]
+        fn test_sweep_stale_claims_respects_limit() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.set_pending_ttl(10).unwrap();
+
+            for i in 0..3u8 {
+                contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap();
+            }
+
+            set_timestamp(11);
+            assert_eq!(contract.sweep_stale_claims(accounts.bob, 2), 2);
+            assert_eq!(contract.get_claims(accounts.bob).len(), 1);
+            assert_eq!(contract.sweep_stale_claims(accounts.bob, 2), 1);
+            assert!(contract.get_claims(accounts.bob).is_empty());
+        }
+
+        #[ink::test]
+        fn test_approve_claims_batch() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let ids: Vec<u64> = (0..3u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+            contract.approve_claim(ids[0]).unwrap();
+
+            // Already approved claim is skipped, duplicates count once
+            let approved = contract.approve_claims_batch(vec![ids[0], ids[1], ids[2], ids[2]]).unwrap();
+            assert_eq!(approved, 2);
+            assert!(contract.get_claims(accounts.bob).iter().all(|c| c.status == ClaimStatus::Approved));
+
+            // Events: [0..3] ClaimAdded, [3] ClaimApproved, [4] and [5] batch ClaimApproved
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 6);
+            let last = <ClaimApproved as scale::Decode>::decode(&mut &events[5].data[..]).unwrap();
+            assert_eq!(last.claim_id, ids[2]);
+        }
+
+        #[ink::test]
+        fn test_approve_claims_batch_foreign_claim_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let own = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+
+            set_caller(accounts.charlie);
+            let foreign = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            // Nothing is approved when any claim belongs to another issuer
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.approve_claims_batch(vec![own, foreign]),
+                Err(ContractError::UnauthorizedApproval)
+            );
+            assert_eq!(contract.claims.get(own).unwrap().status, ClaimStatus::Pending);
+
+            assert_eq!(contract.approve_claims_batch(vec![own, 99]), Err(ContractError::ClaimNotFound));
+        }
+
+        #[ink::test]
+        fn test_approve_claims_batch_too_large_fails() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let ids: Vec<u64> = (0..=MAX_APPROVAL_BATCH as u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+
+            assert_eq!(contract.approve_claims_batch(ids.clone()), Err(ContractError::BatchTooLarge));
+            assert_eq!(contract.approve_claims_batch(ids[..MAX_APPROVAL_BATCH].to_vec()), Ok(50));
+        }
+
+        #[ink::test]
+        fn test_profile_snapshot() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+
+            // Twelve claim types, "type_00" approved 3 times, "type_01" twice, the rest once
+            set_caller(accounts.alice);
+            let mut seed = 0u8;
+            let mut issue = |contract: &mut SkillChainRegistry, claim_type: String| {
+                seed += 1;
+                contract.add_claim(accounts.bob, claim_type, Hash::from([seed; 32]), None, None).unwrap()
+            };
+            let mut ids = Vec::new();
+            for i in 0..12 {
+                ids.push(issue(&mut contract, format!("type_{:02}", i)));
+            }
+            ids.push(issue(&mut contract, "type_00".to_string()));
+            ids.push(issue(&mut contract, "type_00".to_string()));
+            ids.push(issue(&mut contract, "type_01".to_string()));
+            contract.approve_claims_batch(ids.clone()).unwrap();
+
+            // A revoked approval and a pending claim do not count
+            contract.revoke_claim(ids[11]).unwrap();
+            set_timestamp(42_000);
+            issue(&mut contract, "type_05".to_string());
+
+            let snapshot = contract.get_profile_snapshot(accounts.bob).unwrap();
+            assert_eq!(snapshot.profile.owner, accounts.bob);
+            assert_eq!(snapshot.profile.metadata_uri, "ipfs://QmBob");
+            assert_eq!(snapshot.profile.did, Some("did:kilt:light:bob".to_string()));
+            assert!(snapshot.verified);
+            assert_eq!(snapshot.reputation_score, 14);
+            assert_eq!(snapshot.last_claim_at, Some(42_000));
+
+            assert_eq!(snapshot.approved_by_type.len(), MAX_SNAPSHOT_CLAIM_TYPES);
+            assert_eq!(snapshot.approved_by_type[0], ("type_00".to_string(), 3));
+            assert_eq!(snapshot.approved_by_type[1], ("type_01".to_string(), 2));
+            assert_eq!(snapshot.approved_by_type[2], ("type_02".to_string(), 1));
+            assert_eq!(snapshot.approved_by_type[9], ("type_09".to_string(), 1));
+        }
+
+        #[ink::test]
+        fn test_profile_snapshot_without_activity() {
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+
+            let mut contract = SkillChainRegistry::new();
+            assert!(contract.get_profile_snapshot(accounts.bob).is_none());
+
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            let snapshot = contract.get_profile_snapshot(accounts.bob).unwrap();
+            assert!(!snapshot.verified);
+            assert_eq!(snapshot.reputation_score, 0);
+            assert!(snapshot.approved_by_type.is_empty());
+            assert_eq!(snapshot.last_claim_at, None);
+        }
+
+        #[ink::test]
+        fn test_link_did_web_after_allowing_method() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.register_profile("ipfs://QmAlice".to_string()).unwrap();
+            assert_eq!(contract.get_did_methods(), vec!["did:kilt:".to_string()]);
+
+            assert_eq!(contract.link_did("did:web:example.com".to_string()), Err(ContractError::InvalidDid));
+
+            contract.allow_did_method("did:web:".to_string()).unwrap();
+            assert_eq!(contract.get_did_methods(), vec!["did:kilt:".to_string(), "did:web:".to_string()]);
+            contract.link_did("did:web:example.com".to_string()).unwrap();
+            assert_eq!(contract.get_did(accounts.alice), Some("did:web:example.com".to_string()));
+
+            // Events: [0] ProfileRegistered, [1] DidMethodAllowed, [2] DidLinked
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let allowed = <DidMethodAllowed as scale::Decode>::decode(&mut &events[1].data[..]).unwrap();
+            assert_eq!(allowed.prefix, "did:web:");
+
+            // Disallowing keeps linked DIDs but rejects new ones
+            contract.disallow_did_method("did:web:".to_string()).unwrap();
+            assert_eq!(contract.get_did(accounts.alice), Some("did:web:example.com".to_string()));
+            assert_eq!(contract.link_did("did:web:example.org".to_string()), Err(ContractError::InvalidDid));
+            assert_eq!(
+                contract.disallow_did_method("did:web:".to_string()),
+                Err(ContractError::DidMethodNotAllowed)
+            );
+        }
+
+        #[ink::test]
+        fn test_metadata_uri_validation() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            assert_eq!(
+                contract.get_uri_schemes(),
+                vec!["ipfs://".to_string(), "ar://".to_string(), "https://".to_string()]
+            );
+
+            // Each default scheme
+            contract.register_profile("ipfs://QmAlice".to_string()).unwrap();
+            set_caller(accounts.bob);
+            contract.register_profile("ar://bob-tx".to_string()).unwrap();
+            set_caller(accounts.charlie);
+            contract.register_org_profile("https://charlie.example/profile.json".to_string()).unwrap();
+
+            // Garbage is rejected and nothing is stored
+            set_caller(accounts.django);
+            for uri in ["", "asdf", "javascript:alert(1)", "http://example.com", "ipfs://", "IPFS://Qm"] {
+                assert_eq!(contract.register_profile(uri.to_string()), Err(ContractError::InvalidUri));
+            }
+            let too_long = format!("ipfs://{}", "a".repeat(MAX_METADATA_URI_LEN - "ipfs://".len() + 1));
+            assert_eq!(contract.register_profile(too_long.clone()), Err(ContractError::InvalidUri));
+            assert_eq!(
+                contract.onboard("asdf".to_string(), Some("did:kilt:light:django".to_string())),
+                Err(ContractError::InvalidUri)
+            );
+            assert!(contract.get_profile(accounts.django).is_none());
+            let mut longest = too_long;
+            longest.truncate(MAX_METADATA_URI_LEN);
+            contract.register_profile(longest).unwrap();
+
+            // Templates are checked too
+            assert_eq!(
+                contract.create_claim_template("skill".to_string(), 1, 0, "asdf".to_string()),
+                Err(ContractError::InvalidUri)
+            );
+            let template_id = contract
+                .create_claim_template("skill".to_string(), 1, 0, "ipfs://skill".to_string())
+                .unwrap();
+            assert_eq!(
+                contract.update_template(template_id, 1, 0, String::new()),
+                Err(ContractError::InvalidUri)
+            );
+        }
+
+        #[ink::test]
+        fn test_allow_uri_scheme() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.register_profile("ceramic://bob".to_string()), Err(ContractError::InvalidUri));
+            assert_eq!(contract.allow_uri_scheme("ceramic://".to_string()), Err(ContractError::NotAdmin));
+
+            set_caller(accounts.alice);
+            for malformed in ["ceramic:", "://", "Ceramic://", "1pfs://", "java script://"] {
+                assert_eq!(
+                    contract.allow_uri_scheme(malformed.to_string()),
+                    Err(ContractError::InvalidUriScheme)
+                );
+            }
+            contract.allow_uri_scheme("ceramic://".to_string()).unwrap();
+            assert_eq!(
+                contract.allow_uri_scheme("ceramic://".to_string()),
+                Err(ContractError::UriSchemeAlreadyAllowed)
+            );
+
+            set_caller(accounts.bob);
+            contract.register_profile("ceramic://bob".to_string()).unwrap();
+
+            // Disallowing keeps stored URIs but rejects new ones
+            set_caller(accounts.alice);
+            contract.disallow_uri_scheme("ceramic://".to_string()).unwrap();
+            assert_eq!(contract.get_profile(accounts.bob).unwrap().metadata_uri, "ceramic://bob");
+            set_caller(accounts.charlie);
+            assert_eq!(contract.register_profile("ceramic://charlie".to_string()), Err(ContractError::InvalidUri));
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.disallow_uri_scheme("ceramic://".to_string()),
+                Err(ContractError::UriSchemeNotAllowed)
+            );
+
+            // Events: [0] UriSchemeAllowed, [1] ProfileRegistered, [2] UriSchemeDisallowed
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let allowed = <UriSchemeAllowed as scale::Decode>::decode(&mut &events[0].data[..]).unwrap();
+            assert_eq!(allowed.prefix, "ceramic://");
+            let disallowed = <UriSchemeDisallowed as scale::Decode>::decode(&mut &events[2].data[..]).unwrap();
+            assert_eq!(disallowed.prefix, "ceramic://");
+        }
+
+        #[ink::test]
+        fn test_link_did_kilt_unchanged() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.register_profile("ipfs://QmAlice".to_string()).unwrap();
+
+            contract.link_did("did:kilt:4rTLFhZ5FqWnQk7uA9hGVY3Kv1nNTL5j2gC4r3Lx8sU2dPeP".to_string()).unwrap();
+            contract.link_did("did:kilt:light:004rTLFhZ5FqWnQk7uA9hGVY3Kv1nNTL5j".to_string()).unwrap();
+            assert_eq!(contract.link_did("did:key:z6Mkf".to_string()), Err(ContractError::InvalidDid));
+            assert_eq!(contract.link_did("kilt:abc".to_string()), Err(ContractError::InvalidDid));
+        }
+
+        #[ink::test]
+        fn test_link_did_length_and_characters() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.register_profile("ipfs://QmAlice".to_string()).unwrap();
+
+            assert_eq!(contract.link_did("did:kilt:".to_string()), Err(ContractError::InvalidDid));
+            assert_eq!(contract.link_did("did:kilt:abc def".to_string()), Err(ContractError::InvalidDid));
+            assert_eq!(contract.link_did("did:kilt:abc:".to_string()), Err(ContractError::InvalidDid));
+            let too_long = format!("did:kilt:{}", "a".repeat(MAX_DID_LEN));
+            assert_eq!(contract.link_did(too_long), Err(ContractError::InvalidDid));
+        }
+
+        #[ink::test]
+        fn test_did_method_management() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            assert_eq!(contract.allow_did_method("did:kilt:".to_string()), Err(ContractError::DidMethodAlreadyAllowed));
+            assert_eq!(contract.allow_did_method("did:web".to_string()), Err(ContractError::InvalidDidMethod));
+            assert_eq!(contract.allow_did_method("web:".to_string()), Err(ContractError::InvalidDidMethod));
+            assert_eq!(contract.allow_did_method("did:Web:".to_string()), Err(ContractError::InvalidDidMethod));
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.allow_did_method("did:key:".to_string()), Err(ContractError::NotAdmin));
+            assert_eq!(contract.disallow_did_method("did:kilt:".to_string()), Err(ContractError::NotAdmin));
+        }
+
+        #[ink::test]
+        fn test_add_and_approve_claim_requires_automated_issuer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let proof = Hash::from([0x07; 32]);
+
+            // Not listed: the one-shot is rejected
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.bob, "job_completed".to_string(), proof),
+                Err(ContractError::NotAutomatedIssuer)
+            );
+            assert_eq!(contract.get_total_claims(), 0);
+
+            // Only the admin manages the list
+            assert_eq!(contract.add_automated_issuer(accounts.django), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            contract.add_automated_issuer(accounts.django).unwrap();
+            assert!(contract.is_automated_issuer(accounts.django));
+
+            // Listed: claim is created already approved
+            set_caller(accounts.django);
+            set_timestamp(5 * MS_PER_DAY);
+            let claim_id = contract.add_and_approve_claim(accounts.bob, "job_completed".to_string(), proof).unwrap();
+            let claim = contract.get_claim(claim_id).unwrap();
+            assert_eq!(claim.status, ClaimStatus::Approved);
+            assert_eq!(claim.issuer_kind, IssuerKind::Contract);
+            assert_eq!(claim.approved_at, Some(5 * MS_PER_DAY));
+            assert_eq!(contract.get_activity(accounts.bob, 5, 5), vec![(5, 1)]);
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::Valid);
+
+            // Events: [0] AutomatedIssuerAdded, [1] ClaimAdded, [2] ClaimApproved
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 3);
+            let approved = <ClaimApproved as scale::Decode>::decode(&mut &events[2].data[..]).unwrap();
+            assert_eq!(approved.claim_id, claim_id);
+        }
+
+        #[ink::test]
+        fn test_issuer_kind_round_trips() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.add_automated_issuer(accounts.django).unwrap();
+
+            let manual = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            set_caller(accounts.django);
+            let automated = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            let kinds: Vec<IssuerKind> = contract.get_claims(accounts.bob).iter().map(|c| c.issuer_kind).collect();
+            assert_eq!(kinds, vec![IssuerKind::Account, IssuerKind::Contract]);
+            assert_eq!(contract.get_claim(manual).unwrap().issuer_kind, IssuerKind::Account);
+
+            // Removal is not retroactive, but stops the one-shot
+            set_caller(accounts.alice);
+            contract.remove_automated_issuer(accounts.django).unwrap();
+            assert_eq!(contract.get_claim(automated).unwrap().issuer_kind, IssuerKind::Contract);
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.bob, "contribution".to_string(), Hash::from([0x03; 32])),
+                Err(ContractError::NotAutomatedIssuer)
+            );
+        }
+
+        #[ink::test]
+        fn test_unseen_claims_watermark() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let first = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let second = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+            let third = contract.add_claim(accounts.bob, "job_completed".to_string(), Hash::from([0x03; 32]), None, None).unwrap();
+
+            // Nothing seen yet
+            set_caller(accounts.bob);
+            assert_eq!(contract.get_last_seen(accounts.bob), None);
+            let ids: Vec<u64> = contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdAsc).iter().map(|c| c.id).collect();
+            assert_eq!(ids, vec![first, second, third]);
+            assert_eq!(contract.get_unseen_claims(accounts.bob, 2, SortOrder::IdAsc).len(), 2);
+
+            contract.mark_claims_seen(second).unwrap();
+            assert_eq!(contract.get_last_seen(accounts.bob), Some(second));
+            let ids: Vec<u64> = contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdAsc).iter().map(|c| c.id).collect();
+            assert_eq!(ids, vec![third]);
+
+            // Forward only, and never past issued claims
+            assert_eq!(contract.mark_claims_seen(first), Err(ContractError::SeenWatermarkRegression));
+            assert_eq!(contract.mark_claims_seen(third + 1), Err(ContractError::ClaimNotFound));
+            contract.mark_claims_seen(second).unwrap();
+            contract.mark_claims_seen(third).unwrap();
+            assert!(contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdAsc).is_empty());
+
+            // Watermarks are per account
+            assert_eq!(contract.get_last_seen(accounts.charlie), None);
+        }
+
+        #[ink::test]
+        fn test_issue_from_template() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(1_000);
+
+            let mut contract = SkillChainRegistry::new();
+            let template_id = contract
+                .create_claim_template("certification".to_string(), 2, 5_000, "ipfs://cert-v1".to_string())
+                .unwrap();
+            assert_eq!(contract.list_templates(accounts.alice).len(), 1);
+
+            let proof = Hash::from([0x01; 32]);
+            let claim_id = contract.issue_from_template(template_id, accounts.bob, proof, None).unwrap();
+            let claim = contract.get_claim(claim_id).unwrap();
+            assert_eq!(claim.claim_type, "certification");
+            assert_eq!(claim.status, ClaimStatus::Pending);
+            assert_eq!(claim.template_id, Some(template_id));
+            assert_eq!(claim.level, Some(2));
+            assert_eq!(claim.metadata_uri, Some("ipfs://cert-v1".to_string()));
+            assert_eq!(claim.expires_at, Some(6_000));
+
+            // Events: [0] TemplateCreated, [1] ClaimAdded, [2] TemplateUsed
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 3);
+            let used = <TemplateUsed as scale::Decode>::decode(&mut &events[2].data[..]).unwrap();
+            assert_eq!(used.template_id, template_id);
+            assert_eq!(used.claim_id, claim_id);
+
+            // Changing the template leaves the issued claim alone
+            contract.update_template(template_id, 3, 0, "ipfs://cert-v2".to_string()).unwrap();
+            let earlier = contract.get_claim(claim_id).unwrap();
+            assert_eq!(earlier.level, Some(2));
+            assert_eq!(earlier.metadata_uri, Some("ipfs://cert-v1".to_string()));
+            assert_eq!(earlier.expires_at, Some(6_000));
+            let later = contract.issue_from_template(template_id, accounts.bob, proof, None).unwrap();
+            assert_eq!(contract.get_claim(later).unwrap().level, Some(3));
+            assert_eq!(contract.get_claim(later).unwrap().expires_at, None);
+
+            // Expiry is enforced on verification
+            contract.approve_claim(claim_id).unwrap();
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::Valid);
+            set_timestamp(6_000);
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::Expired);
+        }
+
+        #[ink::test]
+        fn test_template_ownership_and_deletion() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let template_id = contract
+                .create_claim_template("certification".to_string(), 1, 0, "ipfs://cert".to_string())
+                .unwrap();
+            let proof = Hash::from([0x01; 32]);
+
+            // Only the issuer uses or changes it
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.bob, proof, None),
+                Err(ContractError::NotTemplateOwner)
+            );
+            assert_eq!(contract.delete_template(template_id), Err(ContractError::NotTemplateOwner));
+            assert_eq!(
+                contract.update_template(template_id, 9, 0, String::new()),
+                Err(ContractError::NotTemplateOwner)
+            );
+
+            // Deleted templates cannot be used
+            set_caller(accounts.alice);
+            contract.delete_template(template_id).unwrap();
+            assert!(contract.list_templates(accounts.alice).is_empty());
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.bob, proof, None),
+                Err(ContractError::TemplateNotFound)
+            );
+
+            // Capped per issuer
+            for _ in 0..MAX_TEMPLATES_PER_ISSUER {
+                contract.create_claim_template("skill".to_string(), 1, 0, "ipfs://skill".to_string()).unwrap();
+            }
+            assert_eq!(
+                contract.create_claim_template("skill".to_string(), 1, 0, "ipfs://skill".to_string()),
+                Err(ContractError::TooManyTemplates)
+            );
+        }
+
+        #[ink::test]
+        fn test_reject_claim_with_reason() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let proof = Hash::from([0x01; 32]);
+            let claim_id = contract.add_claim(accounts.bob, "contribution".to_string(), proof, None, None).unwrap();
+
+            // Only the receiver rejects, with a bounded reason
+            assert_eq!(contract.reject_claim(claim_id, None), Err(ContractError::NotClaimReceiver));
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.reject_claim(claim_id, Some("a".repeat(MAX_REJECTION_URI_LEN + 1))),
+                Err(ContractError::InvalidRejectionReason)
+            );
+            contract.reject_claim(claim_id, Some("ipfs://wrong-dates".to_string())).unwrap();
+
+            let claim = contract.get_claim(claim_id).unwrap();
+            assert_eq!(claim.status, ClaimStatus::Rejected);
+            assert_eq!(claim.rejection_reason_uri, Some("ipfs://wrong-dates".to_string()));
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::NotApproved);
+
+            // Events: [0] ClaimAdded, [1] ClaimRejected
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let rejected = <ClaimRejected as scale::Decode>::decode(&mut &events[1].data[..]).unwrap();
+            assert_eq!(rejected.issuer, accounts.alice);
+            assert_eq!(rejected.receiver, accounts.bob);
+            assert_eq!(rejected.rejection_reason_uri, Some("ipfs://wrong-dates".to_string()));
+
+            // A rejected claim is final
+            assert_eq!(contract.reject_claim(claim_id, None), Err(ContractError::ClaimAlreadyRejected));
+            set_caller(accounts.alice);
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::ClaimAlreadyRejected));
+            assert_eq!(contract.revoke_claim(claim_id), Err(ContractError::ClaimAlreadyRejected));
+        }
+
+        #[ink::test]
+        fn test_rejected_claims_by_issuer_and_reissue() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let proof = Hash::from([0x01; 32]);
+            let first = contract.add_claim(accounts.bob, "contribution".to_string(), proof, None, None).unwrap();
+            let second = contract.add_claim(accounts.charlie, "contribution".to_string(), proof, None, None).unwrap();
+
+            set_caller(accounts.bob);
+            contract.reject_claim(first, None).unwrap();
+            set_caller(accounts.charlie);
+            contract.reject_claim(second, Some("ipfs://typo".to_string())).unwrap();
+
+            assert_eq!(contract.get_rejected_count(accounts.alice), 2);
+            assert_eq!(contract.get_rejected_count(accounts.bob), 0);
+            let ids: Vec<u64> = contract
+                .get_rejected_claims_by_issuer(accounts.alice, 0, 10)
+                .iter()
+                .map(|c| c.id)
+                .collect();
+            assert_eq!(ids, vec![first, second]);
+            let page = contract.get_rejected_claims_by_issuer(accounts.alice, 1, 10);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].rejection_reason_uri, Some("ipfs://typo".to_string()));
+            assert!(contract.get_rejected_claims_by_issuer(accounts.alice, 2, 10).is_empty());
+
+            // The corrected claim is a fresh claim and can be approved
+            set_caller(accounts.alice);
+            let corrected = contract.add_claim(accounts.bob, "contribution".to_string(), proof, None, None).unwrap();
+            contract.approve_claim(corrected).unwrap();
+            assert_eq!(contract.verify_claim(corrected, accounts.bob, proof), VerificationResult::Valid);
+            assert_eq!(contract.get_rejected_count(accounts.alice), 2);
+        }
+
+        #[ink::test]
+        fn test_org_member_issues_as_org() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+
+            // Django is the organization, bob a member
+            set_caller(accounts.django);
+            contract.register_org_profile("ipfs://acme".to_string()).unwrap();
+            assert!(contract.get_profile(accounts.django).unwrap().is_org);
+            contract.add_member(accounts.django, accounts.bob, MemberRole::Member).unwrap();
+            assert_eq!(contract.get_member_role(accounts.django, accounts.bob), Some(MemberRole::Member));
+
+            set_caller(accounts.bob);
+            let proof = Hash::from([0x01; 32]);
+            let claim_id = contract
+                .add_claim_as_org(accounts.django, accounts.charlie, "employment".to_string(), proof)
+                .unwrap();
+            assert_eq!(contract.get_claim(claim_id).unwrap().issuer, accounts.django);
+
+            // Approval goes through the org, not the member's own account
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::UnauthorizedApproval));
+            contract.approve_claim_as_org(accounts.django, claim_id).unwrap();
+            assert_eq!(contract.verify_claim(claim_id, accounts.charlie, proof), VerificationResult::Valid);
+
+            // Events: [1] MemberAdded, [2] ClaimAdded
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let added = <MemberAdded as scale::Decode>::decode(&mut &events[1].data[..]).unwrap();
+            assert_eq!(added.member, accounts.bob);
+            let claim_added = <ClaimAdded as scale::Decode>::decode(&mut &events[2].data[..]).unwrap();
+            assert_eq!(claim_added.issuer, accounts.django);
+        }
+
+        #[ink::test]
+        fn test_org_membership_checks() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            let proof = Hash::from([0x01; 32]);
+
+            // Plain profiles are not organizations
+            set_caller(accounts.alice);
+            contract.register_profile("ipfs://alice".to_string()).unwrap();
+            assert_eq!(
+                contract.add_member(accounts.alice, accounts.bob, MemberRole::Member),
+                Err(ContractError::OrgNotFound)
+            );
+
+            set_caller(accounts.django);
+            contract.register_org_profile("ipfs://acme".to_string()).unwrap();
+            contract.add_member(accounts.django, accounts.bob, MemberRole::Admin).unwrap();
+            contract.add_member(accounts.django, accounts.charlie, MemberRole::Member).unwrap();
+
+            // Non-member and plain member cannot act as admins
+            set_caller(accounts.eve);
+            assert_eq!(
+                contract.add_claim_as_org(accounts.django, accounts.alice, "employment".to_string(), proof),
+                Err(ContractError::NotOrgMember)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.add_member(accounts.django, accounts.eve, MemberRole::Member),
+                Err(ContractError::NotOrgAdmin)
+            );
+            let claim_id = contract
+                .add_claim_as_org(accounts.django, accounts.alice, "employment".to_string(), proof)
+                .unwrap();
+
+            // An admin member removes charlie, who loses access immediately
+            set_caller(accounts.bob);
+            contract.remove_member(accounts.django, accounts.charlie).unwrap();
+            assert_eq!(contract.remove_member(accounts.django, accounts.charlie), Err(ContractError::NotOrgMember));
+            set_caller(accounts.charlie);
+            assert_eq!(contract.approve_claim_as_org(accounts.django, claim_id), Err(ContractError::NotOrgMember));
+            assert_eq!(
+                contract.add_claim_as_org(accounts.django, accounts.alice, "employment".to_string(), proof),
+                Err(ContractError::NotOrgMember)
+            );
+            assert_eq!(contract.get_claim(claim_id).unwrap().issuer, accounts.django);
+        }
+
+        #[ink::test]
+        fn test_org_member_cap() {
+            let mut contract = SkillChainRegistry::new();
+            let org = default_accounts().django;
+            set_caller(org);
+            contract.register_org_profile("ipfs://acme".to_string()).unwrap();
+
+            for i in 0..MAX_ORG_MEMBERS {
+                let member = AccountId::from([i as u8 + 1; 32]);
+                contract.add_member(org, member, MemberRole::Member).unwrap();
+            }
+            // Changing a role doesn't count against the cap
+            contract.add_member(org, AccountId::from([1; 32]), MemberRole::Admin).unwrap();
+            assert_eq!(
+                contract.add_member(org, AccountId::from([0xFF; 32]), MemberRole::Member),
+                Err(ContractError::TooManyMembers)
+            );
+        }
+
+        /// Kinds of the recorded `BadgeEarned` events, in emission order
+        fn badge_events() -> Vec<BadgeKind> {
+            // Other events may decode as a badge too, so match the signature topic first
+            let signature = <BadgeEarned as ink::env::Event>::SIGNATURE_TOPIC.map(|topic| topic.to_vec());
+            ink::env::test::recorded_events()
+                .filter(|event| event.topics.first() == signature.as_ref())
+                .filter_map(|event| <BadgeEarned as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .map(|event| event.kind)
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_badges_from_single_approvals() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+
+            set_caller(accounts.alice);
+            set_timestamp(1_000);
+            let mut claim_ids = Vec::new();
+            for i in 0..10u8 {
+                let claim_id = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap();
+                contract.approve_claim(claim_id).unwrap();
+                claim_ids.push(claim_id);
+
+                let kinds: Vec<BadgeKind> = contract.get_badges(accounts.bob).iter().map(|b| b.kind).collect();
+                if i < 9 {
+                    assert_eq!(kinds, vec![BadgeKind::FirstApprovedClaim]);
+                } else {
+                    assert_eq!(kinds, vec![BadgeKind::FirstApprovedClaim, BadgeKind::TenApprovedClaims]);
+                }
+            }
+            assert_eq!(contract.get_badges(accounts.bob)[0].earned_at, 1_000);
+
+            // Revocation keeps badges, and crossing again doesn't award twice
+            contract.revoke_claim(claim_ids[0]).unwrap();
+            assert_eq!(contract.get_badges(accounts.bob).len(), 2);
+            let again = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0xAA; 32]), None, None).unwrap();
+            contract.approve_claim(again).unwrap();
+            assert_eq!(contract.get_badges(accounts.bob).len(), 2);
+            assert_eq!(badge_events(), vec![BadgeKind::FirstApprovedClaim, BadgeKind::TenApprovedClaims]);
+
+            // No profile, no badges
+            let claim_id = contract.add_claim(accounts.charlie, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            contract.approve_claim(claim_id).unwrap();
+            assert!(contract.get_badges(accounts.charlie).is_empty());
+        }
+
+        #[ink::test]
+        fn test_badges_from_batch_approvals() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+
+            set_caller(accounts.alice);
+            let claim_ids: Vec<u64> = (0..60u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+
+            // The batch crossing ten awards the badge exactly once
+            contract.approve_claims_batch(claim_ids[..5].to_vec()).unwrap();
+            contract.approve_claims_batch(claim_ids[5..12].to_vec()).unwrap();
+            assert_eq!(contract.get_badges(accounts.bob).len(), 2);
+
+            contract.approve_claims_batch(claim_ids[12..].to_vec()).unwrap();
+            assert_eq!(
+                badge_events(),
+                vec![BadgeKind::FirstApprovedClaim, BadgeKind::TenApprovedClaims, BadgeKind::FiftyApprovedClaims]
+            );
+            let kinds: Vec<BadgeKind> = contract.get_badges(accounts.bob).iter().map(|b| b.kind).collect();
+            assert_eq!(
+                kinds,
+                vec![BadgeKind::FirstApprovedClaim, BadgeKind::TenApprovedClaims, BadgeKind::FiftyApprovedClaims]
+            );
+        }
+
+        #[ink::test]
+        fn test_verified_issuer_badge() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            set_caller(accounts.alice);
+            contract.register_profile("ipfs://alice".to_string()).unwrap();
+
+            // Issuer without a DID
+            let first = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            contract.approve_claim(first).unwrap();
+            assert_eq!(contract.get_badges(accounts.bob).len(), 1);
+
+            // Same issuer once verified
+            contract.link_did("did:kilt:light:alice".to_string()).unwrap();
+            let second = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+            contract.approve_claim(second).unwrap();
+            let kinds: Vec<BadgeKind> = contract.get_badges(accounts.bob).iter().map(|b| b.kind).collect();
+            assert_eq!(kinds, vec![BadgeKind::FirstApprovedClaim, BadgeKind::VerifiedIssuerClaim]);
+        }
+
+        #[ink::test]
+        fn test_time_locked_claim() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+            set_timestamp(1_000);
+
+            let proof = Hash::from([0x01; 32]);
+            let claim_id = contract.add_claim(accounts.bob, "award".to_string(), proof, Some(5_000), None).unwrap();
+
+            // Events: [0] ClaimAdded, carrying the activation time
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let added = <ClaimAdded as scale::Decode>::decode(&mut &events[0].data[..]).unwrap();
+            assert_eq!(added.activates_at, Some(5_000));
+
+            // Hidden and locked before activation
+            assert!(contract.get_claim(claim_id).is_none());
+            assert!(contract.get_claims(accounts.bob).is_empty());
+            assert!(contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdAsc).is_empty());
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::NotFound);
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::ClaimNotYetActive));
+            assert_eq!(
+                contract.approve_claims_batch(vec![claim_id]),
+                Err(ContractError::ClaimNotYetActive)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(contract.reject_claim(claim_id, None), Err(ContractError::ClaimNotYetActive));
+
+            // Visible and approvable from the activation time on
+            set_timestamp(5_000);
+            assert_eq!(contract.get_claim(claim_id).unwrap().activates_at, Some(5_000));
+            assert_eq!(contract.get_claims(accounts.bob).len(), 1);
+            assert_eq!(contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdAsc).len(), 1);
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::NotApproved);
+
+            set_caller(accounts.alice);
+            contract.approve_claim(claim_id).unwrap();
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::Valid);
+        }
+
+        #[ink::test]
+        fn test_time_locked_claim_must_activate_before_expiry() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+            set_timestamp(1_000);
+
+            // Claims from this template expire at 6_000
+            let template_id = contract
+                .create_claim_template("certification".to_string(), 1, 5_000, "ipfs://cert".to_string())
+                .unwrap();
+            let proof = Hash::from([0x01; 32]);
+
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.bob, proof, Some(6_000)),
+                Err(ContractError::InvalidActivation)
+            );
+            assert_eq!(contract.get_total_claims(), 0);
+
+            let claim_id = contract.issue_from_template(template_id, accounts.bob, proof, Some(5_999)).unwrap();
+            set_timestamp(5_999);
+            let claim = contract.get_claim(claim_id).unwrap();
+            assert_eq!(claim.activates_at, Some(5_999));
+            assert_eq!(claim.expires_at, Some(6_000));
+
+            // Without an expiry any activation time is accepted
+            contract.add_claim(accounts.bob, "award".to_string(), proof, Some(u64::MAX), None).unwrap();
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "claim receiver rewritten")]
+        fn test_store_claim_rejects_new_receiver() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+
+            let claim_id = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let mut claim = contract.get_claim(claim_id).unwrap();
+            claim.receiver = accounts.charlie;
+            let _ = contract.store_claim(&claim);
+        }
+
+        #[ink::test]
+        fn test_claim_receivers_never_change() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            let people = [accounts.alice, accounts.bob, accounts.charlie, accounts.django, accounts.eve];
+
+            set_caller(accounts.alice);
+            contract.set_pending_ttl(50_000).unwrap();
+            contract.add_automated_issuer(accounts.frank).unwrap();
+            let template_id = contract
+                .create_claim_template("certification".to_string(), 1, 20_000, "ipfs://cert".to_string())
+                .unwrap();
+
+            // Deterministic pseudo-random sequence of claim-mutating calls
+            let mut seed: u64 = 0x5EED;
+            let mut next = move |bound: u64| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+                (seed >> 33) % bound
+            };
+            let mut receivers: Vec<AccountId> = Vec::new();
+            let mut now = 0u64;
+
+            for step in 0..400u32 {
+                let actor = people[next(people.len() as u64) as usize];
+                let target = people[next(people.len() as u64) as usize];
+                let claim_id = if receivers.is_empty() { 0 } else { next(receivers.len() as u64) };
+                let proof = Hash::from([(step % 256) as u8; 32]);
+                set_caller(actor);
+
+                let created = match next(9) {
+                    0 | 1 => contract.add_claim(target, "contribution".to_string(), proof, None, None).ok(),
+                    2 => contract.add_claim(target, "award".to_string(), proof, Some(now + next(10_000)), None).ok(),
+                    3 => {
+                        set_caller(accounts.alice);
+                        contract.issue_from_template(template_id, target, proof, None).ok()
+                    }
+                    4 => {
+                        set_caller(accounts.frank);
+                        contract.add_and_approve_claim(target, "ci_pass".to_string(), proof).ok()
+                    }
+                    5 => {
+                        let _ = contract.approve_claim(claim_id);
+                        let _ = contract.approve_claims_batch(vec![claim_id, claim_id.saturating_add(1)]);
+                        None
+                    }
+                    6 => {
+                        let _ = contract.revoke_claim(claim_id);
+                        None
+                    }
+                    7 => {
+                        let _ = contract.reject_claim(claim_id, Some("ipfs://why".to_string()));
+                        None
+                    }
+                    _ => {
+                        now += next(20_000);
+                        set_timestamp(now);
+                        contract.sweep_stale_claims(target, 5);
+                        None
+                    }
+                };
+
+                if let Some(id) = created {
+                    assert_eq!(id, receivers.len() as u64);
+                    receivers.push(target);
+                }
+            }
+
+            // Every claim still points at its original receiver, including the indexed ones
+            assert!(receivers.len() > 50);
+            for (id, receiver) in receivers.iter().enumerate() {
+                assert_eq!(contract.claims.get(id as u64).unwrap().receiver, *receiver);
+            }
+            for account in people {
+                for id in contract.user_claims.get(account).unwrap_or_default() {
+                    assert_eq!(receivers[id as usize], account);
+                }
+            }
+        }
+
+        #[ink::test]
+        fn test_claims_page_ordering() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+
+            let claim_ids: Vec<u64> = (0..6u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+
+            // Approvals interleaved with creation order; 1 and 4 share a timestamp
+            set_timestamp(1_000);
+            contract.approve_claim(claim_ids[3]).unwrap();
+            set_timestamp(2_000);
+            contract.approve_claim(claim_ids[1]).unwrap();
+            contract.approve_claim(claim_ids[4]).unwrap();
+            set_timestamp(3_000);
+            contract.approve_claim(claim_ids[0]).unwrap();
+
+            let ids = |claims: Vec<Claim>| claims.iter().map(|c| c.id).collect::<Vec<u64>>();
+            assert_eq!(ids(contract.get_claims_page(accounts.bob, 0, 10, SortOrder::IdAsc)), vec![0, 1, 2, 3, 4, 5]);
+            assert_eq!(ids(contract.get_claims_page(accounts.bob, 0, 10, SortOrder::IdDesc)), vec![5, 4, 3, 2, 1, 0]);
+            assert_eq!(
+                ids(contract.get_claims_page(accounts.bob, 0, 10, SortOrder::ApprovedAtDesc)),
+                vec![0, 4, 1, 3, 5, 2]
+            );
+
+            // Pages line up
+            assert_eq!(ids(contract.get_claims_page(accounts.bob, 2, 2, SortOrder::IdDesc)), vec![3, 2]);
+            assert_eq!(ids(contract.get_claims_page(accounts.bob, 2, 2, SortOrder::ApprovedAtDesc)), vec![1, 3]);
+            assert_eq!(ids(contract.get_claims_page(accounts.bob, 6, 2, SortOrder::IdAsc)), Vec::<u64>::new());
+
+            // Same ordering for unseen claims
+            set_caller(accounts.bob);
+            contract.mark_claims_seen(1).unwrap();
+            assert_eq!(ids(contract.get_unseen_claims(accounts.bob, 10, SortOrder::IdDesc)), vec![5, 4, 3, 2]);
+            assert_eq!(ids(contract.get_unseen_claims(accounts.bob, 3, SortOrder::ApprovedAtDesc)), vec![4, 3, 5]);
+        }
+
+        #[ink::test]
+        fn test_claims_carry_schema_version() {
+            let accounts = default_accounts();
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+            assert_eq!(contract.get_schema_version(), CLAIM_SCHEMA_VERSION);
+
+            let claim_id = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            assert_eq!(contract.get_claim(claim_id).unwrap().schema_version, CLAIM_SCHEMA_VERSION);
+
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let added = <ClaimAdded as scale::Decode>::decode(&mut &events[0].data[..]).unwrap();
+            assert_eq!(added.schema_version, CLAIM_SCHEMA_VERSION);
+
+            // Stored claims keep the version they were created with
+            let mut claim = contract.claims.get(claim_id).unwrap();
+            claim.schema_version = 0;
+            contract.claims.insert(claim_id, &claim);
+            contract.approve_claim(claim_id).unwrap();
+            assert_eq!(contract.get_claim(claim_id).unwrap().schema_version, 0);
+        }
+
+        #[ink::test]
+        fn test_approved_count_since_cutoffs() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            // Approve one "contribution" claim at each timestamp, plus one other type
+            for (i, timestamp) in [1_000u64, 2_000, 2_000, 5_000].into_iter().enumerate() {
+                set_timestamp(timestamp);
+                let claim_id = contract
+                    .add_claim(accounts.bob, "contribution".to_string(), Hash::from([i as u8; 32]), None, None)
+                    .unwrap();
+                contract.approve_claim(claim_id).unwrap();
+            }
+            let other = contract
+                .add_claim(accounts.bob, "hackathon_win".to_string(), Hash::from([0xFF; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(other).unwrap();
+
+            let count = |contract: &SkillChainRegistry, since: u64| {
+                contract.get_approved_count_since(accounts.bob, "contribution".to_string(), since)
+            };
+            assert_eq!(count(&contract, 0), 4);
+            assert_eq!(count(&contract, 1_000), 4);
+            assert_eq!(count(&contract, 1_001), 3);
+            assert_eq!(count(&contract, 2_000), 3);
+            assert_eq!(count(&contract, 2_001), 1);
+            assert_eq!(count(&contract, 5_001), 0);
+            assert_eq!(contract.get_approved_count_since(accounts.bob, "hackathon_win".to_string(), 5_000), 1);
+            assert_eq!(contract.get_approved_count_since(accounts.charlie, "contribution".to_string(), 0), 0);
+
+            // Pending claims are not counted
+            contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0xEE; 32]), None, None)
+                .unwrap();
+            assert_eq!(count(&contract, 0), 4);
+        }
+
+        #[ink::test]
+        fn test_approved_count_since_revocation_and_bound() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            let mut ids = Vec::new();
+            for i in 0..(MAX_APPROVAL_TIMES as u64 + 5) {
+                set_timestamp(i * 10);
+                let claim_id = contract
+                    .add_claim(accounts.bob, "contribution".to_string(), Hash::from([(i % 256) as u8; 32]), None, None)
+                    .unwrap();
+                contract.approve_claim(claim_id).unwrap();
+                ids.push(claim_id);
+            }
+
+            // The count saturates at the bound even though more approvals happened
+            let count = |contract: &SkillChainRegistry, since: u64| {
+                contract.get_approved_count_since(accounts.bob, "contribution".to_string(), since)
+            };
+            assert_eq!(count(&contract, 0), MAX_APPROVAL_TIMES as u32);
+            // Approvals inside the kept window are still counted exactly
+            assert_eq!(count(&contract, 1_000), 5);
+
+            // Revoking a kept approval removes it from the count
+            contract.revoke_claim(*ids.last().unwrap()).unwrap();
+            assert_eq!(count(&contract, 1_000), 4);
+
+            // Revoking an approval that already fell out of the window changes nothing
+            contract.revoke_claim(ids[0]).unwrap();
+            assert_eq!(count(&contract, 0), MAX_APPROVAL_TIMES as u32 - 1);
+        }
+
+        #[ink::test]
+        fn test_hidden_did_only_visible_to_owner() {
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+
+            let mut contract = SkillChainRegistry::new();
+            let did = "did:kilt:light:bob".to_string();
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            contract.link_did(did.clone()).unwrap();
+
+            // Public by default
+            set_caller(accounts.alice);
+            assert_eq!(contract.get_did(accounts.bob), Some(did.clone()));
+
+            set_caller(accounts.bob);
+            contract.set_did_visibility(false).unwrap();
+            assert!(!contract.get_profile(accounts.bob).unwrap().did_public);
+
+            // The owner still sees it everywhere
+            assert_eq!(contract.get_did(accounts.bob), Some(did.clone()));
+            assert_eq!(contract.get_profile(accounts.bob).unwrap().did, Some(did.clone()));
+
+            // Others see no DID, but the profile still counts as verified
+            set_caller(accounts.alice);
+            assert_eq!(contract.get_did(accounts.bob), None);
+            assert_eq!(contract.get_profile(accounts.bob).unwrap().did, None);
+            let snapshot = contract.get_profile_snapshot(accounts.bob).unwrap();
+            assert!(snapshot.verified);
+            assert_eq!(snapshot.profile.did, None);
+
+            // Showing it again restores public access
+            set_caller(accounts.bob);
+            contract.set_did_visibility(true).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(contract.get_did(accounts.bob), Some(did));
+
+            // Only profile owners can set visibility
+            assert_eq!(contract.set_did_visibility(false), Err(ContractError::ProfileNotFound));
+        }
+
+        #[ink::test]
+        fn test_prove_did_linked() {
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            assert!(!contract.prove_did_linked(accounts.bob, "did:kilt:light:bob".to_string()));
+
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+            contract.set_did_visibility(false).unwrap();
+
+            // A verifier knowing the DID can confirm it while it stays hidden
+            set_caller(accounts.alice);
+            assert_eq!(contract.get_did(accounts.bob), None);
+            assert!(contract.prove_did_linked(accounts.bob, "did:kilt:light:bob".to_string()));
+            assert!(!contract.prove_did_linked(accounts.bob, "did:kilt:light:other".to_string()));
+            assert!(!contract.prove_did_linked(accounts.charlie, "did:kilt:light:bob".to_string()));
+        }
+
+        #[ink::test]
+        fn test_profile_commitment_pinned_and_tracks_claims() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+
+            set_caller(accounts.alice);
+            let first = contract
+                .add_claim(accounts.bob, "hackathon_win".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            let pending = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None)
+                .unwrap();
+            let second = contract
+                .add_claim(accounts.bob, "job_completed".to_string(), Hash::from([0x03; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(first).unwrap();
+            contract.approve_claim(second).unwrap();
+
+            // Pinned against the documented encoding; the pending claim is left out
+            let expected = |claims: Vec<(u64, Hash)>| {
+                let mut output = [0u8; 32];
+                ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                    &(
+                        accounts.bob,
+                        String::from("ipfs://QmBob"),
+                        Some(String::from("did:kilt:light:bob")),
+                        false,
+                        claims,
+                    ),
+                    &mut output,
+                );
+                Hash::from(output)
+            };
+            let commitment = contract.export_profile_commitment(accounts.bob);
+            assert_eq!(
+                commitment,
+                expected(vec![(first, Hash::from([0x01; 32])), (second, Hash::from([0x03; 32]))])
+            );
+            assert_eq!(contract.export_profile_commitment(accounts.bob), commitment);
+            assert_ne!(contract.export_profile_commitment(accounts.charlie), commitment);
+
+            // Revoking an approved claim changes it; a pending one never counted
+            contract.revoke_claim(pending).unwrap();
+            assert_eq!(contract.export_profile_commitment(accounts.bob), commitment);
+            contract.revoke_claim(first).unwrap();
+            let after_revocation = contract.export_profile_commitment(accounts.bob);
+            assert_ne!(after_revocation, commitment);
+            assert_eq!(after_revocation, expected(vec![(second, Hash::from([0x03; 32]))]));
+        }
+
+        #[ink::test]
+        fn test_record_migration() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let claim_id = contract
+                .add_claim(accounts.bob, "hackathon_win".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(claim_id).unwrap();
+            let commitment = contract.export_profile_commitment(accounts.bob);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.record_migration(accounts.bob, accounts.frank, commitment),
+                Err(ContractError::NotAdmin)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                contract.record_migration(accounts.bob, accounts.frank, Hash::from([0xff; 32])),
+                Err(ContractError::CommitmentMismatch)
+            );
+            assert_eq!(contract.get_migration(accounts.bob), None);
+
+            contract.record_migration(accounts.bob, accounts.frank, commitment).unwrap();
+            assert_eq!(contract.get_migration(accounts.bob), Some((accounts.frank, commitment)));
+
+            // A commitment exported before a revocation is stale
+            contract.revoke_claim(claim_id).unwrap();
+            assert_eq!(
+                contract.record_migration(accounts.bob, accounts.frank, commitment),
+                Err(ContractError::CommitmentMismatch)
+            );
+
+            // Events: [0] ClaimAdded, [1] ClaimApproved, [2] MigrationRecorded, ...
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let recorded = <MigrationRecorded as scale::Decode>::decode(&mut &events[2].data[..]).unwrap();
+            assert_eq!(recorded.account, accounts.bob);
+            assert_eq!(recorded.destination, accounts.frank);
+            assert_eq!(recorded.commitment, commitment);
+        }
+
+        /// Recompute a revocation accumulator from the ordered revoked claim IDs
+        fn revocation_accumulator(claim_ids: &[u64]) -> Hash {
+            claim_ids.iter().fold(Hash::default(), |accumulator, claim_id| {
+                let mut output = [0u8; 32];
+                ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(accumulator, *claim_id), &mut output);
+                Hash::from(output)
+            })
+        }
+
+        #[ink::test]
+        fn test_revocation_accumulator_recomputes() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            assert_eq!(contract.get_revocation_state(accounts.alice), (Hash::default(), 0));
+
+            let ids: Vec<u64> = (0..4u8)
+                .map(|i| contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([i; 32]), None, None).unwrap())
+                .collect();
+            contract.approve_claim(ids[1]).unwrap();
+
+            // Revoke out of ID order; the accumulator follows revocation order
+            let mut revoked = Vec::new();
+            for claim_id in [ids[2], ids[0], ids[1]] {
+                contract.revoke_claim(claim_id).unwrap();
+                revoked.push(claim_id);
+                assert_eq!(
+                    contract.get_revocation_state(accounts.alice),
+                    (revocation_accumulator(&revoked), revoked.len() as u32)
+                );
+            }
+            assert_ne!(revocation_accumulator(&revoked), revocation_accumulator(&[ids[0], ids[2], ids[1]]));
+            assert_eq!(contract.get_recent_revocations(accounts.alice), revoked);
+
+            // The last event carries the new accumulator
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <ClaimRevoked as scale::Decode>::decode(&mut &events[events.len() - 1].data[..]).unwrap();
+            assert_eq!(event.claim_id, ids[1]);
+            assert_eq!(event.revocation_accumulator, revocation_accumulator(&revoked));
+
+            // Failed revocations and other issuers leave the state alone
+            assert_eq!(contract.revoke_claim(ids[0]), Err(ContractError::ClaimAlreadyRevoked));
+            assert_eq!(contract.get_revocation_state(accounts.alice).1, 3);
+            assert_eq!(contract.get_revocation_state(accounts.bob), (Hash::default(), 0));
+            assert!(contract.get_recent_revocations(accounts.bob).is_empty());
+        }
+
+        #[ink::test]
+        fn test_recent_revocations_roll() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let total = MAX_RECENT_REVOCATIONS as u64 + 5;
+            for i in 0..total {
+                let claim_id = contract
+                    .add_claim(accounts.bob, "contribution".to_string(), Hash::from([(i % 256) as u8; 32]), None, None)
+                    .unwrap();
+                contract.revoke_claim(claim_id).unwrap();
+            }
+
+            // Only the latest revocations are kept, but the count and accumulator cover all of them
+            let recent = contract.get_recent_revocations(accounts.alice);
+            assert_eq!(recent, (5..total).collect::<Vec<_>>());
+            let all: Vec<u64> = (0..total).collect();
+            assert_eq!(
+                contract.get_revocation_state(accounts.alice),
+                (revocation_accumulator(&all), total as u32)
+            );
+        }
+
+        #[ink::test]
+        fn test_frozen_claim_type_blocks_issue_and_approve() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let audit = "audit_passed".to_string();
+            let approved = contract.add_claim(accounts.bob, audit.clone(), Hash::from([0x01; 32]), None, None).unwrap();
+            contract.approve_claim(approved).unwrap();
+            let pending = contract.add_claim(accounts.bob, audit.clone(), Hash::from([0x02; 32]), None, None).unwrap();
+
+            // Only the admin can freeze
+            set_caller(accounts.bob);
+            assert_eq!(contract.freeze_claim_type(audit.clone()), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            contract.freeze_claim_type(audit.clone()).unwrap();
+            assert!(contract.is_claim_type_frozen(audit.clone()));
+            assert!(!contract.is_claim_type_frozen("contribution".to_string()));
+
+            // Issuing and approving the type fail, through every issuance path
+            assert_eq!(
+                contract.add_claim(accounts.bob, audit.clone(), Hash::from([0x03; 32]), None, None),
+                Err(ContractError::ClaimTypeFrozen)
+            );
+            assert_eq!(contract.approve_claim(pending), Err(ContractError::ClaimTypeFrozen));
+            let template_id = contract
+                .create_claim_template(audit.clone(), 1, 0, "ipfs://audit".to_string())
+                .unwrap();
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.bob, Hash::from([0x04; 32]), None),
+                Err(ContractError::ClaimTypeFrozen)
+            );
+            contract.add_automated_issuer(accounts.frank).unwrap();
+            set_caller(accounts.frank);
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.bob, audit.clone(), Hash::from([0x05; 32])),
+                Err(ContractError::ClaimTypeFrozen)
+            );
+
+            // Other types are unaffected, existing claims stay readable and revocable
+            set_caller(accounts.alice);
+            assert!(contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x06; 32]), None, None).is_ok());
+            assert_eq!(contract.get_claim(approved).unwrap().status, ClaimStatus::Approved);
+            contract.revoke_claim(approved).unwrap();
+
+            // Unfreezing restores issuance and approval
+            contract.unfreeze_claim_type(audit.clone()).unwrap();
+            assert!(!contract.is_claim_type_frozen(audit.clone()));
+            contract.approve_claim(pending).unwrap();
+            assert!(contract.add_claim(accounts.bob, audit, Hash::from([0x07; 32]), None, None).is_ok());
+        }
+
+        #[ink::test]
+        fn test_frozen_claim_type_fails_whole_batch() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let ok = contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None).unwrap();
+            let frozen = contract.add_claim(accounts.bob, "audit_passed".to_string(), Hash::from([0x02; 32]), None, None).unwrap();
+            contract.freeze_claim_type("audit_passed".to_string()).unwrap();
+
+            assert_eq!(contract.approve_claims_batch(vec![ok, frozen]), Err(ContractError::ClaimTypeFrozen));
+            assert_eq!(contract.get_claim(ok).unwrap().status, ClaimStatus::Pending);
+            assert_eq!(contract.get_claim(frozen).unwrap().status, ClaimStatus::Pending);
+
+            contract.unfreeze_claim_type("audit_passed".to_string()).unwrap();
+            assert_eq!(contract.approve_claims_batch(vec![ok, frozen]), Ok(2));
+        }
+
+        #[ink::test]
+        fn test_reserved_claim_types() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let kyc = "kyc_verified".to_string();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.reserve_claim_type(kyc.clone()), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            contract.reserve_claim_type(kyc.clone()).unwrap();
+            assert_eq!(contract.reserve_claim_type(kyc.clone()), Err(ContractError::ClaimTypeAlreadyReserved));
+            assert_eq!(contract.get_reserved_claim_types(), vec![kyc.clone()]);
+
+            // A regular issuer can neither issue the type nor template it
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.add_claim(accounts.charlie, kyc.clone(), Hash::from([0x01; 32]), None, None),
+                Err(ContractError::ReservedClaimType)
+            );
+            assert_eq!(
+                contract.create_claim_template(kyc.clone(), 1, 0, "ipfs://kyc".to_string()),
+                Err(ContractError::ReservedClaimType)
+            );
+            assert!(contract.add_claim(accounts.charlie, "contribution".to_string(), Hash::from([0x02; 32]), None, None).is_ok());
+
+            // The admin and automated issuers can
+            set_caller(accounts.alice);
+            assert!(contract.add_claim(accounts.charlie, kyc.clone(), Hash::from([0x03; 32]), None, None).is_ok());
+            contract.add_automated_issuer(accounts.frank).unwrap();
+            set_caller(accounts.frank);
+            assert!(contract.add_and_approve_claim(accounts.charlie, kyc.clone(), Hash::from([0x04; 32])).is_ok());
+            assert!(contract.add_claim(accounts.charlie, kyc.clone(), Hash::from([0x05; 32]), None, None).is_ok());
+
+            // Unreserving restores normal behaviour
+            set_caller(accounts.alice);
+            contract.unreserve_claim_type(kyc.clone()).unwrap();
+            assert_eq!(contract.unreserve_claim_type(kyc.clone()), Err(ContractError::ClaimTypeNotReserved));
+            assert!(contract.get_reserved_claim_types().is_empty());
+            set_caller(accounts.bob);
+            assert!(contract.add_claim(accounts.charlie, kyc, Hash::from([0x06; 32]), None, None).is_ok());
+        }
+
+        #[ink::test]
+        fn test_reserved_claim_types_bounded() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            for i in 0..MAX_RESERVED_CLAIM_TYPES {
+                contract.reserve_claim_type(format!("reserved_{}", i)).unwrap();
+            }
+            assert_eq!(
+                contract.reserve_claim_type("one_more".to_string()),
+                Err(ContractError::TooManyReservedTypes)
+            );
+        }
+
+        #[ink::test]
+        fn test_onboard_registers_and_links_did() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.bob);
+            contract.onboard("ipfs://QmBob".to_string(), Some("did:kilt:light:bob".to_string())).unwrap();
+            assert_eq!(contract.get_profile(accounts.bob).unwrap().metadata_uri, "ipfs://QmBob");
+            assert_eq!(contract.get_did(accounts.bob), Some("did:kilt:light:bob".to_string()));
+            assert_eq!(
+                contract.onboard("ipfs://QmBob2".to_string(), None),
+                Err(ContractError::ProfileAlreadyExists)
+            );
+
+            // Without a DID it is a plain registration
+            set_caller(accounts.charlie);
+            contract.onboard("ipfs://QmCharlie".to_string(), None).unwrap();
+            assert_eq!(contract.get_did(accounts.charlie), None);
+
+            // Events: [0] ProfileRegistered, [1] DidLinked, [2] ProfileRegistered
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(events.len(), 3);
+            let linked = <DidLinked as scale::Decode>::decode(&mut &events[1].data[..]).unwrap();
+            assert_eq!(linked.owner, accounts.bob);
+            assert_eq!(linked.did, "did:kilt:light:bob");
+        }
+
+        #[ink::test]
+        fn test_onboard_invalid_did_leaves_no_profile() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract.add_automated_issuer(accounts.django).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.onboard("ipfs://QmBob".to_string(), Some("did:web:example.com".to_string())),
+                Err(ContractError::InvalidDid)
+            );
+            assert!(contract.get_profile(accounts.bob).is_none());
+
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.onboard_with_claim(
+                    accounts.bob,
+                    "ipfs://QmBob".to_string(),
+                    Some("kilt:abc".to_string()),
+                    "job_completed".to_string(),
+                    Hash::from([0x01; 32]),
+                ),
+                Err(ContractError::InvalidDid)
+            );
+            assert!(contract.get_profile(accounts.bob).is_none());
+            assert_eq!(contract.get_total_claims(), 0);
+
+            // A frozen claim type also aborts before the profile is stored
+            set_caller(accounts.alice);
+            contract.freeze_claim_type("job_completed".to_string()).unwrap();
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.onboard_with_claim(
+                    accounts.bob,
+                    "ipfs://QmBob".to_string(),
+                    None,
+                    "job_completed".to_string(),
+                    Hash::from([0x01; 32]),
+                ),
+                Err(ContractError::ClaimTypeFrozen)
+            );
+            assert!(contract.get_profile(accounts.bob).is_none());
+
+            // Only the two admin events were emitted
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn test_onboard_with_claim_requires_automated_issuer() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let proof = Hash::from([0x07; 32]);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.onboard_with_claim(
+                    accounts.bob,
+                    "ipfs://QmBob".to_string(),
+                    Some("did:kilt:light:bob".to_string()),
+                    "job_completed".to_string(),
+                    proof,
+                ),
+                Err(ContractError::NotAutomatedIssuer)
+            );
+            assert!(contract.get_profile(accounts.bob).is_none());
+
+            set_caller(accounts.alice);
+            contract.add_automated_issuer(accounts.django).unwrap();
+
+            set_caller(accounts.django);
+            set_timestamp(3 * MS_PER_DAY);
+            let claim_id = contract
+                .onboard_with_claim(
+                    accounts.bob,
+                    "ipfs://QmBob".to_string(),
+                    Some("did:kilt:light:bob".to_string()),
+                    "job_completed".to_string(),
+                    proof,
+                )
+                .unwrap();
+
+            // The profile belongs to the onboarded account, not the relayer
+            assert_eq!(contract.get_profile(accounts.bob).unwrap().owner, accounts.bob);
+            assert!(contract.get_profile(accounts.django).is_none());
+            assert_eq!(contract.get_did(accounts.bob), Some("did:kilt:light:bob".to_string()));
+
+            let claim = contract.get_claim(claim_id).unwrap();
+            assert_eq!(claim.issuer, accounts.django);
+            assert_eq!(claim.receiver, accounts.bob);
+            assert_eq!(claim.status, ClaimStatus::Approved);
+            assert_eq!(claim.issuer_kind, IssuerKind::Contract);
+            assert_eq!(contract.verify_claim(claim_id, accounts.bob, proof), VerificationResult::Valid);
+
+            // Events: [0] AutomatedIssuerAdded, [1] ProfileRegistered, [2] DidLinked,
+            // [3] ClaimAdded, [4] ClaimApproved, then any badges
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            assert!(events.len() >= 5);
+            let approved = <ClaimApproved as scale::Decode>::decode(&mut &events[4].data[..]).unwrap();
+            assert_eq!(approved.claim_id, claim_id);
+
+            // A second onboarding of the same account fails
+            assert_eq!(
+                contract.onboard_with_claim(
+                    accounts.bob,
+                    "ipfs://QmBob".to_string(),
+                    None,
+                    "job_completed".to_string(),
+                    proof,
+                ),
+                Err(ContractError::ProfileAlreadyExists)
+            );
+        }
+
+        #[ink::test]
+        fn test_attestation_chain() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let skill_proof = Hash::from([0x01; 32]);
+            let audit_proof = Hash::from([0x02; 32]);
+
+            // bob self-reports a skill, charlie audits it, django vouches for the audit
+            set_caller(accounts.bob);
+            let skill = contract.add_claim(accounts.bob, "rust_skill".to_string(), skill_proof, None, None).unwrap();
+            set_caller(accounts.charlie);
+            let audit = contract
+                .add_claim(accounts.bob, "audit".to_string(), audit_proof, None, Some(skill))
+                .unwrap();
+            set_caller(accounts.django);
+            let review = contract
+                .add_claim(accounts.bob, "review".to_string(), Hash::from([0x03; 32]), None, Some(audit))
+                .unwrap();
+
+            // Both directions
+            assert_eq!(contract.get_claim(audit).unwrap().attests_to, Some(skill));
+            assert_eq!(contract.get_claim(review).unwrap().attests_to, Some(audit));
+            assert_eq!(contract.get_claim(skill).unwrap().attests_to, None);
+            assert_eq!(contract.get_attestations_for(skill), vec![audit]);
+            assert_eq!(contract.get_attestations_for(audit), vec![review]);
+            assert!(contract.get_attestations_for(review).is_empty());
+
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let added = <ClaimAdded as scale::Decode>::decode(&mut &events[1].data[..]).unwrap();
+            assert_eq!(added.claim_id, audit);
+            assert_eq!(added.attests_to, Some(skill));
+
+            set_caller(accounts.bob);
+            contract.approve_claim(skill).unwrap();
+            set_caller(accounts.charlie);
+            contract.approve_claim(audit).unwrap();
+            assert_eq!(
+                contract.verify_claim_with_parent(audit, accounts.bob, audit_proof),
+                (VerificationResult::Valid, Some(VerificationResult::Valid))
+            );
+            assert_eq!(
+                contract.verify_claim_with_parent(skill, accounts.bob, skill_proof),
+                (VerificationResult::Valid, None)
+            );
+
+            // Revoking the parent doesn't cascade, but is reported
+            set_caller(accounts.bob);
+            contract.revoke_claim(skill).unwrap();
+            assert_eq!(contract.verify_claim(audit, accounts.bob, audit_proof), VerificationResult::Valid);
+            assert_eq!(
+                contract.verify_claim_with_parent(audit, accounts.bob, audit_proof),
+                (VerificationResult::Valid, Some(VerificationResult::Revoked))
+            );
+            assert_eq!(
+                contract.verify_claim_with_parent(audit, accounts.charlie, audit_proof),
+                (VerificationResult::WrongReceiver, None)
+            );
+        }
+
+        #[ink::test]
+        fn test_attestation_requires_same_receiver() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let skill = contract
+                .add_claim(accounts.bob, "rust_skill".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.add_claim(accounts.eve, "audit".to_string(), Hash::from([0x02; 32]), None, Some(skill)),
+                Err(ContractError::AttestationReceiverMismatch)
+            );
+            assert_eq!(
+                contract.add_claim(accounts.bob, "audit".to_string(), Hash::from([0x02; 32]), None, Some(99)),
+                Err(ContractError::ClaimNotFound)
+            );
+            assert_eq!(contract.get_total_claims(), 1);
+            assert!(contract.get_attestations_for(skill).is_empty());
+            assert!(contract.get_claims(accounts.eve).is_empty());
+        }
+
+        #[ink::test]
+        fn test_get_claims_bounded_caps_result() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            for i in 0..150u64 {
+                contract
+                    .add_claim(accounts.bob, "contribution".to_string(), Hash::from([(i % 256) as u8; 32]), None, None)
+                    .unwrap();
+            }
+
+            // Only the first page is returned, with the flag and the full count
+            let bounded = contract.get_claims_bounded(accounts.bob);
+            assert_eq!(bounded.claims.len(), MAX_BOUNDED_CLAIMS);
+            assert!(bounded.truncated);
+            assert_eq!(bounded.total, 150);
+            let ids: Vec<u64> = bounded.claims.iter().map(|claim| claim.id).collect();
+            assert_eq!(ids, (0..MAX_BOUNDED_CLAIMS as u64).collect::<Vec<_>>());
+
+            // get_claims is capped silently
+            assert_eq!(contract.get_claims(accounts.bob).len(), MAX_BOUNDED_CLAIMS);
+
+            // The rest stays reachable through the paginated getter
+            let rest = contract.get_claims_page(accounts.bob, MAX_BOUNDED_CLAIMS as u32, 50, SortOrder::IdAsc);
+            assert_eq!(rest.len(), 50);
+            assert_eq!(rest[0].id, MAX_BOUNDED_CLAIMS as u64);
+        }
+
+        #[ink::test]
+        fn test_get_claims_bounded_below_cap() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            // Time-locked claims count towards the total but are not returned
+            contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), Some(1_000), None)
+                .unwrap();
+
+            let bounded = contract.get_claims_bounded(accounts.bob);
+            assert_eq!(bounded.claims.len(), 1);
+            assert!(!bounded.truncated);
+            assert_eq!(bounded.total, 2);
+
+            let empty = contract.get_claims_bounded(accounts.charlie);
+            assert!(empty.claims.is_empty());
+            assert!(!empty.truncated);
+            assert_eq!(empty.total, 0);
+        }
+
+        #[ink::test]
+        fn test_claim_policy_allowlist_only() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            contract.add_automated_issuer(accounts.django).unwrap();
+            let add = |contract: &mut SkillChainRegistry, issuer: AccountId| {
+                set_caller(issuer);
+                contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+            };
+
+            // Open by default
+            assert_eq!(contract.get_claim_policy(accounts.bob), ClaimPolicy::Open);
+            let earlier = add(&mut contract, accounts.charlie).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_claim_policy(ClaimPolicy::AllowlistOnly), Err(ContractError::ProfileNotFound));
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            contract.set_claim_policy(ClaimPolicy::AllowlistOnly).unwrap();
+            contract.allow_issuer(accounts.eve).unwrap();
+
+            // Neither outsiders nor the admin or automated issuers get through
+            for issuer in [accounts.charlie, accounts.alice, accounts.django] {
+                assert_eq!(add(&mut contract, issuer), Err(ContractError::IssuerNotPermitted));
+            }
+            add(&mut contract, accounts.eve).unwrap();
+
+            // Template issuance is checked against the same policy
+            set_caller(accounts.charlie);
+            let template_id = contract
+                .create_claim_template("skill".to_string(), 1, 0, "ipfs://skill".to_string())
+                .unwrap();
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.bob, Hash::from([0x02; 32]), None),
+                Err(ContractError::IssuerNotPermitted)
+            );
+
+            // Removing the issuer blocks new claims but leaves earlier ones approvable
+            set_caller(accounts.bob);
+            contract.disallow_issuer(accounts.eve).unwrap();
+            assert_eq!(add(&mut contract, accounts.eve), Err(ContractError::IssuerNotPermitted));
+            set_caller(accounts.charlie);
+            contract.approve_claim(earlier).unwrap();
+        }
+
+        #[ink::test]
+        fn test_claim_policy_trusted_issuers_only() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            contract.add_automated_issuer(accounts.django).unwrap();
+            let add = |contract: &mut SkillChainRegistry, issuer: AccountId| {
+                set_caller(issuer);
+                contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+            };
+
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            contract.set_claim_policy(ClaimPolicy::TrustedIssuersOnly).unwrap();
+            assert_eq!(contract.get_claim_policy(accounts.bob), ClaimPolicy::TrustedIssuersOnly);
+
+            // The admin and automated issuers pass, plain accounts need the allowlist
+            add(&mut contract, accounts.alice).unwrap();
+            add(&mut contract, accounts.django).unwrap();
+            assert_eq!(add(&mut contract, accounts.charlie), Err(ContractError::IssuerNotPermitted));
+
+            set_caller(accounts.bob);
+            contract.allow_issuer(accounts.charlie).unwrap();
+            add(&mut contract, accounts.charlie).unwrap();
+
+            // Other receivers are unaffected
+            set_caller(accounts.eve);
+            contract
+                .add_claim(accounts.frank, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+
+            // Back to open
+            set_caller(accounts.bob);
+            contract.set_claim_policy(ClaimPolicy::Open).unwrap();
+            add(&mut contract, accounts.eve).unwrap();
+        }
+
+        #[ink::test]
+        fn test_issuer_allowlist_management() {
+            let accounts = default_accounts();
+            set_caller(accounts.bob);
+            let mut contract = SkillChainRegistry::new();
+
+            assert_eq!(contract.allow_issuer(accounts.charlie), Err(ContractError::ProfileNotFound));
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+
+            contract.allow_issuer(accounts.charlie).unwrap();
+            assert_eq!(contract.allow_issuer(accounts.charlie), Err(ContractError::IssuerAlreadyAllowed));
+            assert_eq!(contract.disallow_issuer(accounts.eve), Err(ContractError::IssuerNotListed));
+
+            for i in 1..MAX_ALLOWED_ISSUERS {
+                contract.allow_issuer(AccountId::from([100 + i as u8; 32])).unwrap();
+            }
+            assert_eq!(contract.get_allowed_issuers(accounts.bob).len(), MAX_ALLOWED_ISSUERS);
+            assert_eq!(contract.allow_issuer(accounts.eve), Err(ContractError::TooManyAllowedIssuers));
+
+            contract.disallow_issuer(accounts.charlie).unwrap();
+            assert!(!contract.get_allowed_issuers(accounts.bob).contains(&accounts.charlie));
+            contract.allow_issuer(accounts.eve).unwrap();
+        }
+
+        /// Issue and approve `count` claims from charlie to `receiver`, returning their IDs
+        fn approve_claims_for(contract: &mut SkillChainRegistry, receiver: AccountId, count: u32) -> Vec<u64> {
+            set_caller(default_accounts().charlie);
+            (0..count)
+                .map(|i| {
+                    let claim_id = contract
+                        .add_claim(receiver, "contribution".to_string(), Hash::from([(i % 256) as u8; 32]), None, None)
+                        .unwrap();
+                    contract.approve_claim(claim_id).unwrap();
+                    claim_id
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_reputation_tier_boundaries() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            assert_eq!(contract.get_tier_thresholds(), (DEFAULT_SILVER_THRESHOLD, DEFAULT_GOLD_THRESHOLD));
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Bronze);
+
+            // One below each threshold, then exactly on it
+            let mut ids = approve_claims_for(&mut contract, accounts.bob, DEFAULT_SILVER_THRESHOLD - 1);
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Bronze);
+            ids.extend(approve_claims_for(&mut contract, accounts.bob, 1));
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Silver);
+            ids.extend(approve_claims_for(&mut contract, accounts.bob, DEFAULT_GOLD_THRESHOLD - DEFAULT_SILVER_THRESHOLD - 1));
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Silver);
+            ids.extend(approve_claims_for(&mut contract, accounts.bob, 1));
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Gold);
+
+            // Revocations move the account back down
+            set_caller(accounts.charlie);
+            contract.revoke_claim(ids[0]).unwrap();
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Silver);
+        }
+
+        #[ink::test]
+        fn test_tier_thresholds_reclassify() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            approve_claims_for(&mut contract, accounts.bob, 5);
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Bronze);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_tier_thresholds(2, 5), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_tier_thresholds(0, 5), Err(ContractError::InvalidTierThresholds));
+            assert_eq!(contract.set_tier_thresholds(5, 5), Err(ContractError::InvalidTierThresholds));
+
+            contract.set_tier_thresholds(2, 5).unwrap();
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <TierThresholdsUpdated as scale::Decode>::decode(&mut &events[events.len() - 1].data[..]).unwrap();
+            assert_eq!((event.silver, event.gold), (2, 5));
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Gold);
+
+            contract.set_tier_thresholds(3, 6).unwrap();
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Silver);
+        }
+
+        #[ink::test]
+        fn test_reputation_restriction() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            contract.add_automated_issuer(accounts.django).unwrap();
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            approve_claims_for(&mut contract, accounts.bob, 3);
+
+            // Open by default
+            set_caller(accounts.eve);
+            assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 3);
+
+            assert_eq!(contract.set_reputation_restricted(true), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            contract.set_reputation_restricted(true).unwrap();
+            assert!(contract.is_reputation_restricted());
+
+            // Only the account itself and automated issuers see the details
+            for caller in [accounts.eve, accounts.alice, accounts.charlie] {
+                set_caller(caller);
+                assert!(contract.get_profile_snapshot(accounts.bob).is_none());
+                assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Bronze);
+            }
+            for caller in [accounts.bob, accounts.django] {
+                set_caller(caller);
+                assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 3);
+            }
+
+            set_caller(accounts.alice);
+            contract.set_reputation_restricted(false).unwrap();
+            set_caller(accounts.eve);
+            assert!(contract.get_profile_snapshot(accounts.bob).is_some());
+        }
+
+        #[ink::test]
+        fn test_unique_per_receiver_claim_type() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let human = "unique_human".to_string();
+            let rules = ClaimTypeUniqueness { unique_per_receiver: true, unique_proof: false };
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_claim_type_uniqueness(human.clone(), rules), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            contract.set_claim_type_uniqueness(human.clone(), rules).unwrap();
+            assert_eq!(contract.get_claim_type_uniqueness(human.clone()), rules);
+
+            // One active claim per receiver, whoever issues it and whatever its proof
+            set_caller(accounts.bob);
+            let first = contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x01; 32]), None, None).unwrap();
+            assert_eq!(
+                contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x02; 32]), None, None),
+                Err(ContractError::SingletonViolation)
+            );
+            set_caller(accounts.eve);
+            assert_eq!(
+                contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x03; 32]), None, None),
+                Err(ContractError::SingletonViolation)
+            );
+            assert!(contract.add_claim(accounts.django, human.clone(), Hash::from([0x04; 32]), None, None).is_ok());
+            assert!(contract.add_claim(accounts.charlie, "contribution".to_string(), Hash::from([0x05; 32]), None, None).is_ok());
+
+            // Approval keeps the slot; revocation frees it
+            set_caller(accounts.bob);
+            contract.approve_claim(first).unwrap();
+            assert_eq!(
+                contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x06; 32]), None, None),
+                Err(ContractError::SingletonViolation)
+            );
+            contract.revoke_claim(first).unwrap();
+            let second = contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x07; 32]), None, None).unwrap();
+
+            // So does a rejection by the receiver
+            set_caller(accounts.charlie);
+            contract.reject_claim(second, None).unwrap();
+            set_caller(accounts.bob);
+            assert!(contract.add_claim(accounts.charlie, human.clone(), Hash::from([0x08; 32]), None, None).is_ok());
+
+            // Clearing the rules lifts the limit
+            set_caller(accounts.alice);
+            contract.set_claim_type_uniqueness(human.clone(), ClaimTypeUniqueness::default()).unwrap();
+            set_caller(accounts.bob);
+            assert!(contract.add_claim(accounts.charlie, human, Hash::from([0x09; 32]), None, None).is_ok());
+        }
+
+        #[ink::test]
+        fn test_unique_proof_claim_type() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let human = "unique_human".to_string();
+            let rules = ClaimTypeUniqueness { unique_per_receiver: false, unique_proof: true };
+            contract.set_claim_type_uniqueness(human.clone(), rules).unwrap();
+            let proof = Hash::from([0x01; 32]);
+
+            // The proof is bound to charlie, who may still reuse it
+            set_caller(accounts.bob);
+            let first = contract.add_claim(accounts.charlie, human.clone(), proof, None, None).unwrap();
+            let second = contract.add_claim(accounts.charlie, human.clone(), proof, None, None).unwrap();
+            assert_eq!(
+                contract.add_claim(accounts.django, human.clone(), proof, None, None),
+                Err(ContractError::ProofAlreadyBound)
+            );
+            assert!(contract.add_claim(accounts.django, human.clone(), Hash::from([0x02; 32]), None, None).is_ok());
+            assert!(contract.add_claim(accounts.django, "contribution".to_string(), proof, None, None).is_ok());
+
+            // The binding holds until every claim backed by the proof is revoked
+            contract.revoke_claim(second).unwrap();
+            assert_eq!(
+                contract.add_claim(accounts.django, human.clone(), proof, None, None),
+                Err(ContractError::ProofAlreadyBound)
+            );
+            contract.revoke_claim(first).unwrap();
+            assert!(contract.add_claim(accounts.django, human.clone(), proof, None, None).is_ok());
+            assert_eq!(
+                contract.add_claim(accounts.charlie, human, proof, None, None),
+                Err(ContractError::ProofAlreadyBound)
+            );
+        }
+
+        #[ink::test]
+        fn test_claim_type_uniqueness_across_issuance_paths() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = SkillChainRegistry::new();
+            let human = "unique_human".to_string();
+            let rules = ClaimTypeUniqueness { unique_per_receiver: true, unique_proof: true };
+            contract.set_claim_type_uniqueness(human.clone(), rules).unwrap();
+            contract.add_automated_issuer(accounts.frank).unwrap();
+            let proof = Hash::from([0x01; 32]);
+
+            // A verifier issuing back to back can't double up on a receiver or a proof
+            set_caller(accounts.frank);
+            contract.add_and_approve_claim(accounts.charlie, human.clone(), proof).unwrap();
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.charlie, human.clone(), Hash::from([0x02; 32])),
+                Err(ContractError::SingletonViolation)
+            );
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.django, human.clone(), proof),
+                Err(ContractError::ProofAlreadyBound)
+            );
+
+            // Template issuance is checked too, and an expired claim frees its slots
+            set_caller(accounts.bob);
+            let template_id = contract.create_claim_template(human.clone(), 1, 1_000, "ipfs://human".to_string()).unwrap();
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.charlie, Hash::from([0x03; 32]), None),
+                Err(ContractError::SingletonViolation)
+            );
+            let claim_id = contract.issue_from_template(template_id, accounts.django, Hash::from([0x04; 32]), None).unwrap();
+            contract.approve_claim(claim_id).unwrap();
+            assert_eq!(
+                contract.issue_from_template(template_id, accounts.django, Hash::from([0x05; 32]), None),
+                Err(ContractError::SingletonViolation)
+            );
+            set_timestamp(1_000);
+            assert!(contract.issue_from_template(template_id, accounts.django, Hash::from([0x04; 32]), None).is_ok());
+        }
+
+        #[ink::test]
+        fn test_subscriptions() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let callbacks: Vec<AccountId> = (0..=MAX_SUBSCRIPTIONS as u8).map(|i| AccountId::from([100 + i; 32])).collect();
+            for callback in &callbacks {
+                ink::env::test::set_contract::<ink::env::DefaultEnvironment>(*callback);
+            }
+
+            // Charlie lets Bob and Django subscribe for it
+            set_caller(accounts.charlie);
+            contract.set_subscriber_approval(accounts.bob, true).unwrap();
+            contract.set_subscriber_approval(accounts.django, true).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.subscribe(accounts.charlie, accounts.eve), Err(ContractError::NotAContract));
+            contract.subscribe(accounts.charlie, callbacks[0]).unwrap();
+            assert_eq!(contract.subscribe(accounts.charlie, callbacks[0]), Err(ContractError::AlreadySubscribed));
+
+            // Approved subscribers can subscribe, up to the bound per watched account
+            set_caller(accounts.django);
+            for callback in &callbacks[1..MAX_SUBSCRIPTIONS] {
+                contract.subscribe(accounts.charlie, *callback).unwrap();
+            }
+            assert_eq!(
+                contract.subscribe(accounts.charlie, callbacks[MAX_SUBSCRIPTIONS]),
+                Err(ContractError::TooManySubscriptions)
+            );
+            set_caller(accounts.eve);
+            assert!(contract.subscribe(accounts.eve, callbacks[MAX_SUBSCRIPTIONS]).is_ok());
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+            let subscriptions = contract.get_subscriptions(accounts.charlie);
+            assert_eq!(subscriptions.len(), MAX_SUBSCRIPTIONS);
+            assert_eq!(subscriptions[0], Subscription { subscriber: accounts.bob, callback_contract: callbacks[0] });
 
-        /// Helper function to get default accounts
-        fn default_accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
-            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
-        }
+            // Only the subscriber or the watched account can unsubscribe
+            set_caller(accounts.eve);
+            assert_eq!(contract.unsubscribe(accounts.charlie, callbacks[0]), Err(ContractError::NotSubscriber));
+            assert_eq!(
+                contract.unsubscribe(accounts.charlie, callbacks[MAX_SUBSCRIPTIONS]),
+                Err(ContractError::SubscriptionNotFound)
+            );
+            set_caller(accounts.bob);
+            contract.unsubscribe(accounts.charlie, callbacks[0]).unwrap();
+            set_caller(accounts.charlie);
+            contract.unsubscribe(accounts.charlie, callbacks[1]).unwrap();
 
-        /// Helper function to set caller
-        fn set_caller(account: AccountId) {
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+            let remaining: Vec<AccountId> = contract
+                .get_subscriptions(accounts.charlie)
+                .iter()
+                .map(|s| s.callback_contract)
+                .collect();
+            assert_eq!(remaining, callbacks[2..MAX_SUBSCRIPTIONS].to_vec());
+            assert!(contract.get_subscriptions(accounts.django).is_empty());
         }
 
         #[ink::test]
-        fn new_works() {
-            let contract = SkillChainRegistry::new();
-            assert_eq!(contract.get_total_claims(), 0);
+        fn test_subscribe_requires_approval() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+
+            let mut contract = SkillChainRegistry::new();
+            let callback = AccountId::from([100; 32]);
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(callback);
+
+            // Nobody can have a contract called on Charlie's approvals behind its back
+            set_caller(accounts.bob);
+            assert_eq!(contract.subscribe(accounts.charlie, callback), Err(ContractError::SubscriberNotApproved));
+            assert!(!contract.is_subscriber_approved(accounts.charlie, accounts.bob));
+            assert!(contract.get_subscriptions(accounts.charlie).is_empty());
+
+            set_caller(accounts.charlie);
+            contract.set_subscriber_approval(accounts.bob, true).unwrap();
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let set = <SubscriberApprovalSet as scale::Decode>::decode(&mut &events[events.len() - 1].data[..]).unwrap();
+            assert_eq!((set.account_watched, set.subscriber, set.approved), (accounts.charlie, accounts.bob, true));
+
+            set_caller(accounts.bob);
+            contract.subscribe(accounts.charlie, callback).unwrap();
+
+            // Revoking stops new subscriptions but keeps the registered one
+            set_caller(accounts.charlie);
+            contract.set_subscriber_approval(accounts.bob, false).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.subscribe(accounts.charlie, AccountId::from([101; 32])),
+                Err(ContractError::SubscriberNotApproved)
+            );
+            assert_eq!(contract.get_subscriptions(accounts.charlie).len(), 1);
         }
 
         #[ink::test]
-        fn test_register_profile_success() {
+        fn test_approval_without_subscriptions() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
 
             let mut contract = SkillChainRegistry::new();
-            let result = contract.register_profile("ipfs://QmTest123".to_string());
+            let callback = AccountId::from([100; 32]);
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(callback);
+            set_caller(accounts.django);
+            contract.subscribe(accounts.django, callback).unwrap();
 
-            assert!(result.is_ok());
+            // Nobody watches charlie, so approving makes no cross-contract call
+            set_caller(accounts.bob);
+            let claim_id = contract
+                .add_claim(accounts.charlie, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(claim_id).unwrap();
+            assert_eq!(contract.get_claim(claim_id).unwrap().status, ClaimStatus::Approved);
 
-            let profile = contract.get_profile(accounts.alice);
-            assert!(profile.is_some());
-            assert_eq!(profile.unwrap().owner, accounts.alice);
+            // Match the signature topic, since other events may decode as a failed call too
+            let signature = <SubscriberCallFailed as ink::env::Event>::SIGNATURE_TOPIC.map(|topic| topic.to_vec());
+            assert!(!ink::env::test::recorded_events().any(|event| event.topics.first() == signature.as_ref()));
         }
 
         #[ink::test]
-        fn test_register_profile_duplicate_fails() {
+        fn test_access_grants() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
+            set_timestamp(1_000);
 
             let mut contract = SkillChainRegistry::new();
-            
-            // First registration should succeed
-            let result1 = contract.register_profile("ipfs://QmTest123".to_string());
-            assert!(result1.is_ok());
+            contract.set_reputation_restricted(true).unwrap();
+            let did = "did:kilt:light:bob".to_string();
 
-            // Second registration should fail
-            let result2 = contract.register_profile("ipfs://QmTest456".to_string());
-            assert_eq!(result2, Err(ContractError::ProfileAlreadyExists));
+            set_caller(accounts.bob);
+            assert_eq!(contract.grant_access(accounts.charlie, 2_000), Err(ContractError::ProfileNotFound));
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+            contract.link_did(did.clone()).unwrap();
+            contract.set_did_visibility(false).unwrap();
+            approve_claims_for(&mut contract, accounts.bob, 2);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.grant_access(accounts.charlie, 1_000), Err(ContractError::InvalidGrant));
+            assert_eq!(contract.grant_access(accounts.bob, 2_000), Err(ContractError::InvalidGrant));
+            contract.grant_access(accounts.charlie, 2_000).unwrap();
+            contract.grant_access(accounts.django, 5_000).unwrap();
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <AccessGranted as scale::Decode>::decode(&mut &events[events.len() - 1].data[..]).unwrap();
+            assert_eq!((event.owner, event.viewer, event.expires_at), (accounts.bob, accounts.django, 5_000));
+
+            // Grantees see what only the owner sees; an unrelated caller never does
+            let sees_private = |contract: &SkillChainRegistry, caller| {
+                set_caller(caller);
+                let did_visible = contract.get_did(accounts.bob).is_some();
+                assert_eq!(contract.get_profile(accounts.bob).unwrap().did.is_some(), did_visible);
+                let snapshot_visible = contract.get_profile_snapshot(accounts.bob).is_some();
+                assert_eq!(did_visible, snapshot_visible);
+                did_visible
+            };
+            assert!(sees_private(&contract, accounts.charlie));
+            assert!(sees_private(&contract, accounts.django));
+            assert!(!sees_private(&contract, accounts.eve));
+            assert_eq!(contract.get_access_grant(accounts.bob, accounts.charlie), Some(2_000));
+
+            // Expiry ends access without any cleanup
+            set_timestamp(2_000);
+            assert!(!sees_private(&contract, accounts.charlie));
+            assert_eq!(contract.get_access_grant(accounts.bob, accounts.charlie), None);
+            assert!(sees_private(&contract, accounts.django));
+
+            // So does revocation
+            set_caller(accounts.bob);
+            contract.revoke_access(accounts.django).unwrap();
+            assert_eq!(contract.revoke_access(accounts.django), Err(ContractError::GrantNotFound));
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let event = <AccessRevoked as scale::Decode>::decode(&mut &events[events.len() - 1].data[..]).unwrap();
+            assert_eq!((event.owner, event.viewer), (accounts.bob, accounts.django));
+            assert!(!sees_private(&contract, accounts.django));
+            assert!(sees_private(&contract, accounts.bob));
+
+            // The expired grant lingers until purged, then can be granted afresh
+            set_caller(accounts.eve);
+            assert_eq!(contract.purge_expired_grants(accounts.bob), 1);
+            assert_eq!(contract.purge_expired_grants(accounts.bob), 0);
+            set_caller(accounts.bob);
+            assert_eq!(contract.revoke_access(accounts.charlie), Err(ContractError::GrantNotFound));
+            contract.grant_access(accounts.charlie, 3_000).unwrap();
+            assert!(sees_private(&contract, accounts.charlie));
         }
 
         #[ink::test]
-        fn test_add_claim_success() {
+        fn test_access_grants_bounded() {
             let accounts = default_accounts();
-            set_caller(accounts.alice);
+            set_caller(accounts.bob);
+            set_timestamp(0);
 
             let mut contract = SkillChainRegistry::new();
-            
-            let result = contract.add_claim(
-                accounts.bob,
-                "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
+            contract.register_profile("ipfs://QmBob".to_string()).unwrap();
+
+            for i in 0..MAX_ACCESS_GRANTS as u8 {
+                contract.grant_access(AccountId::from([100 + i; 32]), 1_000 + u64::from(i)).unwrap();
+            }
+            assert_eq!(
+                contract.grant_access(accounts.charlie, 5_000),
+                Err(ContractError::TooManyGrants)
             );
 
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), 0); // First claim ID should be 0
+            // Extending an existing grant needs no room
+            contract.grant_access(AccountId::from([100; 32]), 5_000).unwrap();
+
+            // Purging expired grants frees room
+            set_timestamp(1_001);
+            assert_eq!(contract.purge_expired_grants(accounts.bob), 1);
+            contract.grant_access(accounts.charlie, 5_000).unwrap();
         }
 
         #[ink::test]
-        fn test_add_claim_increments_id() {
+        fn test_stale_after_horizon() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
+            set_timestamp(0);
 
             let mut contract = SkillChainRegistry::new();
-            
-            let id1 = contract.add_claim(
-                accounts.bob,
-                "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
-            ).unwrap();
+            contract.set_tier_thresholds(2, 3).unwrap();
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
 
-            let id2 = contract.add_claim(
-                accounts.bob,
-                "job_completed".to_string(),
-                Hash::from([0x02; 32]),
-            ).unwrap();
+            // Two old approvals and two recent ones, across two claim types
+            let mut approve_at = |timestamp, claim_type: &str, proof: u8| {
+                set_timestamp(timestamp);
+                set_caller(accounts.charlie);
+                let claim_id = contract
+                    .add_claim(accounts.bob, claim_type.to_string(), Hash::from([proof; 32]), None, None)
+                    .unwrap();
+                contract.approve_claim(claim_id).unwrap();
+                claim_id
+            };
+            let old = [approve_at(0, "contribution", 0x01), approve_at(500, "contribution", 0x02)];
+            let recent = [approve_at(501, "contribution", 0x03), approve_at(1_000, "hackathon_win", 0x04)];
 
-            assert_eq!(id1, 0);
-            assert_eq!(id2, 1);
-            assert_eq!(contract.get_total_claims(), 2);
+            set_caller(accounts.alice);
+            set_timestamp(2_000);
+            assert_eq!(contract.set_stale_after(1_500), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(contract.set_stale_after(0), Err(ContractError::NotAdmin));
+            assert_eq!(contract.get_stale_after(), 1_500);
+
+            // An approval exactly 1500 ms old is stale, one ms younger is not
+            let proofs = [0x01, 0x02, 0x03, 0x04];
+            for (claim_id, proof) in old.iter().chain(&recent).zip(proofs) {
+                let stale = old.contains(claim_id);
+                assert_eq!(contract.is_claim_stale(*claim_id), stale);
+                let expected = if stale { VerificationResult::Stale } else { VerificationResult::Valid };
+                assert_eq!(contract.verify_claim(*claim_id, accounts.bob, Hash::from([proof; 32])), expected);
+            }
+
+            // Every aggregate only counts the two recent approvals
+            let snapshot = contract.get_profile_snapshot(accounts.bob).unwrap();
+            assert_eq!(snapshot.reputation_score, 2);
+            assert_eq!(
+                snapshot.approved_by_type,
+                vec![("contribution".to_string(), 1), ("hackathon_win".to_string(), 1)]
+            );
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Silver);
+            assert_eq!(contract.get_approved_count_since(accounts.bob, "contribution".to_string(), 0), 1);
+            assert_eq!(contract.get_approved_count_since(accounts.bob, "hackathon_win".to_string(), 1_001), 0);
+
+            // Stale claims stay visible as history
+            assert_eq!(contract.get_claims(accounts.bob).len(), 4);
+            assert_eq!(contract.get_claim(old[0]).unwrap().status, ClaimStatus::Approved);
+
+            // A revoked claim is never stale
+            set_caller(accounts.charlie);
+            contract.revoke_claim(old[0]).unwrap();
+            assert!(!contract.is_claim_stale(old[0]));
+
+            // Disabling the horizon counts everything again
+            set_caller(accounts.alice);
+            contract.set_stale_after(0).unwrap();
+            assert!(!contract.is_claim_stale(old[1]));
+            assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 3);
+            assert_eq!(contract.get_reputation_tier(accounts.bob), Tier::Gold);
+            assert_eq!(contract.get_approved_count_since(accounts.bob, "contribution".to_string(), 0), 2);
         }
 
         #[ink::test]
-        fn test_approve_claim_by_issuer() {
+        fn test_require_receiver_profile_off_by_default() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
-
             let mut contract = SkillChainRegistry::new();
-            
-            let claim_id = contract.add_claim(
-                accounts.bob,
-                "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
-            ).unwrap();
-
-            // Alice (issuer) approves the claim
-            let result = contract.approve_claim(claim_id);
-            assert!(result.is_ok());
+            assert!(!contract.get_require_receiver_profile());
 
-            // Verify claim status is Approved
-            let claims = contract.get_claims(accounts.bob);
-            assert_eq!(claims.len(), 1);
-            assert_eq!(claims[0].status, ClaimStatus::Approved);
+            // Bob never registered, yet his claim can be approved
+            set_caller(accounts.charlie);
+            let claim_id = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            assert_eq!(contract.approve_claim(claim_id), Ok(()));
         }
 
         #[ink::test]
-        fn test_approve_claim_unauthorized_fails() {
+        fn test_require_receiver_profile() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
-
             let mut contract = SkillChainRegistry::new();
-            
-            let claim_id = contract.add_claim(
-                accounts.bob,
-                "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
-            ).unwrap();
+            contract.add_automated_issuer(accounts.django).unwrap();
 
-            // Bob tries to approve Alice's claim (should fail)
             set_caller(accounts.bob);
-            let result = contract.approve_claim(claim_id);
-            assert_eq!(result, Err(ContractError::UnauthorizedApproval));
+            assert_eq!(contract.set_require_receiver_profile(true), Err(ContractError::NotAdmin));
+            set_caller(accounts.alice);
+            assert_eq!(contract.set_require_receiver_profile(true), Ok(()));
+            assert!(contract.get_require_receiver_profile());
+
+            // Adding stays permissive, approving needs Bob's profile
+            set_caller(accounts.charlie);
+            let claim_id = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::ProfileNotFound));
+            assert_eq!(contract.approve_claims_batch(vec![claim_id]), Err(ContractError::ProfileNotFound));
+            assert_eq!(contract.get_claim(claim_id).unwrap().status, ClaimStatus::Pending);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_and_approve_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32])),
+                Err(ContractError::ProfileNotFound)
+            );
+
+            // Once Bob registers, the pending claim can be approved
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(contract.approve_claim(claim_id), Ok(()));
+            assert_eq!(contract.get_claim(claim_id).unwrap().status, ClaimStatus::Approved);
+
+            set_caller(accounts.django);
+            assert!(contract
+                .add_and_approve_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]))
+                .is_ok());
         }
 
         #[ink::test]
-        fn test_approve_claim_twice_fails() {
+        fn test_namespaced_claim_types() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
-
             let mut contract = SkillChainRegistry::new();
-            
-            let claim_id = contract.add_claim(
-                accounts.bob,
-                "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
-            ).unwrap();
+            let proof = Hash::from([0x01; 32]);
 
-            // First approval should succeed
-            let result1 = contract.approve_claim(claim_id);
-            assert!(result1.is_ok());
+            // Charlie claims the namespace
+            set_caller(accounts.charlie);
+            for invalid in ["ab", "Bootcamp", "boot/camp", &"a".repeat(MAX_NAMESPACE_LEN + 1)] {
+                assert_eq!(contract.register_namespace(invalid.to_string()), Err(ContractError::InvalidNamespace));
+            }
+            assert_eq!(contract.register_namespace("bootcamp".to_string()), Ok(()));
+            assert_eq!(contract.get_namespace_owner("bootcamp".to_string()), Some(accounts.charlie));
 
-            // Second approval should fail
-            let result2 = contract.approve_claim(claim_id);
-            assert_eq!(result2, Err(ContractError::ClaimAlreadyApproved));
+            set_caller(accounts.django);
+            assert_eq!(contract.register_namespace("bootcamp".to_string()), Err(ContractError::NamespaceTaken));
+
+            // Only the owner issues under it; plain types stay open
+            set_caller(accounts.charlie);
+            assert!(contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None).is_ok());
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None),
+                Err(ContractError::NotNamespaceOwner)
+            );
+            assert_eq!(
+                contract.add_claim(accounts.bob, "other/certified".to_string(), proof, None, None),
+                Err(ContractError::NamespaceNotFound)
+            );
+            for invalid in ["bootcamp/", "bootcamp/a/b", "BC/certified"] {
+                assert_eq!(
+                    contract.add_claim(accounts.bob, invalid.to_string(), proof, None, None),
+                    Err(ContractError::InvalidNamespace)
+                );
+            }
+            assert!(contract.add_claim(accounts.bob, "certified".to_string(), proof, None, None).is_ok());
+
+            // A delegate issues on the owner's behalf
+            set_caller(accounts.charlie);
+            assert_eq!(contract.add_namespace_delegate("bootcamp".to_string(), accounts.django), Ok(()));
+            set_caller(accounts.django);
+            assert!(contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None).is_ok());
+            assert_eq!(
+                contract.transfer_namespace("bootcamp".to_string(), accounts.django),
+                Err(ContractError::NotNamespaceOwner)
+            );
+
+            // After a transfer, the new owner issues and the old owner and delegates can't
+            set_caller(accounts.charlie);
+            assert_eq!(contract.transfer_namespace("bootcamp".to_string(), accounts.eve), Ok(()));
+            assert_eq!(contract.get_namespace_owner("bootcamp".to_string()), Some(accounts.eve));
+            assert!(contract.get_namespace_delegates("bootcamp".to_string()).is_empty());
+            assert_eq!(
+                contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None),
+                Err(ContractError::NotNamespaceOwner)
+            );
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None),
+                Err(ContractError::NotNamespaceOwner)
+            );
+            set_caller(accounts.eve);
+            assert!(contract.add_claim(accounts.bob, "bootcamp/certified".to_string(), proof, None, None).is_ok());
         }
 
         #[ink::test]
-        fn test_get_profile_existing() {
+        fn test_namespace_delegates() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                contract.add_namespace_delegate("bootcamp".to_string(), accounts.django),
+                Err(ContractError::NamespaceNotFound)
+            );
+            contract.register_namespace("bootcamp".to_string()).unwrap();
+
+            set_caller(accounts.django);
+            assert_eq!(
+                contract.add_namespace_delegate("bootcamp".to_string(), accounts.django),
+                Err(ContractError::NotNamespaceOwner)
+            );
+
+            set_caller(accounts.charlie);
+            for i in 0..MAX_NAMESPACE_DELEGATES as u8 {
+                contract
+                    .add_namespace_delegate("bootcamp".to_string(), AccountId::from([100 + i; 32]))
+                    .unwrap();
+            }
+            assert_eq!(
+                contract.add_namespace_delegate("bootcamp".to_string(), AccountId::from([100; 32])),
+                Err(ContractError::AlreadyNamespaceDelegate)
+            );
+            assert_eq!(
+                contract.add_namespace_delegate("bootcamp".to_string(), accounts.django),
+                Err(ContractError::TooManyNamespaceDelegates)
+            );
+
+            assert_eq!(contract.remove_namespace_delegate("bootcamp".to_string(), AccountId::from([100; 32])), Ok(()));
+            assert_eq!(
+                contract.remove_namespace_delegate("bootcamp".to_string(), AccountId::from([100; 32])),
+                Err(ContractError::NotNamespaceDelegate)
+            );
+            assert_eq!(contract.get_namespace_delegates("bootcamp".to_string()).len(), MAX_NAMESPACE_DELEGATES - 1);
+        }
 
+        #[ink::test]
+        fn test_existence_checks() {
+            let accounts = default_accounts();
+            set_caller(accounts.alice);
+            set_timestamp(0);
             let mut contract = SkillChainRegistry::new();
-            contract.register_profile("ipfs://QmTest123".to_string()).unwrap();
 
-            let profile = contract.get_profile(accounts.alice);
-            assert!(profile.is_some());
-            assert_eq!(profile.unwrap().metadata_uri, "ipfs://QmTest123");
+            assert!(!contract.profile_exists(accounts.bob));
+            set_caller(accounts.bob);
+            contract.register_profile("ipfs://bob".to_string()).unwrap();
+            assert!(contract.profile_exists(accounts.bob));
+            assert!(!contract.profile_exists(accounts.charlie));
+
+            // Pending claims exist but don't count as approved
+            set_caller(accounts.charlie);
+            let claim_id = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            assert!(contract.claim_exists(claim_id));
+            assert!(!contract.claim_exists(claim_id + 1));
+            assert!(!contract.has_approved_claim_of_type(accounts.bob, "contribution".to_string()));
+
+            contract.approve_claim(claim_id).unwrap();
+            assert!(contract.has_approved_claim_of_type(accounts.bob, "contribution".to_string()));
+            assert!(!contract.has_approved_claim_of_type(accounts.bob, "hackathon_win".to_string()));
+            assert!(!contract.has_approved_claim_of_type(accounts.charlie, "contribution".to_string()));
+
+            // Stale approvals stop counting
+            set_caller(accounts.alice);
+            contract.set_stale_after(1_000).unwrap();
+            set_timestamp(1_000);
+            assert!(!contract.has_approved_claim_of_type(accounts.bob, "contribution".to_string()));
+            contract.set_stale_after(0).unwrap();
+            assert!(contract.has_approved_claim_of_type(accounts.bob, "contribution".to_string()));
+
+            // Revocation clears the flag, the claim itself still exists
+            set_caller(accounts.charlie);
+            contract.revoke_claim(claim_id).unwrap();
+            assert!(!contract.has_approved_claim_of_type(accounts.bob, "contribution".to_string()));
+            assert!(contract.claim_exists(claim_id));
         }
 
         #[ink::test]
-        fn test_get_profile_nonexistent() {
+        fn test_self_attestation_flagged() {
             let accounts = default_accounts();
-            let contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
+            assert_eq!(contract.get_self_attestation_policy(), SelfAttestationPolicy::Flag);
 
-            let profile = contract.get_profile(accounts.alice);
-            assert!(profile.is_none());
+            // Bob and his sock puppet Charlie link the same DID, Django another one
+            for (account, did) in [
+                (accounts.bob, "did:kilt:light:bob"),
+                (accounts.charlie, "did:kilt:light:bob"),
+                (accounts.django, "did:kilt:light:django"),
+            ] {
+                set_caller(account);
+                contract.register_profile("ipfs://profile".to_string()).unwrap();
+                contract.link_did(did.to_string()).unwrap();
+            }
+
+            set_caller(accounts.charlie);
+            let puppet_claim = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None)
+                .unwrap();
+            assert!(contract.get_claim(puppet_claim).unwrap().self_attested);
+            assert_eq!(contract.approve_claim(puppet_claim), Ok(()));
+
+            // Approved and visible, but earns no reputation or badges
+            let claim = contract.get_claim(puppet_claim).unwrap();
+            assert_eq!(claim.status, ClaimStatus::Approved);
+            assert!(claim.self_attested);
+            let snapshot = contract.get_profile_snapshot(accounts.bob).unwrap();
+            assert_eq!(snapshot.reputation_score, 0);
+            assert!(contract.get_profile(accounts.bob).unwrap().badges.is_empty());
+
+            // A distinct DID and an issuer without DID pass untouched
+            set_caller(accounts.django);
+            let distinct_claim = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(distinct_claim).unwrap();
+            set_caller(accounts.eve);
+            let no_did_claim = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x03; 32]), None, None)
+                .unwrap();
+            contract.approve_claim(no_did_claim).unwrap();
+            assert!(!contract.get_claim(distinct_claim).unwrap().self_attested);
+            assert!(!contract.get_claim(no_did_claim).unwrap().self_attested);
+            assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 2);
+
+            // Revoking the flagged claim leaves the counted ones alone
+            set_caller(accounts.charlie);
+            contract.revoke_claim(puppet_claim).unwrap();
+            assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 2);
         }
 
         #[ink::test]
-        fn test_get_claims_empty() {
+        fn test_self_attestation_rejected() {
             let accounts = default_accounts();
-            let contract = SkillChainRegistry::new();
+            set_caller(accounts.alice);
+            let mut contract = SkillChainRegistry::new();
 
-            let claims = contract.get_claims(accounts.alice);
-            assert_eq!(claims.len(), 0);
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.set_self_attestation_policy(SelfAttestationPolicy::Reject),
+                Err(ContractError::NotAdmin)
+            );
+            set_caller(accounts.alice);
+            contract.set_self_attestation_policy(SelfAttestationPolicy::Reject).unwrap();
+            assert_eq!(contract.get_self_attestation_policy(), SelfAttestationPolicy::Reject);
+
+            for account in [accounts.bob, accounts.charlie, accounts.eve] {
+                set_caller(account);
+                contract.register_profile("ipfs://profile".to_string()).unwrap();
+            }
+            set_caller(accounts.bob);
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+            set_caller(accounts.charlie);
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+
+            assert_eq!(
+                contract.add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x01; 32]), None, None),
+                Err(ContractError::SelfAttestation)
+            );
+
+            // Eve adds a claim before linking Bob's DID; approval catches it
+            set_caller(accounts.eve);
+            let claim_id = contract
+                .add_claim(accounts.bob, "contribution".to_string(), Hash::from([0x02; 32]), None, None)
+                .unwrap();
+            contract.link_did("did:kilt:light:bob".to_string()).unwrap();
+            assert_eq!(contract.approve_claim(claim_id), Err(ContractError::SelfAttestation));
+            assert_eq!(contract.approve_claims_batch(vec![claim_id]), Err(ContractError::SelfAttestation));
+            assert_eq!(contract.get_claim(claim_id).unwrap().status, ClaimStatus::Pending);
+
+            // Switching to flagging lets the claim through, kept out of reputation
+            set_caller(accounts.alice);
+            contract.set_self_attestation_policy(SelfAttestationPolicy::Flag).unwrap();
+            set_caller(accounts.eve);
+            assert_eq!(contract.approve_claim(claim_id), Ok(()));
+            assert!(contract.get_claim(claim_id).unwrap().self_attested);
+            assert_eq!(contract.get_profile_snapshot(accounts.bob).unwrap().reputation_score, 0);
         }
 
         #[ink::test]
-        fn test_get_claims_multiple() {
+        fn test_verify_claim_all_results() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
 
             let mut contract = SkillChainRegistry::new();
-            
-            // Add multiple claims to Bob
-            contract.add_claim(
+            let proof = Hash::from([0x01; 32]);
+
+            let claim_id = contract.add_claim(
                 accounts.bob,
                 "hackathon_win".to_string(),
-                Hash::from([0x01; 32]),
+                proof,
+                None,
+                None,
             ).unwrap();
 
-            contract.add_claim(
-                accounts.bob,
-                "job_completed".to_string(),
-                Hash::from([0x02; 32]),
-            ).unwrap();
+            // NotFound
+            assert_eq!(
+                contract.verify_claim(999, accounts.bob, proof),
+                VerificationResult::NotFound
+            );
 
-            contract.add_claim(
+            // NotApproved
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.bob, proof),
+                VerificationResult::NotApproved
+            );
+
+            contract.approve_claim(claim_id).unwrap();
+
+            // Valid
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.bob, proof),
+                VerificationResult::Valid
+            );
+
+            // WrongReceiver
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.charlie, proof),
+                VerificationResult::WrongReceiver
+            );
+
+            // WrongProof
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.bob, Hash::from([0x02; 32])),
+                VerificationResult::WrongProof
+            );
+
+            // Revoked
+            contract.revoke_claim(claim_id).unwrap();
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.bob, proof),
+                VerificationResult::Revoked
+            );
+
+            // Expired
+            let expired_id = contract.add_claim(
                 accounts.bob,
-                "contribution".to_string(),
-                Hash::from([0x03; 32]),
+                "job_completed".to_string(),
+                proof,
+                None,
+                None,
             ).unwrap();
-
-            // Get all claims for Bob
-            let claims = contract.get_claims(accounts.bob);
-            assert_eq!(claims.len(), 3);
-            assert_eq!(claims[0].claim_type, "hackathon_win");
-            assert_eq!(claims[1].claim_type, "job_completed");
-            assert_eq!(claims[2].claim_type, "contribution");
+            let mut expired = contract.claims.get(expired_id).unwrap();
+            expired.status = ClaimStatus::Expired;
+            contract.claims.insert(expired_id, &expired);
+            assert_eq!(
+                contract.verify_claim(expired_id, accounts.bob, proof),
+                VerificationResult::Expired
+            );
         }
 
         #[ink::test]
-        fn test_claim_not_found() {
+        fn test_verify_claim_reports_identity_before_status() {
             let accounts = default_accounts();
             set_caller(accounts.alice);
 
             let mut contract = SkillChainRegistry::new();
-            
-            // Try to approve non-existent claim
-            let result = contract.approve_claim(999);
-            assert_eq!(result, Err(ContractError::ClaimNotFound));
+
+            let claim_id = contract.add_claim(
+                accounts.bob,
+                "hackathon_win".to_string(),
+                Hash::from([0x01; 32]),
+                None,
+                None,
+            ).unwrap();
+            contract.revoke_claim(claim_id).unwrap();
+
+            // A mismatched receiver is reported even though the claim is revoked
+            assert_eq!(
+                contract.verify_claim(claim_id, accounts.charlie, Hash::from([0x01; 32])),
+                VerificationResult::WrongReceiver
+            );
         }
     }
 
@@ -649,6 +7664,8 @@ mod skillchain {
                     ink_e2e::account_id(ink_e2e::AccountKeyring::Bob),
                     "hackathon_win".to_string(),
                     Hash::from([0x01; 32]),
+                    None,
+                    None,
                 ));
             
             let _claim_result = client